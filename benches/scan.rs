@@ -0,0 +1,127 @@
+//! 探索・ステータス取得・出力整形のベンチマーク
+//!
+//! `cargo bench` で実行する。合成したディレクトリツリー（N個のリポジトリ、各M個のファイル）を使う。
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use git2::{Repository as Git2Repository, Signature};
+use pendector::core::{RepoScanner, Repository, ScanOptions};
+use pendector::output::{OutputFormat, OutputFormatter};
+use std::fs;
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+const REPO_COUNTS: &[usize] = &[10, 100];
+const FILES_PER_REPO: usize = 20;
+
+/// N個のリポジトリを持つツリーを生成する（偶数番目のリポジトリには未コミットの変更を置く）
+fn generate_tree(repo_count: usize, files_per_repo: usize) -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    for i in 0..repo_count {
+        // 探索の深さに差が出るよう、グループごとにネストさせる
+        let repo_path = temp_dir
+            .path()
+            .join(format!("group{}", i % 10))
+            .join(format!("repo{i:04}"));
+        init_repo(&repo_path, files_per_repo);
+        if i % 2 == 0 {
+            fs::write(repo_path.join("file0.txt"), "modified").unwrap();
+            fs::write(repo_path.join("untracked.txt"), "new").unwrap();
+        }
+        // リポジトリ以外のディレクトリも探索対象になる
+        fs::create_dir_all(repo_path.join("src").join("nested")).unwrap();
+    }
+    temp_dir
+}
+
+fn init_repo(path: &Path, files: usize) {
+    fs::create_dir_all(path).unwrap();
+    let repo = Git2Repository::init(path).unwrap();
+    for j in 0..files {
+        fs::write(path.join(format!("file{j}.txt")), format!("content {j}")).unwrap();
+    }
+    let mut index = repo.index().unwrap();
+    index
+        .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+        .unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = Signature::now("Bench", "bench@example.com").unwrap();
+    repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+        .unwrap();
+}
+
+/// ファイルシステムに触れない出力用のリポジトリ一覧
+fn synthetic_repositories(count: usize) -> Vec<Repository> {
+    (0..count)
+        .map(|i| {
+            let files = (0..i % 5).map(|j| format!(" M src/file{j}.rs")).collect();
+            Repository::new(PathBuf::from(format!("/home/user/src/repo{i:04}")))
+                .with_git_info(i % 5 != 0, Some("main".to_string()), files)
+                .with_remote_info(i % 3 == 0, i % 4 == 0, Some("origin/main".to_string()))
+        })
+        .collect()
+}
+
+fn bench_discovery(c: &mut Criterion) {
+    let mut group = c.benchmark_group("discovery");
+    for &count in REPO_COUNTS {
+        // 中身のない `.git` はステータス取得がすぐに失敗するので、探索のコストだけが残る
+        let temp_dir = TempDir::new().unwrap();
+        for i in 0..count {
+            let repo_path = temp_dir
+                .path()
+                .join(format!("group{}", i % 10))
+                .join(format!("repo{i:04}"));
+            fs::create_dir_all(repo_path.join(".git")).unwrap();
+            fs::create_dir_all(repo_path.join("src").join("nested")).unwrap();
+        }
+        let scanner = RepoScanner::new();
+        let options = ScanOptions::new().with_max_depth(5);
+        group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, _| {
+            b.iter(|| {
+                scanner
+                    .scan_with(black_box(temp_dir.path()), &options)
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_status(c: &mut Criterion) {
+    let mut group = c.benchmark_group("status");
+    group.sample_size(20);
+    for &count in REPO_COUNTS {
+        let temp_dir = generate_tree(count, FILES_PER_REPO);
+        let scanner = RepoScanner::new();
+        let options = ScanOptions::new().with_max_depth(5);
+        group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, _| {
+            b.iter(|| {
+                scanner
+                    .scan_with(black_box(temp_dir.path()), &options)
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_format(c: &mut Criterion) {
+    let repositories = synthetic_repositories(1000);
+    let mut group = c.benchmark_group("format");
+    for (label, verbose, format) in [
+        ("text", false, OutputFormat::Text),
+        ("verbose", true, OutputFormat::Text),
+        ("json", false, OutputFormat::Json),
+    ] {
+        let formatter = OutputFormatter::new(verbose, format).with_width(Some(120));
+        group.bench_function(label, |b| {
+            b.iter(|| formatter.format_repositories(black_box(&repositories)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_discovery, bench_status, bench_format);
+criterion_main!(benches);
@@ -0,0 +1,865 @@
+//! `pendector` コマンドの実行：CLI引数を解釈し、走査・表示・一括操作を行う
+//!
+//! バイナリから [`crate::cli_main`] として呼ぶ。ライブラリの公開APIではない。
+
+use crate::cli::{
+    explain_excludes, install_interrupt_handler, Args, BranchesArgs, BuildInfo, Capabilities,
+    Command, ExecArgs, ExitCode, HealthArgs, StashArgs, WatchArgs, WipArgs,
+};
+use crate::config::Config;
+use crate::core::{group_by_remote_id, Repository, Storage};
+use crate::error::{PendectorError, PendectorResult};
+use crate::git::{repository_stats, RepoStats};
+use crate::interrupt::is_interrupted;
+use crate::notify::slack::SlackNotifier;
+use crate::ops::{
+    create_wip_commit, delete_branch, exec_in_repositories, find_stale_branches,
+    has_pendector_stash, is_on_wip_commit, plan_sync, pop_stash, remove_stale_lock_files,
+    run_maintenance, select_repositories, stash_changes, stash_message, undo_wip_commit, AuditLog,
+    AuditRecord, AuditResult, ExecOutput, ExecResult, SyncPlan, DEFAULT_WIP_BRANCH_TEMPLATE,
+};
+use crate::output::{
+    format_size, write_stdout, BulkAction, EnvelopeWarning, Locale, Message, PrefixedLineWriter,
+    WarningKind,
+};
+use crate::progress::{ProgressEvent, ProgressReporter};
+use crate::run::{run, RunError, RunOptions, ScanReport};
+use crate::time::format_utc;
+use std::fmt::Display;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime};
+
+/// 標準出力に1行書く（`println!` と違い、パイプが閉じられてもパニックせずに書くのをやめる）
+macro_rules! outln {
+    () => {
+        write_stdout("\n")
+    };
+    ($($arg:tt)*) => {
+        write_stdout(&format!("{}\n", format_args!($($arg)*)))
+    };
+}
+
+/// 標準出力に書く（`print!` の代わり）
+macro_rules! out {
+    ($($arg:tt)*) => {
+        write_stdout(&format!($($arg)*))
+    };
+}
+
+/// `pendector` コマンドの入口
+pub fn main() {
+    install_interrupt_handler();
+    let args = Args::parse_with_sources();
+    if args.plain {
+        colored::control::set_override(false);
+    }
+
+    // 設定ファイルやスキャンに依存しないので最初に処理する
+    if args.version {
+        outln!("{}", BuildInfo::current().render(args.is_verbose()));
+        return;
+    }
+    if let Some(Command::Capabilities) = &args.command {
+        outln!("{}", Capabilities::current().render(args.format));
+        return;
+    }
+
+    // 設定ファイルの読み込み（失敗したらデフォルト設定で続け、json-v2 出力にも警告を残す）
+    let mut config_warning = None;
+    let config = if args.no_config {
+        Config::default()
+    } else {
+        match Config::load_layered(&args.config_paths()) {
+            Ok(config) => config,
+            Err(e) => {
+                let locale = args.locale();
+                print_warning(locale, e.to_string());
+                eprintln!("{}", Message::UsingDefaultConfig.text(locale));
+                // json-v2 の警告は機械向けなので言語によらず英語にする
+                config_warning = Some(EnvelopeWarning::new(
+                    WarningKind::ConfigFallback,
+                    None,
+                    Message::ConfigFallback(&e.to_string()).text(Locale::En),
+                ));
+                Config::default()
+            }
+        }
+    };
+
+    // スキャンせずに設定だけから判定する
+    if let Some(Command::ExplainExcludes(explain_args)) = &args.command {
+        match explain_excludes(
+            &args,
+            &config,
+            &explain_args.path,
+            explain_args.root.as_deref(),
+        ) {
+            Ok(explanation) => outln!("{explanation}"),
+            Err(e) => {
+                print_error(args.locale(), e);
+                ExitCode::Usage.exit();
+            }
+        }
+        return;
+    }
+
+    // CLI引数のパスとパスごとの深さ指定の誤りはスキャンの前に報告する
+    let options = RunOptions::from_args(&args, config)
+        .unwrap_or_else(|e| exit_with(&args, e))
+        .with_storage(Storage::default_location());
+
+    if let Some(Command::Watch(watch_args)) = &args.command {
+        run_watch(&args, watch_args, options.config);
+    }
+
+    let report = run(&options).unwrap_or_else(|e| exit_with(&args, e));
+    print_warnings(&args, &report.warnings);
+    let config = &options.config;
+    let audit = config.audit_log();
+    let bulk = Bulk {
+        audit: &audit,
+        locale: args.locale(),
+    };
+
+    match &args.command {
+        Some(Command::Stash(stash_args)) => run_stash(stash_args, bulk, report.repositories),
+        Some(Command::Wip(wip_args)) => run_wip(wip_args, config, bulk, report.repositories),
+        Some(Command::Branches(branches_args)) => {
+            run_branches(branches_args, config, bulk, report.repositories)
+        }
+        Some(Command::Health(health_args)) => run_health(health_args, bulk, report.repositories),
+        Some(Command::SyncPlan(_)) => run_sync_plan(args.locale(), report.repositories),
+        Some(Command::Exec(exec_args)) => run_exec(exec_args, bulk, report.repositories),
+        Some(Command::Watch(_)) => unreachable!("watch runs before the initial scan"),
+        Some(Command::Capabilities) => {
+            unreachable!("capabilities is handled before loading config")
+        }
+        Some(Command::ExplainExcludes(_)) => unreachable!("explain-excludes runs before scanning"),
+        None => show_status(&args, &options, report, config_warning),
+    }
+}
+
+/// 実行の失敗を表示し、対応する終了コードで終了する
+fn exit_with(args: &Args, error: RunError) -> ! {
+    let locale = args.locale();
+    print_error(locale, error.text(locale));
+    error.exit_code().exit()
+}
+
+/// エラーを表示する言語の接頭辞を付けて標準エラー出力に書く
+fn print_error(locale: Locale, error: impl Display) {
+    eprintln!("{}: {error}", Message::ErrorPrefix.text(locale));
+}
+
+/// 警告を表示する言語の接頭辞を付けて標準エラー出力に書く
+fn print_warning(locale: Locale, warning: impl Display) {
+    eprintln!("{}: {warning}", Message::WarningPrefix.text(locale));
+}
+
+/// 走査中の警告を表示する言語で標準エラー出力に書く
+fn print_warnings(args: &Args, warnings: &[EnvelopeWarning]) {
+    for warning in warnings {
+        print_warning(args.locale(), &warning.display);
+    }
+}
+
+/// リポジトリの状態を表示し、必要に応じて通知する
+///
+/// `json-v2` の警告には、設定ファイルを読み込めなかったこと（`config_warning`）と走査中の警告を含める。
+fn show_status(
+    args: &Args,
+    options: &RunOptions,
+    report: ScanReport,
+    config_warning: Option<EnvelopeWarning>,
+) {
+    let config = &options.config;
+    if let Some(last_scan) = &report.last_scan {
+        if let Err(e) = options.save_last_scan(last_scan) {
+            let message = Message::LastScanNotSaved(&e.to_string());
+            print_warning(args.locale(), message.text(args.locale()));
+        }
+    }
+    let sorted_repos = report.repositories;
+
+    ProgressReporter::new(args.progress_mode()).emit(&ProgressEvent::Done {
+        repositories: sorted_repos.len(),
+    });
+    if args.is_verbose() && report.fetch.attempted() > 0 {
+        let message = Message::FetchedRepositories {
+            fetched: report.fetch.fetched,
+            attempted: report.fetch.attempted(),
+            failed: report.fetch.failed,
+        };
+        eprintln!("{}", message.text(args.locale()));
+    }
+
+    let warnings = config_warning.into_iter().chain(report.warnings).collect();
+    let formatter = options.formatter().with_warnings(warnings);
+    if args.report {
+        // 対応が必要なものがなければ何も出力しない（cronのメール送信を抑止する）
+        let report = formatter.format_report(&sorted_repos);
+        if !report.is_empty() {
+            out!("{report}");
+        }
+    } else if args.duplicates {
+        // 同じリモートを持つクローンが複数あるプロジェクトだけを表示する
+        let duplicates: Vec<_> = group_by_remote_id(&sorted_repos)
+            .into_iter()
+            .filter(|(_, clones)| clones.len() > 1)
+            .collect();
+        outln!("{}", formatter.format_duplicates(&duplicates));
+    } else {
+        if !args.is_explicit("format") {
+            let formats = options.configured_formats();
+            if formats.len() > 1 {
+                let formats = formats
+                    .iter()
+                    .map(|format| format.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let message = Message::FormatMismatch {
+                    formats: &formats,
+                    using: formatter.format.as_str(),
+                };
+                print_warning(args.locale(), message.text(args.locale()));
+            }
+        }
+        outln!("{}", options.render(formatter, &sorted_repos));
+    }
+
+    // Slack通知
+    if args.notify_slack {
+        let webhook_url = args
+            .slack_webhook_url
+            .clone()
+            .or_else(|| config.slack.as_ref().and_then(|s| s.webhook_url.clone()));
+
+        match webhook_url {
+            Some(url) => {
+                let notify_only_changes = if args.slack_notify_always {
+                    false
+                } else {
+                    config
+                        .slack
+                        .as_ref()
+                        .map(|s| s.notify_only_changes)
+                        .unwrap_or(true)
+                };
+
+                let has_any_changes = sorted_repos.iter().any(|r| r.needs_attention());
+                if !notify_only_changes || has_any_changes {
+                    let slack_config = config.slack.as_ref();
+                    let notifier = SlackNotifier::new(
+                        url,
+                        slack_config.and_then(|s| s.username.clone()),
+                        slack_config.and_then(|s| s.icon_emoji.clone()),
+                        slack_config.and_then(|s| s.channel.clone()),
+                    );
+
+                    if let Err(e) = notifier.notify(&sorted_repos) {
+                        print_warning(args.locale(), e);
+                    }
+                }
+            }
+            None => {
+                print_error(
+                    args.locale(),
+                    Message::SlackWebhookMissing.text(args.locale()),
+                );
+                ExitCode::Usage.exit();
+            }
+        }
+    }
+
+    // --check/--report指定時は対応が必要なリポジトリがあれば終了コード1
+    if (args.check || args.report) && sorted_repos.iter().any(|r| r.needs_attention()) {
+        ExitCode::Findings.exit();
+    }
+}
+
+/// `pendector watch` の実行：一定間隔で再スキャンし、状態が変わったときだけ表示する
+///
+/// 設定ファイルが更新されたら再読み込みし、スキャン対象・除外パターン・間隔を再起動なしで反映する。
+fn run_watch(args: &Args, watch_args: &WatchArgs, mut config: Config) -> ! {
+    let config_paths = if args.no_config {
+        Vec::new()
+    } else {
+        Config::file_paths(&args.config_paths()).unwrap_or_default()
+    };
+    let modified = |paths: &[std::path::PathBuf]| -> Vec<Option<SystemTime>> {
+        paths
+            .iter()
+            .map(|p| std::fs::metadata(p).and_then(|m| m.modified()).ok())
+            .collect()
+    };
+    let mut config_modified = modified(&config_paths);
+    let mut last_output: Option<String> = None;
+    let mut known: Vec<Repository> = Vec::new();
+    let mut rechecked = 0;
+
+    loop {
+        let options =
+            RunOptions::from_args(args, config.clone()).unwrap_or_else(|e| exit_with(args, e));
+        // --batch 指定時はデータの古いものから少しずつ調べ直し、一巡したら探索し直す
+        match watch_args.batch {
+            Some(batch) if rechecked < known.len() => {
+                let batch = usize::try_from(batch).unwrap_or(usize::MAX);
+                rechecked += options
+                    .recheck_oldest(&mut known, batch)
+                    .unwrap_or_else(|e| exit_with(args, e));
+            }
+            _ => {
+                let (repositories, warnings) = options
+                    .scan(true, None)
+                    .unwrap_or_else(|e| exit_with(args, e));
+                print_warnings(args, &warnings);
+                known = repositories;
+                rechecked = 0;
+            }
+        }
+
+        let repositories = options.select_for_display(known.clone());
+        // 確認日時は毎回変わるので、状態の変化は確認日時を除いた出力で判断する
+        let unstamped: Vec<Repository> = repositories
+            .iter()
+            .map(|repo| repo.clone().with_checked_at(None))
+            .collect();
+        let output = options.render(options.formatter(), &unstamped);
+        if last_output.as_ref() != Some(&output) {
+            let now = SystemTime::now();
+            let formatter = options.formatter().with_checked_ages_at(now);
+            let shown = options.render(formatter, &repositories);
+            outln!("[{}]\n{shown}", format_utc(now));
+            last_output = Some(output);
+        }
+
+        // 設定の変更をすぐに反映できるよう、1秒ごとに設定ファイルの更新を確認しながら待つ
+        let started = Instant::now();
+        loop {
+            let interval = watch_args
+                .interval
+                .map_or_else(|| config.watch_interval(), Duration::from_secs)
+                .max(Duration::from_secs(1));
+            if is_interrupted() {
+                ExitCode::Interrupted.exit();
+            }
+            if started.elapsed() >= interval {
+                break;
+            }
+            std::thread::sleep(Duration::from_secs(1).min(interval - started.elapsed()));
+
+            let current = modified(&config_paths);
+            if current == config_modified {
+                continue;
+            }
+            config_modified = current;
+            match Config::load_layered(&args.config_paths()) {
+                Ok(new_config) => {
+                    for change in new_config.changes_from(&config) {
+                        let message = Message::ConfigReloaded(&change.to_string());
+                        eprintln!("{}", message.text(args.locale()));
+                    }
+                    config = new_config;
+                    // スキャン対象などが変わった可能性があるので、すぐに探索からやり直す
+                    known.clear();
+                    break;
+                }
+                Err(e) => {
+                    let message = Message::ConfigReloadFailed(&e.to_string());
+                    print_warning(args.locale(), message.text(args.locale()));
+                }
+            }
+        }
+    }
+}
+
+/// `pendector stash` の実行：変更のあるリポジトリをstashする（--popで元に戻す）
+fn run_stash(stash_args: &StashArgs, bulk: Bulk, repositories: Vec<Repository>) {
+    if stash_args.pop {
+        let targets = repositories
+            .into_iter()
+            .filter(|r| matches!(has_pendector_stash(&r.path), Ok(true)))
+            .collect();
+        apply_to_repositories(
+            targets,
+            (bulk, "stash_pop"),
+            stash_args.interactive,
+            BulkAction::StashPop,
+            |repo| pop_stash(&repo.path).map(|_| bulk.outcome(Message::Restored(&repo.name))),
+        );
+    } else {
+        let message = stash_message(SystemTime::now());
+        let targets = repositories.into_iter().filter(|r| r.has_changes).collect();
+        apply_to_repositories(
+            targets,
+            (bulk, "stash"),
+            stash_args.interactive,
+            BulkAction::Stash,
+            |repo| {
+                stash_changes(&repo.path, &message)
+                    .map(|_| bulk.outcome(Message::Stashed(&repo.name)))
+            },
+        );
+    }
+}
+
+/// `pendector wip` の実行：変更をWIPブランチにコミットする（--undoで元に戻す）
+fn run_wip(wip_args: &WipArgs, config: &Config, bulk: Bulk, repositories: Vec<Repository>) {
+    if wip_args.undo {
+        let targets = repositories
+            .into_iter()
+            .filter(|r| matches!(is_on_wip_commit(&r.path), Ok(true)))
+            .collect();
+        apply_to_repositories(
+            targets,
+            (bulk, "wip_undo"),
+            wip_args.interactive,
+            BulkAction::WipUndo,
+            |repo| {
+                undo_wip_commit(&repo.path).map(|base| {
+                    let base = base.unwrap_or_default();
+                    bulk.outcome(Message::RestoredTo {
+                        name: &repo.name,
+                        base: &base,
+                    })
+                })
+            },
+        );
+    } else {
+        // ブランチ名テンプレート：CLI引数 > 設定ファイル > 既定値
+        let template = wip_args
+            .branch_template
+            .clone()
+            .or_else(|| config.wip.as_ref().map(|w| w.branch_template.clone()))
+            .unwrap_or_else(|| DEFAULT_WIP_BRANCH_TEMPLATE.to_string());
+        let now = SystemTime::now();
+        let targets = repositories.into_iter().filter(|r| r.has_changes).collect();
+        apply_to_repositories(
+            targets,
+            (bulk, "wip"),
+            wip_args.interactive,
+            BulkAction::Wip,
+            |repo| {
+                create_wip_commit(&repo.path, &template, now).map(|branch| {
+                    bulk.outcome(Message::CommittedTo {
+                        name: &repo.name,
+                        branch: &branch,
+                    })
+                })
+            },
+        );
+    }
+}
+
+/// `pendector branches` の実行：ブランチ一覧と不要なブランチの整理
+fn run_branches(
+    branches_args: &BranchesArgs,
+    config: &Config,
+    bulk: Bulk,
+    mut repositories: Vec<Repository>,
+) {
+    repositories.sort_by(|a, b| a.path.cmp(&b.path));
+    let mut failed = false;
+
+    for repo in &repositories {
+        if is_interrupted() {
+            ExitCode::Interrupted.exit();
+        }
+        if !branches_args.stale {
+            if repo.branches.is_empty() {
+                continue;
+            }
+            outln!("{} ({})", repo.name, repo.path.display());
+            for branch in &repo.branches {
+                outln!("  {}: {}", branch.name, branch.sync_summary());
+            }
+            continue;
+        }
+
+        let stale = match find_stale_branches(&repo.path, &config.defaults.default_branches) {
+            Ok(stale) => stale,
+            Err(e) => {
+                print_error(bulk.locale, e);
+                failed = true;
+                continue;
+            }
+        };
+        if stale.is_empty() {
+            continue;
+        }
+
+        outln!("{} ({})", repo.name, repo.path.display());
+        for branch in &stale {
+            let (name, reason) = (&branch.name, &branch.reason.to_string());
+            if !branches_args.delete {
+                outln!("  {name}: {reason}");
+            } else if branches_args.dry_run {
+                let message = Message::WouldDeleteBranch { name, reason };
+                outln!("  {}", message.text(bulk.locale));
+            } else {
+                match delete_branch(&repo.path, name) {
+                    Ok(()) => {
+                        let done = bulk.outcome(Message::DeletedBranch { name, reason });
+                        outln!("  {}", done.shown);
+                        bulk.record(&repo.path, "branch_delete", AuditResult::Ok, done.logged);
+                    }
+                    Err(e) => {
+                        print_error(bulk.locale, &e);
+                        bulk.record(&repo.path, "branch_delete", AuditResult::Failed, e);
+                        failed = true;
+                    }
+                }
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::ScanError.exit();
+    }
+}
+
+/// `pendector health` の実行：ロックファイルの残っているリポジトリを報告する（--fix-locksで削除）
+///
+/// `--verbose` では参照・packの統計も表示し、`--fix` でgcが必要なリポジトリを整理する。
+fn run_health(health_args: &HealthArgs, bulk: Bulk, mut repositories: Vec<Repository>) {
+    repositories.sort_by(|a, b| a.path.cmp(&b.path));
+
+    if health_args.fix_locks || health_args.fix {
+        if health_args.fix_locks {
+            fix_lock_files(health_args, bulk, &repositories);
+        }
+        if health_args.fix {
+            let targets = repositories
+                .into_iter()
+                .filter(|repo| {
+                    stats_of(repo, bulk.locale)
+                        .is_some_and(|stats| stats.maintenance_reason().is_some())
+                })
+                .collect();
+            apply_to_repositories(
+                targets,
+                (bulk, "maintenance"),
+                health_args.interactive,
+                BulkAction::Maintenance,
+                |repo| {
+                    run_maintenance(&repo.path)?;
+                    Ok(bulk.outcome(Message::RanMaintenance(&repo.name)))
+                },
+            );
+        }
+        return;
+    }
+
+    if health_args.verbose {
+        for repo in &repositories {
+            let Some(stats) = stats_of(repo, bulk.locale) else {
+                continue;
+            };
+            outln!("{} ({})", repo.name, repo.path.display());
+            let message = Message::RepositoryStats {
+                refs: stats.refs,
+                loose_refs: stats.loose_refs,
+                packs: stats.packs,
+                pack_size: &format_size(stats.pack_size),
+                loose_objects: stats.loose_objects,
+            };
+            outln!("  {}", message.text(bulk.locale));
+            if let Some(reason) = stats.maintenance_reason() {
+                let message = Message::MaintenanceAdvised(&reason.to_string());
+                outln!("  {}", message.text(bulk.locale));
+            }
+        }
+        outln!();
+    }
+
+    let locked: Vec<&Repository> = repositories
+        .iter()
+        .filter(|r| !r.locks.is_empty())
+        .collect();
+    if locked.is_empty() {
+        outln!("{}", Message::NoLockFiles.text(bulk.locale));
+        return;
+    }
+    for repo in locked {
+        outln!("{} ({})", repo.name, repo.path.display());
+        for lock in &repo.locks {
+            let message = Message::RepositoryLocked(&lock.describe());
+            outln!("  {}", message.text(bulk.locale));
+        }
+    }
+}
+
+/// ロックファイルの残っているリポジトリから古いロックファイルを削除する
+fn fix_lock_files(health_args: &HealthArgs, bulk: Bulk, repositories: &[Repository]) {
+    let locked = repositories
+        .iter()
+        .filter(|r| !r.locks.is_empty())
+        .cloned()
+        .collect();
+    apply_to_repositories(
+        locked,
+        (bulk, "fix_locks"),
+        health_args.interactive,
+        BulkAction::FixLocks,
+        |repo| {
+            let removed = remove_stale_lock_files(&repo.path)?;
+            if removed.is_empty() {
+                return Ok(bulk.outcome(Message::KeptRecentLocks(&repo.name)));
+            }
+            let names: Vec<_> = removed.iter().map(|l| l.name.as_str()).collect();
+            Ok(bulk.outcome(Message::RemovedLocks {
+                locks: &names.join(", "),
+                name: &repo.name,
+            }))
+        },
+    );
+}
+
+/// 参照・packの統計（調べられなければ警告してNone）
+fn stats_of(repo: &Repository, locale: Locale) -> Option<RepoStats> {
+    repository_stats(&repo.path)
+        .map_err(|e| print_warning(locale, e))
+        .ok()
+}
+
+/// `pendector sync-plan` の実行：同期待ちのリポジトリを必要な操作ごとに、簡単なものから表示する
+///
+/// fetchは行わず、最後にfetchしたリモート追跡ブランチと比べる。
+fn run_sync_plan(locale: Locale, repositories: Vec<Repository>) {
+    let mut plans = Vec::new();
+    let mut failed = false;
+    for repo in repositories {
+        let Some(remote_branch) = repo
+            .remote_branch
+            .clone()
+            .filter(|_| repo.needs_pull || repo.needs_push)
+        else {
+            continue;
+        };
+        match plan_sync(&repo.path, &remote_branch) {
+            Ok(Some(plan)) => plans.push((plan, repo)),
+            Ok(None) => {}
+            Err(e) => {
+                print_error(locale, e);
+                failed = true;
+            }
+        }
+    }
+
+    if plans.is_empty() && !failed {
+        outln!("{}", Message::AllInSync.text(locale));
+    }
+    plans.sort_by(|(a, a_repo), (b, b_repo)| {
+        a.rank()
+            .cmp(&b.rank())
+            .then_with(|| a_repo.path.cmp(&b_repo.path))
+    });
+    for (i, (plan, repo)) in plans.iter().enumerate() {
+        if i == 0 || plans[i - 1].0.rank() != plan.rank() {
+            let count = plans
+                .iter()
+                .filter(|(p, _)| p.rank() == plan.rank())
+                .count();
+            if i > 0 {
+                outln!();
+            }
+            outln!("{} ({count}):", plan.label());
+        }
+        let mut line = format!("  {} ({})", repo.name, repo.path.display());
+        if let SyncPlan::Conflict { paths } = plan {
+            line.push_str(&format!(": {}", paths.join(", ")));
+        }
+        if repo.has_changes {
+            line.push_str(&format!(" {}", Message::UncommittedChanges.text(locale)));
+        }
+        outln!("{line}");
+    }
+
+    if failed {
+        ExitCode::ScanError.exit();
+    }
+}
+
+/// 各リポジトリでコマンドを実行する（失敗したものがあれば終了コード3）
+///
+/// 対象の選び方（操作の途中のリポジトリを飛ばす・`--interactive`）は他の一括操作と同じで、
+/// 選んだリポジトリでは並列に実行する。
+fn run_exec(exec_args: &ExecArgs, bulk: Bulk, repositories: Vec<Repository>) {
+    let repositories = select_targets(
+        repositories,
+        (bulk, "exec"),
+        exec_args.interactive,
+        BulkAction::Exec,
+    );
+    if repositories.is_empty() {
+        outln!(
+            "{}",
+            Message::NothingToDo(BulkAction::Exec).text(bulk.locale)
+        );
+        return;
+    }
+    let output = if exec_args.stream {
+        ExecOutput::Stream
+    } else {
+        ExecOutput::Buffered
+    };
+    let (stdout, stderr) = (PrefixedLineWriter::stdout(), PrefixedLineWriter::stderr());
+    let results = exec_in_repositories(
+        &repositories,
+        &exec_args.command,
+        output,
+        exec_args.jobs,
+        &stdout,
+        &stderr,
+    );
+    // 失敗の一覧より前にコマンドの出力を書き終える
+    let _ = stdout.finish();
+    let _ = stderr.finish();
+
+    // 結果はリポジトリと同じ順に並んでいる
+    let command = exec_args.command.join(" ");
+    for (repo, result) in repositories.iter().zip(&results) {
+        let (outcome, detail) = match &result.status {
+            Ok(status) if status.success() => (AuditResult::Ok, status.to_string()),
+            Ok(status) => (AuditResult::Failed, status.to_string()),
+            Err(e @ PendectorError::Interrupted) => (AuditResult::Skipped, e.to_string()),
+            Err(e) => (AuditResult::Failed, e.to_string()),
+        };
+        bulk.record(&repo.path, "exec", outcome, format!("{command}: {detail}"));
+    }
+
+    if is_interrupted() {
+        ExitCode::Interrupted.exit();
+    }
+
+    let failed: Vec<&ExecResult> = results.iter().filter(|r| !r.success()).collect();
+    for result in &failed {
+        match &result.status {
+            Ok(status) => eprintln!("{}: {status}", result.name),
+            Err(e) => print_error(bulk.locale, e),
+        }
+    }
+    if !failed.is_empty() {
+        let message = Message::CommandFailed {
+            failed: failed.len(),
+            total: results.len(),
+        };
+        eprintln!("{}", message.text(bulk.locale));
+        ExitCode::ScanError.exit();
+    }
+}
+
+/// 一括操作の記録先と表示の言語
+#[derive(Clone, Copy)]
+struct Bulk<'a> {
+    audit: &'a AuditLog,
+    locale: Locale,
+}
+
+/// 一括操作の結果（表示する言語の文言と、監査ログに残す英語の文言）
+struct Outcome {
+    shown: String,
+    logged: String,
+}
+
+impl Bulk<'_> {
+    /// 操作の結果を表示と監査ログのそれぞれの言語で書く
+    fn outcome(&self, message: Message) -> Outcome {
+        Outcome {
+            shown: message.text(self.locale),
+            logged: message.text(Locale::En),
+        }
+    }
+
+    /// 監査ログに1件記録する（書き込めなくても操作は続ける）
+    fn record(&self, repo: &Path, action: &str, result: AuditResult, message: impl ToString) {
+        let record = AuditRecord::new(repo, action, result, message.to_string());
+        if let Err(e) = self.audit.append(&record) {
+            print_warning(self.locale, e);
+        }
+    }
+}
+
+/// 対象リポジトリに操作を順に適用して結果を表示する（失敗があれば終了コード3）
+///
+/// 操作の結果は `(一括操作, 操作の種類)` の監査ログに記録する。
+fn apply_to_repositories(
+    targets: Vec<Repository>,
+    (bulk, action): (Bulk, &str),
+    interactive: bool,
+    kind: BulkAction,
+    operation: impl Fn(&Repository) -> PendectorResult<Outcome>,
+) {
+    let targets = select_targets(targets, (bulk, action), interactive, kind);
+    if targets.is_empty() {
+        outln!("{}", Message::NothingToDo(kind).text(bulk.locale));
+        return;
+    }
+
+    let mut failed = false;
+    for repo in &targets {
+        // 中断が要求されたら、実行中の操作を終えたところで残りには手を付けない
+        if is_interrupted() {
+            ExitCode::Interrupted.exit();
+        }
+        match operation(repo) {
+            Ok(outcome) => {
+                outln!("{} ({})", outcome.shown, repo.path.display());
+                bulk.record(&repo.path, action, AuditResult::Ok, outcome.logged);
+            }
+            Err(e) => {
+                print_error(bulk.locale, &e);
+                bulk.record(&repo.path, action, AuditResult::Failed, e);
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::ScanError.exit();
+    }
+}
+
+/// 一括操作の対象を決める：パス順に並べ、操作の途中のものを飛ばし、`interactive` なら選ばせる
+fn select_targets(
+    mut targets: Vec<Repository>,
+    (bulk, action): (Bulk, &str),
+    interactive: bool,
+    kind: BulkAction,
+) -> Vec<Repository> {
+    targets.sort_by(|a, b| a.path.cmp(&b.path));
+
+    // bisectやamの途中にあるリポジトリには手を付けない
+    targets.retain(|repo| match repo.operation {
+        Some(operation) => {
+            let operation = operation.to_string();
+            let skipped = Message::SkippedInOperation {
+                name: &repo.name,
+                operation: &operation,
+                path: &repo.path.display().to_string(),
+            };
+            eprintln!("{}", skipped.text(bulk.locale));
+            let message = Message::InProgress(&operation).text(Locale::En);
+            bulk.record(&repo.path, action, AuditResult::Skipped, message);
+            false
+        }
+        None => true,
+    });
+
+    if interactive {
+        let prompt = Message::SelectRepositories(kind).text(bulk.locale);
+        targets = match select_repositories(targets, &prompt) {
+            Ok(Some(selected)) => selected,
+            Ok(None) => {
+                // 取り消したときは何もせず、対象がなかった場合と区別できる終了コードにする
+                eprintln!("{}", Message::SelectionCancelled.text(bulk.locale));
+                ExitCode::Interrupted.exit();
+            }
+            Err(e) => {
+                print_error(bulk.locale, e);
+                ExitCode::Usage.exit();
+            }
+        };
+    }
+    targets
+}
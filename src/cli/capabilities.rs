@@ -0,0 +1,157 @@
+use crate::output::{IconStyle, OutputFormat};
+use crate::time::format_utc;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::time::{Duration, UNIX_EPOCH};
+
+/// ビルドで有効になっているcargoフィーチャー
+const FEATURES: &[(&str, bool)] = &[
+    ("test-util", cfg!(feature = "test-util")),
+    ("static", cfg!(feature = "static")),
+];
+
+/// 有効になっているフィーチャーの名前
+fn enabled_features() -> Vec<&'static str> {
+    FEATURES
+        .iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(name, _)| *name)
+        .collect()
+}
+
+/// `--version` で表示するビルドの情報（build.rsで埋め込む）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub commit: &'static str,
+    /// ビルド日時（UTCのRFC 3339形式）
+    pub built_at: String,
+    pub target: &'static str,
+    pub profile: &'static str,
+    pub features: Vec<&'static str>,
+}
+
+impl BuildInfo {
+    /// 実行中のバイナリのビルド情報
+    pub fn current() -> Self {
+        let epoch = env!("PENDECTOR_BUILD_EPOCH").parse().unwrap_or(0);
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            commit: env!("PENDECTOR_GIT_COMMIT"),
+            built_at: format_utc(UNIX_EPOCH + Duration::from_secs(epoch)),
+            target: env!("PENDECTOR_BUILD_TARGET"),
+            profile: env!("PENDECTOR_BUILD_PROFILE"),
+            features: enabled_features(),
+        }
+    }
+
+    /// バージョンの表示（`verbose` ならバグ報告に使えるようビルドの詳細を加える）
+    pub fn render(&self, verbose: bool) -> String {
+        let mut output = format!("pendector {}", self.version);
+        if verbose {
+            let features = if self.features.is_empty() {
+                "(none)".to_string()
+            } else {
+                self.features.join(", ")
+            };
+            output.push_str(&format!(
+                "\ncommit: {}\nbuilt: {}\ntarget: {}\nprofile: {}\nfeatures: {features}",
+                self.commit, self.built_at, self.target, self.profile
+            ));
+        }
+        output
+    }
+}
+
+/// ラッパーツール向けに、インストールされたビルドの機能を表す
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Capabilities {
+    pub version: &'static str,
+    pub features: Vec<&'static str>,
+    pub output_formats: Vec<&'static str>,
+    pub icon_styles: Vec<String>,
+}
+
+impl Capabilities {
+    /// 実行中のバイナリの機能を集める
+    pub fn current() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            features: enabled_features(),
+            output_formats: OutputFormat::value_variants()
+                .iter()
+                .map(|format| format.as_str())
+                .collect(),
+            icon_styles: IconStyle::value_variants()
+                .iter()
+                .filter_map(|style| style.to_possible_value())
+                .map(|value| value.get_name().to_string())
+                .collect(),
+        }
+    }
+
+    /// `format` がJSON形式ならJSON、それ以外は人が読むためのテキストで表す
+    pub fn render(&self, format: OutputFormat) -> String {
+        if format.is_json() {
+            return serde_json::to_string_pretty(self)
+                .unwrap_or_else(|e| format!("Error serializing to JSON: {e}"));
+        }
+        let list = |items: &[&str]| {
+            if items.is_empty() {
+                "(none)".to_string()
+            } else {
+                items.join(", ")
+            }
+        };
+        let icon_styles: Vec<&str> = self.icon_styles.iter().map(String::as_str).collect();
+        format!(
+            "pendector {}\nfeatures: {}\noutput formats: {}\nicon styles: {}",
+            self.version,
+            list(&self.features),
+            list(&self.output_formats),
+            list(&icon_styles)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capabilities_render() {
+        let capabilities = Capabilities {
+            version: "1.2.3",
+            features: Vec::new(),
+            output_formats: vec!["text", "json"],
+            icon_styles: vec!["unicode".to_string(), "ascii".to_string()],
+        };
+        assert_eq!(
+            capabilities.render(OutputFormat::Text),
+            "pendector 1.2.3\nfeatures: (none)\noutput formats: text, json\nicon styles: unicode, ascii"
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&capabilities.render(OutputFormat::Json)).unwrap();
+        assert_eq!(json["version"], "1.2.3");
+        assert_eq!(json["output_formats"][1], "json");
+    }
+
+    #[test]
+    fn test_build_info_render() {
+        let info = BuildInfo {
+            version: "1.2.3",
+            commit: "0123456789ab",
+            built_at: "2026-10-16T00:00:00Z".to_string(),
+            target: "x86_64-unknown-linux-musl",
+            profile: "release",
+            features: vec!["static"],
+        };
+        assert_eq!(info.render(false), "pendector 1.2.3");
+        assert_eq!(
+            info.render(true),
+            "pendector 1.2.3\ncommit: 0123456789ab\nbuilt: 2026-10-16T00:00:00Z\n\
+             target: x86_64-unknown-linux-musl\nprofile: release\nfeatures: static"
+        );
+    }
+}
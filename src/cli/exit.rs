@@ -0,0 +1,70 @@
+/// `--help` に表示する終了コードの一覧
+pub const EXIT_CODES_HELP: &str = "\
+Exit codes:
+  0  Success (no findings, or --check/--report not given)
+  1  Findings present (with --check or --report)
+  2  Usage or configuration error
+  3  Scan or repository operation error
+  4  Interrupted";
+
+/// プロセスの終了コード
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    /// 正常終了（--check指定時は検出なし）
+    Success = 0,
+    /// --check指定時に対応が必要なリポジトリがある
+    Findings = 1,
+    /// CLI引数や設定の誤り
+    Usage = 2,
+    /// スキャン中やリポジトリ操作中のエラー
+    ScanError = 3,
+    /// シグナルや対象の選択の取り消しによる中断
+    Interrupted = 4,
+}
+
+impl ExitCode {
+    /// 数値の終了コード
+    pub fn code(self) -> i32 {
+        self as i32
+    }
+
+    /// この終了コードでプロセスを終了する
+    pub fn exit(self) -> ! {
+        std::process::exit(self.code())
+    }
+}
+
+/// SIGINT/SIGTERMを受けたら中断を要求する（2回目はすぐに終了コード4で終了する）
+///
+/// 走査や一括操作は [`crate::interrupt::is_interrupted`] を確かめて後始末をしてから終了する。
+pub fn install_interrupt_handler() {
+    #[cfg(unix)]
+    unsafe {
+        let handler = on_interrupt as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        libc::signal(libc::SIGINT, handler);
+        libc::signal(libc::SIGTERM, handler);
+    }
+}
+
+#[cfg(unix)]
+extern "C" fn on_interrupt(_signal: libc::c_int) {
+    // 後始末が終わらないときのために、2回目は待たずに終了する
+    // （シグナルハンドラ内では非同期シグナル安全な関数しか呼べないため_exitを使う）
+    if crate::interrupt::request_interrupt() {
+        unsafe { libc::_exit(ExitCode::Interrupted.code()) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_code_values() {
+        assert_eq!(ExitCode::Success.code(), 0);
+        assert_eq!(ExitCode::Findings.code(), 1);
+        assert_eq!(ExitCode::Usage.code(), 2);
+        assert_eq!(ExitCode::ScanError.code(), 3);
+        assert_eq!(ExitCode::Interrupted.code(), 4);
+    }
+}
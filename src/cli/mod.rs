@@ -1,8 +1,37 @@
-use clap::Parser;
+mod app;
+mod capabilities;
+mod exit;
+mod options;
+
+pub use app::main;
+pub use capabilities::{BuildInfo, Capabilities};
+pub use exit::{install_interrupt_handler, ExitCode, EXIT_CODES_HELP};
+pub use options::{explain_excludes, resolve_options, EffectiveOptions, Overrides};
+
+use crate::core::{Attention, ProjectType};
+use crate::output::{IconStyle, Locale, OutputFormat};
+use crate::progress::ProgressMode;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand};
+use std::collections::BTreeSet;
+use std::path::Path;
 
 #[derive(Parser, Debug)]
-#[command(version, about, long_about = None)]
+#[command(
+    version,
+    disable_version_flag = true,
+    about,
+    long_about = None,
+    after_help = EXIT_CODES_HELP
+)]
 pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Print version (with --verbose, also the commit, build date, target and features)
+    #[arg(short = 'V', long)]
+    pub version: bool,
+
     /// Base directories to scan for repositories
     pub paths: Vec<String>,
 
@@ -14,40 +43,159 @@ pub struct Args {
     #[arg(short = 'c', long)]
     pub changes_only: bool,
 
-    /// Enable verbose output
-    #[arg(short, long)]
-    pub verbose: bool,
+    /// Show only repositories in the given attention classes (none, dirty, unsynced, error, in-operation)
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub attention: Vec<Attention>,
+
+    /// Show only repositories of the given project types (rust, node, go, python), detected
+    /// from Cargo.toml, package.json, go.mod or pyproject.toml at the top of the work tree
+    #[arg(long = "type", value_enum, value_delimiter = ',')]
+    pub project_type: Vec<ProjectType>,
+
+    /// Exit with status 1 when any repository has pending changes
+    #[arg(long)]
+    pub check: bool,
+
+    /// Print a concise report only when repositories need attention (implies --check)
+    #[arg(long)]
+    pub report: bool,
+
+    /// Re-check only the repositories that needed attention in the last report (dirty, unsynced
+    /// or errored), plus paths not scanned last time, instead of scanning every root again
+    #[arg(long)]
+    pub recheck: bool,
+
+    /// Enable verbose output (-vv also logs why each repository was kept or filtered out)
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Maximum depth for recursive directory search [default: 3]
+    ///
+    /// Repeat to give each path its own depth (matched positionally), or use PATH:DEPTH.
+    #[arg(short = 'd', long, global = true)]
+    pub max_depth: Vec<usize>,
 
-    /// Maximum depth for recursive directory search
-    #[arg(short = 'd', long, default_value = "3")]
-    pub max_depth: usize,
+    /// List remote branches that have no local branch (verbose output only)
+    #[arg(long)]
+    pub remote_branches: bool,
+
+    /// Show how much disk space each repository uses
+    #[arg(long)]
+    pub du: bool,
+
+    /// Report large git-ignored directories (build artifacts such as target/ or node_modules/)
+    #[arg(long)]
+    pub artifacts: bool,
 
-    /// Output format: text or json
-    #[arg(short = 'f', long, default_value = "text")]
-    pub format: String,
+    /// Show the first N lines of the working-tree diff of each changed repository
+    #[arg(long, value_name = "N", requires = "verbose")]
+    pub preview: Option<usize>,
+
+    /// Report projects cloned in more than one location, with the state of each clone
+    #[arg(long, conflicts_with = "report")]
+    pub duplicates: bool,
+
+    /// Highlight uncommitted changes older than this many days [default: 7]
+    #[arg(long)]
+    pub dirty_warn_days: Option<u64>,
+
+    /// Fit text output to this many columns [default: terminal width]
+    #[arg(long)]
+    pub width: Option<usize>,
+
+    /// Symbols in text output [default: unicode]
+    #[arg(long, value_enum)]
+    pub icons: Option<IconStyle>,
+
+    /// Show repository paths as discovered instead of resolving symlinks
+    #[arg(long)]
+    pub no_resolve_symlinks: bool,
+
+    /// Make repository names and paths clickable terminal hyperlinks (OSC 8) in text output
+    #[arg(long)]
+    pub hyperlinks: bool,
+
+    /// Screen-reader friendly text: no color, symbols, path shortening or progress bar;
+    /// states are spelled out ("needs push")
+    #[arg(long)]
+    pub plain: bool,
+
+    /// Language of headers, summaries and errors [default: from LC_ALL, LC_MESSAGES or LANG]
+    #[arg(long, value_enum)]
+    pub lang: Option<Locale>,
+
+    /// Output format
+    #[arg(short = 'f', long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
 
     /// Fetch from remote before checking sync status
     #[arg(long)]
     pub fetch: bool,
 
+    /// Never fetch, even when the config enables it, and mark sync status as possibly stale
+    #[arg(long, conflicts_with = "fetch")]
+    pub offline: bool,
+
+    /// Only look at working-tree changes: skip ahead/behind checks against the remote (and
+    /// fetching), for air-gapped machines or repositories with very many refs
+    #[arg(long, conflicts_with = "fetch")]
+    pub no_remote_check: bool,
+
     /// Timeout for fetch operations in seconds
     #[arg(long, default_value = "5")]
     pub fetch_timeout: u64,
 
-    /// Path to configuration file
+    /// Report fetches still running after this many seconds
+    #[arg(long, default_value = "15")]
+    pub fetch_slow_threshold: u64,
+
+    /// Abandon fetches that exceed --fetch-slow-threshold instead of waiting for the timeout
     #[arg(long)]
-    pub config: Option<String>,
+    pub fetch_skip_slow: bool,
 
-    /// Ignore configuration file
+    /// Skip directories that can't be read for lack of permission without warning
+    #[arg(long, conflicts_with = "strict")]
+    pub skip_permission_errors: bool,
+
+    /// Warn about scan roots that don't exist and keep scanning the others
     #[arg(long)]
+    pub skip_missing_paths: bool,
+
+    /// Fail the scan when any directory can't be read (for CI)
+    #[arg(long)]
+    pub strict: bool,
+
+    /// Skip fetching repositories that git maintenance already prefetches on a schedule
+    #[arg(long)]
+    pub trust_maintenance: bool,
+
+    /// Follow symbolic links while searching (repositories reached twice are reported once)
+    #[arg(long, global = true)]
+    pub follow_symlinks: bool,
+
+    /// Also compare HEAD with this remote's default branch (e.g. upstream for forks)
+    #[arg(long, value_name = "REMOTE")]
+    pub compare_remote: Option<String>,
+
+    /// Do not take the lock that prevents overlapping fetching runs
+    #[arg(long)]
+    pub no_lock: bool,
+
+    /// Path to configuration file, or - to read it from stdin (can be specified multiple times;
+    /// later files override earlier ones)
+    #[arg(long, global = true)]
+    pub config: Vec<String>,
+
+    /// Ignore configuration file
+    #[arg(long, global = true)]
     pub no_config: bool,
 
     /// Exclude patterns (can be specified multiple times)
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub exclude: Vec<String>,
 
     /// Ignore exclude patterns from configuration file
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub no_exclude: bool,
 
     /// Send notification to Slack
@@ -62,7 +210,418 @@ pub struct Args {
     #[arg(long)]
     pub slack_notify_always: bool,
 
-    /// Sort output by key (path)
-    #[arg(long, default_value = "path")]
-    pub sort: String,
+    /// Progress display: bar, json (newline-delimited events on stderr), or none
+    #[arg(long, value_enum, default_value_t = ProgressMode::Bar)]
+    pub progress: ProgressMode,
+
+    /// Seconds between plain-text progress lines when stderr is not a terminal (default: 10)
+    #[arg(long, value_name = "SECS")]
+    pub progress_interval: Option<u64>,
+
+    /// Sort output by key
+    #[arg(long, value_enum, value_name = "KEY", default_value_t = SortKey::Path)]
+    pub sort: SortKey,
+
+    /// Split text output into one section per scan root
+    #[arg(long, value_enum, value_name = "KEY")]
+    pub group_by: Option<GroupBy>,
+
+    /// Stop after N repositories (the first N in path order) for quick spot checks
+    #[arg(long, value_name = "N")]
+    pub limit: Option<usize>,
+
+    /// Show only the N repositories most in need of action (errors, interrupted operations,
+    /// uncommitted changes, then unsynced commits; longest neglected first)
+    #[arg(long, value_name = "N")]
+    pub top: Option<usize>,
+
+    /// コマンドラインで明示的に指定された引数のID（既定値と同じ値の指定も区別する）
+    #[arg(skip)]
+    pub explicit: BTreeSet<String>,
+}
+
+/// `--sort` で指定する表示の並び順
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SortKey {
+    /// Repository path
+    #[default]
+    Path,
+    /// Stale-dirty, then idle, then active; oldest first
+    Staleness,
+}
+
+/// `--group-by` で出力を分ける単位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum GroupBy {
+    /// リポジトリを見つけたスキャンルート
+    Root,
+}
+
+/// サブコマンド（省略時はリポジトリの状態を表示する）
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Stash working-tree changes across repositories (restore them with --pop)
+    Stash(StashArgs),
+    /// Commit uncommitted changes onto throwaway wip branches (undo with --undo)
+    Wip(WipArgs),
+    /// List local branches and find stale ones to clean up
+    Branches(BranchesArgs),
+    /// Check repositories for problems such as leftover lock files
+    Health(HealthArgs),
+    /// Show what syncing each unsynced repository takes (push only, fast-forward, clean rebase
+    /// or conflicts), easiest first, without touching the work trees
+    SyncPlan(SyncPlanArgs),
+    /// Run a command in every repository in parallel (e.g. `pendector exec ~/src -- git gc`)
+    Exec(ExecArgs),
+    /// Rescan periodically and print the status whenever it changes (reloads the config file)
+    Watch(WatchArgs),
+    /// Print the version, enabled features and supported output formats (use --format json)
+    Capabilities,
+    /// Show which exclude pattern, and from where, excludes or keeps a path
+    ExplainExcludes(ExplainExcludesArgs),
+}
+
+impl Command {
+    /// サブコマンドに指定されたスキャン対象パス
+    pub fn paths(&self) -> &[String] {
+        match self {
+            Command::Stash(args) => &args.paths,
+            Command::Wip(args) => &args.paths,
+            Command::Branches(args) => &args.paths,
+            Command::Health(args) => &args.paths,
+            Command::SyncPlan(args) => &args.paths,
+            Command::Exec(args) => &args.paths,
+            Command::Watch(args) => &args.paths,
+            Command::Capabilities | Command::ExplainExcludes(_) => &[],
+        }
+    }
+}
+
+#[derive(clap::Args, Debug)]
+pub struct StashArgs {
+    /// Base directories to scan for repositories
+    pub paths: Vec<String>,
+
+    /// Restore the latest stash created by `pendector stash` instead
+    #[arg(long)]
+    pub pop: bool,
+
+    /// Choose the target repositories interactively
+    #[arg(short, long)]
+    pub interactive: bool,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct WipArgs {
+    /// Base directories to scan for repositories
+    pub paths: Vec<String>,
+
+    /// Return to the original branch, keeping the changes uncommitted, and delete the wip branch
+    #[arg(long)]
+    pub undo: bool,
+
+    /// Branch name template; {branch} and {date} are substituted [default: wip/{branch}-{date}]
+    #[arg(long)]
+    pub branch_template: Option<String>,
+
+    /// Choose the target repositories interactively
+    #[arg(short, long)]
+    pub interactive: bool,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct BranchesArgs {
+    /// Base directories to scan for repositories
+    pub paths: Vec<String>,
+
+    /// Show only branches merged into the default branch or whose upstream is gone
+    #[arg(long)]
+    pub stale: bool,
+
+    /// Delete the stale branches
+    #[arg(long, requires = "stale")]
+    pub delete: bool,
+
+    /// Show what --delete would remove without deleting anything
+    #[arg(long, requires = "delete")]
+    pub dry_run: bool,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(group = clap::ArgGroup::new("fixes").args(["fix_locks", "fix"]).multiple(true))]
+pub struct HealthArgs {
+    /// Base directories to scan for repositories
+    pub paths: Vec<String>,
+
+    /// Also show ref counts, packs and loose objects, and whether `git gc` would help
+    #[arg(short, long)]
+    pub verbose: bool,
+
+    /// Remove lock files left behind by interrupted git commands (older than 10 minutes)
+    #[arg(long)]
+    pub fix_locks: bool,
+
+    /// Run `git maintenance run` in repositories that would benefit from `git gc`
+    #[arg(long)]
+    pub fix: bool,
+
+    /// Choose the target repositories interactively (with --fix-locks or --fix)
+    #[arg(short, long, requires = "fixes")]
+    pub interactive: bool,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct SyncPlanArgs {
+    /// Base directories to scan for repositories
+    pub paths: Vec<String>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ExecArgs {
+    /// Base directories to scan for repositories
+    pub paths: Vec<String>,
+
+    /// Print output as it arrives, prefixing each line with `[repo]`, instead of one block per repository
+    #[arg(long)]
+    pub stream: bool,
+
+    /// Number of repositories to run the command in at once (default: number of CPUs)
+    #[arg(short, long, value_name = "N")]
+    pub jobs: Option<usize>,
+
+    /// Choose the target repositories interactively
+    #[arg(short, long)]
+    pub interactive: bool,
+
+    /// Command to run in each repository's working tree
+    #[arg(last = true, required = true, value_name = "COMMAND")]
+    pub command: Vec<String>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ExplainExcludesArgs {
+    /// Path to check
+    pub path: String,
+
+    /// Scan root to evaluate patterns against, as if given on the command line
+    /// (default: the innermost configured path containing PATH)
+    #[arg(long)]
+    pub root: Option<String>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct WatchArgs {
+    /// Base directories to scan for repositories
+    pub paths: Vec<String>,
+
+    /// Seconds between scans (overrides [watch] interval_secs; default: 60)
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub interval: Option<u64>,
+
+    /// Re-check only the N repositories with the oldest data each interval, looking for new
+    /// repositories after every full round (default: rescan everything each interval)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub batch: Option<u64>,
+}
+
+impl Args {
+    /// 進捗の表示方式（`--plain` ではプログレスバーを出さない）
+    pub fn progress_mode(&self) -> ProgressMode {
+        match self.progress {
+            ProgressMode::Bar if self.plain => ProgressMode::None,
+            mode => mode,
+        }
+    }
+
+    /// メッセージの言語（`--lang` がなければ環境変数から決める）
+    pub fn locale(&self) -> Locale {
+        self.lang.unwrap_or_else(Locale::from_env)
+    }
+
+    /// 詳細を表示するか（`-v`）
+    pub fn is_verbose(&self) -> bool {
+        self.verbose > 0
+    }
+
+    /// リポジトリを表示に残した・除いた理由を出力するか（`-vv`）
+    pub fn explain_filtering(&self) -> bool {
+        self.verbose > 1
+    }
+
+    /// CLIで指定された設定ファイルのパス（指定した順）
+    pub fn config_paths(&self) -> Vec<&Path> {
+        self.config.iter().map(Path::new).collect()
+    }
+
+    /// スキャン対象としてCLIで指定されたパス（サブコマンドに指定されたものを優先）
+    pub fn target_paths(&self) -> &[String] {
+        match &self.command {
+            Some(command) if !command.paths().is_empty() => command.paths(),
+            _ => &self.paths,
+        }
+    }
+
+    /// CLIで指定されたスキャンルートと、それぞれに明示された深さ
+    ///
+    /// `PATH:DEPTH` 形式が最優先で、次に複数回指定された `-d` を位置で対応付ける。
+    pub fn scan_roots(&self) -> Result<Vec<(String, Option<usize>)>, String> {
+        let paths = self.target_paths();
+        let positional_depths = self.max_depth.len() > 1;
+        if positional_depths && self.max_depth.len() != paths.len() {
+            return Err(format!(
+                "--max-depth was given {} times but {} paths were specified",
+                self.max_depth.len(),
+                paths.len()
+            ));
+        }
+
+        Ok(paths
+            .iter()
+            .enumerate()
+            .map(|(i, spec)| {
+                let (path, depth) = split_depth_suffix(spec);
+                let depth = depth.or_else(|| {
+                    positional_depths
+                        .then(|| self.max_depth.get(i).copied())
+                        .flatten()
+                });
+                (path, depth)
+            })
+            .collect())
+    }
+
+    /// コマンドライン引数を解析し、明示的に指定された引数を記録する
+    pub fn parse_with_sources() -> Self {
+        Self::from_matches(&Self::command().get_matches()).unwrap_or_else(|e| e.exit())
+    }
+
+    /// 引数の列を解析し、明示的に指定された引数を記録する
+    #[cfg(test)]
+    pub fn try_parse_with_sources_from<I, T>(iter: I) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<std::ffi::OsString> + Clone,
+    {
+        Self::from_matches(&Self::command().try_get_matches_from(iter)?)
+    }
+
+    fn from_matches(matches: &ArgMatches) -> Result<Self, clap::Error> {
+        let mut args = Self::from_arg_matches(matches)?;
+        args.explicit = matches
+            .ids()
+            .filter(|id| matches.value_source(id.as_str()) == Some(ValueSource::CommandLine))
+            .map(|id| id.to_string())
+            .collect();
+        Ok(args)
+    }
+
+    /// 引数 `id`（フィールド名）がコマンドラインで明示的に指定されたか
+    pub fn is_explicit(&self, id: &str) -> bool {
+        self.explicit.contains(id)
+    }
+
+    /// すべてのパスに共通する深さ（`-d` が1回だけ指定された場合）
+    pub fn global_max_depth(&self) -> Option<usize> {
+        match self.max_depth.as_slice() {
+            [depth] => Some(*depth),
+            _ => None,
+        }
+    }
+}
+
+/// `PATH:DEPTH` 形式の指定からパスと深さを分離する
+///
+/// 指定全体が既存のパスとして存在する場合や、`:` 以降が数値でない場合は分離しない。
+pub fn split_depth_suffix(spec: &str) -> (String, Option<usize>) {
+    if let Some((path, depth)) = spec.rsplit_once(':') {
+        if !path.is_empty() && !std::path::Path::new(spec).exists() {
+            if let Ok(depth) = depth.parse::<usize>() {
+                return (path.to_string(), Some(depth));
+            }
+        }
+    }
+    (spec.to_string(), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Args {
+        Args::parse_from(std::iter::once("pendector").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn test_stash_subcommand_paths() {
+        let args = parse(&["--no-config", "stash", "--pop", "/nonexistent/a"]);
+        match &args.command {
+            Some(Command::Stash(stash)) => assert!(stash.pop && !stash.interactive),
+            other => panic!("unexpected command: {other:?}"),
+        }
+        assert!(args.no_config);
+        assert_eq!(args.target_paths(), ["/nonexistent/a".to_string()]);
+
+        let args = parse(&["/nonexistent/a", "/nonexistent/b"]);
+        assert!(args.command.is_none());
+        assert_eq!(args.target_paths().len(), 2);
+    }
+
+    #[test]
+    fn test_split_depth_suffix() {
+        assert_eq!(
+            split_depth_suffix("~/src:2"),
+            ("~/src".to_string(), Some(2))
+        );
+        assert_eq!(split_depth_suffix("~/src"), ("~/src".to_string(), None));
+        assert_eq!(
+            split_depth_suffix("/tmp/a:b"),
+            ("/tmp/a:b".to_string(), None)
+        );
+        assert_eq!(split_depth_suffix(":3"), (":3".to_string(), None));
+    }
+
+    #[test]
+    fn test_scan_roots_with_suffix() {
+        let args = parse(&["/nonexistent/src:2", "/nonexistent/work"]);
+        assert_eq!(
+            args.scan_roots().unwrap(),
+            vec![
+                ("/nonexistent/src".to_string(), Some(2)),
+                ("/nonexistent/work".to_string(), None)
+            ]
+        );
+        assert_eq!(args.global_max_depth(), None);
+    }
+
+    #[test]
+    fn test_scan_roots_with_repeated_depth() {
+        let args = parse(&["-d", "2", "-d", "5", "/nonexistent/a", "/nonexistent/b:7"]);
+        assert_eq!(
+            args.scan_roots().unwrap(),
+            vec![
+                ("/nonexistent/a".to_string(), Some(2)),
+                ("/nonexistent/b".to_string(), Some(7))
+            ]
+        );
+        assert_eq!(args.global_max_depth(), None);
+    }
+
+    #[test]
+    fn test_scan_roots_with_single_depth() {
+        let args = parse(&["-d", "4", "/nonexistent/a", "/nonexistent/b"]);
+        assert_eq!(
+            args.scan_roots().unwrap(),
+            vec![
+                ("/nonexistent/a".to_string(), None),
+                ("/nonexistent/b".to_string(), None)
+            ]
+        );
+        assert_eq!(args.global_max_depth(), Some(4));
+    }
+
+    #[test]
+    fn test_scan_roots_depth_count_mismatch() {
+        let args = parse(&["-d", "2", "-d", "5", "/nonexistent/a"]);
+        assert!(args.scan_roots().is_err());
+    }
 }
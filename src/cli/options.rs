@@ -0,0 +1,467 @@
+use super::Args;
+use crate::config::{expand_tilde, Config, PathConfigResolved};
+use crate::core::Priority;
+use crate::exclude::{ExcludeFilter, ExcludeMatch};
+use crate::output::OutputFormat;
+use crate::path_style::canonicalize_path;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// 設定ファイルの値に重ねる指定（CLI引数など。指定しなければ設定ファイルの値を使う）
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct Overrides {
+    /// すべてのスキャンルートに共通する探索の深さ
+    pub max_depth: Option<usize>,
+    /// 設定にかかわらずfetchする
+    pub fetch: bool,
+    /// fetchしない（設定ファイルの `fetch = true` より優先する）
+    pub offline: bool,
+    /// fetchのタイムアウト（秒）
+    pub fetch_timeout: Option<u64>,
+    /// シンボリックリンクをたどって探索する
+    pub follow_symlinks: bool,
+    /// 設定ファイルのパターンの後に加える除外パターン
+    pub exclude_patterns: Vec<String>,
+    /// 設定ファイルの除外パターンを使わない
+    pub no_config_excludes: bool,
+    /// 変更のあるリポジトリだけを表示する
+    pub changes_only: bool,
+    /// 出力形式
+    pub format: Option<OutputFormat>,
+    /// 詳細を表示する
+    pub verbose: bool,
+    /// スキャンルートにパス固有設定を適用するか（スキャンルートを置き換えた場合はデフォルト設定のみ使う）
+    pub path_configs: bool,
+}
+
+impl Default for Overrides {
+    fn default() -> Self {
+        Self {
+            max_depth: None,
+            fetch: false,
+            offline: false,
+            fetch_timeout: None,
+            follow_symlinks: false,
+            exclude_patterns: Vec::new(),
+            no_config_excludes: false,
+            changes_only: false,
+            format: None,
+            verbose: false,
+            path_configs: true,
+        }
+    }
+}
+
+impl Overrides {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// CLI引数で指定された値（既定値と同じ値でも、明示的に指定されていれば設定ファイルより優先する）
+    pub(crate) fn from_args(args: &Args) -> Self {
+        Self {
+            max_depth: args.global_max_depth(),
+            fetch: args.fetch,
+            offline: args.offline,
+            fetch_timeout: Some(args.fetch_timeout).filter(|_| args.is_explicit("fetch_timeout")),
+            follow_symlinks: args.follow_symlinks,
+            exclude_patterns: args.exclude.clone(),
+            no_config_excludes: args.no_exclude,
+            changes_only: args.changes_only,
+            format: Some(args.format).filter(|_| args.is_explicit("format")),
+            verbose: args.is_verbose(),
+            // CLI引数でスキャンルートを置き換えた場合は、設定ファイルのパスでもパス固有設定を使わない
+            path_configs: args.target_paths().is_empty() || args.add_path,
+        }
+    }
+
+    /// 探索の深さを設定する
+    pub fn with_max_depth(mut self, max_depth: Option<usize>) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// 設定にかかわらずfetchするかを設定する
+    pub fn with_fetch(mut self, fetch: bool) -> Self {
+        self.fetch = fetch;
+        self
+    }
+
+    /// fetchしないかを設定する
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// fetchのタイムアウト（秒）を設定する
+    pub fn with_fetch_timeout(mut self, fetch_timeout: Option<u64>) -> Self {
+        self.fetch_timeout = fetch_timeout;
+        self
+    }
+
+    /// シンボリックリンクをたどるかを設定する
+    pub fn with_follow_symlinks(mut self, follow_symlinks: bool) -> Self {
+        self.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    /// 設定ファイルのパターンの後に加える除外パターンを設定する
+    pub fn with_exclude_patterns(mut self, exclude_patterns: Vec<String>) -> Self {
+        self.exclude_patterns = exclude_patterns;
+        self
+    }
+
+    /// 設定ファイルの除外パターンを使わないかを設定する
+    pub fn with_no_config_excludes(mut self, no_config_excludes: bool) -> Self {
+        self.no_config_excludes = no_config_excludes;
+        self
+    }
+
+    /// 変更のあるリポジトリだけを表示するかを設定する
+    pub fn with_changes_only(mut self, changes_only: bool) -> Self {
+        self.changes_only = changes_only;
+        self
+    }
+
+    /// 出力形式を設定する
+    pub fn with_format(mut self, format: Option<OutputFormat>) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// 詳細を表示するかを設定する
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// スキャンルートにパス固有設定を適用するかを設定する
+    pub fn with_path_configs(mut self, path_configs: bool) -> Self {
+        self.path_configs = path_configs;
+        self
+    }
+}
+
+/// スキャンルートに適用する実効的な設定（CLI引数 > パス固有設定 > デフォルト設定）
+#[derive(Debug, Clone)]
+pub struct EffectiveOptions {
+    /// 探索の深さ（`PATH:DEPTH` などルートごとの指定は呼び出し側で優先する）
+    pub max_depth: usize,
+    /// fetchするか
+    pub fetch: bool,
+    /// fetchのタイムアウト（秒）
+    pub fetch_timeout: u64,
+    /// シンボリックリンクをたどって探索するか
+    pub follow_symlinks: bool,
+    /// 除外パターン（後のものほど優先される）
+    pub exclude_patterns: Vec<String>,
+    /// 変更のあるリポジトリだけを表示するか
+    pub changes_only: bool,
+    /// 出力形式
+    pub format: OutputFormat,
+    /// 詳細を表示するか
+    pub verbose: bool,
+    /// CLI引数で上書きしない設定値（並列数や優先度など）
+    pub path_config: PathConfigResolved,
+}
+
+impl EffectiveOptions {
+    /// 設定ファイルから決まる値に指定を重ねる
+    pub fn from_path_config(overrides: &Overrides, path_config: PathConfigResolved) -> Self {
+        let exclude_patterns = if overrides.no_config_excludes {
+            // --no-excludeフラグがある場合は設定ファイルの除外パターンを無視
+            overrides.exclude_patterns.clone()
+        } else {
+            // 設定ファイルのパターンの後にCLI引数を連結する（CLI引数が最も優先される）
+            ExcludeFilter::ordered_patterns(&[
+                &path_config.exclude_patterns,
+                &overrides.exclude_patterns,
+            ])
+        };
+        Self {
+            max_depth: overrides.max_depth.unwrap_or(path_config.max_depth),
+            // --offline は設定ファイルの fetch = true より優先する
+            fetch: !overrides.offline && (overrides.fetch || path_config.fetch),
+            fetch_timeout: overrides.fetch_timeout.unwrap_or(path_config.fetch_timeout),
+            follow_symlinks: overrides.follow_symlinks || path_config.follow_symlinks,
+            exclude_patterns,
+            changes_only: overrides.changes_only || path_config.changes_only,
+            format: overrides.format.unwrap_or(path_config.format),
+            verbose: overrides.verbose || path_config.verbose,
+            path_config,
+        }
+    }
+}
+
+/// スキャンルート `path` に適用する設定を解決する
+pub fn resolve_options(overrides: &Overrides, config: &Config, path: &str) -> EffectiveOptions {
+    EffectiveOptions::from_path_config(overrides, path_config_for(overrides, config, path))
+}
+
+/// スキャンルートに適用する設定（パス固有設定を使わない場合はデフォルト設定のみ）
+fn path_config_for(overrides: &Overrides, config: &Config, path: &str) -> PathConfigResolved {
+    if overrides.path_configs {
+        // 設定ファイルのパスを使用している場合はパス固有設定を適用
+        config.get_path_config(path)
+    } else {
+        // CLI引数で上書きした場合はデフォルト設定のみ使用
+        PathConfigResolved {
+            max_depth: config.defaults.max_depth,
+            fetch: config.defaults.fetch,
+            fetch_timeout: config.defaults.fetch_timeout,
+            format: config.defaults.format,
+            verbose: config.defaults.verbose,
+            changes_only: config.defaults.changes_only,
+            follow_symlinks: config.defaults.follow_symlinks,
+            exclude_patterns: config.defaults.exclude_patterns.clone(),
+            exclude_repos: config.defaults.exclude_repos.clone(),
+            jobs: config.defaults.jobs,
+            fetch_jobs: config.defaults.fetch_jobs,
+            time_budget: config.defaults.time_budget,
+            hide_clean: false,
+            priority: Priority::Normal,
+            fetch_env: config.defaults.fetch_env.clone(),
+            fetch_env_passthrough: config.defaults.fetch_env_passthrough.clone(),
+            name_style: config.defaults.name_style,
+            proxy: config.defaults.proxy.clone(),
+            ssh_proxy_command: config.defaults.ssh_proxy_command.clone(),
+            dirty_ignore: config.defaults.dirty_ignore.clone(),
+        }
+    }
+}
+
+/// パスが除外されるかどうかと、それを決めたパターンの説明（`explain-excludes`）
+#[derive(Debug)]
+pub struct ExcludeExplanation {
+    /// 調べたパス
+    pub path: PathBuf,
+    /// パターンの評価の基準にしたスキャンルート
+    pub root: PathBuf,
+    /// スキャンルートに適用したパス固有設定（CLI引数のルートやパス固有設定がなければNone）
+    pub path_config: Option<String>,
+    /// 除外・再包含を決めたパターン
+    pub path_match: Option<ExcludeMatch>,
+    /// ディレクトリ名に一致した `exclude_repos` のパターン
+    pub repo_match: Option<ExcludeMatch>,
+}
+
+/// `path` が除外されるかを、スキャンと同じ優先順位（デフォルト設定 → パス固有設定 → CLI引数）で調べる
+///
+/// `root` を省略すると、`path` を含む設定ファイルのスキャンルートのうち最も内側のものを使う。
+/// `root` を指定した場合はCLI引数で指定したスキャンルートと同じくデフォルト設定のみを使う。
+pub fn explain_excludes(
+    args: &Args,
+    config: &Config,
+    path: &str,
+    root: Option<&str>,
+) -> Result<ExcludeExplanation, String> {
+    let path = canonical(path);
+    let (root, configured) = match root {
+        Some(root) => (root.to_string(), false),
+        None => config
+            .get_default_paths()
+            .iter()
+            .filter(|root| path.starts_with(canonical(root)))
+            .max_by_key(|root| canonical(root).components().count())
+            .map(|root| (root.clone(), true))
+            .ok_or_else(|| {
+                format!(
+                    "'{}' is not under any configured scan root; pass --root",
+                    path.display()
+                )
+            })?,
+    };
+    let root_path = canonical(&root);
+    let relative_path = path
+        .strip_prefix(&root_path)
+        .map_err(|_| format!("'{}' is not under the scan root '{root}'", path.display()))?;
+    let path_config = config.find_path_config(&root).filter(|_| configured);
+    let path_source = path_config.map(|pc| format!("path config '{}'", pc.path));
+
+    let mut sources: Vec<(&str, &[String])> = Vec::new();
+    if !args.no_exclude {
+        sources.push(("defaults", &config.defaults.exclude_patterns));
+        if let (Some(source), Some(patterns)) = (
+            path_source.as_deref(),
+            path_config.and_then(|pc| pc.exclude_patterns.as_deref()),
+        ) {
+            sources.push((source, patterns));
+        }
+    }
+    sources.push(("--exclude", &args.exclude));
+    let filter = ExcludeFilter::for_root_with_sources(&root_path, &sources)
+        .map_err(|e| format!("invalid exclude pattern: {e}"))?;
+
+    // exclude_repos も既定値の後にパス固有設定を連結する
+    let path_repos_source = path_source
+        .as_deref()
+        .map(|source| format!("exclude_repos in {source}"));
+    let mut repo_sources: Vec<(&str, &[String])> =
+        vec![("exclude_repos in defaults", &config.defaults.exclude_repos)];
+    if let (Some(source), Some(patterns)) = (
+        path_repos_source.as_deref(),
+        path_config.and_then(|pc| pc.exclude_repos.as_deref()),
+    ) {
+        repo_sources.push((source, patterns));
+    }
+    let repo_filter = ExcludeFilter::for_root_with_sources(&root_path, &repo_sources)
+        .map_err(|e| format!("invalid exclude_repos pattern: {e}"))?;
+
+    Ok(ExcludeExplanation {
+        path_match: filter.explain_at(relative_path, path.is_dir()),
+        repo_match: path
+            .file_name()
+            .and_then(|name| repo_filter.explain_at(name, true))
+            .filter(|m| m.excluded),
+        root: root_path.clone(),
+        path_config: path_config.map(|pc| pc.path.clone()),
+        path,
+    })
+}
+
+/// チルダを展開して正規化したパス（存在しなければ展開しただけのパス）
+fn canonical(path: &str) -> PathBuf {
+    canonicalize_path(Path::new(&expand_tilde(path)))
+}
+
+impl fmt::Display for ExcludeExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Path: {}", self.path.display())?;
+        match self.path_config {
+            Some(ref path_config) => writeln!(
+                f,
+                "Scan root: {} (path config '{path_config}')",
+                self.root.display()
+            )?,
+            None => writeln!(f, "Scan root: {} (defaults only)", self.root.display())?,
+        }
+        match self.path_match {
+            Some(ref m) if m.excluded => {
+                write!(f, "Excluded by '{}' from {}", m.pattern, m.source)?
+            }
+            Some(ref m) => write!(f, "Kept: '{}' from {} re-includes it", m.pattern, m.source)?,
+            None => write!(f, "Kept: no exclude pattern matches")?,
+        }
+        if let Some(ref m) = self.repo_match {
+            write!(
+                f,
+                "\nRepository name excluded by '{}' from {}",
+                m.pattern, m.source
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PathConfig;
+
+    fn parse(args: &[&str]) -> Overrides {
+        let args = Args::try_parse_with_sources_from(
+            std::iter::once("pendector").chain(args.iter().copied()),
+        )
+        .unwrap();
+        Overrides::from_args(&args)
+    }
+
+    fn config_with_path(path: &str) -> Config {
+        let mut config = Config::default();
+        config.defaults.paths = vec![path.to_string()];
+        config.defaults.exclude_patterns = vec!["node_modules".to_string()];
+        config.path_configs.push(PathConfig {
+            path: path.to_string(),
+            max_depth: Some(6),
+            fetch: Some(true),
+            fetch_timeout: Some(30),
+            format: None,
+            verbose: None,
+            changes_only: None,
+            follow_symlinks: None,
+            exclude_patterns: Some(vec!["vendor".to_string()]),
+            exclude_repos: None,
+            jobs: None,
+            fetch_jobs: None,
+            time_budget: None,
+            hide_clean: Some(true),
+            priority: None,
+            fetch_env: None,
+            fetch_env_passthrough: None,
+            name_style: None,
+            proxy: None,
+            ssh_proxy_command: None,
+            dirty_ignore: None,
+        });
+        config
+    }
+
+    #[test]
+    fn test_path_config_applies_to_config_paths() {
+        let config = config_with_path("/work");
+        let options = resolve_options(&parse(&[]), &config, "/work");
+
+        assert_eq!(options.max_depth, 6);
+        assert!(options.fetch);
+        assert_eq!(options.fetch_timeout, 30);
+        assert_eq!(options.exclude_patterns, vec!["node_modules", "vendor"]);
+        assert!(options.path_config.hide_clean);
+    }
+
+    #[test]
+    fn test_cli_paths_use_defaults_only() {
+        let config = config_with_path("/work");
+        let options = resolve_options(&parse(&["/work"]), &config, "/work");
+
+        assert_eq!(options.max_depth, config.defaults.max_depth);
+        assert!(!options.fetch);
+        assert_eq!(options.exclude_patterns, vec!["node_modules"]);
+        assert!(!options.path_config.hide_clean);
+
+        // --add-path では設定ファイルのパスと同じくパス固有設定を使う
+        let options = resolve_options(&parse(&["--add-path", "/work"]), &config, "/work");
+        assert_eq!(options.max_depth, 6);
+    }
+
+    #[test]
+    fn test_cli_args_override_path_config() {
+        let config = config_with_path("/work");
+        let args = parse(&[
+            "-d",
+            "2",
+            "--fetch-timeout",
+            "9",
+            "--exclude",
+            "!vendor",
+            "--changes-only",
+        ]);
+        let options = resolve_options(&args, &config, "/work");
+
+        assert_eq!(options.max_depth, 2);
+        assert_eq!(options.fetch_timeout, 9);
+        assert_eq!(
+            options.exclude_patterns,
+            vec!["node_modules", "vendor", "!vendor"]
+        );
+        assert!(options.changes_only);
+
+        let options = resolve_options(&parse(&["--no-exclude"]), &config, "/work");
+        assert!(options.exclude_patterns.is_empty());
+    }
+
+    #[test]
+    fn test_explicit_default_values_win() {
+        let mut config = config_with_path("/work");
+        config.path_configs[0].format = Some(OutputFormat::Json);
+
+        let options = resolve_options(&parse(&[]), &config, "/work");
+        assert_eq!(options.fetch_timeout, 30);
+        assert_eq!(options.format, OutputFormat::Json);
+
+        let args = parse(&["--fetch-timeout", "5", "--format", "text"]);
+        let options = resolve_options(&args, &config, "/work");
+        assert_eq!(options.fetch_timeout, 5);
+        assert_eq!(options.format, OutputFormat::Text);
+    }
+}
@@ -1,7 +1,18 @@
+use crate::core::{NameStyle, Priority, Storage};
 use crate::error::{PendectorError, PendectorResult};
+use crate::exclude::ExcludeFilter;
+use crate::git::{UnbornRemote, DEFAULT_BRANCH_NAMES};
+use crate::ops::AuditLog;
+use crate::output::{IconStyle, OutputFormat};
+use crate::path_style::canonicalize_path;
+use crate::progress::DEFAULT_PROGRESS_INTERVAL_SECS;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
+/// `--config` にこのパスを指定すると、設定を標準入力から読み込む
+pub const STDIN_CONFIG_PATH: &str = "-";
+
 /// チルダ (`~`) をホームディレクトリに展開する
 pub fn expand_tilde(path: &str) -> String {
     if path == "~" {
@@ -16,7 +27,7 @@ pub fn expand_tilde(path: &str) -> String {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct Config {
     #[serde(default)]
     pub defaults: DefaultConfig,
@@ -26,9 +37,21 @@ pub struct Config {
 
     #[serde(default)]
     pub slack: Option<SlackConfig>,
+
+    #[serde(default)]
+    pub wip: Option<WipConfig>,
+
+    #[serde(default)]
+    pub watch: Option<WatchConfig>,
+
+    #[serde(default)]
+    pub repos: Option<ReposConfig>,
+
+    #[serde(default)]
+    pub audit: Option<AuditConfig>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DefaultConfig {
     #[serde(default = "default_max_depth")]
     pub max_depth: usize,
@@ -39,8 +62,8 @@ pub struct DefaultConfig {
     #[serde(default = "default_fetch_timeout")]
     pub fetch_timeout: u64,
 
-    #[serde(default = "default_format")]
-    pub format: String,
+    #[serde(default)]
+    pub format: OutputFormat,
 
     #[serde(default)]
     pub verbose: bool,
@@ -48,26 +71,136 @@ pub struct DefaultConfig {
     #[serde(default)]
     pub changes_only: bool,
 
+    #[serde(default)]
+    pub follow_symlinks: bool,
+
+    /// 存在しないスキャンルートを警告して飛ばす（マシンごとにディレクトリ構成が異なる場合など）
+    #[serde(default)]
+    pub skip_missing_paths: bool,
+
+    /// この日数より古い未コミットの変更を強調表示する
+    #[serde(default = "default_dirty_warn_days")]
+    pub dirty_warn_days: u64,
+
+    /// テキスト出力の記号（unicode, ascii, nerdfont）
+    #[serde(default)]
+    pub icons: IconStyle,
+
+    /// リモートのHEADが取得できない場合に順に試すデフォルトブランチ名
+    #[serde(default = "default_default_branches")]
+    pub default_branches: Vec<String>,
+
+    /// ahead/behindの報告から外すブランチ名のパターン（例: `wip/*`）
+    #[serde(default)]
+    pub ignore_branches: Vec<String>,
+
+    /// fetchのプロセスに追加で設定する環境変数（例: `GIT_SSH_COMMAND`）
+    #[serde(default)]
+    pub fetch_env: BTreeMap<String, String>,
+
+    /// fetchで無効化せずに呼び出し元の値を引き継ぐ環境変数（例: `GIT_ASKPASS`）
+    #[serde(default)]
+    pub fetch_env_passthrough: Vec<String>,
+
+    /// リポジトリの表示名の決め方（`dirname`、`relative`、`remote`）
+    #[serde(default)]
+    pub name_style: NameStyle,
+
+    /// HTTP(S)のリモートをfetchするときのプロキシ（例: `http://proxy.example.com:8080`）
+    #[serde(default)]
+    pub proxy: Option<String>,
+
+    /// SSHのリモートをfetchするときのプロキシコマンド（例: `nc -X connect -x proxy:8080 %h %p`）
+    #[serde(default)]
+    pub ssh_proxy_command: Option<String>,
+
     #[serde(default)]
     pub paths: Vec<String>,
 
     #[serde(default)]
     pub exclude_patterns: Vec<String>,
+
+    /// リポジトリのディレクトリ名に対する除外パターン（例: `*-archive`）
+    #[serde(default)]
+    pub exclude_repos: Vec<String>,
+
+    /// 未コミットの変更として数えないファイルのパターン（例: `.DS_Store`、gitignore形式）
+    #[serde(default)]
+    pub dirty_ignore: Vec<String>,
+
+    /// ステータス取得の並列数（未指定ならCPU数）
+    #[serde(default)]
+    pub jobs: Option<usize>,
+
+    /// fetchの並列数（未指定ならCPU数）
+    #[serde(default)]
+    pub fetch_jobs: Option<usize>,
+
+    /// スキャンルートごとの探索時間の上限（秒）。超えたらそのルートの探索・fetch・状態の取得を打ち切り次のルートに進む
+    #[serde(default)]
+    pub time_budget: Option<u64>,
+
+    /// stderrが端末でないときに進捗を1行で書き出す間隔（秒）
+    #[serde(default = "default_progress_interval")]
+    pub progress_interval: u64,
+
+    /// 変更ファイルをこの件数まで調べたら打ち切り、`1000+ changed files` のように表示する
+    #[serde(default)]
+    pub status_file_limit: Option<usize>,
+
+    /// ステータス取得で調べ直したファイルの情報をindexに書き戻し、次回以降を速くする
+    #[serde(default)]
+    pub status_update_index: bool,
+
+    /// コミットのないブランチ（`git init` 直後など）でリモートにコミットがある場合の扱い
+    #[serde(default)]
+    pub unborn_remote: UnbornRemote,
+
+    /// リモートとの同期状態（push・pull待ち、ブランチごとのahead/behind）を調べるか
+    #[serde(default = "default_true")]
+    pub remote_check: bool,
+
+    /// セルフホストのフォージでリポジトリのページを開くURLのテンプレート（ホスト名ごと）
+    #[serde(default)]
+    pub web_url_templates: BTreeMap<String, String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PathConfig {
     pub path: String,
     pub max_depth: Option<usize>,
     pub fetch: Option<bool>,
     pub fetch_timeout: Option<u64>,
-    pub format: Option<String>,
+    pub format: Option<OutputFormat>,
     pub verbose: Option<bool>,
     pub changes_only: Option<bool>,
+    pub follow_symlinks: Option<bool>,
     pub exclude_patterns: Option<Vec<String>>,
+    /// リポジトリのディレクトリ名に対する除外パターン（既定値の設定に追加される）
+    pub exclude_repos: Option<Vec<String>>,
+    pub jobs: Option<usize>,
+    pub fetch_jobs: Option<usize>,
+    /// このパスの探索時間の上限（秒）
+    pub time_budget: Option<u64>,
+    /// 変更がなく同期済みのリポジトリを表示しない（push待ちなどは表示する）
+    pub hide_clean: Option<bool>,
+    /// 配下のリポジトリの優先度（`high` は常に先に表示・fetchする）
+    pub priority: Option<Priority>,
+    /// fetchの環境変数（同じ名前は既定値の設定より優先される）
+    pub fetch_env: Option<BTreeMap<String, String>>,
+    /// fetchで引き継ぐ環境変数（既定値の設定に追加される）
+    pub fetch_env_passthrough: Option<Vec<String>>,
+    /// 配下のリポジトリの表示名の決め方
+    pub name_style: Option<NameStyle>,
+    /// HTTP(S)のリモートのプロキシ
+    pub proxy: Option<String>,
+    /// SSHのリモートのプロキシコマンド
+    pub ssh_proxy_command: Option<String>,
+    /// 変更として数えないファイルのパターン（既定値の設定に追加される）
+    pub dirty_ignore: Option<Vec<String>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SlackConfig {
     pub webhook_url: Option<String>,
 
@@ -84,6 +217,89 @@ pub struct SlackConfig {
     pub channel: Option<String>,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WipConfig {
+    /// WIPブランチ名のテンプレート（`{branch}` と `{date}` を置換する）
+    #[serde(default = "default_wip_branch_template")]
+    pub branch_template: String,
+}
+
+impl Default for WipConfig {
+    fn default() -> Self {
+        Self {
+            branch_template: default_wip_branch_template(),
+        }
+    }
+}
+
+/// ディレクトリを探索せずに常に調べるリポジトリ
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct ReposConfig {
+    /// リポジトリのパス（スキャンルートの外にあってもよい）
+    #[serde(default)]
+    pub paths: Vec<String>,
+
+    /// gitディレクトリと作業ツリーが離れているリポジトリ（`git --git-dir=... --work-tree=...` 形式）
+    #[serde(default)]
+    pub detached: Vec<DetachedRepoConfig>,
+}
+
+/// gitディレクトリと作業ツリーの組
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DetachedRepoConfig {
+    pub git_dir: String,
+    pub work_tree: String,
+}
+
+/// `pendector watch` の設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchConfig {
+    /// 再スキャンの間隔（秒）
+    #[serde(default = "default_watch_interval_secs")]
+    pub interval_secs: u64,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: default_watch_interval_secs(),
+        }
+    }
+}
+
+/// 一括操作の監査ログの設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditConfig {
+    /// 監査ログを記録するか
+    #[serde(default = "default_audit_enabled")]
+    pub enabled: bool,
+
+    /// 記録先のファイル（省略時はデータディレクトリの `pendector/audit.jsonl`）
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_audit_enabled(),
+            path: None,
+        }
+    }
+}
+
+fn default_audit_enabled() -> bool {
+    true
+}
+
+fn default_watch_interval_secs() -> u64 {
+    60
+}
+
+fn default_wip_branch_template() -> String {
+    crate::ops::DEFAULT_WIP_BRANCH_TEMPLATE.to_string()
+}
+
 fn default_true() -> bool {
     true
 }
@@ -106,11 +322,47 @@ impl Default for DefaultConfig {
             max_depth: default_max_depth(),
             fetch: false,
             fetch_timeout: default_fetch_timeout(),
-            format: default_format(),
+            format: OutputFormat::default(),
             verbose: false,
             changes_only: false,
+            follow_symlinks: false,
+            skip_missing_paths: false,
+            dirty_warn_days: default_dirty_warn_days(),
+            icons: IconStyle::default(),
+            default_branches: default_default_branches(),
+            ignore_branches: Vec::new(),
+            fetch_env: BTreeMap::new(),
+            fetch_env_passthrough: Vec::new(),
+            name_style: NameStyle::default(),
+            proxy: None,
+            ssh_proxy_command: None,
             paths: vec![".".to_string()],
             exclude_patterns: Vec::new(),
+            exclude_repos: Vec::new(),
+            dirty_ignore: Vec::new(),
+            jobs: None,
+            fetch_jobs: None,
+            time_budget: None,
+            progress_interval: default_progress_interval(),
+            status_file_limit: None,
+            status_update_index: false,
+            unborn_remote: UnbornRemote::default(),
+            remote_check: true,
+            web_url_templates: BTreeMap::new(),
+        }
+    }
+}
+
+/// `overlay` の値で `base` を上書きする（両方がテーブルなら項目ごとに再帰的に重ねる）
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overlay)) => {
+                merge_tables(base, overlay)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
         }
     }
 }
@@ -123,39 +375,174 @@ fn default_fetch_timeout() -> u64 {
     5
 }
 
-fn default_format() -> String {
-    "text".to_string()
+fn default_progress_interval() -> u64 {
+    DEFAULT_PROGRESS_INTERVAL_SECS
+}
+
+fn default_dirty_warn_days() -> u64 {
+    7
+}
+
+fn default_default_branches() -> Vec<String> {
+    DEFAULT_BRANCH_NAMES.iter().map(|s| s.to_string()).collect()
 }
 
 impl Config {
     /// 設定ファイルを読み込む
     pub fn load(config_path: Option<&Path>) -> PendectorResult<Self> {
-        let config_file_path = if let Some(path) = config_path {
-            // CLI で指定されたパス
-            path.to_path_buf()
-        } else {
-            // デフォルトの設定ファイルパス
-            Self::default_config_path()?
-        };
+        Self::load_layered(config_path.as_slice())
+    }
 
-        if !config_file_path.exists() {
-            // 設定ファイルが存在しない場合はデフォルト設定を返す
-            return Ok(Self::default());
+    /// 設定ファイルを順に重ねて読み込む（後のファイルの値が優先され、テーブルは項目ごと・配列は丸ごと上書きする）
+    pub fn load_layered(config_paths: &[&Path]) -> PendectorResult<Self> {
+        let mut merged = toml::Table::new();
+        let mut last_path = None;
+        for config_file_path in Self::file_paths(config_paths)? {
+            let content = if config_file_path == Path::new(STDIN_CONFIG_PATH) {
+                Self::read_stdin()?
+            } else if config_file_path.exists() {
+                Self::read_file(&config_file_path)?
+            } else {
+                // 設定ファイルが存在しない場合は何も設定していないものとして扱う
+                continue;
+            };
+            merge_tables(&mut merged, Self::parse_table(&config_file_path, &content)?);
+            last_path = Some(config_file_path);
         }
 
-        let content = std::fs::read_to_string(&config_file_path).map_err(|e| {
+        let Some(config_file_path) = last_path else {
+            return Ok(Self::default());
+        };
+        merged.try_into().map_err(|e| PendectorError::ConfigError {
+            path: config_file_path,
+            message: format!("Failed to parse config file: {e}"),
+        })
+    }
+
+    /// 設定ファイルの内容を読み込む
+    fn read_file(config_file_path: &Path) -> PendectorResult<String> {
+        std::fs::read_to_string(config_file_path).map_err(|e| PendectorError::FileSystemError {
+            path: config_file_path.to_path_buf(),
+            message: format!("Failed to read config file: {e}"),
+        })
+    }
+
+    /// `--config -` で渡された設定を標準入力から読み込む
+    fn read_stdin() -> PendectorResult<String> {
+        let mut content = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut content).map_err(|e| {
             PendectorError::FileSystemError {
-                path: config_file_path.clone(),
-                message: format!("Failed to read config file: {e}"),
+                path: PathBuf::from(STDIN_CONFIG_PATH),
+                message: format!("Failed to read config from stdin: {e}"),
             }
         })?;
+        Ok(content)
+    }
 
-        let config: Config = toml::from_str(&content).map_err(|e| PendectorError::ConfigError {
-            path: config_file_path,
+    /// 設定ファイル1つの内容を解析し、単独でも設定として正しいことを確かめる
+    fn parse_table(config_file_path: &Path, content: &str) -> PendectorResult<toml::Table> {
+        let parse_error = |e: toml::de::Error| PendectorError::ConfigError {
+            path: config_file_path.to_path_buf(),
             message: format!("Failed to parse config file: {e}"),
-        })?;
+        };
+        toml::from_str::<Config>(content).map_err(parse_error)?;
+        toml::from_str(content).map_err(parse_error)
+    }
+
+    /// 読み込む設定ファイルのパス（CLIで指定されたパス、なければデフォルトのパス）
+    pub fn file_path(config_path: Option<&Path>) -> PendectorResult<PathBuf> {
+        match config_path {
+            Some(path) => Ok(path.to_path_buf()),
+            None => Self::default_config_path(),
+        }
+    }
+
+    /// 読み込む設定ファイルのパスの一覧（CLIで指定された順、なければデフォルトのパスのみ）
+    pub fn file_paths(config_paths: &[&Path]) -> PendectorResult<Vec<PathBuf>> {
+        if config_paths.is_empty() {
+            return Ok(vec![Self::default_config_path()?]);
+        }
+        Ok(config_paths.iter().map(|path| path.to_path_buf()).collect())
+    }
+
+    /// 一括操作を記録する監査ログ（無効にした場合は何も記録しない）
+    pub(crate) fn audit_log(&self) -> AuditLog {
+        let audit = self.audit.clone().unwrap_or_default();
+        if !audit.enabled {
+            return AuditLog::disabled();
+        }
+        match audit.path {
+            Some(path) => AuditLog::at(PathBuf::from(expand_tilde(&path))),
+            None => {
+                Storage::default_location().map_or_else(AuditLog::disabled, AuditLog::in_storage)
+            }
+        }
+    }
+
+    /// 再スキャンの間隔
+    pub fn watch_interval(&self) -> std::time::Duration {
+        let interval_secs = self
+            .watch
+            .as_ref()
+            .map_or_else(default_watch_interval_secs, |w| w.interval_secs);
+        std::time::Duration::from_secs(interval_secs)
+    }
+
+    /// `[repos]` で固定したリポジトリのパス
+    pub fn pinned_repos(&self) -> Vec<String> {
+        self.repos
+            .as_ref()
+            .map(|repos| repos.paths.clone())
+            .unwrap_or_default()
+    }
 
-        Ok(config)
+    /// `[[repos.detached]]` で指定したgitディレクトリと作業ツリーの組
+    pub fn detached_repos(&self) -> Vec<DetachedRepoConfig> {
+        self.repos
+            .as_ref()
+            .map(|repos| repos.detached.clone())
+            .unwrap_or_default()
+    }
+
+    /// `previous` からの変更点を人が読める形で列挙する（設定の再読み込み時のログ用）
+    pub fn changes_from(&self, previous: &Config) -> Vec<String> {
+        let mut changes = Vec::new();
+        let mut list_change = |name: &str, old: &[String], new: &[String]| {
+            if old != new {
+                changes.push(format!("{name}: {old:?} -> {new:?}"));
+            }
+        };
+        list_change("paths", &previous.defaults.paths, &self.defaults.paths);
+        list_change(
+            "exclude_patterns",
+            &previous.defaults.exclude_patterns,
+            &self.defaults.exclude_patterns,
+        );
+        list_change(
+            "exclude_repos",
+            &previous.defaults.exclude_repos,
+            &self.defaults.exclude_repos,
+        );
+        list_change(
+            "ignore_branches",
+            &previous.defaults.ignore_branches,
+            &self.defaults.ignore_branches,
+        );
+        list_change("repos", &previous.pinned_repos(), &self.pinned_repos());
+        if previous.path_configs != self.path_configs {
+            changes.push("path_configs updated".to_string());
+        }
+        if previous.watch_interval() != self.watch_interval() {
+            changes.push(format!(
+                "watch interval: {}s -> {}s",
+                previous.watch_interval().as_secs(),
+                self.watch_interval().as_secs()
+            ));
+        }
+        if changes.is_empty() && previous != self {
+            changes.push("other settings updated".to_string());
+        }
+        changes
     }
 
     /// デフォルトの設定ファイルパスを取得
@@ -168,13 +555,16 @@ impl Config {
         Ok(config_dir.join("pendector").join("config.toml"))
     }
 
+    /// 指定されたパスに適用されるパス固有の設定
+    pub fn find_path_config(&self, target_path: &str) -> Option<&PathConfig> {
+        self.path_configs
+            .iter()
+            .find(|pc| Self::path_matches(&pc.path, target_path))
+    }
+
     /// 指定されたパスに対する設定を取得
     pub fn get_path_config(&self, target_path: &str) -> PathConfigResolved {
-        // パス固有の設定を検索
-        let path_config = self
-            .path_configs
-            .iter()
-            .find(|pc| Self::path_matches(&pc.path, target_path));
+        let path_config = self.find_path_config(target_path);
 
         PathConfigResolved {
             max_depth: path_config
@@ -187,17 +577,79 @@ impl Config {
                 .and_then(|pc| pc.fetch_timeout)
                 .unwrap_or(self.defaults.fetch_timeout),
             format: path_config
-                .and_then(|pc| pc.format.clone())
-                .unwrap_or_else(|| self.defaults.format.clone()),
+                .and_then(|pc| pc.format)
+                .unwrap_or(self.defaults.format),
             verbose: path_config
                 .and_then(|pc| pc.verbose)
                 .unwrap_or(self.defaults.verbose),
             changes_only: path_config
                 .and_then(|pc| pc.changes_only)
                 .unwrap_or(self.defaults.changes_only),
-            exclude_patterns: path_config
-                .and_then(|pc| pc.exclude_patterns.clone())
-                .unwrap_or_else(|| self.defaults.exclude_patterns.clone()),
+            follow_symlinks: path_config
+                .and_then(|pc| pc.follow_symlinks)
+                .unwrap_or(self.defaults.follow_symlinks),
+            // 除外パターンは既定値の後にパス固有の設定を連結する（後の否定パターンが優先される）
+            exclude_patterns: ExcludeFilter::ordered_patterns(&[
+                &self.defaults.exclude_patterns,
+                path_config
+                    .and_then(|pc| pc.exclude_patterns.as_deref())
+                    .unwrap_or_default(),
+            ]),
+            exclude_repos: ExcludeFilter::ordered_patterns(&[
+                &self.defaults.exclude_repos,
+                path_config
+                    .and_then(|pc| pc.exclude_repos.as_deref())
+                    .unwrap_or_default(),
+            ]),
+            jobs: path_config.and_then(|pc| pc.jobs).or(self.defaults.jobs),
+            fetch_jobs: path_config
+                .and_then(|pc| pc.fetch_jobs)
+                .or(self.defaults.fetch_jobs),
+            time_budget: path_config
+                .and_then(|pc| pc.time_budget)
+                .or(self.defaults.time_budget),
+            // パス固有の設定のみ（CLI引数で指定したパスの表示は変えない）
+            hide_clean: path_config.and_then(|pc| pc.hide_clean).unwrap_or(false),
+            priority: path_config.and_then(|pc| pc.priority).unwrap_or_default(),
+            fetch_env: self
+                .defaults
+                .fetch_env
+                .clone()
+                .into_iter()
+                .chain(
+                    path_config
+                        .and_then(|pc| pc.fetch_env.clone())
+                        .unwrap_or_default(),
+                )
+                .collect(),
+            fetch_env_passthrough: self
+                .defaults
+                .fetch_env_passthrough
+                .iter()
+                .chain(
+                    path_config
+                        .and_then(|pc| pc.fetch_env_passthrough.as_ref())
+                        .into_iter()
+                        .flatten(),
+                )
+                .cloned()
+                .collect(),
+            name_style: path_config
+                .and_then(|pc| pc.name_style)
+                .unwrap_or(self.defaults.name_style),
+            proxy: path_config
+                .and_then(|pc| pc.proxy.clone())
+                .or_else(|| self.defaults.proxy.clone()),
+            ssh_proxy_command: path_config
+                .and_then(|pc| pc.ssh_proxy_command.clone())
+                .or_else(|| self.defaults.ssh_proxy_command.clone()),
+            // 除外パターンと同じく既定値の後にパス固有の設定を連結する
+            dirty_ignore: ExcludeFilter::ordered_patterns(&[
+                &self.defaults.dirty_ignore,
+                path_config
+                    .and_then(|pc| pc.dirty_ignore.as_deref())
+                    .unwrap_or_default(),
+            ]),
         }
     }
 
@@ -207,13 +659,9 @@ impl Config {
         let expanded_config_path = expand_tilde(config_path);
         let expanded_target_path = expand_tilde(target_path);
 
-        // 正規化
-        let config_canonical = Path::new(&expanded_config_path)
-            .canonicalize()
-            .unwrap_or_else(|_| PathBuf::from(&expanded_config_path));
-        let target_canonical = Path::new(&expanded_target_path)
-            .canonicalize()
-            .unwrap_or_else(|_| PathBuf::from(&expanded_target_path));
+        // 正規化（Windowsのドライブレターや区切り文字の書き方の違いも揃える）
+        let config_canonical = canonicalize_path(Path::new(&expanded_config_path));
+        let target_canonical = canonicalize_path(Path::new(&expanded_target_path));
 
         // 完全一致または親ディレクトリかチェック
         target_canonical == config_canonical || target_canonical.starts_with(&config_canonical)
@@ -230,10 +678,23 @@ pub struct PathConfigResolved {
     pub max_depth: usize,
     pub fetch: bool,
     pub fetch_timeout: u64,
-    pub format: String,
+    pub format: OutputFormat,
     pub verbose: bool,
     pub changes_only: bool,
+    pub follow_symlinks: bool,
     pub exclude_patterns: Vec<String>,
+    pub exclude_repos: Vec<String>,
+    pub jobs: Option<usize>,
+    pub fetch_jobs: Option<usize>,
+    pub time_budget: Option<u64>,
+    pub hide_clean: bool,
+    pub priority: Priority,
+    pub fetch_env: BTreeMap<String, String>,
+    pub fetch_env_passthrough: Vec<String>,
+    pub name_style: NameStyle,
+    pub proxy: Option<String>,
+    pub ssh_proxy_command: Option<String>,
+    pub dirty_ignore: Vec<String>,
 }
 
 #[cfg(test)]
@@ -247,7 +708,7 @@ mod tests {
         assert_eq!(config.defaults.max_depth, 3);
         assert!(!config.defaults.fetch);
         assert_eq!(config.defaults.fetch_timeout, 5);
-        assert_eq!(config.defaults.format, "text");
+        assert_eq!(config.defaults.format, OutputFormat::Text);
         assert!(!config.defaults.verbose);
         assert!(!config.defaults.changes_only);
         assert_eq!(config.defaults.paths, vec!["."]);
@@ -262,6 +723,53 @@ mod tests {
         assert_eq!(config.defaults.max_depth, 3);
     }
 
+    #[test]
+    fn test_load_layered_later_files_override() {
+        let temp_dir = TempDir::new().unwrap();
+        let base = temp_dir.path().join("base.toml");
+        let local = temp_dir.path().join("local.toml");
+        std::fs::write(
+            &base,
+            r#"
+[defaults]
+max_depth = 5
+fetch = true
+paths = ["~/src", "~/work"]
+
+[slack]
+webhook_url = "https://hooks.slack.com/base"
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            &local,
+            r#"
+[defaults]
+fetch = false
+paths = ["/data/src"]
+"#,
+        )
+        .unwrap();
+
+        let missing = temp_dir.path().join("missing.toml");
+        let config = Config::load_layered(&[&base, &local, &missing]).unwrap();
+        // 後のファイルにない項目は前のファイルの値が残り、配列は丸ごと置き換わる
+        assert_eq!(config.defaults.max_depth, 5);
+        assert!(!config.defaults.fetch);
+        assert_eq!(config.defaults.paths, vec!["/data/src"]);
+        assert_eq!(
+            config.slack.unwrap().webhook_url.as_deref(),
+            Some("https://hooks.slack.com/base")
+        );
+
+        // どのファイルの誤りかを報告する
+        std::fs::write(&local, "[defaults]\nmax_depth = \"deep\"\n").unwrap();
+        match Config::load_layered(&[&base, &local]) {
+            Err(PendectorError::ConfigError { path, .. }) => assert_eq!(path, local),
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
     #[test]
     fn test_load_valid_config_file() {
         let temp_dir = TempDir::new().unwrap();
@@ -275,6 +783,10 @@ fetch_timeout = 10
 format = "json"
 verbose = true
 changes_only = true
+dirty_warn_days = 3
+icons = "nerdfont"
+default_branches = ["trunk", "develop"]
+ignore_branches = ["wip/*", "backup/*"]
 paths = ["~/src", "~/work"]
 exclude_patterns = ["node_modules", "*.log"]
 
@@ -296,9 +808,13 @@ fetch = false
         assert_eq!(config.defaults.max_depth, 5);
         assert!(config.defaults.fetch);
         assert_eq!(config.defaults.fetch_timeout, 10);
-        assert_eq!(config.defaults.format, "json");
+        assert_eq!(config.defaults.format, OutputFormat::Json);
         assert!(config.defaults.verbose);
         assert!(config.defaults.changes_only);
+        assert_eq!(config.defaults.dirty_warn_days, 3);
+        assert_eq!(config.defaults.icons, IconStyle::Nerdfont);
+        assert_eq!(config.defaults.default_branches, vec!["trunk", "develop"]);
+        assert_eq!(config.defaults.ignore_branches, vec!["wip/*", "backup/*"]);
         assert_eq!(config.defaults.paths, vec!["~/src", "~/work"]);
         assert_eq!(
             config.defaults.exclude_patterns,
@@ -323,7 +839,7 @@ fetch = false
         assert_eq!(path_config.max_depth, 3);
         assert!(!path_config.fetch);
         assert_eq!(path_config.fetch_timeout, 5);
-        assert_eq!(path_config.format, "text");
+        assert_eq!(path_config.format, OutputFormat::Text);
         assert!(path_config.exclude_patterns.is_empty());
     }
 
@@ -335,20 +851,209 @@ fetch = false
             max_depth: Some(10),
             fetch: Some(true),
             fetch_timeout: Some(20),
-            format: Some("json".to_string()),
+            format: Some(OutputFormat::Json),
             verbose: Some(true),
             changes_only: Some(true),
+            follow_symlinks: Some(true),
             exclude_patterns: Some(vec!["*.tmp".to_string()]),
+            exclude_repos: Some(vec!["*-bak".to_string()]),
+            jobs: Some(2),
+            fetch_jobs: Some(1),
+            time_budget: None,
+            hide_clean: Some(true),
+            priority: Some(Priority::High),
+            fetch_env: None,
+            fetch_env_passthrough: None,
+            name_style: None,
+            proxy: None,
+            ssh_proxy_command: None,
+            dirty_ignore: None,
         });
 
         let path_config = config.get_path_config("/test/path");
         assert_eq!(path_config.max_depth, 10);
         assert!(path_config.fetch);
         assert_eq!(path_config.fetch_timeout, 20);
-        assert_eq!(path_config.format, "json");
+        assert_eq!(path_config.format, OutputFormat::Json);
         assert!(path_config.verbose);
         assert!(path_config.changes_only);
+        assert!(path_config.follow_symlinks);
         assert_eq!(path_config.exclude_patterns, vec!["*.tmp".to_string()]);
+        assert_eq!(path_config.exclude_repos, vec!["*-bak".to_string()]);
+        assert_eq!(path_config.jobs, Some(2));
+        assert_eq!(path_config.fetch_jobs, Some(1));
+        assert!(path_config.hide_clean);
+        assert_eq!(path_config.priority, Priority::High);
+        assert_eq!(
+            config.get_path_config("/other/path").priority,
+            Priority::Normal
+        );
+    }
+
+    #[test]
+    fn test_get_path_config_appends_exclude_repos_to_defaults() {
+        let mut config = Config::default();
+        config.defaults.exclude_repos = vec!["*-archive".to_string()];
+        config.path_configs.push(PathConfig {
+            path: "/test/path".to_string(),
+            max_depth: None,
+            fetch: None,
+            fetch_timeout: None,
+            format: None,
+            verbose: None,
+            changes_only: None,
+            follow_symlinks: None,
+            exclude_patterns: None,
+            exclude_repos: Some(vec!["sandbox-*".to_string()]),
+            jobs: None,
+            fetch_jobs: None,
+            time_budget: None,
+            hide_clean: None,
+            priority: None,
+            fetch_env: None,
+            fetch_env_passthrough: None,
+            name_style: None,
+            proxy: None,
+            ssh_proxy_command: None,
+            dirty_ignore: None,
+        });
+
+        assert_eq!(
+            config.get_path_config("/test/path").exclude_repos,
+            vec!["*-archive".to_string(), "sandbox-*".to_string()]
+        );
+        assert_eq!(
+            config.get_path_config("/other/path").exclude_repos,
+            vec!["*-archive".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_get_path_config_merges_fetch_env() {
+        let mut config = Config::default();
+        config.defaults.fetch_env = BTreeMap::from([
+            (
+                "GIT_SSH_COMMAND".to_string(),
+                "ssh -i ~/.ssh/id_default".to_string(),
+            ),
+            ("GIT_TRACE".to_string(), "0".to_string()),
+        ]);
+        config.defaults.fetch_env_passthrough = vec!["SSH_AUTH_SOCK".to_string()];
+        config.defaults.proxy = Some("http://proxy.example:8080".to_string());
+        config.path_configs.push(PathConfig {
+            path: "/test/path".to_string(),
+            max_depth: None,
+            fetch: None,
+            fetch_timeout: None,
+            format: None,
+            verbose: None,
+            changes_only: None,
+            follow_symlinks: None,
+            exclude_patterns: None,
+            exclude_repos: None,
+            jobs: None,
+            fetch_jobs: None,
+            time_budget: None,
+            hide_clean: None,
+            priority: None,
+            fetch_env: Some(BTreeMap::from([(
+                "GIT_SSH_COMMAND".to_string(),
+                "ssh -i ~/.ssh/id_work".to_string(),
+            )])),
+            fetch_env_passthrough: Some(vec!["GIT_ASKPASS".to_string()]),
+            name_style: None,
+            proxy: Some("http://proxy.work.example:3128".to_string()),
+            ssh_proxy_command: None,
+            dirty_ignore: None,
+        });
+
+        let path_config = config.get_path_config("/test/path");
+        assert_eq!(
+            path_config.fetch_env["GIT_SSH_COMMAND"],
+            "ssh -i ~/.ssh/id_work"
+        );
+        assert_eq!(path_config.fetch_env["GIT_TRACE"], "0");
+        assert_eq!(
+            path_config.fetch_env_passthrough,
+            vec!["SSH_AUTH_SOCK".to_string(), "GIT_ASKPASS".to_string()]
+        );
+        assert_eq!(
+            config.get_path_config("/other/path").fetch_env["GIT_SSH_COMMAND"],
+            "ssh -i ~/.ssh/id_default"
+        );
+        // プロキシはパス固有の設定が既定値を置き換える
+        assert_eq!(
+            path_config.proxy.as_deref(),
+            Some("http://proxy.work.example:3128")
+        );
+        assert_eq!(
+            config.get_path_config("/other/path").proxy.as_deref(),
+            Some("http://proxy.example:8080")
+        );
+    }
+
+    #[test]
+    fn test_get_path_config_appends_path_excludes_after_defaults() {
+        let mut config = Config::default();
+        config.defaults.exclude_patterns = vec!["*-dir".to_string()];
+        config.path_configs.push(PathConfig {
+            path: "/test/path".to_string(),
+            max_depth: None,
+            fetch: None,
+            fetch_timeout: None,
+            format: None,
+            verbose: None,
+            changes_only: None,
+            follow_symlinks: None,
+            exclude_patterns: Some(vec!["!important-dir".to_string()]),
+            exclude_repos: None,
+            jobs: None,
+            fetch_jobs: None,
+            time_budget: None,
+            hide_clean: None,
+            priority: None,
+            fetch_env: None,
+            fetch_env_passthrough: None,
+            name_style: None,
+            proxy: None,
+            ssh_proxy_command: None,
+            dirty_ignore: None,
+        });
+
+        assert_eq!(
+            config.get_path_config("/test/path").exclude_patterns,
+            vec!["*-dir".to_string(), "!important-dir".to_string()]
+        );
+        assert_eq!(
+            config.get_path_config("/other/path").exclude_patterns,
+            vec!["*-dir".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_changes_from_describes_reloaded_settings() {
+        let previous = Config::default();
+        assert!(previous.changes_from(&previous).is_empty());
+
+        let mut config = Config::default();
+        config.defaults.paths = vec!["~/src".to_string()];
+        config.defaults.exclude_patterns = vec!["*.tmp".to_string()];
+        config.watch = Some(WatchConfig { interval_secs: 30 });
+        assert_eq!(
+            config.changes_from(&previous),
+            vec![
+                r#"paths: ["."] -> ["~/src"]"#.to_string(),
+                r#"exclude_patterns: [] -> ["*.tmp"]"#.to_string(),
+                "watch interval: 60s -> 30s".to_string(),
+            ]
+        );
+
+        let mut config = Config::default();
+        config.defaults.verbose = true;
+        assert_eq!(
+            config.changes_from(&previous),
+            vec!["other settings updated".to_string()]
+        );
     }
 
     #[test]
@@ -382,6 +1087,55 @@ channel = "#alerts"
         assert_eq!(slack.channel, Some("#alerts".to_string()));
     }
 
+    #[test]
+    fn test_load_config_with_wip() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+
+        std::fs::write(
+            &config_path,
+            "[wip]\nbranch_template = \"handoff/{date}\"\n",
+        )
+        .unwrap();
+        let config = Config::load(Some(&config_path)).unwrap();
+        assert_eq!(config.wip.unwrap().branch_template, "handoff/{date}");
+
+        std::fs::write(&config_path, "[wip]\n").unwrap();
+        let config = Config::load(Some(&config_path)).unwrap();
+        assert_eq!(config.wip.unwrap().branch_template, "wip/{branch}-{date}");
+    }
+
+    #[test]
+    fn test_load_config_with_pinned_repos() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+
+        std::fs::write(
+            &config_path,
+            "[repos]\npaths = [\"/etc\", \"~/dotfiles\"]\n",
+        )
+        .unwrap();
+        let config = Config::load(Some(&config_path)).unwrap();
+        assert_eq!(config.pinned_repos(), vec!["/etc", "~/dotfiles"]);
+        assert!(config.detached_repos().is_empty());
+
+        std::fs::write(
+            &config_path,
+            "[[repos.detached]]\ngit_dir = \"~/.dotfiles\"\nwork_tree = \"~\"\n",
+        )
+        .unwrap();
+        let config = Config::load(Some(&config_path)).unwrap();
+        assert_eq!(
+            config.detached_repos(),
+            vec![DetachedRepoConfig {
+                git_dir: "~/.dotfiles".to_string(),
+                work_tree: "~".to_string(),
+            }]
+        );
+
+        assert!(Config::default().pinned_repos().is_empty());
+    }
+
     #[test]
     fn test_load_config_without_slack() {
         let temp_dir = TempDir::new().unwrap();
@@ -429,6 +1183,24 @@ webhook_url = "https://hooks.slack.com/services/T00/B00/XXX"
         assert!(!Config::path_matches("/test/path", "/other/path"));
     }
 
+    #[test]
+    fn test_path_matches_windows_styles() {
+        assert!(Config::path_matches(
+            r"C:\Users\me\src",
+            "c:/Users/me/src/app"
+        ));
+        assert!(Config::path_matches(
+            r"C:\Users\me\src\",
+            r"\\?\C:\Users\me\src\app"
+        ));
+        assert!(Config::path_matches(
+            r"\\server\share\src",
+            r"\\?\UNC\server\share\src\app"
+        ));
+        assert!(!Config::path_matches(r"C:\src", r"D:\src\app"));
+        assert!(!Config::path_matches(r"C:\src", r"C:\src2"));
+    }
+
     #[test]
     fn test_expand_tilde_home_only() {
         let home = std::env::var("HOME").unwrap();
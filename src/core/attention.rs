@@ -0,0 +1,112 @@
+use crate::core::{sort_by_staleness, Repository};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::fmt;
+
+/// 対応が必要な理由による分類（複数に当てはまる場合は並び順が先のものになる）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Attention {
+    /// ステータスを取得できなかった
+    Error,
+    /// merge・rebaseなどの操作が途中で止まっている
+    InOperation,
+    /// 未コミットの変更がある
+    Dirty,
+    /// push・pull待ちのコミットがある
+    Unsynced,
+    /// 対応は不要
+    None,
+}
+
+impl fmt::Display for Attention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Attention::Error => "error",
+            Attention::InOperation => "in-operation",
+            Attention::Dirty => "dirty",
+            Attention::Unsynced => "unsynced",
+            Attention::None => "none",
+        };
+        f.write_str(name)
+    }
+}
+
+impl Attention {
+    /// リポジトリの状態から分類する
+    pub fn of(repo: &Repository) -> Self {
+        if repo.status_error.is_some() {
+            Attention::Error
+        } else if repo.operation.is_some() {
+            Attention::InOperation
+        } else if repo.has_changes {
+            Attention::Dirty
+        } else if repo.needs_push || repo.needs_pull {
+            Attention::Unsynced
+        } else {
+            Attention::None
+        }
+    }
+}
+
+/// 対応が必要なリポジトリを急ぐ順に `n` 件だけ残す
+///
+/// 優先度・分類（エラー、途中の操作、未コミット、未同期の順）で並べ、同じ分類の中では放置の長いものを先にする。
+pub fn most_actionable(mut repositories: Vec<Repository>, n: usize) -> Vec<Repository> {
+    repositories.retain(Repository::needs_attention);
+    sort_by_staleness(&mut repositories);
+    repositories.sort_by_key(|repo| (repo.priority, repo.attention()));
+    repositories.truncate(n);
+    repositories
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{ErrorCode, ErrorReport};
+    use crate::git::InProgressOperation;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_attention_priority() {
+        let clean = Repository::new(PathBuf::from("/src/foo"));
+        assert_eq!(clean.attention(), Attention::None);
+
+        let unsynced = clean.clone().with_remote_info(false, true, None);
+        assert_eq!(unsynced.attention(), Attention::Unsynced);
+
+        let dirty = unsynced.with_git_info(true, None, vec!["a.txt".to_string()]);
+        assert_eq!(dirty.attention(), Attention::Dirty);
+
+        let in_operation = dirty.with_operation(Some(InProgressOperation::Rebase));
+        assert_eq!(in_operation.attention(), Attention::InOperation);
+
+        let error = in_operation.with_status_error(Some(ErrorReport {
+            code: ErrorCode::GitOperation,
+            message: "index is locked".to_string(),
+        }));
+        assert_eq!(error.attention(), Attention::Error);
+        assert_eq!(Attention::InOperation.to_string(), "in-operation");
+    }
+
+    #[test]
+    fn test_most_actionable() {
+        let repo = |name: &str| Repository::new(PathBuf::from(format!("/src/{name}")));
+        let dirty = |name: &str| repo(name).with_git_info(true, None, vec!["a.txt".to_string()]);
+        let repositories = vec![
+            repo("clean"),
+            repo("unsynced").with_remote_info(false, true, None),
+            dirty("dirty"),
+            dirty("rebasing").with_operation(Some(InProgressOperation::Rebase)),
+        ];
+
+        let top: Vec<String> = most_actionable(repositories.clone(), 2)
+            .into_iter()
+            .map(|r| r.name)
+            .collect();
+        assert_eq!(top, vec!["rebasing", "dirty"]);
+
+        // 対応の不要なものは件数に余裕があっても含めない
+        assert_eq!(most_actionable(repositories, 10).len(), 3);
+    }
+}
@@ -0,0 +1,92 @@
+use std::fmt;
+use std::path::Path;
+
+/// リポジトリを表示に残した・除いた理由（`-vv` で機械可読なコードとして出力する）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterReason {
+    /// 除外パターンに一致するディレクトリの配下にある
+    ExcludePattern,
+    /// ディレクトリ名が `exclude_repos` に一致した
+    RepoName,
+    /// 別のパス（シンボリックリンクや重なったスキャンルート）から同じリポジトリに到達した
+    Duplicate,
+    /// `changes_only` で変更のないものを除いた
+    ChangesOnly,
+    /// `hide_clean` で対応の不要なものを除いた
+    HideClean,
+    /// `--attention` で指定した分類に当てはまらない
+    Attention,
+    /// `--type` で指定したプロジェクトの種類でない
+    ProjectType,
+    /// すべての絞り込みを通過した
+    Included,
+}
+
+impl FilterReason {
+    /// 理由のコード（出力に使う値）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FilterReason::ExcludePattern => "exclude_pattern",
+            FilterReason::RepoName => "repo_name",
+            FilterReason::Duplicate => "duplicate",
+            FilterReason::ChangesOnly => "changes_only",
+            FilterReason::HideClean => "hide_clean",
+            FilterReason::Attention => "attention",
+            FilterReason::ProjectType => "project_type",
+            FilterReason::Included => "included",
+        }
+    }
+}
+
+impl fmt::Display for FilterReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 絞り込みの判定を1行にまとめる（`filter: dropped reason=changes_only path="..."` の形式）
+pub fn format_filter_decision(reason: FilterReason, path: &Path, detail: Option<&str>) -> String {
+    let decision = if reason == FilterReason::Included {
+        "kept"
+    } else {
+        "dropped"
+    };
+    let mut line = format!(
+        "filter: {decision} reason={reason} path={:?}",
+        path.display().to_string()
+    );
+    if let Some(detail) = detail {
+        line.push_str(&format!(" detail={detail:?}"));
+    }
+    line
+}
+
+/// 絞り込みの判定を標準エラー出力に書く
+pub fn log_filter_decision(reason: FilterReason, path: &Path, detail: Option<&str>) {
+    eprintln!("{}", format_filter_decision(reason, path, detail));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_filter_decision() {
+        assert_eq!(
+            format_filter_decision(FilterReason::ChangesOnly, Path::new("/src/app"), None),
+            "filter: dropped reason=changes_only path=\"/src/app\""
+        );
+        assert_eq!(
+            format_filter_decision(
+                FilterReason::ExcludePattern,
+                Path::new("/src/node_modules/dep"),
+                Some("node_modules")
+            ),
+            "filter: dropped reason=exclude_pattern path=\"/src/node_modules/dep\" detail=\"node_modules\""
+        );
+        assert!(
+            format_filter_decision(FilterReason::Included, Path::new("/a"), None)
+                .starts_with("filter: kept reason=included")
+        );
+    }
+}
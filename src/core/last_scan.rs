@@ -0,0 +1,81 @@
+use crate::core::{Repository, Storage};
+use crate::error::PendectorResult;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// 前回の結果のファイル名
+pub const LAST_SCAN_FILE: &str = "last_scan.json";
+
+/// 前回の結果のスキーマのバージョン
+pub const LAST_SCAN_SCHEMA_VERSION: u32 = 1;
+
+/// `--recheck` で調べ直すリポジトリ
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingRepository {
+    pub path: PathBuf,
+    /// 見つけたスキャンルート（パス固有設定を引き継ぐため）
+    pub scan_root: Option<String>,
+}
+
+/// 前回の状態表示の結果（走査したスキャンルートと、対応が必要だったリポジトリ）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LastScan {
+    pub roots: Vec<String>,
+    pub pending: Vec<PendingRepository>,
+}
+
+impl LastScan {
+    /// 走査したスキャンルートと表示したリポジトリから作る（対応の不要なものは記録しない）
+    pub fn new(roots: Vec<String>, repositories: &[Repository]) -> Self {
+        let pending = repositories
+            .iter()
+            .filter(|repo| repo.needs_attention())
+            .map(|repo| PendingRepository {
+                path: repo.path.clone(),
+                scan_root: repo.scan_root.clone(),
+            })
+            .collect();
+        Self { roots, pending }
+    }
+
+    /// 保存領域から読み込む（保存されていないかスキーマが古ければNone）
+    pub fn load(storage: &Storage) -> PendectorResult<Option<Self>> {
+        storage.read(LAST_SCAN_FILE, LAST_SCAN_SCHEMA_VERSION)
+    }
+
+    /// 保存領域に書き込む
+    pub fn save(&self, storage: &Storage) -> PendectorResult<()> {
+        storage.write(LAST_SCAN_FILE, LAST_SCAN_SCHEMA_VERSION, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_only_repositories_needing_attention_are_pending() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = Storage::new(temp_dir.path().to_path_buf());
+        let clean = Repository::new(PathBuf::from("/src/clean"));
+        let dirty = Repository::new(PathBuf::from("/src/dirty"))
+            .with_git_info(true, Some("main".to_string()), vec!["?? a".to_string()])
+            .with_scan_root(Some("/src".to_string()));
+
+        assert_eq!(LastScan::load(&storage).unwrap(), None);
+        LastScan::new(vec!["/src".to_string()], &[clean, dirty])
+            .save(&storage)
+            .unwrap();
+
+        let last = LastScan::load(&storage).unwrap().unwrap();
+        assert_eq!(last.roots, vec!["/src".to_string()]);
+        assert_eq!(
+            last.pending,
+            vec![PendingRepository {
+                path: PathBuf::from("/src/dirty"),
+                scan_root: Some("/src".to_string()),
+            }]
+        );
+    }
+}
@@ -1,5 +1,21 @@
+pub mod attention;
+pub mod filter;
+pub mod last_scan;
+pub mod name_style;
+pub mod priority;
+pub mod project_type;
 pub mod repo;
 pub mod scanner;
+pub mod staleness;
+pub mod storage;
 
+pub use attention::*;
+pub use filter::*;
+pub use last_scan::*;
+pub use name_style::*;
+pub use priority::*;
+pub use project_type::*;
 pub use repo::*;
 pub use scanner::*;
+pub use staleness::*;
+pub use storage::*;
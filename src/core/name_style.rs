@@ -0,0 +1,81 @@
+use crate::core::Repository;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+/// リポジトリの表示名の決め方（同じディレクトリ名のリポジトリを見分けるため）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NameStyle {
+    /// ディレクトリ名（例: `api`）
+    #[default]
+    Dirname,
+    /// スキャンルートからの相対パス（例: `org/api`）
+    Relative,
+    /// リモートURLの `owner/repo`（例: `acme/api`）
+    Remote,
+}
+
+impl NameStyle {
+    /// この方式での表示名（決められなければNoneで、ディレクトリ名のままにする）
+    pub fn name_for(&self, repo: &Repository, root: Option<&Path>) -> Option<String> {
+        match self {
+            NameStyle::Dirname => None,
+            NameStyle::Relative => {
+                let relative = repo.path.strip_prefix(root?).ok()?;
+                if relative.as_os_str().is_empty() {
+                    return None;
+                }
+                Some(relative.to_string_lossy().replace('\\', "/"))
+            }
+            NameStyle::Remote => {
+                // remote_id は `host/owner/repo` の形なので、ホスト名を除く
+                let (_, slug) = repo.remote_id.as_deref()?.split_once('/')?;
+                Some(slug.to_string())
+            }
+        }
+    }
+}
+
+impl fmt::Display for NameStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            NameStyle::Dirname => "dirname",
+            NameStyle::Relative => "relative",
+            NameStyle::Remote => "remote",
+        };
+        f.write_str(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_name_for_each_style() {
+        let repo = Repository::new(PathBuf::from("/src/acme/api"))
+            .with_remote_id(Some("github.com/acme-corp/api".to_string()));
+        let root = Some(Path::new("/src"));
+
+        assert_eq!(NameStyle::Dirname.name_for(&repo, root), None);
+        assert_eq!(
+            NameStyle::Relative.name_for(&repo, root).as_deref(),
+            Some("acme/api")
+        );
+        assert_eq!(
+            NameStyle::Remote.name_for(&repo, root).as_deref(),
+            Some("acme-corp/api")
+        );
+
+        // スキャンルートそのものやリモートのないリポジトリはディレクトリ名のまま
+        assert_eq!(
+            NameStyle::Relative.name_for(&repo, Some(Path::new("/src/acme/api"))),
+            None
+        );
+        assert_eq!(NameStyle::Relative.name_for(&repo, None), None);
+        let no_remote = Repository::new(PathBuf::from("/src/acme/api"));
+        assert_eq!(NameStyle::Remote.name_for(&no_remote, root), None);
+    }
+}
@@ -0,0 +1,78 @@
+use serde::Serialize;
+use std::fmt;
+use std::path::Path;
+
+/// 作業ツリーの直下にあるファイルから判定したプロジェクトの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectType {
+    /// `Cargo.toml`
+    Rust,
+    /// `package.json`
+    Node,
+    /// `go.mod`
+    Go,
+    /// `pyproject.toml`
+    Python,
+}
+
+impl ProjectType {
+    /// 判定に使うファイル（複数当てはまる場合は先にあるものを採る）
+    const MARKERS: [(&'static str, ProjectType); 4] = [
+        ("Cargo.toml", ProjectType::Rust),
+        ("go.mod", ProjectType::Go),
+        ("pyproject.toml", ProjectType::Python),
+        ("package.json", ProjectType::Node),
+    ];
+
+    /// 作業ツリーのプロジェクトの種類（判定できなければNone）
+    pub fn detect(repo_path: &Path) -> Option<Self> {
+        Self::MARKERS
+            .iter()
+            .find(|(marker, _)| repo_path.join(marker).is_file())
+            .map(|&(_, project_type)| project_type)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProjectType::Rust => "rust",
+            ProjectType::Node => "node",
+            ProjectType::Go => "go",
+            ProjectType::Python => "python",
+        }
+    }
+}
+
+impl fmt::Display for ProjectType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detect_project_type() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path();
+        assert_eq!(ProjectType::detect(path), None);
+
+        fs::write(path.join("package.json"), "{}").unwrap();
+        assert_eq!(ProjectType::detect(path), Some(ProjectType::Node));
+
+        // フロントエンドを同梱したRustのプロジェクトはRustとみなす
+        fs::write(path.join("Cargo.toml"), "[package]").unwrap();
+        assert_eq!(ProjectType::detect(path), Some(ProjectType::Rust));
+    }
+
+    #[test]
+    fn test_marker_directories_are_ignored() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir(temp_dir.path().join("go.mod")).unwrap();
+        assert_eq!(ProjectType::detect(temp_dir.path()), None);
+    }
+}
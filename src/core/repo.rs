@@ -1,7 +1,14 @@
+use crate::core::{Attention, Priority, ProjectType, Staleness};
+use crate::error::ErrorReport;
+use crate::git::{
+    Artifact, BranchStatus, DiffPreview, InProgressOperation, LockFile, RemoteComparison,
+};
 use serde::Serialize;
 use std::path::PathBuf;
+use std::time::SystemTime;
 
 #[derive(Debug, Clone, Serialize)]
+#[non_exhaustive]
 pub struct Repository {
     pub path: PathBuf,
     pub name: String,
@@ -11,6 +18,118 @@ pub struct Repository {
     pub needs_pull: bool,
     pub needs_push: bool,
     pub remote_branch: Option<String>,
+    pub branches: Vec<BranchStatus>,
+    pub remote_only_branches: Vec<String>,
+    #[serde(serialize_with = "crate::time::serialize_optional_utc")]
+    pub dirty_since: Option<SystemTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_comparison: Option<RemoteComparison>,
+    /// リモートとの比較に失敗した場合のエラー（`--compare-remote` 指定時のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comparison_error: Option<ErrorReport>,
+    /// このスキャンでのfetchの結果（fetchしなかった場合はNone）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fetch_outcome: Option<FetchOutcome>,
+    pub fetch_error: Option<ErrorReport>,
+    pub status_error: Option<ErrorReport>,
+    pub locks: Vec<LockFile>,
+    /// HEADのコミット日時
+    #[serde(
+        serialize_with = "crate::time::serialize_optional_utc",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub last_commit_at: Option<SystemTime>,
+    /// 最後のコミットからの経過時間と変更の有無による分類
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub staleness: Option<Staleness>,
+    /// 正規化したoriginのURL（同じプロジェクトの別のクローンを見分けるのに使う）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_id: Option<String>,
+    /// リモートをブラウザで開くURL（フォージのページ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub web_url: Option<String>,
+    /// pendectorが状態を取得した日時
+    #[serde(
+        serialize_with = "crate::time::serialize_optional_utc",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub checked_at: Option<SystemTime>,
+    /// 設定ファイルで指定した優先度（高いものを先に表示する）
+    #[serde(skip_serializing_if = "Priority::is_normal")]
+    pub priority: Priority,
+    /// このリポジトリを見つけたスキャンルート（設定ファイルやCLI引数で指定したままのパス）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scan_root: Option<String>,
+    /// 作業ツリーから離れた場所にあるgitディレクトリ（`[[repos.detached]]` で指定したもののみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_dir: Option<PathBuf>,
+    /// 途中で止まっている操作（merge、rebase、bisect、amなど）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation: Option<InProgressOperation>,
+    /// 作業ツリーと `.git` を合わせたディスク使用量（`--du` 指定時のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disk_usage: Option<u64>,
+    /// 無視された大きなディレクトリ（`--artifacts` 指定時のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artifacts: Option<Vec<Artifact>>,
+    /// 無視された成果物の集計に失敗した場合のエラー
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artifacts_error: Option<ErrorReport>,
+    /// 作業ツリーの差分の先頭部分（`--preview` 指定時のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff_preview: Option<DiffPreview>,
+    /// 差分のプレビューの取得に失敗した場合のエラー
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff_preview_error: Option<ErrorReport>,
+    /// fetchせずに調べたため、リモートとの同期状態が古い可能性がある（`--offline` 指定時のみ）
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub sync_stale: bool,
+    /// 変更ファイルが `status_file_limit` を超え、一覧を途中で打ち切った
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub changed_files_truncated: bool,
+    /// 現在のブランチにまだコミットがない（`git init` 直後や空のリモートのクローン）
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub unborn: bool,
+    /// 作業ツリーの直下のファイルから判定したプロジェクトの種類
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_type: Option<ProjectType>,
+}
+
+/// スキャン中に行ったfetchの結果（失敗の詳細は `fetch_error` に入る）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FetchOutcome {
+    Fetched,
+    Failed,
+}
+
+/// リポジトリ一覧のfetchの結果の集計
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct FetchSummary {
+    /// fetchに成功したリポジトリの数
+    pub fetched: usize,
+    /// fetchに失敗したリポジトリの数
+    pub failed: usize,
+}
+
+impl FetchSummary {
+    pub fn of(repositories: &[Repository]) -> Self {
+        let count = |outcome| {
+            repositories
+                .iter()
+                .filter(|r| r.fetch_outcome == Some(outcome))
+                .count()
+        };
+        Self {
+            fetched: count(FetchOutcome::Fetched),
+            failed: count(FetchOutcome::Failed),
+        }
+    }
+
+    /// fetchを試みたリポジトリの数
+    pub fn attempted(&self) -> usize {
+        self.fetched + self.failed
+    }
 }
 
 impl Repository {
@@ -47,6 +166,33 @@ impl Repository {
             needs_pull: false,
             needs_push: false,
             remote_branch: None,
+            branches: Vec::new(),
+            remote_only_branches: Vec::new(),
+            dirty_since: None,
+            remote_comparison: None,
+            comparison_error: None,
+            fetch_outcome: None,
+            fetch_error: None,
+            status_error: None,
+            locks: Vec::new(),
+            last_commit_at: None,
+            staleness: None,
+            remote_id: None,
+            web_url: None,
+            checked_at: None,
+            priority: Priority::Normal,
+            scan_root: None,
+            git_dir: None,
+            operation: None,
+            disk_usage: None,
+            artifacts: None,
+            artifacts_error: None,
+            diff_preview: None,
+            diff_preview_error: None,
+            sync_stale: false,
+            changed_files_truncated: false,
+            unborn: false,
+            project_type: None,
         }
     }
 
@@ -63,6 +209,16 @@ impl Repository {
         self
     }
 
+    /// 未コミットの変更や未同期のコミットがあり、対応が必要か（状態を取得できなかった場合も含む）
+    pub fn needs_attention(&self) -> bool {
+        self.attention() != Attention::None
+    }
+
+    /// 対応が必要な理由による分類
+    pub fn attention(&self) -> Attention {
+        Attention::of(self)
+    }
+
     /// リモート同期情報を取得する
     pub fn with_remote_info(
         mut self,
@@ -75,6 +231,208 @@ impl Repository {
         self.remote_branch = remote_branch;
         self
     }
+
+    /// ローカルブランチごとの同期状態と、ローカルにないリモートブランチを設定する
+    pub fn with_branches(
+        mut self,
+        branches: Vec<BranchStatus>,
+        remote_only_branches: Vec<String>,
+    ) -> Self {
+        self.branches = branches;
+        self.remote_only_branches = remote_only_branches;
+        self
+    }
+
+    /// ディスク使用量を設定する
+    pub fn with_disk_usage(mut self, disk_usage: Option<u64>) -> Self {
+        self.disk_usage = disk_usage;
+        self
+    }
+
+    /// 無視された大きなディレクトリを設定する
+    pub fn with_artifacts(mut self, artifacts: Option<Vec<Artifact>>) -> Self {
+        self.artifacts = artifacts;
+        self
+    }
+
+    /// 無視された成果物の集計に失敗した場合のエラーを設定する
+    pub fn with_artifacts_error(mut self, artifacts_error: Option<ErrorReport>) -> Self {
+        self.artifacts_error = artifacts_error;
+        self
+    }
+
+    /// 作業ツリーの差分の先頭部分を設定する
+    pub fn with_diff_preview(mut self, diff_preview: Option<DiffPreview>) -> Self {
+        self.diff_preview = diff_preview;
+        self
+    }
+
+    /// 差分のプレビューの取得に失敗した場合のエラーを設定する
+    pub fn with_diff_preview_error(mut self, diff_preview_error: Option<ErrorReport>) -> Self {
+        self.diff_preview_error = diff_preview_error;
+        self
+    }
+
+    /// 無視された大きなディレクトリの合計サイズ
+    pub fn artifacts_size(&self) -> u64 {
+        self.artifacts
+            .iter()
+            .flatten()
+            .map(|artifact| artifact.size_bytes)
+            .sum()
+    }
+
+    /// HEADのコミット日時を設定する
+    pub fn with_last_commit_at(mut self, last_commit_at: Option<SystemTime>) -> Self {
+        self.last_commit_at = last_commit_at;
+        self
+    }
+
+    /// 分類を設定する
+    pub fn with_staleness(mut self, staleness: Option<Staleness>) -> Self {
+        self.staleness = staleness;
+        self
+    }
+
+    /// 状態を取得した日時を設定する
+    pub fn with_checked_at(mut self, checked_at: Option<SystemTime>) -> Self {
+        self.checked_at = checked_at;
+        self
+    }
+
+    /// 優先度を設定する
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// 同期状態が古い可能性があるかを設定する
+    pub fn with_sync_stale(mut self, sync_stale: bool) -> Self {
+        self.sync_stale = sync_stale;
+        self
+    }
+
+    /// 変更ファイルの一覧を途中で打ち切ったかを設定する
+    pub fn with_changed_files_truncated(mut self, truncated: bool) -> Self {
+        self.changed_files_truncated = truncated;
+        self
+    }
+
+    /// 変更ファイル数の表示（打ち切った場合は `1000+` のように上限を示す）
+    pub fn changed_files_count(&self) -> String {
+        let count = self.changed_files.len();
+        if self.changed_files_truncated {
+            format!("{count}+")
+        } else {
+            count.to_string()
+        }
+    }
+
+    /// 現在のブランチにまだコミットがないかを設定する
+    pub fn with_unborn(mut self, unborn: bool) -> Self {
+        self.unborn = unborn;
+        self
+    }
+
+    /// プロジェクトの種類を設定する
+    pub fn with_project_type(mut self, project_type: Option<ProjectType>) -> Self {
+        self.project_type = project_type;
+        self
+    }
+
+    /// ブランチ名の表示（コミットがなければ `main (unborn)`、detached HEADなどは `unknown`）
+    pub fn branch_label(&self) -> String {
+        match &self.current_branch {
+            Some(branch) if self.unborn => format!("{branch} (unborn)"),
+            Some(branch) => branch.clone(),
+            None => "unknown".to_string(),
+        }
+    }
+
+    /// 見つけたスキャンルートを設定する
+    pub fn with_scan_root(mut self, scan_root: Option<String>) -> Self {
+        self.scan_root = scan_root;
+        self
+    }
+
+    /// 作業ツリーから離れたgitディレクトリを設定する
+    pub fn with_git_dir(mut self, git_dir: Option<PathBuf>) -> Self {
+        self.git_dir = git_dir;
+        self
+    }
+
+    /// 正規化したリモートURLを設定する
+    pub fn with_remote_id(mut self, remote_id: Option<String>) -> Self {
+        self.remote_id = remote_id;
+        self
+    }
+
+    /// リモートをブラウザで開くURLを設定する
+    pub fn with_web_url(mut self, web_url: Option<String>) -> Self {
+        self.web_url = web_url;
+        self
+    }
+
+    /// 途中で止まっている操作を設定する
+    pub fn with_operation(mut self, operation: Option<InProgressOperation>) -> Self {
+        self.operation = operation;
+        self
+    }
+
+    /// gitディレクトリに残っているロックファイルを設定する
+    pub fn with_locks(mut self, locks: Vec<LockFile>) -> Self {
+        self.locks = locks;
+        self
+    }
+
+    /// ステータスの取得に失敗した場合のエラーを設定する
+    pub fn with_status_error(mut self, status_error: Option<ErrorReport>) -> Self {
+        self.status_error = status_error;
+        self
+    }
+
+    /// fetchに失敗した場合のエラーを設定する
+    pub fn with_fetch_error(mut self, fetch_error: Option<ErrorReport>) -> Self {
+        self.fetch_error = fetch_error;
+        self
+    }
+
+    /// このスキャンでのfetchの結果を設定する
+    pub fn with_fetch_outcome(mut self, fetch_outcome: Option<FetchOutcome>) -> Self {
+        self.fetch_outcome = fetch_outcome;
+        self
+    }
+
+    /// 別リモートのデフォルトブランチとの比較結果を設定する
+    pub fn with_remote_comparison(mut self, remote_comparison: Option<RemoteComparison>) -> Self {
+        self.remote_comparison = remote_comparison;
+        self
+    }
+
+    /// 別リモートとの比較に失敗した場合のエラーを設定する
+    pub fn with_comparison_error(mut self, comparison_error: Option<ErrorReport>) -> Self {
+        self.comparison_error = comparison_error;
+        self
+    }
+
+    /// 失敗した処理の段階（`status`、`fetch` など）とそのエラー
+    pub fn errors(&self) -> impl Iterator<Item = (&'static str, &ErrorReport)> {
+        [
+            ("status", self.status_error.as_ref()),
+            ("fetch", self.fetch_error.as_ref()),
+            ("compare", self.comparison_error.as_ref()),
+            ("artifacts", self.artifacts_error.as_ref()),
+            ("preview", self.diff_preview_error.as_ref()),
+        ]
+        .into_iter()
+        .filter_map(|(stage, error)| error.map(|error| (stage, error)))
+    }
+
+    /// 未コミットの変更が最後に更新された時刻を設定する
+    pub fn with_dirty_since(mut self, dirty_since: Option<SystemTime>) -> Self {
+        self.dirty_since = dirty_since;
+        self
+    }
 }
 
 #[cfg(test)]
@@ -145,6 +503,12 @@ mod tests {
         assert!(repo.has_changes);
         assert_eq!(repo.current_branch, Some("main".to_string()));
         assert_eq!(repo.changed_files, files);
+        assert_eq!(repo.changed_files_count(), "2");
+        assert_eq!(
+            repo.with_changed_files_truncated(true)
+                .changed_files_count(),
+            "2+"
+        );
     }
 
     #[test]
@@ -158,6 +522,28 @@ mod tests {
         assert!(repo.changed_files.is_empty());
     }
 
+    #[test]
+    fn test_repository_needs_attention() {
+        let path = PathBuf::from("/test/repo");
+
+        assert!(!Repository::new(path.clone()).needs_attention());
+        assert!(Repository::new(path.clone())
+            .with_git_info(true, None, vec!["?? a".to_string()])
+            .needs_attention());
+        assert!(Repository::new(path.clone())
+            .with_remote_info(false, true, None)
+            .needs_attention());
+        assert!(Repository::new(path.clone())
+            .with_remote_info(true, false, None)
+            .needs_attention());
+        assert!(Repository::new(path)
+            .with_status_error(Some(ErrorReport {
+                code: crate::error::ErrorCode::GitOperation,
+                message: "index is locked".to_string(),
+            }))
+            .needs_attention());
+    }
+
     #[test]
     fn test_repository_clone() {
         let path = PathBuf::from("/test/repo");
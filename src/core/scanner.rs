@@ -1,12 +1,689 @@
-use crate::core::Repository;
-use crate::error::PendectorResult;
+use crate::core::{
+    log_filter_decision, FetchOutcome, FilterReason, NameStyle, Priority, ProjectType, Repository,
+    Staleness, DEFAULT_STALE_DIRTY_AFTER,
+};
+use crate::error::{ErrorReport, PendectorError, PendectorResult};
 use crate::exclude::ExcludeFilter;
-use crate::git::GitStatus;
+use crate::git::status::run_with_jobs;
+use crate::git::status::{DEFAULT_FETCH_SLOW_THRESHOLD_SECS, DEFAULT_FETCH_TIMEOUT_SECS};
+use crate::git::{
+    detached_git_dir, diff_preview, dir_size, find_ignored_artifacts, find_lock_files,
+    has_scheduled_fetch, web_url, FetchOptions, GitStatus, StatusQuery, UnbornRemote,
+    DEFAULT_BRANCH_NAMES,
+};
+use crate::interrupt::is_interrupted;
+use crate::progress::{ProgressEvent, ProgressReporter, DEFAULT_PROGRESS_INTERVAL_SECS};
 use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime};
 use walkdir::WalkDir;
 
+pub use crate::progress::ProgressMode;
+
+/// リポジトリ探索の動作を指定するオプション
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ScanOptions {
+    /// 再帰探索する最大の深さ
+    pub max_depth: usize,
+    /// ステータス取得前にfetchを実行するか
+    pub fetch: bool,
+    /// fetch 1回あたりのタイムアウト
+    pub fetch_timeout: Duration,
+    /// この時間を超えたfetchを「遅い」とみなして報告する
+    pub fetch_slow_threshold: Duration,
+    /// 遅いfetchをタイムアウトを待たずに打ち切るか
+    pub fetch_skip_slow: bool,
+    /// シンボリックリンクをたどって探索するか
+    pub follow_symlinks: bool,
+    /// 除外パターン（gitignore形式）
+    pub exclude_patterns: Vec<String>,
+    /// リポジトリのディレクトリ名に対する除外パターン（例: `*-archive`）
+    pub exclude_repos: Vec<String>,
+    /// 進捗の表示方式
+    pub progress: ProgressMode,
+    /// stderrが端末でないときに進捗の行を書く間隔
+    pub progress_interval: Duration,
+    /// デフォルトブランチと比較する追加のリモート（例: フォーク元の `upstream`）
+    pub compare_remote: Option<String>,
+    /// リモートのHEADが取得できない場合に試すデフォルトブランチ名
+    pub default_branches: Vec<String>,
+    /// ahead/behindの報告から外すブランチ名のパターン
+    pub ignore_branches: Vec<String>,
+    /// 変更ファイルをこの件数まで調べたら打ち切る（未指定なら無制限）
+    pub status_file_limit: Option<usize>,
+    /// ステータス取得で調べ直したファイルの情報をindexに書き戻す
+    pub status_update_index: bool,
+    /// 変更として数えないファイルのパターン（設定の読み込み時にコンパイルしたもの）
+    pub dirty_ignore: ExcludeFilter,
+    /// コミットのないブランチでリモートにコミットがある場合の扱い
+    pub unborn_remote: UnbornRemote,
+    /// リモートとの同期状態を調べない（fetchも行わない）
+    pub skip_remote_check: bool,
+    /// セルフホストのフォージのホスト名ごとの、ブラウザで開くURLのテンプレート
+    pub web_url_templates: BTreeMap<String, String>,
+    /// fetchのプロセスに追加で設定する環境変数
+    pub fetch_env: BTreeMap<String, String>,
+    /// fetchで無効化せずに呼び出し元の値を引き継ぐ環境変数
+    pub fetch_env_passthrough: Vec<String>,
+    /// fetchでHTTP(S)のリモートに使うプロキシ
+    pub fetch_proxy: Option<String>,
+    /// fetchでSSHのリモートに使うプロキシコマンド
+    pub fetch_ssh_proxy_command: Option<String>,
+    /// 発見したリポジトリがこの数に達したら探索を打ち切る
+    pub limit: Option<usize>,
+    /// この時間を過ぎたらスキャンルートの探索・fetch・状態の取得を打ち切る
+    pub time_budget: Option<Duration>,
+    /// リポジトリごとのディスク使用量を計測するか
+    pub disk_usage: bool,
+    /// 無視された大きなディレクトリ（ビルド成果物など）を調べるか
+    pub artifacts: bool,
+    /// `git maintenance` の定期fetchが設定されたリポジトリはfetchしないか
+    pub trust_maintenance: bool,
+    /// 見つかったリポジトリに付ける優先度
+    pub priority: Priority,
+    /// 探索中に読めなかったディレクトリの扱い
+    pub walk_errors: WalkErrors,
+    /// 除外や深さの上限で探索しなかったディレクトリの数を表示するか
+    pub scan_stats: bool,
+    /// リポジトリを除いた理由を理由コード付きで出力するか（`-vv`）
+    pub explain_filtering: bool,
+    /// リポジトリの表示名の決め方
+    pub name_style: NameStyle,
+    /// fetchを一切行わず、同期状態が古い可能性があることを記録するか
+    pub offline: bool,
+    /// 変更のあるリポジトリで作業ツリーの差分を先頭から何行取得するか
+    pub preview_lines: Option<usize>,
+    /// この期間より古い未コミットの変更を放置されたものとして分類する
+    pub stale_dirty_after: Duration,
+    /// ステータス取得の並列数（Noneならrayonの既定値）
+    pub jobs: Option<usize>,
+    /// fetchの並列数（Noneならrayonの既定値）
+    pub fetch_jobs: Option<usize>,
+}
+
+impl ScanOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 探索する最大の深さを設定する
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// fetchの有無を設定する
+    pub fn with_fetch(mut self, fetch: bool) -> Self {
+        self.fetch = fetch;
+        self
+    }
+
+    /// fetchのタイムアウトを設定する
+    pub fn with_fetch_timeout(mut self, fetch_timeout: Duration) -> Self {
+        self.fetch_timeout = fetch_timeout;
+        self
+    }
+
+    /// 遅いfetchとみなす閾値を設定する
+    pub fn with_fetch_slow_threshold(mut self, fetch_slow_threshold: Duration) -> Self {
+        self.fetch_slow_threshold = fetch_slow_threshold;
+        self
+    }
+
+    /// 遅いfetchを打ち切るかを設定する
+    pub fn with_fetch_skip_slow(mut self, fetch_skip_slow: bool) -> Self {
+        self.fetch_skip_slow = fetch_skip_slow;
+        self
+    }
+
+    /// シンボリックリンクをたどるかを設定する
+    pub fn with_follow_symlinks(mut self, follow_symlinks: bool) -> Self {
+        self.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    /// 除外パターンを設定する
+    pub fn with_exclude_patterns(mut self, exclude_patterns: Vec<String>) -> Self {
+        self.exclude_patterns = exclude_patterns;
+        self
+    }
+
+    /// リポジトリ名に対する除外パターンを設定する
+    pub fn with_exclude_repos(mut self, exclude_repos: Vec<String>) -> Self {
+        self.exclude_repos = exclude_repos;
+        self
+    }
+
+    /// 進捗の表示方式を設定する
+    pub fn with_progress(mut self, progress: ProgressMode) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// stderrが端末でないときに進捗の行を書く間隔を設定する
+    pub fn with_progress_interval(mut self, interval: Duration) -> Self {
+        self.progress_interval = interval;
+        self
+    }
+
+    /// デフォルトブランチと比較する追加のリモートを設定する
+    pub fn with_compare_remote(mut self, compare_remote: Option<String>) -> Self {
+        self.compare_remote = compare_remote;
+        self
+    }
+
+    /// デフォルトブランチ名の候補を設定する
+    pub fn with_default_branches(mut self, default_branches: Vec<String>) -> Self {
+        self.default_branches = default_branches;
+        self
+    }
+
+    /// ahead/behindの報告から外すブランチ名のパターンを設定する
+    pub fn with_ignore_branches(mut self, ignore_branches: Vec<String>) -> Self {
+        self.ignore_branches = ignore_branches;
+        self
+    }
+
+    /// ステータス取得時のindexへの書き戻しを設定する
+    pub fn with_status_update_index(mut self, status_update_index: bool) -> Self {
+        self.status_update_index = status_update_index;
+        self
+    }
+
+    /// コミットのないブランチでリモートにコミットがある場合の扱いを設定する
+    pub fn with_unborn_remote(mut self, unborn_remote: UnbornRemote) -> Self {
+        self.unborn_remote = unborn_remote;
+        self
+    }
+
+    /// リモートとの同期状態を調べないかを設定する
+    pub fn with_skip_remote_check(mut self, skip_remote_check: bool) -> Self {
+        self.skip_remote_check = skip_remote_check;
+        self
+    }
+
+    /// 変更として数えないファイルのパターンを設定する
+    pub fn with_dirty_ignore(mut self, dirty_ignore: ExcludeFilter) -> Self {
+        self.dirty_ignore = dirty_ignore;
+        self
+    }
+
+    /// ブラウザで開くURLのテンプレートを設定する
+    pub fn with_web_url_templates(mut self, web_url_templates: BTreeMap<String, String>) -> Self {
+        self.web_url_templates = web_url_templates;
+        self
+    }
+
+    /// 変更ファイルを調べる上限を設定する
+    pub fn with_status_file_limit(mut self, status_file_limit: Option<usize>) -> Self {
+        self.status_file_limit = status_file_limit;
+        self
+    }
+
+    /// fetchのプロセスに追加で設定する環境変数を設定する
+    pub fn with_fetch_env(mut self, fetch_env: BTreeMap<String, String>) -> Self {
+        self.fetch_env = fetch_env;
+        self
+    }
+
+    /// fetchで呼び出し元の値を引き継ぐ環境変数を設定する
+    pub fn with_fetch_env_passthrough(mut self, fetch_env_passthrough: Vec<String>) -> Self {
+        self.fetch_env_passthrough = fetch_env_passthrough;
+        self
+    }
+
+    /// fetchで使うプロキシ（HTTP(S)）とプロキシコマンド（SSH）を設定する
+    pub fn with_fetch_proxy(
+        mut self,
+        proxy: Option<String>,
+        ssh_proxy_command: Option<String>,
+    ) -> Self {
+        self.fetch_proxy = proxy;
+        self.fetch_ssh_proxy_command = ssh_proxy_command;
+        self
+    }
+
+    /// ディスク使用量の計測の有無を設定する
+    pub fn with_disk_usage(mut self, disk_usage: bool) -> Self {
+        self.disk_usage = disk_usage;
+        self
+    }
+
+    /// 無視された大きなディレクトリを調べるかを設定する
+    pub fn with_artifacts(mut self, artifacts: bool) -> Self {
+        self.artifacts = artifacts;
+        self
+    }
+
+    /// `git maintenance` の定期fetchに任せるかを設定する
+    pub fn with_trust_maintenance(mut self, trust_maintenance: bool) -> Self {
+        self.trust_maintenance = trust_maintenance;
+        self
+    }
+
+    /// 探索中に読めなかったディレクトリの扱いを設定する
+    pub fn with_walk_errors(mut self, walk_errors: WalkErrors) -> Self {
+        self.walk_errors = walk_errors;
+        self
+    }
+
+    /// 探索の集計を表示するかを設定する
+    pub fn with_scan_stats(mut self, scan_stats: bool) -> Self {
+        self.scan_stats = scan_stats;
+        self
+    }
+
+    /// 絞り込みの理由を出力するかを設定する
+    pub fn with_explain_filtering(mut self, explain_filtering: bool) -> Self {
+        self.explain_filtering = explain_filtering;
+        self
+    }
+
+    /// リポジトリの表示名の決め方を設定する
+    pub fn with_name_style(mut self, name_style: NameStyle) -> Self {
+        self.name_style = name_style;
+        self
+    }
+
+    /// オフラインで調べるかを設定する（`fetch` の設定より優先される）
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// 見つかったリポジトリに付ける優先度を設定する
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// 差分のプレビューの行数を設定する（Noneなら取得しない）
+    pub fn with_preview_lines(mut self, preview_lines: Option<usize>) -> Self {
+        self.preview_lines = preview_lines;
+        self
+    }
+
+    /// 放置された変更とみなす経過時間を設定する
+    pub fn with_stale_dirty_after(mut self, stale_dirty_after: Duration) -> Self {
+        self.stale_dirty_after = stale_dirty_after;
+        self
+    }
+
+    /// ステータス取得の並列数を設定する（低速なディスクやNFSでは小さくする）
+    pub fn with_jobs(mut self, jobs: Option<usize>) -> Self {
+        self.jobs = jobs;
+        self
+    }
+
+    /// fetchの並列数を設定する
+    pub fn with_fetch_jobs(mut self, fetch_jobs: Option<usize>) -> Self {
+        self.fetch_jobs = fetch_jobs;
+        self
+    }
+
+    /// 探索するリポジトリ数の上限を設定する
+    pub fn with_limit(mut self, limit: Option<usize>) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// スキャンルートの探索時間の上限を設定する
+    pub fn with_time_budget(mut self, time_budget: Option<Duration>) -> Self {
+        self.time_budget = time_budget;
+        self
+    }
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: 10,
+            fetch: false,
+            fetch_timeout: Duration::from_secs(DEFAULT_FETCH_TIMEOUT_SECS),
+            fetch_slow_threshold: Duration::from_secs(DEFAULT_FETCH_SLOW_THRESHOLD_SECS),
+            fetch_skip_slow: false,
+            follow_symlinks: false,
+            exclude_patterns: Vec::new(),
+            exclude_repos: Vec::new(),
+            progress: ProgressMode::default(),
+            progress_interval: Duration::from_secs(DEFAULT_PROGRESS_INTERVAL_SECS),
+            compare_remote: None,
+            default_branches: DEFAULT_BRANCH_NAMES.iter().map(|s| s.to_string()).collect(),
+            ignore_branches: Vec::new(),
+            status_file_limit: None,
+            status_update_index: false,
+            dirty_ignore: ExcludeFilter::new(),
+            unborn_remote: UnbornRemote::default(),
+            skip_remote_check: false,
+            web_url_templates: BTreeMap::new(),
+            fetch_env: BTreeMap::new(),
+            fetch_env_passthrough: Vec::new(),
+            fetch_proxy: None,
+            fetch_ssh_proxy_command: None,
+            limit: None,
+            time_budget: None,
+            disk_usage: false,
+            artifacts: false,
+            trust_maintenance: false,
+            walk_errors: WalkErrors::default(),
+            scan_stats: false,
+            explain_filtering: false,
+            name_style: NameStyle::default(),
+            offline: false,
+            priority: Priority::Normal,
+            preview_lines: None,
+            stale_dirty_after: DEFAULT_STALE_DIRTY_AFTER,
+            jobs: None,
+            fetch_jobs: None,
+        }
+    }
+}
+
+/// 探索中に読めなかったディレクトリの扱い
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WalkErrors {
+    /// 権限のないディレクトリは件数だけをまとめて警告する
+    #[default]
+    Summary,
+    /// 権限のないディレクトリを件数とパスの一覧で警告する
+    Details,
+    /// 権限のないディレクトリは警告せずに飛ばす
+    SkipPermissionDenied,
+    /// 読めないディレクトリがあればスキャンを失敗させる（CI向け）
+    Strict,
+}
+
+/// ディレクトリ探索の集計（除外や深さの設定の効果を確かめるため `-v` で表示する）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct ScanStats {
+    /// 調べたディレクトリ（`.git` の中は除く）
+    directories: usize,
+    /// 除外パターンにより配下を探索しなかったディレクトリ
+    excluded: usize,
+    /// 深さの上限に達して配下を探索しなかったディレクトリ
+    depth_limited: usize,
+    /// 読めなかったパス
+    errors: usize,
+    /// 探索時間の上限を過ぎたため配下を探索しなかったディレクトリ
+    over_budget: usize,
+}
+
+/// 探索時間の上限（[`ScanOptions::time_budget`]）を過ぎて、スキャンルートの一部を調べられなかったこと
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct BudgetExceeded {
+    /// スキャンルート
+    pub root: PathBuf,
+    /// 探索時間の上限
+    pub budget: Duration,
+    /// 状態を取得できたリポジトリ
+    pub repositories: usize,
+    /// 上限を過ぎたため配下を探索しなかったディレクトリ
+    pub directories_not_descended: usize,
+    /// 見つけたが上限を過ぎたため状態を取得しなかったリポジトリ
+    pub repositories_not_checked: usize,
+    /// 上限を過ぎたため打ち切った・始めなかったfetch
+    pub fetches_abandoned: usize,
+}
+
+/// 探索中に読めなかったパス（権限がないものを除く）
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct WalkFailure {
+    /// 読めなかったパス（特定できない場合はNone）
+    pub path: Option<PathBuf>,
+    /// 失敗の内容
+    pub message: String,
+}
+
+/// スキャンルート1つを探索した結果
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct RootScan {
+    /// 見つかったリポジトリ
+    pub repositories: Vec<Repository>,
+    /// 探索時間の上限を過ぎて調べられなかった範囲
+    pub budget_exceeded: Option<BudgetExceeded>,
+    /// 権限がなく探索できなかったディレクトリ（[`WalkErrors::SkipPermissionDenied`] では空）
+    pub permission_denied: Vec<PathBuf>,
+    /// 権限以外の理由で読めなかったパス
+    pub walk_failures: Vec<WalkFailure>,
+}
+
+/// ディレクトリ探索で見つけたリポジトリのパスと、読めなかったパス
+#[derive(Debug, Default)]
+struct Discovery {
+    repo_paths: Vec<PathBuf>,
+    stats: ScanStats,
+    permission_denied: Vec<PathBuf>,
+    walk_failures: Vec<WalkFailure>,
+}
+
+/// 時間の上限を過ぎたため状態の取得で飛ばしたもの
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct OverBudget {
+    /// 状態を取得しなかったリポジトリ
+    statuses: usize,
+    /// 打ち切った・始めなかったfetch
+    fetches: usize,
+}
+
+/// 時間の上限を過ぎたか
+fn is_past(deadline: Option<Instant>) -> bool {
+    deadline.is_some_and(|deadline| Instant::now() >= deadline)
+}
+
+/// 表示名の決め方に従ってリポジトリ名を付け直す
+fn apply_name_style(repositories: &mut [Repository], name_style: NameStyle, root: Option<&Path>) {
+    for repo in repositories {
+        if let Some(name) = name_style.name_for(repo, root) {
+            repo.name = name;
+        }
+    }
+}
+
+/// 探索の集計を表示する
+fn report_scan_stats(root: &Path, stats: &ScanStats, max_depth: usize) {
+    eprintln!(
+        "Scanned '{}': {} directories, {} pruned by excludes, {} not descended (max depth {max_depth}), {} errors",
+        root.display(),
+        stats.directories,
+        stats.excluded,
+        stats.depth_limited,
+        stats.errors
+    );
+}
+
+/// スキャンルート配下のリポジトリのパスを集める（除外パターン適用、上限に達したら探索を打ち切る）
+///
+/// `deadline` を過ぎたら新しいディレクトリには入らない。
+fn discover_repo_paths(
+    base_path: &Path,
+    options: &ScanOptions,
+    exclude_filter: &ExcludeFilter,
+    repo_name_filter: &ExcludeFilter,
+    deadline: Option<Instant>,
+) -> PendectorResult<Discovery> {
+    let mut seen = HashSet::new();
+    let mut denied: Vec<PathBuf> = Vec::new();
+    let mut walk_failures: Vec<WalkFailure> = Vec::new();
+    let mut walk_error: Option<walkdir::Error> = None;
+    let mut stats = ScanStats::default();
+    let repo_paths: Vec<PathBuf> = WalkDir::new(base_path)
+        .follow_links(options.follow_symlinks)
+        .max_depth(options.max_depth)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            // 除外されたディレクトリの配下は探索しない（スキャンルートからの相対パスで判定）
+            let relative_path = entry.path().strip_prefix(base_path).unwrap_or(entry.path());
+            let is_dir = entry.file_type().is_dir();
+            // 時間切れになったら新しいディレクトリには入らない（入ったディレクトリの `.git` は調べる）
+            let over_budget =
+                is_dir && entry.depth() > 0 && entry.file_name() != ".git" && is_past(deadline);
+            if over_budget {
+                stats.over_budget += 1;
+                return false;
+            }
+            // 中断が要求されたら新しいディレクトリには入らない（結果は呼び出し側が捨てる）
+            if is_dir && entry.depth() > 0 && is_interrupted() {
+                return false;
+            }
+            let excluded = exclude_filter.is_excluded_at(relative_path, is_dir);
+            // 否定パターンで配下が再包含されうるディレクトリには入り、見つけた `.git` をあとで判定する
+            if excluded && is_dir && exclude_filter.may_reinclude_below(relative_path) {
+                return true;
+            }
+            if excluded && is_dir {
+                stats.excluded += 1;
+                if options.explain_filtering {
+                    let pattern = exclude_filter.explain_at(relative_path, is_dir);
+                    log_filter_decision(
+                        FilterReason::ExcludePattern,
+                        entry.path(),
+                        pattern.as_ref().map(|m| m.pattern.as_str()),
+                    );
+                }
+            }
+            !excluded
+        })
+        .filter_map(|e| match e {
+            Ok(entry) => Some(entry),
+            Err(err) => {
+                stats.errors += 1;
+                let permission_denied = err
+                    .io_error()
+                    .is_some_and(|e| e.kind() == ErrorKind::PermissionDenied);
+                if options.walk_errors == WalkErrors::Strict {
+                    walk_error.get_or_insert(err);
+                } else if permission_denied {
+                    // 権限のないディレクトリは呼び出し側がまとめて警告する
+                    if options.walk_errors != WalkErrors::SkipPermissionDenied {
+                        denied.extend(err.path().map(Path::to_path_buf));
+                    }
+                } else {
+                    walk_failures.push(WalkFailure {
+                        path: err.path().map(Path::to_path_buf),
+                        message: err.to_string(),
+                    });
+                }
+                None
+            }
+        })
+        .inspect(|entry| {
+            let relative_path = entry.path().strip_prefix(base_path).unwrap_or(entry.path());
+            let in_git_dir = relative_path.components().any(|c| c.as_os_str() == ".git");
+            if entry.file_type().is_dir() && !in_git_dir {
+                stats.directories += 1;
+                // 上限の深さにあっても、配下にディレクトリがなければ探索を打ち切ったことにはならない
+                if options.scan_stats
+                    && entry.depth() == options.max_depth
+                    && has_subdirectory(entry.path())
+                {
+                    stats.depth_limited += 1;
+                }
+            }
+        })
+        .filter(|entry| entry.file_type().is_dir() && entry.file_name() == ".git")
+        .filter(|entry| {
+            // 除外したディレクトリの配下では、否定パターンで再包含されたリポジトリだけを残す
+            let relative_path = entry.path().strip_prefix(base_path).unwrap_or(entry.path());
+            !exclude_filter.is_excluded_at(relative_path, true)
+        })
+        .filter_map(|entry| entry.path().parent().map(|p| p.to_path_buf()))
+        .filter(|path| {
+            let excluded = path
+                .file_name()
+                .is_some_and(|name| repo_name_filter.is_name_excluded(name));
+            if excluded && options.explain_filtering {
+                log_filter_decision(FilterReason::RepoName, path, None);
+            }
+            !excluded
+        })
+        // シンボリックリンク経由で同じリポジトリに複数回到達した場合は1つにまとめる
+        .filter(|path| {
+            let first = seen.insert(canonical_git_dir(path));
+            if !first && options.explain_filtering {
+                log_filter_decision(FilterReason::Duplicate, path, None);
+            }
+            first
+        })
+        .take(options.limit.unwrap_or(usize::MAX))
+        .collect();
+
+    if let Some(err) = walk_error {
+        let path = err.path().unwrap_or(base_path).to_path_buf();
+        return Err(PendectorError::from_io_error(path, err.to_string()));
+    }
+    Ok(Discovery {
+        repo_paths,
+        stats,
+        permission_denied: denied,
+        walk_failures,
+    })
+}
+
+/// 直下にディレクトリがあるか
+fn has_subdirectory(dir: &Path) -> bool {
+    std::fs::read_dir(dir).is_ok_and(|entries| {
+        entries
+            .filter_map(Result::ok)
+            .any(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+    })
+}
+
+/// リポジトリの `.git` ディレクトリの正規化パス（重複判定のキー）
+fn canonical_git_dir(repo_path: &Path) -> PathBuf {
+    let git_dir = detached_git_dir(repo_path).unwrap_or_else(|| repo_path.join(".git"));
+    git_dir.canonicalize().unwrap_or(git_dir)
+}
+
+/// 同じ物理リポジトリを指すエントリを取り除く（最初に見つかったものを残す）
+///
+/// 設定ファイルの複数のパスがシンボリックリンクで同じ場所を指している場合に使う。
+pub fn dedup_repositories(repositories: Vec<Repository>) -> Vec<Repository> {
+    partition_duplicates(repositories).0
+}
+
+/// 同じgitディレクトリを指すリポジトリを、最初に現れたものとそれ以外の重複に分ける
+pub fn partition_duplicates(repositories: Vec<Repository>) -> (Vec<Repository>, Vec<Repository>) {
+    let mut seen = HashSet::new();
+    repositories
+        .into_iter()
+        .partition(|repo| seen.insert(canonical_git_dir(&repo.path)))
+}
+
+/// 正規化したリモートURLごとにリポジトリをまとめる（最初に現れた順、リモートのないものは除く）
+///
+/// 同じgitディレクトリの重複は [`dedup_repositories`] で除くが、別の場所にある同じプロジェクトのクローンは
+/// 状態が異なりうるので残し、ここでまとめて扱う。
+pub fn group_by_remote_id(repositories: &[Repository]) -> Vec<(&str, Vec<&Repository>)> {
+    let mut groups: Vec<(&str, Vec<&Repository>)> = Vec::new();
+    for repo in repositories {
+        let Some(remote_id) = repo.remote_id.as_deref() else {
+            continue;
+        };
+        match groups.iter_mut().find(|(id, _)| *id == remote_id) {
+            Some((_, members)) => members.push(repo),
+            None => groups.push((remote_id, vec![repo])),
+        }
+    }
+    groups
+}
+
+/// 正規化したパス順に並べ替える（並列処理やディレクトリの読み出し順に依存しない出力にする）
+pub fn sort_by_canonical_path(repositories: &mut [Repository]) {
+    repositories.sort_by_cached_key(|repo| {
+        repo.path
+            .canonicalize()
+            .unwrap_or_else(|_| repo.path.clone())
+    });
+}
+
+/// ディレクトリツリーからGitリポジトリを探索するスキャナ
 pub struct RepoScanner;
 
 impl RepoScanner {
@@ -24,7 +701,7 @@ impl Default for RepoScanner {
 impl RepoScanner {
     /// 指定のパス以下でリポジトリを再帰的に探索する
     pub fn scan<P: AsRef<Path>>(&self, base_path: P) -> PendectorResult<Vec<Repository>> {
-        self.scan_with_depth(base_path, 10)
+        self.scan_with(base_path, &ScanOptions::default())
     }
 
     /// 指定の深さまでリポジトリを再帰的に探索する
@@ -33,7 +710,7 @@ impl RepoScanner {
         base_path: P,
         max_depth: usize,
     ) -> PendectorResult<Vec<Repository>> {
-        self.scan_with_options(base_path, max_depth, false)
+        self.scan_with(base_path, &ScanOptions::new().with_max_depth(max_depth))
     }
 
     /// 指定の深さとfetchオプションでリポジトリを再帰的に探索する
@@ -43,57 +720,29 @@ impl RepoScanner {
         max_depth: usize,
         should_fetch: bool,
     ) -> PendectorResult<Vec<Repository>> {
-        let base_path = base_path.as_ref();
-        let _base_path_str = base_path.to_string_lossy().to_string();
-
-        // まずすべてのリポジトリパスを収集
-        let repo_paths: Vec<PathBuf> = WalkDir::new(base_path)
-            .follow_links(false)
-            .max_depth(max_depth)
-            .into_iter()
-            .filter_map(|e| match e {
-                Ok(entry) => Some(entry),
-                Err(err) => {
-                    eprintln!("Warning: Failed to access path during scan: {err}");
-                    None
-                }
-            })
-            .filter(|entry| entry.file_type().is_dir() && entry.file_name() == ".git")
-            .filter_map(|entry| entry.path().parent().map(|p| p.to_path_buf()))
-            .collect();
-
-        // fetchが必要な場合は並列実行（プログレスバー付き）
-        if should_fetch && !repo_paths.is_empty() {
-            let _fetch_results = GitStatus::perform_parallel_fetch_with_progress(&repo_paths, true);
-            // fetch結果は警告として出力されるので、ここでは特に処理しない
-        }
-
-        // 各リポジトリの状態を並列取得
-        let repositories: Vec<Repository> = repo_paths
-            .par_iter()
-            .filter_map(|repo_path| {
-                let mut repository = Repository::new(repo_path.clone());
-
-                // Get git status information (fetchなしで実行)
-                if let Ok(status) = GitStatus::get_repository_status(repo_path) {
-                    repository = repository
-                        .with_git_info(
-                            status.has_changes,
-                            status.current_branch,
-                            status.changed_files,
-                        )
-                        .with_remote_info(
-                            status.needs_pull,
-                            status.needs_push,
-                            status.remote_branch,
-                        );
-                }
-
-                Some(repository)
-            })
-            .collect();
+        self.scan_with(
+            base_path,
+            &ScanOptions::new()
+                .with_max_depth(max_depth)
+                .with_fetch(should_fetch),
+        )
+    }
 
-        Ok(repositories)
+    /// タイムアウト設定付きで指定の深さとfetchオプションでリポジトリを再帰的に探索する
+    pub fn scan_with_options_and_timeout<P: AsRef<Path>>(
+        &self,
+        base_path: P,
+        max_depth: usize,
+        should_fetch: bool,
+        fetch_timeout_secs: u64,
+    ) -> PendectorResult<Vec<Repository>> {
+        self.scan_with(
+            base_path,
+            &ScanOptions::new()
+                .with_max_depth(max_depth)
+                .with_fetch(should_fetch)
+                .with_fetch_timeout(Duration::from_secs(fetch_timeout_secs)),
+        )
     }
 
     /// 除外パターン付きでリポジトリを再帰的に探索する
@@ -105,103 +754,290 @@ impl RepoScanner {
         fetch_timeout_secs: u64,
         exclude_patterns: &[String],
     ) -> PendectorResult<Vec<Repository>> {
-        let exclude_filter = ExcludeFilter::from_patterns(exclude_patterns).map_err(|e| {
-            crate::error::PendectorError::ConfigError {
-                path: std::path::PathBuf::new(),
-                message: format!("Invalid exclude pattern: {e}"),
-            }
-        })?;
-
-        self.scan_with_exclude_filter(
+        self.scan_with(
             base_path,
-            max_depth,
-            should_fetch,
-            fetch_timeout_secs,
-            &exclude_filter,
+            &ScanOptions::new()
+                .with_max_depth(max_depth)
+                .with_fetch(should_fetch)
+                .with_fetch_timeout(Duration::from_secs(fetch_timeout_secs))
+                .with_exclude_patterns(exclude_patterns.to_vec()),
         )
     }
 
+    /// ScanOptionsに従ってリポジトリを再帰的に探索する
+    pub fn scan_with<P: AsRef<Path>>(
+        &self,
+        base_path: P,
+        options: &ScanOptions,
+    ) -> PendectorResult<Vec<Repository>> {
+        self.scan_root(base_path, options)
+            .map(|scan| scan.repositories)
+    }
+
+    /// ScanOptionsに従ってリポジトリを再帰的に探索し、調べられなかった範囲や読めなかったパスも返す
+    pub fn scan_root<P: AsRef<Path>>(
+        &self,
+        base_path: P,
+        options: &ScanOptions,
+    ) -> PendectorResult<RootScan> {
+        // 除外パターンはスキャンルートを基準に評価する
+        let exclude_filter = ExcludeFilter::for_root(base_path.as_ref(), &options.exclude_patterns)
+            .map_err(|e| PendectorError::ConfigError {
+                path: PathBuf::new(),
+                message: format!("Invalid exclude pattern: {e}"),
+            })?;
+        let repo_name_filter =
+            ExcludeFilter::from_patterns(&options.exclude_repos).map_err(|e| {
+                PendectorError::ConfigError {
+                    path: PathBuf::new(),
+                    message: format!("Invalid exclude_repos pattern: {e}"),
+                }
+            })?;
+
+        self.scan_with_exclude_filter(base_path, options, &exclude_filter, &repo_name_filter)
+    }
+
     /// ExcludeFilterを使ってリポジトリを再帰的に探索する
     fn scan_with_exclude_filter<P: AsRef<Path>>(
         &self,
         base_path: P,
-        max_depth: usize,
-        should_fetch: bool,
-        fetch_timeout_secs: u64,
+        options: &ScanOptions,
         exclude_filter: &ExcludeFilter,
-    ) -> PendectorResult<Vec<Repository>> {
+        repo_name_filter: &ExcludeFilter,
+    ) -> PendectorResult<RootScan> {
         let base_path = base_path.as_ref();
+        // 探索・fetch・状態の取得を通した上限（表せないほど長い時間は上限なしとみなす）
+        let deadline = options
+            .time_budget
+            .and_then(|budget| Instant::now().checked_add(budget));
+        let reporter =
+            ProgressReporter::new(options.progress).with_plain_interval(options.progress_interval);
+        reporter.emit(&ProgressEvent::DiscoveryStarted { root: base_path });
 
-        // まずすべてのリポジトリパスを収集（除外パターン適用）
-        let repo_paths: Vec<PathBuf> = WalkDir::new(base_path)
-            .follow_links(false)
-            .max_depth(max_depth)
-            .into_iter()
-            .filter_map(|e| match e {
-                Ok(entry) => Some(entry),
-                Err(err) => {
-                    eprintln!("Warning: Failed to access path during scan: {err}");
-                    None
-                }
-            })
-            .filter(|entry| {
-                // 除外パターンチェック
-                let relative_path = entry.path().strip_prefix(base_path).unwrap_or(entry.path());
-                !exclude_filter.is_excluded(relative_path)
-            })
-            .filter(|entry| entry.file_type().is_dir() && entry.file_name() == ".git")
-            .filter_map(|entry| entry.path().parent().map(|p| p.to_path_buf()))
+        let Discovery {
+            repo_paths,
+            stats,
+            permission_denied,
+            walk_failures,
+        } = discover_repo_paths(
+            base_path,
+            options,
+            exclude_filter,
+            repo_name_filter,
+            deadline,
+        )?;
+        if options.scan_stats {
+            report_scan_stats(base_path, &stats, options.max_depth);
+        }
+
+        reporter.emit(&ProgressEvent::DiscoveryFinished {
+            root: base_path,
+            repositories: repo_paths.len(),
+        });
+
+        let (mut repositories, over_budget) =
+            self.collect_statuses(&repo_paths, options, &reporter, deadline);
+        apply_name_style(&mut repositories, options.name_style, Some(base_path));
+
+        let budget_exceeded = options
+            .time_budget
+            .filter(|_| stats.over_budget > 0 || over_budget != OverBudget::default())
+            .map(|budget| BudgetExceeded {
+                root: base_path.to_path_buf(),
+                budget,
+                repositories: repositories.len(),
+                directories_not_descended: stats.over_budget,
+                repositories_not_checked: over_budget.statuses,
+                fetches_abandoned: over_budget.fetches,
+            });
+        Ok(RootScan {
+            repositories,
+            budget_exceeded,
+            permission_denied,
+            walk_failures,
+        })
+    }
+
+    /// 指定したリポジトリだけをディレクトリを探索せずに調べる（存在しないパスは除く）
+    pub fn scan_repositories<P: AsRef<Path>>(
+        &self,
+        repo_paths: &[P],
+        options: &ScanOptions,
+    ) -> Vec<Repository> {
+        let reporter =
+            ProgressReporter::new(options.progress).with_plain_interval(options.progress_interval);
+        let repo_paths: Vec<PathBuf> = repo_paths
+            .iter()
+            .map(|path| path.as_ref().to_path_buf())
+            .filter(|path| path.join(".git").exists() || detached_git_dir(path).is_some())
             .collect();
+        let (mut repositories, _) = self.collect_statuses(&repo_paths, options, &reporter, None);
+        // 個別に指定したリポジトリにはスキャンルートがないので、相対パスの表示名は使えない
+        apply_name_style(&mut repositories, options.name_style, None);
+        repositories
+    }
 
-        // fetchが必要な場合は並列実行（プログレスバー付き）
-        if should_fetch && !repo_paths.is_empty() {
-            let timeout = Duration::from_secs(fetch_timeout_secs);
-            let _fetch_results = GitStatus::perform_parallel_fetch_with_timeout_and_progress(
-                &repo_paths,
-                timeout,
-                true,
+    /// 各リポジトリをfetchし（指定時のみ）、状態を並列に取得する
+    ///
+    /// `deadline` を過ぎたらfetchを打ち切り、まだ状態を取得していないリポジトリは結果に含めない。
+    fn collect_statuses(
+        &self,
+        repo_paths: &[PathBuf],
+        options: &ScanOptions,
+        reporter: &ProgressReporter,
+        deadline: Option<Instant>,
+    ) -> (Vec<Repository>, OverBudget) {
+        let mut over_budget = OverBudget::default();
+        // fetchが必要な場合は並列実行し、失敗はリポジトリごとに結果へ含める
+        let mut fetch_results: HashMap<&PathBuf, Option<ErrorReport>> = HashMap::new();
+        // 同期状態を調べない場合は取得しても使わない
+        let fetch = options.fetch && !options.offline && !options.skip_remote_check;
+        // gitの定期fetchに任せるリポジトリは重ねてfetchしない
+        let fetch_paths: Vec<&PathBuf> = repo_paths
+            .iter()
+            .filter(|path| fetch && !(options.trust_maintenance && has_scheduled_fetch(path)))
+            .collect();
+        if !fetch_paths.is_empty() {
+            let fetch_options = FetchOptions::new(options.fetch_timeout)
+                .with_slow_threshold(options.fetch_slow_threshold)
+                .with_skip_slow(options.fetch_skip_slow)
+                .with_jobs(options.fetch_jobs)
+                .with_env(options.fetch_env.clone())
+                .with_passthrough_env(options.fetch_env_passthrough.clone())
+                .with_proxy(options.fetch_proxy.clone())
+                .with_ssh_proxy_command(options.fetch_ssh_proxy_command.clone())
+                .with_deadline(deadline);
+            let results = GitStatus::perform_parallel_fetch_with_errors(
+                &fetch_paths,
+                &fetch_options,
+                reporter,
             );
-            // fetch結果は警告として出力されるので、ここでは特に処理しない
+            over_budget.fetches = results
+                .iter()
+                .filter(|result| matches!(result, Err(PendectorError::BudgetExceeded { .. })))
+                .count();
+            fetch_results = fetch_paths
+                .into_iter()
+                .zip(results)
+                .map(|(path, result)| (path, result.err().map(|e| ErrorReport::from(&e))))
+                .collect();
         }
 
         // 各リポジトリの状態を並列取得
-        let repositories: Vec<Repository> = repo_paths
-            .par_iter()
-            .filter_map(|repo_path| {
-                let mut repository = Repository::new(repo_path.clone());
-
-                // Get git status information (fetchなしで実行)
-                if let Ok(status) = GitStatus::get_repository_status(repo_path) {
-                    repository = repository
-                        .with_git_info(
-                            status.has_changes,
-                            status.current_branch,
-                            status.changed_files,
-                        )
-                        .with_remote_info(
-                            status.needs_pull,
-                            status.needs_push,
-                            status.remote_branch,
-                        );
-                }
+        let status_query = StatusQuery::new()
+            .with_ignore_branches(options.ignore_branches.clone())
+            .with_file_limit(options.status_file_limit)
+            .with_update_index(options.status_update_index)
+            .with_dirty_ignore(options.dirty_ignore.clone())
+            .with_unborn_remote(options.unborn_remote)
+            .with_skip_remote_check(options.skip_remote_check);
+        let mut repositories: Vec<Repository> = run_with_jobs(options.jobs, || {
+            repo_paths
+                .par_iter()
+                .filter_map(|repo_path| {
+                    // 時間切れになったら残りのリポジトリは調べない（変更なしとは表示しない）
+                    if is_past(deadline) {
+                        return None;
+                    }
+                    let fetch_error = fetch_results.get(repo_path).cloned();
+                    let fetch_outcome = fetch_error.as_ref().map(|error| match error {
+                        Some(_) => FetchOutcome::Failed,
+                        None => FetchOutcome::Fetched,
+                    });
+                    // ロックファイルが残っているとステータス取得に失敗しうるので、先に調べておく
+                    let mut repository = Repository::new(repo_path.clone())
+                        .with_checked_at(Some(SystemTime::now()))
+                        .with_priority(options.priority)
+                        .with_git_dir(detached_git_dir(repo_path))
+                        .with_fetch_outcome(fetch_outcome)
+                        .with_fetch_error(fetch_error.flatten())
+                        .with_locks(find_lock_files(repo_path))
+                        .with_project_type(ProjectType::detect(repo_path));
 
-                Some(repository)
-            })
-            .collect();
+                    // Get git status information (fetchなしで実行)
+                    // 失敗を握りつぶすと変更なしに見えてしまうので、エラーとして結果に含める
+                    match GitStatus::get_repository_status_with_query(repo_path, &status_query) {
+                        Ok(status) => {
+                            let web_url = status
+                                .remote_id
+                                .as_deref()
+                                .and_then(|id| web_url(id, &options.web_url_templates));
+                            repository = repository
+                                .with_git_info(
+                                    status.has_changes,
+                                    status.current_branch,
+                                    status.changed_files,
+                                )
+                                .with_remote_info(
+                                    status.needs_pull,
+                                    status.needs_push,
+                                    status.remote_branch,
+                                )
+                                .with_changed_files_truncated(status.changed_files_truncated)
+                                .with_unborn(status.unborn)
+                                .with_branches(status.branches, status.remote_only_branches)
+                                .with_dirty_since(status.dirty_since)
+                                .with_operation(status.operation)
+                                .with_remote_id(status.remote_id)
+                                .with_web_url(web_url)
+                                .with_last_commit_at(status.last_commit_at);
+                            // 追跡ブランチがなければ古くなる同期状態もない
+                            let sync_stale = options.offline && repository.remote_branch.is_some();
+                            repository = repository.with_sync_stale(sync_stale);
+                            let staleness = Staleness::classify(
+                                &repository,
+                                SystemTime::now(),
+                                options.stale_dirty_after,
+                            );
+                            repository = repository.with_staleness(Some(staleness));
+                        }
+                        Err(e) => {
+                            repository = repository.with_status_error(Some(ErrorReport::from(&e)));
+                        }
+                    }
 
-        Ok(repositories)
-    }
+                    if options.disk_usage {
+                        repository = repository.with_disk_usage(Some(dir_size(repo_path)));
+                    }
+                    if options.artifacts {
+                        repository = match find_ignored_artifacts(repo_path) {
+                            Ok(artifacts) => repository.with_artifacts(Some(artifacts)),
+                            Err(e) => repository.with_artifacts_error(Some(ErrorReport::from(&e))),
+                        };
+                    }
+                    if let Some(lines) = options.preview_lines.filter(|_| repository.has_changes) {
+                        repository = match diff_preview(repo_path, lines) {
+                            Ok(preview) => repository.with_diff_preview(Some(preview)),
+                            Err(e) => {
+                                repository.with_diff_preview_error(Some(ErrorReport::from(&e)))
+                            }
+                        };
+                    }
 
-    /// タイムアウト設定付きで指定の深さとfetchオプションでリポジトリを再帰的に探索する
-    pub fn scan_with_options_and_timeout<P: AsRef<Path>>(
-        &self,
-        base_path: P,
-        max_depth: usize,
-        should_fetch: bool,
-        fetch_timeout_secs: u64,
-    ) -> PendectorResult<Vec<Repository>> {
-        // 除外パターンなしで実行
-        self.scan_with_exclude(base_path, max_depth, should_fetch, fetch_timeout_secs, &[])
+                    if let Some(ref remote) = options.compare_remote {
+                        repository = match GitStatus::compare_with_remote(
+                            repo_path,
+                            remote,
+                            &options.default_branches,
+                        ) {
+                            Ok(comparison) => repository.with_remote_comparison(comparison),
+                            Err(e) => repository.with_comparison_error(Some(ErrorReport::from(&e))),
+                        };
+                    }
+
+                    reporter.emit(&ProgressEvent::RepoStatused {
+                        path: repo_path,
+                        has_changes: repository.has_changes,
+                    });
+
+                    Some(repository)
+                })
+                .collect()
+        });
+        over_budget.statuses = repo_paths.len() - repositories.len();
+
+        sort_by_canonical_path(&mut repositories);
+        (repositories, over_budget)
     }
 }
 
@@ -228,6 +1064,126 @@ mod tests {
         assert_eq!(repositories[0].name, "test_repo");
     }
 
+    #[test]
+    fn test_fetch_timeout_is_reported_on_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = temp_dir.path().join("hanging_repo");
+        let repo = git2::Repository::init(&repo_path).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("protocol.ext.allow", "always").unwrap();
+        repo.remote("origin", "ext::sleep 30").unwrap();
+
+        let options = ScanOptions::new()
+            .with_fetch(true)
+            .with_fetch_timeout(Duration::from_millis(300))
+            .with_progress(ProgressMode::None);
+        let repositories = RepoScanner::new().scan_repositories(&[&repo_path], &options);
+
+        let error = repositories[0].fetch_error.as_ref().unwrap();
+        assert_eq!(error.code, crate::error::ErrorCode::Timeout);
+    }
+
+    #[test]
+    fn test_discover_stops_descending_when_time_budget_is_spent() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+        fs::create_dir_all(base_path.join(".git")).unwrap();
+        fs::create_dir_all(base_path.join("nested/repo/.git")).unwrap();
+
+        let filter = ExcludeFilter::new();
+        let options = ScanOptions::new();
+        let discovery =
+            discover_repo_paths(base_path, &options, &filter, &filter, Some(Instant::now()))
+                .unwrap();
+        assert_eq!(discovery.repo_paths, vec![base_path.to_path_buf()]);
+        assert_eq!(discovery.stats.over_budget, 1);
+
+        let deadline = Instant::now() + Duration::from_secs(60);
+        let discovery =
+            discover_repo_paths(base_path, &options, &filter, &filter, Some(deadline)).unwrap();
+        assert_eq!(discovery.repo_paths.len(), 2);
+    }
+
+    #[test]
+    fn test_scan_reports_what_the_time_budget_left_unchecked() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+        fs::create_dir_all(base_path.join(".git")).unwrap();
+        fs::create_dir_all(base_path.join("nested/repo/.git")).unwrap();
+
+        // 見つけたリポジトリも、上限を過ぎていれば状態を取得せずに数だけ報告する
+        let options = ScanOptions::new()
+            .with_time_budget(Some(Duration::ZERO))
+            .with_progress(ProgressMode::None);
+        let scan = RepoScanner::new().scan_root(base_path, &options).unwrap();
+        assert!(scan.repositories.is_empty());
+        let exceeded = scan.budget_exceeded.unwrap();
+        assert_eq!(exceeded.root, base_path);
+        assert_eq!(exceeded.directories_not_descended, 1);
+        assert_eq!(exceeded.repositories_not_checked, 1);
+
+        // 表せないほど長い時間は上限なしとして扱う
+        let options = options.with_time_budget(Some(Duration::MAX));
+        let scan = RepoScanner::new().scan_root(base_path, &options).unwrap();
+        assert_eq!(scan.repositories.len(), 2);
+        assert_eq!(scan.budget_exceeded, None);
+    }
+
+    #[test]
+    fn test_discover_counts_pruned_directories() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+
+        fs::create_dir_all(base_path.join("repo/.git")).unwrap();
+        fs::create_dir_all(base_path.join("node_modules/dep/.git")).unwrap();
+        fs::create_dir_all(base_path.join("deep/nested/repo/.git")).unwrap();
+        fs::create_dir_all(base_path.join("deep/leaf")).unwrap();
+
+        let options = ScanOptions::new().with_max_depth(2).with_scan_stats(true);
+        let exclude_filter = ExcludeFilter::from_patterns(&["node_modules".to_string()]).unwrap();
+        let Discovery {
+            repo_paths, stats, ..
+        } = discover_repo_paths(
+            base_path,
+            &options,
+            &exclude_filter,
+            &ExcludeFilter::new(),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(repo_paths, vec![base_path.join("repo")]);
+        assert_eq!(stats.excluded, 1);
+        // deep/nested の配下は深さの上限で探索しない（配下のない deep/leaf は数えない）
+        assert_eq!(stats.depth_limited, 1);
+        assert_eq!(stats.errors, 0);
+        // ルート, repo, deep, deep/leaf, deep/nested
+        assert_eq!(stats.directories, 5);
+    }
+
+    #[test]
+    fn test_scan_keeps_repositories_reincluded_under_excluded_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        for name in ["legacy/keep", "legacy/other", "app"] {
+            git2::Repository::init(root.join(name)).unwrap();
+        }
+
+        let legacy = root.join("legacy");
+        let options = ScanOptions::new().with_exclude_patterns(vec![
+            legacy.to_string_lossy().into_owned(),
+            format!("!{}", legacy.join("keep").display()),
+        ]);
+        let mut names: Vec<String> = RepoScanner::new()
+            .scan_with(root, &options)
+            .unwrap()
+            .into_iter()
+            .map(|repo| repo.path.strip_prefix(root).unwrap().display().to_string())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["app", "legacy/keep"]);
+    }
+
     #[test]
     fn test_scan_ignores_non_git_directories() {
         let temp_dir = TempDir::new().unwrap();
@@ -243,6 +1199,71 @@ mod tests {
         assert_eq!(repositories.len(), 0);
     }
 
+    #[test]
+    fn test_scan_reports_status_errors_instead_of_clean() {
+        let temp_dir = TempDir::new().unwrap();
+        // 中身のない `.git` はgit2で開けない
+        fs::create_dir_all(temp_dir.path().join("broken_repo").join(".git")).unwrap();
+
+        let scanner = RepoScanner::new();
+        let repositories = scanner.scan(temp_dir.path()).unwrap();
+
+        assert_eq!(repositories.len(), 1);
+        assert!(repositories[0].status_error.is_some());
+        assert!(repositories[0].needs_attention());
+    }
+
+    #[test]
+    fn test_scan_records_remote_comparison_errors() {
+        let fixture = crate::test_util::RemoteFixture::new();
+        let repo_path = fixture.clone_repo("fork");
+        // 存在しないコミットを指す追跡ブランチとは比較できない
+        let upstream = repo_path.join(".git/refs/remotes/upstream");
+        fs::create_dir_all(&upstream).unwrap();
+        fs::write(upstream.join("main"), format!("{}\n", "1".repeat(40))).unwrap();
+
+        let options = ScanOptions::new()
+            .with_compare_remote(Some("upstream".to_string()))
+            .with_progress(ProgressMode::None);
+        let repositories = RepoScanner::new().scan_repositories(&[&repo_path], &options);
+
+        assert!(repositories[0].remote_comparison.is_none());
+        assert!(repositories[0].comparison_error.is_some());
+        assert_eq!(repositories[0].errors().next().unwrap().0, "compare");
+    }
+
+    #[test]
+    fn test_scan_records_artifact_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("broken_repo").join(".git")).unwrap();
+
+        let options = ScanOptions::new()
+            .with_artifacts(true)
+            .with_progress(ProgressMode::None);
+        let repositories = RepoScanner::new()
+            .scan_with(temp_dir.path(), &options)
+            .unwrap();
+
+        assert!(repositories[0].artifacts.is_none());
+        assert!(repositories[0].artifacts_error.is_some());
+        assert!(repositories[0]
+            .errors()
+            .any(|(stage, _)| stage == "artifacts"));
+    }
+
+    #[test]
+    fn test_scan_returns_repositories_in_path_order() {
+        let temp_dir = TempDir::new().unwrap();
+        for name in ["zeta", "alpha", "mid/beta", "mid"] {
+            fs::create_dir_all(temp_dir.path().join(name).join(".git")).unwrap();
+        }
+
+        let scanner = RepoScanner::new();
+        let repos = scanner.scan(temp_dir.path()).unwrap();
+        let names: Vec<&str> = repos.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["alpha", "mid", "beta", "zeta"]);
+    }
+
     #[test]
     fn test_scan_with_depth_limit() {
         let temp_dir = TempDir::new().unwrap();
@@ -272,4 +1293,157 @@ mod tests {
         let repositories = scanner.scan_with_depth(base_path, 4).unwrap();
         assert_eq!(repositories.len(), 2);
     }
+
+    #[test]
+    fn test_scan_with_options_excludes_patterns() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+
+        for name in ["kept_repo", "skipped_repo"] {
+            fs::create_dir_all(base_path.join(name).join(".git")).unwrap();
+        }
+
+        let scanner = RepoScanner::new();
+        let options = ScanOptions::new()
+            .with_max_depth(3)
+            .with_exclude_patterns(vec!["skipped_repo/**".to_string()]);
+        let repositories = scanner.scan_with(base_path, &options).unwrap();
+
+        assert_eq!(repositories.len(), 1);
+        assert_eq!(repositories[0].name, "kept_repo");
+    }
+
+    #[test]
+    fn test_scan_anchors_exclude_patterns_to_scan_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+
+        for name in ["build/top", "project/build/nested", "project/app"] {
+            fs::create_dir_all(base_path.join(name).join(".git")).unwrap();
+        }
+
+        let scanner = RepoScanner::new();
+        let names = |patterns: &[&str]| -> Vec<String> {
+            let options = ScanOptions::new()
+                .with_exclude_patterns(patterns.iter().map(|p| p.to_string()).collect());
+            scanner
+                .scan_with(base_path, &options)
+                .unwrap()
+                .into_iter()
+                .map(|r| r.name)
+                .collect()
+        };
+
+        assert_eq!(names(&["/build"]), vec!["app", "nested"]);
+        assert_eq!(names(&["build/"]), vec!["app"]);
+        let absolute = base_path.join("project").to_string_lossy().to_string();
+        assert_eq!(names(&[absolute.as_str()]), vec!["top"]);
+    }
+
+    #[test]
+    fn test_scan_excludes_repositories_by_name() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+
+        for name in ["site", "site-archive", "nested/tmp-scratch"] {
+            fs::create_dir_all(base_path.join(name).join(".git")).unwrap();
+        }
+
+        let scanner = RepoScanner::new();
+        let options = ScanOptions::new()
+            .with_exclude_repos(vec!["*-archive".to_string(), "tmp-*".to_string()]);
+        let repositories = scanner.scan_with(base_path, &options).unwrap();
+
+        let names: Vec<_> = repositories.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["site"]);
+    }
+
+    #[test]
+    fn test_scan_with_limit_stops_after_first_repositories() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+
+        for name in ["c_repo", "a_repo", "b_repo"] {
+            fs::create_dir_all(base_path.join(name).join(".git")).unwrap();
+        }
+
+        let scanner = RepoScanner::new();
+        let options = ScanOptions::new().with_limit(Some(2));
+        let repositories = scanner.scan_with(base_path, &options).unwrap();
+
+        let names: Vec<_> = repositories.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["a_repo", "b_repo"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_deduplicates_symlinked_repositories() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+
+        let real_dir = base_path.join("real");
+        fs::create_dir_all(real_dir.join("repo").join(".git")).unwrap();
+        std::os::unix::fs::symlink(&real_dir, base_path.join("alias")).unwrap();
+
+        let scanner = RepoScanner::new();
+        let options = ScanOptions::new().with_follow_symlinks(true);
+        let repositories = scanner.scan_with(base_path, &options).unwrap();
+        assert_eq!(repositories.len(), 1);
+
+        // 2つのスキャンルートから同じリポジトリに到達した場合
+        let mut combined = scanner.scan(&real_dir).unwrap();
+        combined.extend(scanner.scan(base_path.join("alias")).unwrap());
+        assert_eq!(combined.len(), 2);
+        assert_eq!(dedup_repositories(combined).len(), 1);
+    }
+
+    #[test]
+    fn test_scan_strict_fails_on_unreadable_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let base_path = temp_dir.path();
+        fs::create_dir_all(base_path.join("repo").join(".git")).unwrap();
+        // たどると自身に戻るシンボリックリンクは探索時のエラーになる
+        std::os::unix::fs::symlink(base_path, base_path.join("loop")).unwrap();
+
+        let scanner = RepoScanner::new();
+        let options = ScanOptions::new().with_follow_symlinks(true);
+        // 既定では読めなかったパスを出力せずに結果として返す
+        let scan = scanner.scan_root(base_path, &options).unwrap();
+        assert_eq!(scan.repositories.len(), 1);
+        assert_eq!(scan.walk_failures.len(), 1);
+        assert_eq!(scan.walk_failures[0].path, Some(base_path.join("loop")));
+
+        let options = options.with_walk_errors(WalkErrors::Strict);
+        assert!(matches!(
+            scanner.scan_with(base_path, &options),
+            Err(PendectorError::FileSystemError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_group_by_remote_id_joins_clones_with_different_urls() {
+        let temp_dir = TempDir::new().unwrap();
+        for (name, url) in [
+            ("ssh_clone", "git@github.com:me/foo.git"),
+            ("https_clone", "https://github.com/me/foo"),
+            ("other", "https://github.com/me/bar.git"),
+        ] {
+            let repo = git2::Repository::init(temp_dir.path().join(name)).unwrap();
+            repo.remote("origin", url).unwrap();
+        }
+        git2::Repository::init(temp_dir.path().join("local_only")).unwrap();
+
+        let repositories = RepoScanner::new().scan(temp_dir.path()).unwrap();
+        let groups: Vec<(&str, Vec<&str>)> = group_by_remote_id(&repositories)
+            .into_iter()
+            .map(|(id, repos)| (id, repos.iter().map(|r| r.name.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                ("github.com/me/foo", vec!["https_clone", "ssh_clone"]),
+                ("github.com/me/bar", vec!["other"]),
+            ]
+        );
+    }
 }
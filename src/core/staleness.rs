@@ -0,0 +1,117 @@
+use crate::core::Repository;
+use serde::Serialize;
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+/// 未コミットの変更を古いとみなす既定の経過時間（7日）
+pub const DEFAULT_STALE_DIRTY_AFTER: Duration = Duration::from_secs(7 * 86_400);
+
+/// 最後のコミットからこの期間が過ぎたリポジトリを休止中とみなす（30日）
+pub const IDLE_AFTER: Duration = Duration::from_secs(30 * 86_400);
+
+/// 最後のコミットからの経過時間と変更の有無による分類（並び順が対応の優先度）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Staleness {
+    /// 未コミットの変更が長く放置されている
+    StaleDirty,
+    /// 変更はなく、しばらくコミットもされていない
+    Idle,
+    /// 最近コミットされたか、変更が新しい
+    Active,
+}
+
+impl fmt::Display for Staleness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Staleness::StaleDirty => "stale-dirty",
+            Staleness::Idle => "idle",
+            Staleness::Active => "active",
+        };
+        f.write_str(name)
+    }
+}
+
+impl Staleness {
+    /// `now` 時点の分類（変更の時刻が分からなければ最後のコミットの時刻で判断する）
+    pub fn classify(repo: &Repository, now: SystemTime, stale_dirty_after: Duration) -> Self {
+        let age = |time: SystemTime| now.duration_since(time).unwrap_or_default();
+
+        if repo.has_changes {
+            let dirty_since = repo.dirty_since.or(repo.last_commit_at);
+            if dirty_since.is_some_and(|since| age(since) >= stale_dirty_after) {
+                return Staleness::StaleDirty;
+            }
+            return Staleness::Active;
+        }
+        match repo.last_commit_at {
+            Some(last_commit) if age(last_commit) >= IDLE_AFTER => Staleness::Idle,
+            _ => Staleness::Active,
+        }
+    }
+}
+
+/// 対応の優先度順（放置された変更、休止中、活動中）に並べ、同じ分類では古いものを先にする
+pub fn sort_by_staleness(repositories: &mut [Repository]) {
+    repositories.sort_by_key(|repo| {
+        let since = if repo.has_changes {
+            repo.dirty_since.or(repo.last_commit_at)
+        } else {
+            repo.last_commit_at
+        };
+        (
+            repo.staleness.unwrap_or(Staleness::Active),
+            since.is_none(),
+            since,
+        )
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const DAY: Duration = Duration::from_secs(86_400);
+
+    fn repo(name: &str, dirty_days: Option<u64>, commit_days: u64, now: SystemTime) -> Repository {
+        Repository::new(PathBuf::from(format!("/src/{name}")))
+            .with_git_info(dirty_days.is_some(), Some("main".to_string()), Vec::new())
+            .with_dirty_since(dirty_days.map(|days| now - DAY * days as u32))
+            .with_last_commit_at(Some(now - DAY * commit_days as u32))
+    }
+
+    #[test]
+    fn test_classify_and_sort_by_staleness() {
+        let now = SystemTime::now();
+        let mut repositories: Vec<Repository> = [
+            repo("active", None, 2, now),
+            repo("idle", None, 60, now),
+            repo("fresh-dirty", Some(1), 90, now),
+            repo("old-dirty", Some(20), 30, now),
+            repo("older-dirty", Some(40), 50, now),
+        ]
+        .into_iter()
+        .map(|r| {
+            let staleness = Staleness::classify(&r, now, DEFAULT_STALE_DIRTY_AFTER);
+            r.with_staleness(Some(staleness))
+        })
+        .collect();
+
+        let classes: Vec<String> = repositories
+            .iter()
+            .map(|r| r.staleness.unwrap().to_string())
+            .collect();
+        assert_eq!(
+            classes,
+            ["active", "idle", "active", "stale-dirty", "stale-dirty"]
+        );
+
+        sort_by_staleness(&mut repositories);
+        let names: Vec<&str> = repositories.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["older-dirty", "old-dirty", "idle", "active", "fresh-dirty"]
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use crate::error::{PendectorError, PendectorResult};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// スキーマのバージョンを付けて保存する値（`{"version": 1, ...}` の形になる）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Versioned<T> {
+    pub version: u32,
+    #[serde(flatten)]
+    pub data: T,
+}
+
+/// キャッシュ・履歴・監査ログなどのファイルをまとめて管理する保存領域
+///
+/// 書き込みはファイルごとのロックファイル（`<name>.lock`）で排他し、同時に動く
+/// 複数のpendectorが同じファイルを壊さないようにする。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Storage {
+    dir: PathBuf,
+}
+
+impl Storage {
+    /// 指定したディレクトリを保存領域にする
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// デフォルトの保存領域（データディレクトリの `pendector`）
+    pub fn default_location() -> Option<Self> {
+        dirs::data_dir().map(|dir| Self::new(dir.join("pendector")))
+    }
+
+    /// 保存領域のディレクトリ
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 保存領域内のファイルのパス
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(name)
+    }
+
+    /// JSONの文書を読み込む（ファイルがないかバージョンが異なる場合はNone）
+    pub fn read<T: DeserializeOwned>(
+        &self,
+        name: &str,
+        version: u32,
+    ) -> PendectorResult<Option<T>> {
+        let path = self.path(name);
+        let _lock = self.lock(name, false)?;
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(io_error(&path, "read", e)),
+        };
+        Ok(decode(&content, version))
+    }
+
+    /// JSONの文書を書き込む（一時ファイルに書いてから置き換えるので、読み手が途中の内容を見ることはない）
+    pub fn write<T: Serialize>(&self, name: &str, version: u32, data: &T) -> PendectorResult<()> {
+        let path = self.path(name);
+        let content = encode(&path, version, data)?;
+        let _lock = self.lock(name, true)?;
+        let temp_path = self.path(&format!("{name}.tmp"));
+        std::fs::write(&temp_path, content).map_err(|e| io_error(&temp_path, "write", e))?;
+        std::fs::rename(&temp_path, &path).map_err(|e| io_error(&path, "replace", e))
+    }
+
+    /// JSON Lines形式のファイルに1行追記する
+    pub fn append<T: Serialize>(&self, name: &str, version: u32, data: &T) -> PendectorResult<()> {
+        let path = self.path(name);
+        let mut line = encode(&path, version, data)?;
+        line.push('\n');
+        let _lock = self.lock(name, true)?;
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|e| io_error(&path, "append to", e))
+    }
+
+    /// JSON Lines形式のファイルを読み込む（他のバージョンや壊れた行は読み飛ばす）
+    pub fn read_lines<T: DeserializeOwned>(
+        &self,
+        name: &str,
+        version: u32,
+    ) -> PendectorResult<Vec<T>> {
+        let path = self.path(name);
+        let _lock = self.lock(name, false)?;
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(io_error(&path, "read", e)),
+        };
+        Ok(BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| decode(&line, version))
+            .collect())
+    }
+
+    /// ファイルごとのロックを取得する（保存領域のディレクトリがなければ作成する）
+    fn lock(&self, name: &str, exclusive: bool) -> PendectorResult<File> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| io_error(&self.dir, "create", e))?;
+        let path = self.path(&format!("{name}.lock"));
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .map_err(|e| io_error(&path, "open", e))?;
+        let locked = if exclusive {
+            file.lock()
+        } else {
+            file.lock_shared()
+        };
+        locked.map_err(|e| io_error(&path, "lock", e))?;
+        Ok(file)
+    }
+}
+
+fn encode<T: Serialize>(path: &Path, version: u32, data: &T) -> PendectorResult<String> {
+    serde_json::to_string(&Versioned { version, data })
+        .map_err(|e| PendectorError::from_io_error(path.to_path_buf(), format!("serialize: {e}")))
+}
+
+fn decode<T: DeserializeOwned>(content: &str, version: u32) -> Option<T> {
+    serde_json::from_str::<Versioned<T>>(content)
+        .ok()
+        .filter(|versioned| versioned.version == version)
+        .map(|versioned| versioned.data)
+}
+
+fn io_error(path: &Path, action: &str, error: std::io::Error) -> PendectorError {
+    PendectorError::from_io_error(path.to_path_buf(), format!("{action}: {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Entry {
+        name: String,
+    }
+
+    fn entry(name: &str) -> Entry {
+        Entry {
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_write_and_read_document() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = Storage::new(temp_dir.path().join("store"));
+
+        assert_eq!(storage.read::<Entry>("cache.json", 1).unwrap(), None);
+        storage.write("cache.json", 1, &entry("app")).unwrap();
+        assert_eq!(storage.read("cache.json", 1).unwrap(), Some(entry("app")));
+
+        let content = std::fs::read_to_string(storage.path("cache.json")).unwrap();
+        assert_eq!(content, r#"{"version":1,"name":"app"}"#);
+    }
+
+    #[test]
+    fn test_other_schema_versions_are_ignored() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = Storage::new(temp_dir.path().to_path_buf());
+
+        storage.write("cache.json", 1, &entry("app")).unwrap();
+        assert_eq!(storage.read::<Entry>("cache.json", 2).unwrap(), None);
+
+        storage.append("history.jsonl", 1, &entry("old")).unwrap();
+        storage.append("history.jsonl", 2, &entry("new")).unwrap();
+        assert_eq!(
+            storage.read_lines::<Entry>("history.jsonl", 2).unwrap(),
+            vec![entry("new")]
+        );
+    }
+
+    #[test]
+    fn test_concurrent_appends_keep_every_line() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = Storage::new(temp_dir.path().to_path_buf());
+
+        std::thread::scope(|scope| {
+            for thread in 0..4 {
+                let storage = &storage;
+                scope.spawn(move || {
+                    for i in 0..25 {
+                        let name = format!("{thread}-{i}");
+                        storage.append("audit.jsonl", 1, &entry(&name)).unwrap();
+                    }
+                });
+            }
+        });
+        assert_eq!(
+            storage.read_lines::<Entry>("audit.jsonl", 1).unwrap().len(),
+            100
+        );
+    }
+}
@@ -1,107 +1,149 @@
-use std::fmt;
+use serde::Serialize;
+use thiserror::Error;
 
 /// Pendectorのカスタムエラー型
-#[derive(Debug)]
+#[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum PendectorError {
     /// Gitリポジトリが見つからない
+    #[error("Git repository not found at '{0}'")]
     GitRepositoryNotFound(String),
     /// Gitリポジトリの操作に失敗
+    #[error("Git operation '{operation}' failed in '{repo_path}': {source}")]
     GitOperationFailed {
         repo_path: String,
         operation: String,
         source: Box<dyn std::error::Error + Send + Sync>,
     },
     /// ファイルシステム操作に失敗
+    #[error("File system error for '{}': {message}", path.display())]
     FileSystemError {
         path: std::path::PathBuf,
         message: String,
     },
     /// パスが無効
+    #[error("Invalid path: '{0}'")]
     InvalidPath(String),
     /// ディレクトリスキャンに失敗
+    #[error("Failed to scan directory '{path}': {source}")]
     ScanError {
         path: String,
         source: Box<dyn std::error::Error + Send + Sync>,
     },
     /// フォーマット処理に失敗
+    #[error("Format error: {0}")]
     FormatError(String),
     /// 設定エラー
+    #[error("Configuration error in '{}': {message}", path.display())]
     ConfigError {
         path: std::path::PathBuf,
         message: String,
     },
     /// ネットワークエラー（fetch関連）
+    #[error("Network error for '{repo_path}': {message}")]
     NetworkError { repo_path: String, message: String },
     /// タイムアウトエラー
+    #[error("Operation timed out after {timeout_secs}s for '{repo_path}'")]
     TimeoutError {
         repo_path: String,
         timeout_secs: u64,
     },
+    /// 遅いfetchを打ち切った
+    #[error("Fetch skipped after {elapsed_secs}s for '{repo_path}' (--fetch-skip-slow)")]
+    FetchSkipped {
+        repo_path: String,
+        elapsed_secs: u64,
+    },
+    /// 探索時間の上限（`--time-budget`）を過ぎたためfetchを打ち切った
+    #[error("Fetch abandoned for '{repo_path}': time budget exceeded")]
+    BudgetExceeded { repo_path: String },
     /// 認証エラー
+    #[error("Authentication error for '{repo_path}': {message}")]
     AuthenticationError { repo_path: String, message: String },
+    /// プロキシへの接続・認証に失敗
+    #[error("Proxy error for '{repo_path}': {message}")]
+    ProxyError { repo_path: String, message: String },
+    /// 別のpendectorが実行中
+    #[error("another pendector is running (lock file: '{}'); use --no-lock to bypass", lock_path.display())]
+    AlreadyRunning { lock_path: std::path::PathBuf },
+    /// SIGINT/SIGTERMで中断した
+    #[error("interrupted")]
+    Interrupted,
     /// Slack通知に失敗
+    #[error("Slack notification error: {message}")]
     SlackNotifyError { message: String },
+    /// リポジトリのコンテキストを持たないgit2のエラー
+    #[error("Git error: {0}")]
+    Git(#[from] git2::Error),
+    /// パスのコンテキストを持たないI/Oエラー
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// TOMLのパースエラー
+    #[error("Failed to parse TOML: {0}")]
+    TomlParse(#[from] toml::de::Error),
+}
+
+/// エラー分類を表す機械可読なコード
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum ErrorCode {
+    RepositoryNotFound,
+    GitOperation,
+    FileSystem,
+    InvalidPath,
+    Scan,
+    Format,
+    Config,
+    Network,
+    Timeout,
+    Authentication,
+    Proxy,
+    AlreadyRunning,
+    Notification,
+    Interrupted,
 }
 
-impl fmt::Display for PendectorError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl ErrorCode {
+    /// コードの文字列表現（JSON出力と同じ値）を返す
+    pub fn as_str(&self) -> &'static str {
         match self {
-            PendectorError::GitRepositoryNotFound(path) => {
-                write!(f, "Git repository not found at '{path}'")
-            }
-            PendectorError::GitOperationFailed {
-                repo_path,
-                operation,
-                source,
-            } => {
-                write!(
-                    f,
-                    "Git operation '{operation}' failed in '{repo_path}': {source}"
-                )
-            }
-            PendectorError::FileSystemError { path, message } => {
-                write!(f, "File system error for '{}': {message}", path.display())
-            }
-            PendectorError::InvalidPath(path) => {
-                write!(f, "Invalid path: '{path}'")
-            }
-            PendectorError::ScanError { path, source } => {
-                write!(f, "Failed to scan directory '{path}': {source}")
-            }
-            PendectorError::FormatError(msg) => {
-                write!(f, "Format error: {msg}")
-            }
-            PendectorError::ConfigError { path, message } => {
-                write!(f, "Configuration error in '{}': {message}", path.display())
-            }
-            PendectorError::NetworkError { repo_path, message } => {
-                write!(f, "Network error for '{repo_path}': {message}")
-            }
-            PendectorError::TimeoutError {
-                repo_path,
-                timeout_secs,
-            } => {
-                write!(
-                    f,
-                    "Operation timed out after {timeout_secs}s for '{repo_path}'"
-                )
-            }
-            PendectorError::AuthenticationError { repo_path, message } => {
-                write!(f, "Authentication error for '{repo_path}': {message}")
-            }
-            PendectorError::SlackNotifyError { message } => {
-                write!(f, "Slack notification error: {message}")
-            }
+            ErrorCode::RepositoryNotFound => "repository_not_found",
+            ErrorCode::GitOperation => "git_operation",
+            ErrorCode::FileSystem => "file_system",
+            ErrorCode::InvalidPath => "invalid_path",
+            ErrorCode::Scan => "scan",
+            ErrorCode::Format => "format",
+            ErrorCode::Config => "config",
+            ErrorCode::Network => "network",
+            ErrorCode::Timeout => "timeout",
+            ErrorCode::Authentication => "authentication",
+            ErrorCode::Proxy => "proxy",
+            ErrorCode::AlreadyRunning => "already_running",
+            ErrorCode::Notification => "notification",
+            ErrorCode::Interrupted => "interrupted",
         }
     }
 }
 
-impl std::error::Error for PendectorError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        match self {
-            PendectorError::GitOperationFailed { source, .. } => Some(source.as_ref()),
-            PendectorError::ScanError { source, .. } => Some(source.as_ref()),
-            _ => None,
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 出力に含めるためのエラーの要約（分類コードとメッセージ）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ErrorReport {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl From<&PendectorError> for ErrorReport {
+    fn from(error: &PendectorError) -> Self {
+        Self {
+            code: error.code(),
+            message: error.to_string(),
         }
     }
 }
@@ -111,6 +153,30 @@ pub type PendectorResult<T> = Result<T, PendectorError>;
 
 /// よく使用されるエラー変換用のヘルパー関数
 impl PendectorError {
+    /// エラー分類コードを取得
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            PendectorError::GitRepositoryNotFound(_) => ErrorCode::RepositoryNotFound,
+            PendectorError::GitOperationFailed { .. } | PendectorError::Git(_) => {
+                ErrorCode::GitOperation
+            }
+            PendectorError::FileSystemError { .. } | PendectorError::Io(_) => ErrorCode::FileSystem,
+            PendectorError::InvalidPath(_) => ErrorCode::InvalidPath,
+            PendectorError::ScanError { .. } => ErrorCode::Scan,
+            PendectorError::FormatError(_) => ErrorCode::Format,
+            PendectorError::ConfigError { .. } | PendectorError::TomlParse(_) => ErrorCode::Config,
+            PendectorError::NetworkError { .. } => ErrorCode::Network,
+            PendectorError::TimeoutError { .. }
+            | PendectorError::FetchSkipped { .. }
+            | PendectorError::BudgetExceeded { .. } => ErrorCode::Timeout,
+            PendectorError::AuthenticationError { .. } => ErrorCode::Authentication,
+            PendectorError::ProxyError { .. } => ErrorCode::Proxy,
+            PendectorError::AlreadyRunning { .. } => ErrorCode::AlreadyRunning,
+            PendectorError::SlackNotifyError { .. } => ErrorCode::Notification,
+            PendectorError::Interrupted => ErrorCode::Interrupted,
+        }
+    }
+
     /// Git2エラーからPendectorErrorを作成
     pub fn from_git2_error(repo_path: String, operation: String, error: git2::Error) -> Self {
         PendectorError::GitOperationFailed {
@@ -142,7 +208,19 @@ impl PendectorError {
         }
 
         // エラーメッセージの内容で分類
-        if stderr.contains("Repository not found") {
+        // プロキシ経由の失敗はリモートへの接続や認証の失敗に見えるので先に判定する
+        let lower = stderr.to_lowercase();
+        if lower.contains("407") && lower.contains("proxy") {
+            PendectorError::ProxyError {
+                repo_path: repo_name,
+                message: "Proxy authentication required".to_string(),
+            }
+        } else if lower.contains("proxy") {
+            PendectorError::ProxyError {
+                repo_path: repo_name,
+                message: "Failed to connect through proxy".to_string(),
+            }
+        } else if stderr.contains("Repository not found") {
             PendectorError::NetworkError {
                 repo_path: repo_name,
                 message: "Remote repository not found".to_string(),
@@ -173,3 +251,88 @@ impl PendectorError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn test_display_is_unchanged() {
+        let error = PendectorError::TimeoutError {
+            repo_path: "repo".to_string(),
+            timeout_secs: 5,
+        };
+        assert_eq!(error.to_string(), "Operation timed out after 5s for 'repo'");
+
+        let error = PendectorError::FileSystemError {
+            path: std::path::PathBuf::from("/tmp/x"),
+            message: "boom".to_string(),
+        };
+        assert_eq!(error.to_string(), "File system error for '/tmp/x': boom");
+    }
+
+    #[test]
+    fn test_from_conversions() {
+        let io_error = std::io::Error::new(std::io::ErrorKind::NotFound, "missing");
+        let error: PendectorError = io_error.into();
+        assert_eq!(error.code(), ErrorCode::FileSystem);
+        assert!(error.source().is_some());
+
+        let git_error = git2::Error::from_str("bad ref");
+        let error: PendectorError = git_error.into();
+        assert_eq!(error.code(), ErrorCode::GitOperation);
+
+        let toml_error = toml::from_str::<toml::Table>("= broken").unwrap_err();
+        let error: PendectorError = toml_error.into();
+        assert_eq!(error.code(), ErrorCode::Config);
+    }
+
+    #[test]
+    fn test_git_operation_failed_has_source() {
+        let error = PendectorError::from_git2_error(
+            "/repo".to_string(),
+            "get status".to_string(),
+            git2::Error::from_str("oops"),
+        );
+        assert_eq!(error.code(), ErrorCode::GitOperation);
+        assert_eq!(error.source().unwrap().to_string(), "oops");
+    }
+
+    #[test]
+    fn test_error_code_strings() {
+        let error = PendectorError::from_fetch_error("/a/repo".to_string(), "", Some(124));
+        assert_eq!(error.code(), ErrorCode::Timeout);
+        assert_eq!(error.code().as_str(), "timeout");
+        assert_eq!(
+            serde_json::to_string(&ErrorCode::RepositoryNotFound).unwrap(),
+            "\"repository_not_found\""
+        );
+    }
+
+    #[test]
+    fn test_from_fetch_error_classifies_proxy_failures() {
+        let error = PendectorError::from_fetch_error(
+            "/a/repo".to_string(),
+            "fatal: unable to access 'https://example.com/repo.git/': Received HTTP code 407 from proxy after CONNECT",
+            Some(128),
+        );
+        assert_eq!(error.code(), ErrorCode::Proxy);
+        assert!(error.to_string().contains("Proxy authentication required"));
+
+        let error = PendectorError::from_fetch_error(
+            "/a/repo".to_string(),
+            "fatal: unable to access 'https://example.com/repo.git/': Could not resolve proxy: proxy.invalid",
+            Some(128),
+        );
+        assert_eq!(error.code(), ErrorCode::Proxy);
+
+        // プロキシに触れないメッセージは従来どおり分類する
+        let error = PendectorError::from_fetch_error(
+            "/a/repo".to_string(),
+            "fatal: Could not read from remote repository.",
+            Some(128),
+        );
+        assert_eq!(error.code(), ErrorCode::Authentication);
+    }
+}
@@ -1,15 +1,32 @@
+use crate::path_style::{canonicalize_path, is_absolute_path, normalize_path};
 use ignore::gitignore::{Gitignore, GitignoreBuilder};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ExcludeFilter {
     gitignore: Option<Gitignore>,
+    /// 否定パターン（`!` を除いたもの）。除外したディレクトリの配下を探索するかの判定に使う
+    negations: Vec<String>,
+}
+
+/// パスの除外・再包含を決めたパターンとその出どころ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExcludeMatch {
+    /// 一致したパターン（絶対パスのパターンはルートからの相対パターンに変換したもの）
+    pub pattern: String,
+    /// パターンを指定した場所（`defaults` や `--exclude` など）
+    pub source: String,
+    /// 除外されるか（否定パターンで再包含された場合はfalse）
+    pub excluded: bool,
 }
 
 impl ExcludeFilter {
     /// 新しいExcludeFilterを作成する
     pub fn new() -> Self {
-        Self { gitignore: None }
+        Self {
+            gitignore: None,
+            negations: Vec::new(),
+        }
     }
 
     /// パターンリストからExcludeFilterを作成する
@@ -27,6 +44,120 @@ impl ExcludeFilter {
         let gitignore = builder.build()?;
         Ok(Self {
             gitignore: Some(gitignore),
+            negations: negations(patterns.iter()),
+        })
+    }
+
+    /// スキャンルートを基準にパターンを評価するExcludeFilterを作成する
+    ///
+    /// `/build` のように `/` で始まるパターンはgitignoreと同じくルート直下に固定される。
+    /// ただしスキャンルート配下を指す絶対パスは、ルートからの相対パターンとして扱う。
+    pub fn for_root<P: AsRef<Path>>(root: P, patterns: &[String]) -> Result<Self, ignore::Error> {
+        let root = root.as_ref();
+        // シンボリックリンク経由のルートでも絶対パスのパターンが一致するよう、正規化したパスも試す
+        let roots = [normalize_path(root), canonicalize_path(root)];
+        let anchored: Vec<String> = patterns
+            .iter()
+            .map(|pattern| anchor_to_root(&roots, pattern))
+            .collect();
+        Self::from_patterns(&anchored)
+    }
+
+    /// 出どころ付きのパターンリストから、スキャンルートを基準に評価するExcludeFilterを作成する
+    ///
+    /// リストは優先度の低い順に渡す。[`ExcludeFilter::explain_at`] で一致したパターンの出どころを調べられる。
+    pub fn for_root_with_sources<P: AsRef<Path>>(
+        root: P,
+        sources: &[(&str, &[String])],
+    ) -> Result<Self, ignore::Error> {
+        let root = root.as_ref();
+        let roots = [normalize_path(root), canonicalize_path(root)];
+        let mut builder = GitignoreBuilder::new("");
+        let mut anchored = Vec::new();
+        for (source, patterns) in sources {
+            for pattern in patterns.iter() {
+                // 出どころはgitignoreのファイル名として持たせる
+                let pattern = anchor_to_root(&roots, pattern);
+                builder.add_line(Some(PathBuf::from(source)), &pattern)?;
+                anchored.push(pattern);
+            }
+        }
+        Ok(Self {
+            gitignore: Some(builder.build()?),
+            negations: negations(anchored.iter()),
+        })
+    }
+
+    /// スキャンルートからの相対パスの除外・再包含を決めたパターンを返す（どれにも一致しなければNone）
+    pub fn explain_at<P: AsRef<Path>>(
+        &self,
+        relative_path: P,
+        is_dir: bool,
+    ) -> Option<ExcludeMatch> {
+        let gitignore = self.gitignore.as_ref()?;
+        let (glob, excluded) =
+            match gitignore.matched_path_or_any_parents(relative_path.as_ref(), is_dir) {
+                ignore::Match::Ignore(glob) => (glob, true),
+                ignore::Match::Whitelist(glob) => (glob, false),
+                ignore::Match::None => return None,
+            };
+        Some(ExcludeMatch {
+            pattern: glob.original().to_string(),
+            source: glob
+                .from()
+                .map(|from| from.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            excluded,
+        })
+    }
+
+    /// スキャンルートからの相対パスが除外対象かどうかを判定する
+    ///
+    /// 親ディレクトリが除外されていれば配下のパスも除外される。`build/` のような
+    /// ディレクトリ専用のパターンのため、ディレクトリかどうかを呼び出し側で渡す。
+    pub fn is_excluded_at<P: AsRef<Path>>(&self, relative_path: P, is_dir: bool) -> bool {
+        if let Some(ref gitignore) = self.gitignore {
+            matches!(
+                gitignore.matched_path_or_any_parents(relative_path.as_ref(), is_dir),
+                ignore::Match::Ignore(_)
+            )
+        } else {
+            false
+        }
+    }
+
+    /// 除外されたディレクトリの配下に、否定パターンで再包含されうるパスがあるか
+    ///
+    /// これが偽のディレクトリだけは配下を探索せずに飛ばせる。ワイルドカードを含むパターンや
+    /// どの階層にも一致するパターン（`!keep`）は判定できないので、再包含されうるものとみなす。
+    pub fn may_reinclude_below<P: AsRef<Path>>(&self, relative_dir: P) -> bool {
+        let dir: Vec<String> = relative_dir
+            .as_ref()
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        self.negations.iter().any(|negation| {
+            let body = negation.trim_end_matches('/');
+            // 途中に `/` を含まないパターンはどの階層にも一致する
+            let Some(anchored) = body
+                .strip_prefix('/')
+                .or(body.contains('/').then_some(body))
+            else {
+                return true;
+            };
+            let parts: Vec<&str> = anchored.split('/').collect();
+            for (i, part) in parts.iter().enumerate() {
+                if part.contains(['*', '?', '[']) {
+                    return true;
+                }
+                match dir.get(i) {
+                    Some(component) if component == part => continue,
+                    Some(_) => return false,
+                    // ディレクトリより深い階層を指す
+                    None => return true,
+                }
+            }
+            false
         })
     }
 
@@ -42,15 +173,63 @@ impl ExcludeFilter {
         }
     }
 
+    /// ディレクトリ名（パスの最後の要素）が除外対象かどうかを判定する
+    pub fn is_name_excluded<P: AsRef<Path>>(&self, name: P) -> bool {
+        if let Some(ref gitignore) = self.gitignore {
+            matches!(
+                gitignore.matched(name.as_ref(), true),
+                ignore::Match::Ignore(_)
+            )
+        } else {
+            false
+        }
+    }
+
     /// 複数のパターンリストをマージして新しいExcludeFilterを作成する
+    ///
+    /// リストは優先度の低い順に渡す（設定の既定値 → パス固有設定 → CLI引数）。
+    /// 詳細は [`ExcludeFilter::ordered_patterns`] を参照。
     pub fn merge_patterns(pattern_lists: &[&[String]]) -> Result<Self, ignore::Error> {
-        let merged_patterns: Vec<String> = pattern_lists
+        Self::from_patterns(&Self::ordered_patterns(pattern_lists))
+    }
+
+    /// 優先度の低い順に並んだパターンリストを1つに連結する
+    ///
+    /// gitignoreと同じく後に書かれたパターンほど優先されるので、後のリストにある
+    /// 否定パターン（`!important-dir`）は前のリストでの除外を打ち消し、
+    /// 逆に後のリストの除外パターンは前のリストの否定を打ち消す。
+    pub fn ordered_patterns(pattern_lists: &[&[String]]) -> Vec<String> {
+        pattern_lists
             .iter()
             .flat_map(|patterns| patterns.iter().cloned())
-            .collect();
+            .collect()
+    }
+}
+
+/// 否定パターンの `!` を除いた本体
+fn negations<'a>(patterns: impl Iterator<Item = &'a String>) -> Vec<String> {
+    patterns
+        .filter_map(|pattern| pattern.strip_prefix('!'))
+        .map(str::to_string)
+        .collect()
+}
 
-        Self::from_patterns(&merged_patterns)
+/// スキャンルート配下を指す絶対パスのパターンを、ルートに固定した相対パターンに変換する
+fn anchor_to_root(roots: &[PathBuf], pattern: &str) -> String {
+    let (negation, body) = match pattern.strip_prefix('!') {
+        Some(body) => ("!", body),
+        None => ("", pattern),
+    };
+    if !is_absolute_path(body) {
+        return pattern.to_string();
     }
+    // Windowsでは `C:\src\build` と `c:/src/build` を同じパスとして扱う
+    let body = normalize_path(Path::new(body));
+    roots
+        .iter()
+        .find_map(|root| body.strip_prefix(root).ok())
+        .map(|relative| format!("{negation}/{}", relative.to_string_lossy()))
+        .unwrap_or_else(|| pattern.to_string())
 }
 
 impl Default for ExcludeFilter {
@@ -115,6 +294,17 @@ mod tests {
         assert!(!filter.is_excluded("src/main.rs"));
     }
 
+    #[test]
+    fn test_exclude_filter_name_patterns() {
+        let patterns = vec!["*-archive".to_string(), "tmp-*".to_string()];
+        let filter = ExcludeFilter::from_patterns(&patterns).unwrap();
+
+        assert!(filter.is_name_excluded("site-archive"));
+        assert!(filter.is_name_excluded("tmp-experiment"));
+        assert!(!filter.is_name_excluded("archive-tools"));
+        assert!(!filter.is_name_excluded("pendector"));
+    }
+
     #[test]
     fn test_exclude_filter_merge_patterns() {
         let patterns1 = ["node_modules".to_string()];
@@ -129,6 +319,120 @@ mod tests {
         assert!(!filter.is_excluded("src/main.rs"));
     }
 
+    #[test]
+    fn test_exclude_filter_merge_patterns_later_negation_wins() {
+        let defaults = ["*-dir".to_string()];
+        let path_specific = ["!important-dir".to_string()];
+        let filter = ExcludeFilter::merge_patterns(&[&defaults[..], &path_specific[..]]).unwrap();
+
+        assert!(filter.is_excluded("scratch-dir"));
+        assert!(!filter.is_excluded("important-dir"));
+
+        // CLI引数は最後に適用されるので、設定の否定パターンより優先される
+        let cli = ["important-dir".to_string()];
+        let filter =
+            ExcludeFilter::merge_patterns(&[&defaults[..], &path_specific[..], &cli[..]]).unwrap();
+        assert!(filter.is_excluded("important-dir"));
+    }
+
+    #[test]
+    fn test_exclude_filter_anchored_pattern_matches_only_at_root() {
+        let patterns = vec!["/build".to_string()];
+        let filter = ExcludeFilter::for_root("/srv/src", &patterns).unwrap();
+
+        assert!(filter.is_excluded_at("build", true));
+        assert!(filter.is_excluded_at("build/app/.git", true));
+        assert!(!filter.is_excluded_at("project/build", true));
+    }
+
+    #[test]
+    fn test_exclude_filter_directory_pattern_matches_nested_directories() {
+        let patterns = vec!["build/".to_string()];
+        let filter = ExcludeFilter::for_root("/srv/src", &patterns).unwrap();
+
+        assert!(filter.is_excluded_at("build", true));
+        assert!(filter.is_excluded_at("project/build", true));
+        assert!(!filter.is_excluded_at("project/build", false));
+    }
+
+    #[test]
+    fn test_exclude_filter_absolute_pattern_under_root() {
+        let patterns = vec![
+            "/srv/src/legacy".to_string(),
+            "!/srv/src/legacy/keep".to_string(),
+        ];
+        let filter = ExcludeFilter::for_root("/srv/src", &patterns).unwrap();
+
+        assert!(filter.is_excluded_at("legacy", true));
+        assert!(!filter.is_excluded_at("legacy/keep", true));
+        assert!(!filter.is_excluded_at("other/legacy", true));
+
+        // 別のスキャンルートでは絶対パスのパターンは何にも一致しない
+        let filter = ExcludeFilter::for_root("/home/me/work", &patterns).unwrap();
+        assert!(!filter.is_excluded_at("legacy", true));
+    }
+
+    #[test]
+    fn test_may_reinclude_below() {
+        let patterns = vec![
+            "legacy".to_string(),
+            "!/legacy/keep".to_string(),
+            "vendor".to_string(),
+        ];
+        let filter = ExcludeFilter::from_patterns(&patterns).unwrap();
+        assert!(filter.may_reinclude_below("legacy"));
+        assert!(!filter.may_reinclude_below("legacy/other"));
+        assert!(!filter.may_reinclude_below("vendor"));
+
+        let filter =
+            ExcludeFilter::from_patterns(&["vendor".to_string(), "!keep".to_string()]).unwrap();
+        assert!(filter.may_reinclude_below("vendor"));
+        let filter =
+            ExcludeFilter::from_patterns(&["vendor".to_string(), "!vendor/*/keep".to_string()])
+                .unwrap();
+        assert!(filter.may_reinclude_below("vendor"));
+        assert!(!ExcludeFilter::new().may_reinclude_below("vendor"));
+    }
+
+    #[test]
+    fn test_exclude_filter_windows_absolute_pattern_under_root() {
+        let patterns = vec![
+            r"c:\src\legacy".to_string(),
+            r"!C:/src/legacy/keep".to_string(),
+        ];
+        let filter = ExcludeFilter::for_root(r"\\?\C:\src\", &patterns).unwrap();
+
+        assert!(filter.is_excluded_at("legacy", true));
+        assert!(!filter.is_excluded_at("legacy/keep", true));
+
+        let filter = ExcludeFilter::for_root(r"\\server\share\src", &patterns).unwrap();
+        assert!(!filter.is_excluded_at("legacy", true));
+    }
+
+    #[test]
+    fn test_exclude_filter_explain_reports_deciding_source() {
+        let defaults = vec!["vendor".to_string(), "node_modules".to_string()];
+        let cli = vec!["!vendor".to_string()];
+        let filter = ExcludeFilter::for_root_with_sources(
+            "/src",
+            &[("defaults", &defaults), ("--exclude", &cli)],
+        )
+        .unwrap();
+
+        let explained = filter.explain_at("app/node_modules/dep", true).unwrap();
+        assert_eq!(explained.pattern, "node_modules");
+        assert_eq!(explained.source, "defaults");
+        assert!(explained.excluded);
+
+        // 後のリストの否定パターンが最終的な判定を決める
+        let explained = filter.explain_at("vendor", true).unwrap();
+        assert_eq!(explained.pattern, "!vendor");
+        assert_eq!(explained.source, "--exclude");
+        assert!(!explained.excluded);
+
+        assert_eq!(filter.explain_at("app", true), None);
+    }
+
     #[test]
     fn test_exclude_filter_directory_vs_file() {
         let patterns = vec!["build".to_string()];
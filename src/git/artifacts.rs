@@ -0,0 +1,108 @@
+use crate::error::{PendectorError, PendectorResult};
+use crate::git::layout::open_repository;
+use git2::{Status, StatusOptions};
+use serde::Serialize;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// これより小さい無視ディレクトリは報告しない（1 MiB）
+pub const ARTIFACT_MIN_SIZE: u64 = 1024 * 1024;
+
+/// 作業ツリーに残っている、gitに無視されたディレクトリ（ビルド成果物など）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct Artifact {
+    /// リポジトリからの相対パス（例: `target/`）
+    pub path: String,
+    /// ディスク上のサイズ（バイト）
+    pub size_bytes: u64,
+}
+
+/// 無視されたディレクトリのうち [`ARTIFACT_MIN_SIZE`] 以上のものを大きい順に返す
+pub fn find_ignored_artifacts<P: AsRef<Path>>(repo_path: P) -> PendectorResult<Vec<Artifact>> {
+    let repo_path = repo_path.as_ref();
+    let repo = open_repository(repo_path).map_err(|e| {
+        PendectorError::from_git2_error(
+            repo_path.to_string_lossy().to_string(),
+            "open repository".to_string(),
+            e,
+        )
+    })?;
+
+    // 無視ディレクトリは中まで列挙せず、ディレクトリ単位で受け取る
+    let mut options = StatusOptions::new();
+    options
+        .include_ignored(true)
+        .recurse_ignored_dirs(false)
+        .include_untracked(false);
+    let statuses = repo.statuses(Some(&mut options)).map_err(|e| {
+        PendectorError::from_git2_error(
+            repo_path.to_string_lossy().to_string(),
+            "list ignored files".to_string(),
+            e,
+        )
+    })?;
+
+    let mut artifacts: Vec<Artifact> = statuses
+        .iter()
+        .filter(|entry| entry.status().contains(Status::IGNORED))
+        .filter_map(|entry| entry.path().map(|p| p.to_string()))
+        .filter(|path| path.ends_with('/'))
+        .map(|path| {
+            let size_bytes = dir_size(repo_path.join(&path));
+            Artifact { path, size_bytes }
+        })
+        .filter(|artifact| artifact.size_bytes >= ARTIFACT_MIN_SIZE)
+        .collect();
+    artifacts.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes).then(a.path.cmp(&b.path)));
+    Ok(artifacts)
+}
+
+/// ディレクトリ配下のファイルサイズの合計（シンボリックリンクはたどらない）
+pub fn dir_size<P: AsRef<Path>>(path: P) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_find_ignored_artifacts_reports_large_ignored_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path();
+        Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(path)
+            .output()
+            .unwrap();
+        fs::write(path.join(".gitignore"), "target/\nnode_modules/\n*.log\n").unwrap();
+
+        let big = vec![0u8; ARTIFACT_MIN_SIZE as usize];
+        fs::create_dir_all(path.join("target/debug")).unwrap();
+        fs::write(path.join("target/debug/app"), &big).unwrap();
+        fs::write(path.join("target/debug/app.d"), &big).unwrap();
+        // 小さい無視ディレクトリと無視ファイルは報告しない
+        fs::create_dir_all(path.join("node_modules/pkg")).unwrap();
+        fs::write(path.join("node_modules/pkg/index.js"), "x").unwrap();
+        fs::write(path.join("build.log"), &big).unwrap();
+
+        let artifacts = find_ignored_artifacts(path).unwrap();
+        assert_eq!(
+            artifacts,
+            vec![Artifact {
+                path: "target/".to_string(),
+                size_bytes: 2 * ARTIFACT_MIN_SIZE,
+            }]
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use crate::error::{PendectorError, PendectorResult};
+use crate::git::layout::open_repository;
+use git2::{DiffFormat, DiffOptions};
+use serde::Serialize;
+use std::path::Path;
+
+/// 作業ツリーの差分の先頭部分（`--preview` 指定時のみ）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct DiffPreview {
+    /// パッチ形式の差分の先頭の行
+    pub lines: Vec<String>,
+    /// 表示した行数より差分が長いか
+    pub truncated: bool,
+}
+
+/// HEADから作業ツリー（インデックスと未追跡ファイルを含む）への差分の先頭 `max_lines` 行
+pub fn diff_preview<P: AsRef<Path>>(
+    repo_path: P,
+    max_lines: usize,
+) -> PendectorResult<DiffPreview> {
+    let repo_path = repo_path.as_ref();
+    let git_error = |operation: &str, e: git2::Error| {
+        PendectorError::from_git2_error(
+            repo_path.to_string_lossy().to_string(),
+            operation.to_string(),
+            e,
+        )
+    };
+    let repo = open_repository(repo_path).map_err(|e| git_error("open repository", e))?;
+
+    // 最初のコミット前はHEADがないので、空のツリーとの差分になる
+    let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+    let mut options = DiffOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true);
+    let diff = repo
+        .diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut options))
+        .map_err(|e| git_error("diff working tree", e))?;
+
+    // 1行多く集めて、省略したかどうかを判断する
+    let mut lines = Vec::new();
+    let result = diff.print(DiffFormat::Patch, |_, _, line| {
+        let content = String::from_utf8_lossy(line.content());
+        let prefix = match line.origin() {
+            origin @ ('+' | '-' | ' ') => origin.to_string(),
+            _ => String::new(),
+        };
+        for text in content.lines() {
+            lines.push(format!("{prefix}{text}"));
+        }
+        lines.len() <= max_lines
+    });
+    // 行数に達してコールバックが打ち切った場合もエラーになるので、それ以外のみを失敗とする
+    if lines.len() <= max_lines {
+        result.map_err(|e| git_error("print diff", e))?;
+    }
+
+    let truncated = lines.len() > max_lines;
+    lines.truncate(max_lines);
+    Ok(DiffPreview { lines, truncated })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_diff_preview_limits_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path();
+        git2::Repository::init(path).unwrap();
+        fs::write(path.join("notes.txt"), "one\ntwo\nthree\n").unwrap();
+
+        let preview = diff_preview(path, 100).unwrap();
+        assert!(!preview.truncated);
+        assert!(preview.lines[0].starts_with("diff --git a/notes.txt b/notes.txt"));
+        assert!(preview.lines.ends_with(&[
+            "+one".to_string(),
+            "+two".to_string(),
+            "+three".to_string()
+        ]));
+
+        let preview = diff_preview(path, 2).unwrap();
+        assert!(preview.truncated);
+        assert_eq!(preview.lines.len(), 2);
+    }
+}
@@ -0,0 +1,68 @@
+use git2::Repository as Git2Repository;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+
+/// 作業ツリーのパスから、離れた場所にあるgitディレクトリへの対応
+fn detached_layouts() -> &'static RwLock<HashMap<PathBuf, PathBuf>> {
+    static LAYOUTS: OnceLock<RwLock<HashMap<PathBuf, PathBuf>>> = OnceLock::new();
+    LAYOUTS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// `git --git-dir=<git_dir> --work-tree=<work_tree>` 形式のリポジトリを登録する
+///
+/// 登録後は作業ツリーのパスを通常のリポジトリと同じように扱える。
+pub fn register_detached_layout<P: AsRef<Path>, Q: AsRef<Path>>(work_tree: P, git_dir: Q) {
+    let mut layouts = detached_layouts()
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    layouts.insert(
+        work_tree.as_ref().to_path_buf(),
+        git_dir.as_ref().to_path_buf(),
+    );
+}
+
+/// 作業ツリーに対応するgitディレクトリ（登録されていなければNone）
+pub fn detached_git_dir<P: AsRef<Path>>(work_tree: P) -> Option<PathBuf> {
+    let layouts = detached_layouts()
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    layouts.get(work_tree.as_ref()).cloned()
+}
+
+/// リポジトリを開く（登録された作業ツリーなら、そのgitディレクトリを開いて作業ツリーを設定する）
+pub fn open_repository<P: AsRef<Path>>(repo_path: P) -> Result<Git2Repository, git2::Error> {
+    let repo_path = repo_path.as_ref();
+    match detached_git_dir(repo_path) {
+        Some(git_dir) => {
+            let repo = Git2Repository::open_bare(&git_dir)?;
+            repo.set_workdir(repo_path, false)?;
+            Ok(repo)
+        }
+        None => Git2Repository::open(repo_path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_open_repository_with_detached_git_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let git_dir = temp_dir.path().join("dotfiles.git");
+        let work_tree = temp_dir.path().join("home");
+        fs::create_dir(&work_tree).unwrap();
+        Git2Repository::init_bare(&git_dir).unwrap();
+        fs::write(work_tree.join(".bashrc"), "alias ll='ls -l'\n").unwrap();
+
+        assert!(open_repository(&work_tree).is_err());
+        register_detached_layout(&work_tree, &git_dir);
+
+        let repo = open_repository(&work_tree).unwrap();
+        assert_eq!(repo.workdir(), Some(work_tree.as_path()));
+        assert!(repo.status_file(Path::new(".bashrc")).is_ok());
+    }
+}
@@ -0,0 +1,90 @@
+use crate::git::layout::open_repository;
+use crate::time::format_age_long;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// 中断したgitコマンドが残しがちなロックファイル（gitディレクトリからの相対パス）
+const LOCK_FILE_NAMES: &[&str] = &[
+    "index.lock",
+    "HEAD.lock",
+    "ORIG_HEAD.lock",
+    "config.lock",
+    "packed-refs.lock",
+    "shallow.lock",
+];
+
+/// gitディレクトリに残っているロックファイル
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct LockFile {
+    /// ロックファイル名（例: `index.lock`）
+    pub name: String,
+    /// ロックファイルのパス
+    pub path: PathBuf,
+    /// 最終更新からの経過秒数
+    pub age_secs: u64,
+}
+
+impl LockFile {
+    /// 最終更新からの経過時間
+    pub fn age(&self) -> Duration {
+        Duration::from_secs(self.age_secs)
+    }
+
+    /// 例: `index.lock present, 3 days old`
+    pub fn describe(&self) -> String {
+        format!("{} present, {} old", self.name, format_age_long(self.age()))
+    }
+}
+
+/// リポジトリのgitディレクトリに残っているロックファイルを列挙する
+pub fn find_lock_files<P: AsRef<Path>>(repo_path: P) -> Vec<LockFile> {
+    let repo_path = repo_path.as_ref();
+    // worktreeやサブモジュールでは `.git` がファイルなので、git2でgitディレクトリを解決する
+    let git_dir = open_repository(repo_path)
+        .map(|repo| repo.path().to_path_buf())
+        .unwrap_or_else(|_| repo_path.join(".git"));
+    let now = SystemTime::now();
+
+    LOCK_FILE_NAMES
+        .iter()
+        .filter_map(|name| {
+            let path = git_dir.join(name);
+            let modified = path.metadata().ok()?.modified().ok()?;
+            let age_secs = now
+                .duration_since(modified)
+                .map(|age| age.as_secs())
+                .unwrap_or(0);
+            Some(LockFile {
+                name: name.to_string(),
+                path,
+                age_secs,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_find_lock_files_reports_age() {
+        let temp_dir = TempDir::new().unwrap();
+        let git_dir = temp_dir.path().join(".git");
+        fs::create_dir(&git_dir).unwrap();
+        assert!(find_lock_files(temp_dir.path()).is_empty());
+
+        let lock = File::create(git_dir.join("index.lock")).unwrap();
+        let three_days_ago = SystemTime::now() - Duration::from_secs(3 * 86_400 + 60);
+        lock.set_modified(three_days_ago).unwrap();
+
+        let locks = find_lock_files(temp_dir.path());
+        assert_eq!(locks.len(), 1);
+        assert_eq!(locks[0].name, "index.lock");
+        assert_eq!(locks[0].describe(), "index.lock present, 3 days old");
+    }
+}
@@ -0,0 +1,206 @@
+use crate::error::{PendectorError, PendectorResult};
+use crate::git::layout::open_repository;
+use std::path::Path;
+
+/// `gc.auto` の既定値（これより緩いオブジェクトが多いと `git gc --auto` がパックし直す）
+pub const DEFAULT_GC_AUTO: usize = 6700;
+
+/// `gc.autoPackLimit` の既定値（これよりpackが多いと `git gc --auto` がまとめ直す）
+pub const DEFAULT_GC_AUTO_PACK_LIMIT: usize = 50;
+
+/// 参照とオブジェクトの格納状況（`pendector health --verbose`）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepoStats {
+    /// 参照の数（packed-refsに入っているものも含む）
+    pub refs: usize,
+    /// packed-refsに入っていない参照の数
+    pub loose_refs: usize,
+    /// packに入っていないオブジェクトの数
+    pub loose_objects: usize,
+    pub packs: usize,
+    /// packファイルの合計サイズ（バイト）
+    pub pack_size: u64,
+    /// リポジトリの `gc.auto`（0なら自動gcは無効）
+    pub gc_auto: usize,
+    /// リポジトリの `gc.autoPackLimit`（0ならpackの数では判定しない）
+    pub gc_auto_pack_limit: usize,
+}
+
+impl RepoStats {
+    /// `git gc` や `git maintenance run` で整理したほうがよい理由（`git gc --auto` と同じ基準、不要ならNone）
+    pub fn maintenance_reason(&self) -> Option<String> {
+        if self.gc_auto == 0 {
+            return None;
+        }
+        if self.loose_objects > self.gc_auto {
+            return Some(format!(
+                "{} loose objects (gc.auto is {})",
+                self.loose_objects, self.gc_auto
+            ));
+        }
+        if self.gc_auto_pack_limit > 0 && self.packs > self.gc_auto_pack_limit {
+            return Some(format!(
+                "{} packs (gc.autoPackLimit is {})",
+                self.packs, self.gc_auto_pack_limit
+            ));
+        }
+        None
+    }
+}
+
+/// 参照・packの数と緩いオブジェクトの数を調べる（worktreeでは共有のgitディレクトリを見る）
+pub fn repository_stats<P: AsRef<Path>>(repo_path: P) -> PendectorResult<RepoStats> {
+    let repo_path = repo_path.as_ref();
+    let git_error = |e| {
+        PendectorError::from_git2_error(repo_path.display().to_string(), "stats".to_string(), e)
+    };
+    let repo = open_repository(repo_path).map_err(git_error)?;
+    let refs = repo.references().map_err(git_error)?.count();
+    let config = repo.config().map_err(git_error)?;
+    let config_limit = |name: &str, default: usize| {
+        config
+            .get_i64(name)
+            .map_or(default, |value| usize::try_from(value).unwrap_or(0))
+    };
+
+    let common_dir = repo.commondir();
+    let objects = common_dir.join("objects");
+    let loose_objects = std::fs::read_dir(&objects)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            // 緩いオブジェクトはハッシュの先頭2文字のディレクトリに置かれる
+            let name = entry.file_name();
+            name.len() == 2
+                && name
+                    .to_string_lossy()
+                    .chars()
+                    .all(|c| c.is_ascii_hexdigit())
+        })
+        .map(|entry| count_files(&entry.path()))
+        .sum();
+    let packs: Vec<u64> = std::fs::read_dir(objects.join("pack"))
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "pack"))
+        .map(|entry| entry.metadata().map_or(0, |m| m.len()))
+        .collect();
+
+    Ok(RepoStats {
+        refs,
+        loose_refs: count_files(&common_dir.join("refs")),
+        loose_objects,
+        packs: packs.len(),
+        pack_size: packs.iter().sum(),
+        gc_auto: config_limit("gc.auto", DEFAULT_GC_AUTO),
+        gc_auto_pack_limit: config_limit("gc.autoPackLimit", DEFAULT_GC_AUTO_PACK_LIMIT),
+    })
+}
+
+/// ディレクトリ以下のファイルの数（サブディレクトリも数える）
+fn count_files(dir: &Path) -> usize {
+    walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .count()
+}
+
+/// `git maintenance` による定期的なfetch（prefetchタスク）が設定されているか
+///
+/// リポジトリ単位で `maintenance.prefetch.enabled` が有効か、
+/// `git maintenance start` で登録され（`maintenance.repo`）prefetchを含む戦略になっている場合に真。
+/// prefetchは `refs/prefetch/` を更新するだけなので、pull待ちの判定は最後の通常のfetch時点のままになる。
+pub fn has_scheduled_fetch<P: AsRef<Path>>(repo_path: P) -> bool {
+    let repo_path = repo_path.as_ref();
+    let Ok(repo) = open_repository(repo_path) else {
+        return false;
+    };
+    let Ok(config) = repo.config() else {
+        return false;
+    };
+
+    if let Ok(enabled) = config.get_bool("maintenance.prefetch.enabled") {
+        return enabled;
+    }
+
+    let registered = repo.workdir().is_some_and(|workdir| {
+        let workdir = workdir
+            .canonicalize()
+            .unwrap_or_else(|_| workdir.to_path_buf());
+        let Ok(mut entries) = config.multivar("maintenance.repo", None) else {
+            return false;
+        };
+        let mut found = false;
+        while let Some(Ok(entry)) = entries.next() {
+            let registered = entry
+                .value()
+                .map(Path::new)
+                .map(|path| path.canonicalize().unwrap_or_else(|_| path.to_path_buf()));
+            found |= registered.as_deref() == Some(workdir.as_path());
+        }
+        found
+    });
+    // incremental戦略は毎時のprefetchを含む
+    registered && config.get_string("maintenance.strategy").as_deref() == Ok("incremental")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Repository as Git2Repository;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_has_scheduled_fetch() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path();
+        let repo = Git2Repository::init(path).unwrap();
+        assert!(!has_scheduled_fetch(path));
+
+        let mut config = repo.config().unwrap();
+        config
+            .set_str("maintenance.repo", path.to_str().unwrap())
+            .unwrap();
+        config
+            .set_str("maintenance.strategy", "incremental")
+            .unwrap();
+        assert!(has_scheduled_fetch(path));
+
+        config
+            .set_bool("maintenance.prefetch.enabled", false)
+            .unwrap();
+        assert!(!has_scheduled_fetch(path));
+    }
+
+    #[test]
+    fn test_repository_stats_counts_refs_and_objects() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Git2Repository::init(temp_dir.path()).unwrap();
+        let tree = repo.treebuilder(None).unwrap().write().unwrap();
+        let tree = repo.find_tree(tree).unwrap();
+        let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+        let commit = repo
+            .commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+            .unwrap();
+        repo.reference("refs/tags/v1", commit, false, "tag")
+            .unwrap();
+
+        let stats = repository_stats(temp_dir.path()).unwrap();
+        assert_eq!(stats.refs, 2);
+        assert_eq!(stats.loose_refs, 2);
+        // 空のツリーとコミット
+        assert_eq!(stats.loose_objects, 2);
+        assert_eq!(stats.packs, 0);
+        assert_eq!(stats.maintenance_reason(), None);
+
+        repo.config().unwrap().set_i64("gc.auto", 1).unwrap();
+        let stats = repository_stats(temp_dir.path()).unwrap();
+        assert_eq!(
+            stats.maintenance_reason().as_deref(),
+            Some("2 loose objects (gc.auto is 1)")
+        );
+    }
+}
@@ -1,3 +1,22 @@
-pub mod status;
+pub(crate) mod artifacts;
+pub(crate) mod diff;
+pub(crate) mod layout;
+pub(crate) mod locks;
+pub(crate) mod maintenance;
+pub(crate) mod operation;
+pub(crate) mod remote;
+pub(crate) mod status;
 
-pub use status::*;
+pub use artifacts::{dir_size, find_ignored_artifacts, Artifact, ARTIFACT_MIN_SIZE};
+pub use diff::{diff_preview, DiffPreview};
+pub use layout::{detached_git_dir, open_repository};
+pub use locks::{find_lock_files, LockFile};
+pub use maintenance::{
+    has_scheduled_fetch, repository_stats, RepoStats, DEFAULT_GC_AUTO, DEFAULT_GC_AUTO_PACK_LIMIT,
+};
+pub use operation::{detect_in_progress_operation, in_progress_operation, InProgressOperation};
+pub use remote::{normalize_remote_url, remote_id, web_url};
+pub use status::{
+    BranchStatus, FetchOptions, GitStatus, RemoteComparison, RepoStatus, StatusQuery, UnbornRemote,
+    DEFAULT_BRANCH_NAMES,
+};
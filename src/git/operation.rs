@@ -0,0 +1,91 @@
+use crate::git::layout::open_repository;
+use git2::{Repository as Git2Repository, RepositoryState};
+use serde::Serialize;
+use std::fmt;
+use std::path::Path;
+
+/// 途中で止まっているgitの操作（この状態のリポジトリには一括操作を行わない）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum InProgressOperation {
+    Merge,
+    Rebase,
+    CherryPick,
+    Revert,
+    Bisect,
+    Am,
+}
+
+impl fmt::Display for InProgressOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            InProgressOperation::Merge => "merge",
+            InProgressOperation::Rebase => "rebase",
+            InProgressOperation::CherryPick => "cherry-pick",
+            InProgressOperation::Revert => "revert",
+            InProgressOperation::Bisect => "bisect",
+            InProgressOperation::Am => "am",
+        };
+        f.write_str(name)
+    }
+}
+
+/// リポジトリの状態（MERGE_HEAD、BISECT_LOG、rebase-applyなど）から途中の操作を判定する
+pub fn in_progress_operation(repo: &Git2Repository) -> Option<InProgressOperation> {
+    match repo.state() {
+        RepositoryState::Clean => None,
+        RepositoryState::Merge => Some(InProgressOperation::Merge),
+        RepositoryState::Revert | RepositoryState::RevertSequence => {
+            Some(InProgressOperation::Revert)
+        }
+        RepositoryState::CherryPick | RepositoryState::CherryPickSequence => {
+            Some(InProgressOperation::CherryPick)
+        }
+        RepositoryState::Bisect => Some(InProgressOperation::Bisect),
+        RepositoryState::Rebase
+        | RepositoryState::RebaseInteractive
+        | RepositoryState::RebaseMerge => Some(InProgressOperation::Rebase),
+        // rebase-applyのみの場合はgit amとgit rebase --applyの区別がつかないが、amとして扱う
+        RepositoryState::ApplyMailbox | RepositoryState::ApplyMailboxOrRebase => {
+            Some(InProgressOperation::Am)
+        }
+    }
+}
+
+/// パスを指定して途中の操作を判定する（開けない場合はNone）
+pub fn detect_in_progress_operation<P: AsRef<Path>>(repo_path: P) -> Option<InProgressOperation> {
+    open_repository(repo_path)
+        .ok()
+        .and_then(|repo| in_progress_operation(&repo))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detect_in_progress_operation_bisect_and_am() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path();
+        Git2Repository::init(path).unwrap();
+        assert_eq!(detect_in_progress_operation(path), None);
+
+        fs::write(path.join(".git/BISECT_LOG"), "git bisect start\n").unwrap();
+        assert_eq!(
+            detect_in_progress_operation(path),
+            Some(InProgressOperation::Bisect)
+        );
+        fs::remove_file(path.join(".git/BISECT_LOG")).unwrap();
+
+        fs::create_dir(path.join(".git/rebase-apply")).unwrap();
+        fs::write(path.join(".git/rebase-apply/applying"), "").unwrap();
+        assert_eq!(
+            detect_in_progress_operation(path),
+            Some(InProgressOperation::Am)
+        );
+        assert_eq!(InProgressOperation::CherryPick.to_string(), "cherry-pick");
+    }
+}
@@ -1,25 +1,368 @@
-use crate::error::{PendectorError, PendectorResult};
-use git2::{Repository as Git2Repository, StatusOptions};
-use indicatif::{ProgressBar, ProgressStyle};
+use crate::error::{ErrorReport, PendectorError, PendectorResult};
+use crate::exclude::ExcludeFilter;
+use crate::git::layout::{detached_git_dir, open_repository};
+use crate::git::operation::{in_progress_operation, InProgressOperation};
+use crate::git::remote::remote_id;
+use crate::interrupt::is_interrupted;
+use crate::progress::{ProgressEvent, ProgressMode, ProgressReporter};
+use git2::{BranchType, Repository as Git2Repository, StatusOptions};
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Read;
 use std::path::Path;
-use std::process::Command;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
 
+/// fetchのデフォルトタイムアウト秒数
+pub(crate) const DEFAULT_FETCH_TIMEOUT_SECS: u64 = 5;
+
+/// 遅いfetchとみなすデフォルトの秒数
+pub(crate) const DEFAULT_FETCH_SLOW_THRESHOLD_SECS: u64 = 15;
+
+/// fetchプロセスの終了を確認する間隔
+const FETCH_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 単一リポジトリのGitステータス
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct RepoStatus {
+    /// 未コミットの変更（未追跡ファイルを含む）があるか
     pub has_changes: bool,
+    /// 現在のブランチ名（detached HEADの場合はNone、コミットがなければHEADが指すブランチ名）
     pub current_branch: Option<String>,
+    /// HEADが指すブランチにまだコミットがない（`git init` 直後や空のリモートのクローン）
+    pub unborn: bool,
+    /// `git status --short` 形式の変更ファイル一覧
     pub changed_files: Vec<String>,
+    /// 変更ファイルが上限を超え、一覧を途中で打ち切ったか
+    pub changed_files_truncated: bool,
+    /// リモートに取り込むべきコミットがあるか
     pub needs_pull: bool,
+    /// リモートに送るべきコミットがあるか
     pub needs_push: bool,
+    /// 比較対象のリモートブランチ名（例: `origin/main`）
     pub remote_branch: Option<String>,
+    /// すべてのローカルブランチと上流ブランチとの差分
+    pub branches: Vec<BranchStatus>,
+    /// 対応するローカルブランチがないリモートブランチ（例: `origin/feature`）
+    pub remote_only_branches: Vec<String>,
+    /// 変更のうち最も新しいファイルの更新時刻（取得できなければindexの更新時刻）
+    pub dirty_since: Option<SystemTime>,
+    /// fetchを実行して失敗した場合のエラー
+    pub fetch_error: Option<ErrorReport>,
+    /// 途中で止まっている操作（bisect、amなど）
+    pub operation: Option<InProgressOperation>,
+    /// 正規化したoriginのURL（例: `github.com/me/foo`）
+    pub remote_id: Option<String>,
+    /// HEADのコミット日時
+    pub last_commit_at: Option<SystemTime>,
+}
+
+/// ローカルブランチと上流ブランチとの差分
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct BranchStatus {
+    /// ローカルブランチ名
+    pub name: String,
+    /// 上流ブランチ名（設定されていない場合はNone）
+    pub upstream: Option<String>,
+    /// 上流にないコミット数
+    pub ahead: usize,
+    /// 上流から取り込んでいないコミット数
+    pub behind: usize,
+}
+
+/// コミットのないブランチ（unborn HEAD）で、リモートにコミットがある場合の扱い
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnbornRemote {
+    /// リモートのコミットを取り込む必要があるとみなす
+    #[default]
+    Pull,
+    /// 同期状態を報告しない
+    Ignore,
 }
 
+/// 別のリモート（フォーク元など）のデフォルトブランチとの比較結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[non_exhaustive]
+pub struct RemoteComparison {
+    /// 比較したリモートブランチ名（例: `upstream/main`）
+    pub remote_branch: String,
+    /// 比較先にないコミット数
+    pub ahead: usize,
+    /// 比較先から取り込んでいないコミット数
+    pub behind: usize,
+}
+
+/// `<remote>/HEAD` が取得できない場合に順に試すデフォルトブランチ名
+pub const DEFAULT_BRANCH_NAMES: &[&str] = &["main", "master"];
+
+/// デフォルトブランチ名の候補（`<remote>/HEAD` を優先し、続けて指定の名前を順に試す）
+pub(crate) fn default_branch_candidates(
+    repo: &Git2Repository,
+    remote: &str,
+    default_branches: &[String],
+) -> Vec<String> {
+    remote_head_branch(repo, remote)
+        .into_iter()
+        .chain(default_branches.iter().cloned())
+        .collect()
+}
+
+/// リモートのデフォルトブランチ名（`<remote>/HEAD` が指すブランチ）
+pub(crate) fn remote_head_branch(repo: &Git2Repository, remote: &str) -> Option<String> {
+    let prefix = format!("refs/remotes/{remote}/");
+    repo.find_reference(&format!("{prefix}HEAD"))
+        .ok()
+        .and_then(|r| r.symbolic_target().map(|t| t.to_string()))
+        .and_then(|t| t.strip_prefix(&prefix).map(|s| s.to_string()))
+}
+
+impl BranchStatus {
+    /// 上流ブランチとの同期状態を短く説明する
+    pub fn sync_summary(&self) -> String {
+        match &self.upstream {
+            None => "no upstream".to_string(),
+            Some(upstream) if self.ahead == 0 && self.behind == 0 => {
+                format!("up to date with {upstream}")
+            }
+            Some(upstream) => format!("ahead {}, behind {} of {upstream}", self.ahead, self.behind),
+        }
+    }
+}
+
+/// ステータス取得の設定
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct StatusQuery {
+    /// ahead/behindの報告から外すブランチ名のパターン
+    pub ignore_branches: Vec<String>,
+    /// 変更ファイルをこの件数まで調べたら打ち切る（Noneなら無制限）
+    pub file_limit: Option<usize>,
+    /// 調べ直したファイルの情報をindexに書き戻し、次回以降の比較を省けるようにする
+    pub update_index: bool,
+    /// 変更として数えないファイルのパターン（gitignore形式、例: `.DS_Store`）
+    pub dirty_ignore: ExcludeFilter,
+    /// コミットのないブランチでリモートにコミットがある場合の扱い
+    pub unborn_remote: UnbornRemote,
+    /// リモートとの同期状態とブランチごとのahead/behindを調べない（作業ツリーの変更だけを見る）
+    pub skip_remote_check: bool,
+}
+
+impl StatusQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// ahead/behindの報告から外すブランチ名のパターンを設定する
+    pub fn with_ignore_branches(mut self, ignore_branches: Vec<String>) -> Self {
+        self.ignore_branches = ignore_branches;
+        self
+    }
+
+    /// 変更ファイルを調べる上限を設定する
+    pub fn with_file_limit(mut self, file_limit: Option<usize>) -> Self {
+        self.file_limit = file_limit;
+        self
+    }
+
+    /// indexへの書き戻しを設定する
+    pub fn with_update_index(mut self, update_index: bool) -> Self {
+        self.update_index = update_index;
+        self
+    }
+
+    /// 変更として数えないファイルのパターンを設定する
+    pub fn with_dirty_ignore(mut self, dirty_ignore: ExcludeFilter) -> Self {
+        self.dirty_ignore = dirty_ignore;
+        self
+    }
+
+    /// コミットのないブランチでリモートにコミットがある場合の扱いを設定する
+    pub fn with_unborn_remote(mut self, unborn_remote: UnbornRemote) -> Self {
+        self.unborn_remote = unborn_remote;
+        self
+    }
+
+    /// リモートとの同期状態を調べないかを設定する
+    pub fn with_skip_remote_check(mut self, skip_remote_check: bool) -> Self {
+        self.skip_remote_check = skip_remote_check;
+        self
+    }
+}
+
+/// fetch実行時の設定
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct FetchOptions {
+    /// 1リポジトリあたりのタイムアウト
+    pub timeout: Duration,
+    /// この時間を超えたfetchを「遅い」とみなして報告する
+    pub slow_threshold: Duration,
+    /// 遅いfetchをタイムアウトを待たずに打ち切るか
+    pub skip_slow: bool,
+    /// 同時に実行するfetchの数（Noneならrayonの既定値）
+    pub jobs: Option<usize>,
+    /// fetchのプロセスに追加で設定する環境変数
+    pub env: BTreeMap<String, String>,
+    /// プロンプトを無効にする既定値で上書きせず、呼び出し元の値を引き継ぐ環境変数
+    pub passthrough_env: Vec<String>,
+    /// HTTP(S)のリモートに使うプロキシ（`http.proxy`）
+    pub proxy: Option<String>,
+    /// SSHのリモートに使うプロキシコマンド（`ssh -o ProxyCommand=...`）
+    pub ssh_proxy_command: Option<String>,
+    /// この時刻を過ぎたらfetchを始めず、実行中のものも打ち切る（スキャンの時間の上限）
+    pub deadline: Option<Instant>,
+}
+
+/// fetchが認証の入力待ちで止まらないよう、既定で設定する環境変数
+const NON_INTERACTIVE_ENV: &[(&str, &str)] = &[
+    ("GIT_TERMINAL_PROMPT", "0"), // ターミナルプロンプトを無効化
+    ("GIT_ASKPASS", "true"),      // 認証プロンプトを無効化（常にfalseを返す）
+    ("SSH_ASKPASS", "true"),      // SSH認証プロンプトも無効化
+];
+
+impl FetchOptions {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            slow_threshold: Duration::from_secs(DEFAULT_FETCH_SLOW_THRESHOLD_SECS),
+            skip_slow: false,
+            jobs: None,
+            env: BTreeMap::new(),
+            passthrough_env: Vec::new(),
+            proxy: None,
+            ssh_proxy_command: None,
+            deadline: None,
+        }
+    }
+
+    /// fetchを打ち切る時刻を設定する
+    pub fn with_deadline(mut self, deadline: Option<Instant>) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    /// HTTP(S)のリモートに使うプロキシを設定する
+    pub fn with_proxy(mut self, proxy: Option<String>) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
+    /// SSHのリモートに使うプロキシコマンドを設定する
+    pub fn with_ssh_proxy_command(mut self, ssh_proxy_command: Option<String>) -> Self {
+        self.ssh_proxy_command = ssh_proxy_command;
+        self
+    }
+
+    /// fetchを打ち切る時刻を過ぎたか
+    fn is_past_deadline(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// プロンプトを無効にする環境変数のいずれかを呼び出し元の値や `env` で上書きし、端末での入力を許すか
+    fn allows_prompts(&self) -> bool {
+        NON_INTERACTIVE_ENV.iter().any(|(name, _)| {
+            self.passthrough_env.iter().any(|n| n == name) || self.env.contains_key(*name)
+        })
+    }
+
+    /// プロキシの設定をfetchだけに適用する `git -c` の引数
+    fn git_config_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(ref proxy) = self.proxy {
+            args.extend(["-c".to_string(), format!("http.proxy={proxy}")]);
+        }
+        if let Some(ref proxy_command) = self.ssh_proxy_command {
+            // core.sshCommand はシェルで解釈されるので、コマンド全体を1つの引数としてクォートする
+            let quoted = proxy_command.replace('\'', "'\\''");
+            args.extend([
+                "-c".to_string(),
+                format!("core.sshCommand=ssh -o 'ProxyCommand={quoted}'"),
+            ]);
+        }
+        args
+    }
+
+    /// fetchのプロセスに追加で設定する環境変数を設定する（既定値より優先される）
+    pub fn with_env(mut self, env: BTreeMap<String, String>) -> Self {
+        self.env = env;
+        self
+    }
+
+    /// 呼び出し元の値を引き継ぐ環境変数を設定する
+    pub fn with_passthrough_env(mut self, passthrough_env: Vec<String>) -> Self {
+        self.passthrough_env = passthrough_env;
+        self
+    }
+
+    /// 遅いfetchとみなす閾値を設定する
+    pub fn with_slow_threshold(mut self, slow_threshold: Duration) -> Self {
+        self.slow_threshold = slow_threshold;
+        self
+    }
+
+    /// 遅いfetchを打ち切るかを設定する
+    pub fn with_skip_slow(mut self, skip_slow: bool) -> Self {
+        self.skip_slow = skip_slow;
+        self
+    }
+
+    /// 同時に実行するfetchの数を設定する
+    pub fn with_jobs(mut self, jobs: Option<usize>) -> Self {
+        self.jobs = jobs;
+        self
+    }
+}
+
+/// 並列数を指定した専用のスレッドプールで `f` を実行する（Noneや0ならグローバルプールを使う）
+pub(crate) fn run_with_jobs<R: Send>(jobs: Option<usize>, f: impl FnOnce() -> R + Send) -> R {
+    let pool = jobs
+        .filter(|&n| n > 0)
+        .and_then(|n| rayon::ThreadPoolBuilder::new().num_threads(n).build().ok());
+    match pool {
+        Some(pool) => pool.install(f),
+        None => f(),
+    }
+}
+
+/// Gitステータス取得とfetch操作のエントリポイント
 pub struct GitStatus;
 
+/// ブランチ名がパターンに一致するか（`*` は `/` を含む任意の文字列、`?` は任意の1文字）
+pub(crate) fn branch_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    // 最後に現れた `*` の位置からやり直す貪欲でないマッチング
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    n = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
 impl GitStatus {
     /// git2ライブラリを使用してリポジトリの状態を取得
     pub fn get_repository_status<P: AsRef<Path>>(repo_path: P) -> PendectorResult<RepoStatus> {
@@ -31,11 +374,36 @@ impl GitStatus {
         repo_path: P,
         should_fetch: bool,
     ) -> PendectorResult<RepoStatus> {
-        let repo_path = repo_path.as_ref();
+        Self::repository_status(repo_path.as_ref(), should_fetch, &StatusQuery::new())
+    }
+
+    /// パターンに一致するブランチをahead/behindの報告から外して状態を取得する
+    pub fn get_repository_status_ignoring_branches<P: AsRef<Path>>(
+        repo_path: P,
+        ignore_branches: &[String],
+    ) -> PendectorResult<RepoStatus> {
+        let query = StatusQuery::new().with_ignore_branches(ignore_branches.to_vec());
+        Self::repository_status(repo_path.as_ref(), false, &query)
+    }
+
+    /// 設定を指定して状態を取得する（fetchはしない）
+    pub fn get_repository_status_with_query<P: AsRef<Path>>(
+        repo_path: P,
+        query: &StatusQuery,
+    ) -> PendectorResult<RepoStatus> {
+        Self::repository_status(repo_path.as_ref(), false, query)
+    }
+
+    fn repository_status(
+        repo_path: &Path,
+        should_fetch: bool,
+        query: &StatusQuery,
+    ) -> PendectorResult<RepoStatus> {
+        let ignore_branches = query.ignore_branches.as_slice();
         let repo_path_str = repo_path.to_string_lossy().to_string();
 
         // git2でリポジトリを開く
-        let repo = Git2Repository::open(repo_path).map_err(|e| {
+        let repo = open_repository(repo_path).map_err(|e| {
             if e.code() == git2::ErrorCode::NotFound {
                 PendectorError::GitRepositoryNotFound(repo_path_str.clone())
             } else {
@@ -48,31 +416,59 @@ impl GitStatus {
         })?;
 
         // fetchが要求された場合は実行
-        if should_fetch {
-            Self::perform_fetch(repo_path)?;
-        }
+        // fetchの失敗はステータス取得を妨げないので、結果に含めて返す
+        let fetch_error = if should_fetch {
+            Self::perform_fetch(repo_path)
+                .err()
+                .map(|e| ErrorReport::from(&e))
+        } else {
+            None
+        };
 
-        // 現在のブランチ名を取得
+        // 現在のブランチ名を取得（コミットがなければHEADが指すブランチ名）
+        let unborn_branch = Self::unborn_branch(&repo);
+        let unborn = unborn_branch.is_some();
         let current_branch = if let Ok(head) = repo.head() {
             head.shorthand().map(|name| name.to_string())
         } else {
-            None
+            unborn_branch
         };
 
-        // ステータス情報を取得
+        // ステータス情報を取得（core.excludesfileなどの無視設定はgit2が読み込む）
+        let (include_untracked, recurse_untracked) = Self::untracked_files_setting(&repo);
         let mut opts = StatusOptions::new();
-        opts.include_untracked(true)
+        opts.include_untracked(include_untracked)
+            .recurse_untracked_dirs(recurse_untracked)
             .include_ignored(false)
             .renames_head_to_index(false)
-            .renames_index_to_workdir(false);
+            .renames_index_to_workdir(false)
+            .update_index(query.update_index);
 
-        let statuses = repo.statuses(Some(&mut opts)).map_err(|e| {
+        // indexを書き戻せない（別のgitがロック中など）ときは書き戻さずに調べ直す
+        let statuses = match repo.statuses(Some(&mut opts)) {
+            Err(_) if query.update_index => repo.statuses(Some(opts.update_index(false))),
+            result => result,
+        }
+        .map_err(|e| {
             PendectorError::from_git2_error(repo_path_str.clone(), "get status".to_string(), e)
         })?;
-        let has_changes = !statuses.is_empty();
 
-        let changed_files: Vec<String> = statuses
-            .iter()
+        // `dirty_ignore` に一致するファイルは変更として扱わない
+        let dirty_ignore = &query.dirty_ignore;
+        let entries = || {
+            statuses.iter().filter(|entry| {
+                entry
+                    .path()
+                    .is_none_or(|path| !dirty_ignore.is_excluded_at(path, path.ends_with('/')))
+            })
+        };
+        let has_changes = entries().next().is_some();
+
+        // 上限を超える分は一覧にも更新時刻の確認にも含めない
+        let file_limit = query.file_limit.unwrap_or(usize::MAX);
+        let changed_files_truncated = entries().nth(file_limit).is_some();
+        let changed_files: Vec<String> = entries()
+            .take(file_limit)
             .filter_map(|entry| {
                 entry.path().map(|path| {
                     let status = entry.status();
@@ -92,19 +488,255 @@ impl GitStatus {
             })
             .collect();
 
-        // リモート同期状態の確認
-        let (needs_pull, needs_push, remote_branch) = Self::check_remote_sync(&repo)?;
+        let dirty_since = if has_changes {
+            Self::newest_change_time(&repo, entries().take(file_limit))
+        } else {
+            None
+        };
+
+        // リモート同期状態の確認（報告しないブランチにいる場合は同期待ちとしない）
+        let is_ignored = |name: &str| {
+            ignore_branches
+                .iter()
+                .any(|pattern| branch_matches(pattern, name))
+        };
+        let (mut needs_pull, mut needs_push, remote_branch) = match &current_branch {
+            // 参照を1つも読まないので、参照の多いリポジトリでも速い
+            _ if query.skip_remote_check => (false, false, None),
+            // 送るコミットはないので、リモートにコミットがあるかどうかだけを見る
+            Some(branch) if unborn => {
+                let remote_branch = Self::unborn_remote_branch(&repo, branch);
+                let needs_pull =
+                    remote_branch.is_some() && query.unborn_remote == UnbornRemote::Pull;
+                (needs_pull, false, remote_branch)
+            }
+            _ => Self::check_remote_sync(&repo)?,
+        };
+        if current_branch.as_deref().is_some_and(is_ignored) {
+            (needs_pull, needs_push) = (false, false);
+        }
+        let mut branches = if query.skip_remote_check {
+            Vec::new()
+        } else {
+            Self::collect_branch_statuses(&repo)
+        };
+        branches.retain(|branch| !is_ignored(&branch.name));
+        let mut remote_only_branches = if query.skip_remote_check {
+            Vec::new()
+        } else {
+            Self::collect_remote_only_branches(&repo, &branches)
+        };
+        remote_only_branches.retain(|name| {
+            name.split_once('/')
+                .is_none_or(|(_, short_name)| !is_ignored(short_name))
+        });
 
         Ok(RepoStatus {
             has_changes,
             current_branch,
+            unborn,
             changed_files,
+            changed_files_truncated,
             needs_pull,
             needs_push,
             remote_branch,
+            branches,
+            remote_only_branches,
+            dirty_since,
+            fetch_error,
+            operation: in_progress_operation(&repo),
+            remote_id: remote_id(&repo),
+            last_commit_at: Self::head_commit_time(&repo),
         })
     }
 
+    /// HEADを指定したリモートのデフォルトブランチと比較する
+    ///
+    /// デフォルトブランチは `<remote>/HEAD` が指すものを使い、なければ `default_branches`
+    /// （設定の `default_branches`、既定は `main`、`master`）の順に探す。
+    /// リモートやブランチが見つからない場合は `None` を返す。
+    pub fn compare_with_remote<P: AsRef<Path>>(
+        repo_path: P,
+        remote: &str,
+        default_branches: &[String],
+    ) -> PendectorResult<Option<RemoteComparison>> {
+        let repo_path = repo_path.as_ref();
+        let repo = open_repository(repo_path).map_err(|e| {
+            PendectorError::from_git2_error(
+                repo_path.to_string_lossy().to_string(),
+                "open repository".to_string(),
+                e,
+            )
+        })?;
+        let Some(head_oid) = repo.head().ok().and_then(|head| head.target()) else {
+            return Ok(None);
+        };
+
+        for branch in default_branch_candidates(&repo, remote, default_branches) {
+            let remote_branch = format!("{remote}/{branch}");
+            let Some(remote_oid) = repo
+                .find_reference(&format!("refs/remotes/{remote_branch}"))
+                .ok()
+                .and_then(|r| r.target())
+            else {
+                continue;
+            };
+            let (ahead, behind) = repo.graph_ahead_behind(head_oid, remote_oid).map_err(|e| {
+                PendectorError::from_git2_error(
+                    repo_path.to_string_lossy().to_string(),
+                    "compare with remote".to_string(),
+                    e,
+                )
+            })?;
+            return Ok(Some(RemoteComparison {
+                remote_branch,
+                ahead,
+                behind,
+            }));
+        }
+        Ok(None)
+    }
+
+    /// `status.showUntrackedFiles` に従って未追跡ファイルを含めるか・ディレクトリを再帰するかを決める
+    ///
+    /// git2はこの設定を読まないため、`git status` と結果を揃えるためにここで反映する。
+    fn untracked_files_setting(repo: &Git2Repository) -> (bool, bool) {
+        let value = repo
+            .config()
+            .and_then(|config| config.get_string("status.showUntrackedFiles"))
+            .unwrap_or_default();
+        match value.to_ascii_lowercase().as_str() {
+            "no" | "false" | "off" | "0" => (false, false),
+            "all" => (true, true),
+            _ => (true, false),
+        }
+    }
+
+    /// HEADが指すブランチにコミットがなければ、そのブランチ名
+    fn unborn_branch(repo: &Git2Repository) -> Option<String> {
+        match repo.head() {
+            Err(e) if e.code() == git2::ErrorCode::UnbornBranch => {}
+            _ => return None,
+        }
+        let head = repo.find_reference("HEAD").ok()?;
+        let target = head.symbolic_target()?;
+        Some(
+            target
+                .strip_prefix("refs/heads/")
+                .unwrap_or(target)
+                .to_string(),
+        )
+    }
+
+    /// コミットのないブランチに対応するリモートブランチ
+    ///
+    /// 同名の `origin/<branch>` がなければ、リモートのデフォルトブランチ（`origin/HEAD`）を使う。
+    /// 空のリモートをクローンした場合はどちらもないのでNone。
+    fn unborn_remote_branch(repo: &Git2Repository, branch: &str) -> Option<String> {
+        let exists = |name: &str| {
+            repo.find_reference(&format!("refs/remotes/origin/{name}"))
+                .is_ok()
+        };
+        if exists(branch) {
+            return Some(format!("origin/{branch}"));
+        }
+        remote_head_branch(repo, "origin")
+            .filter(|name| exists(name))
+            .map(|name| format!("origin/{name}"))
+    }
+
+    /// HEADのコミット日時（コミットがなければNone）
+    fn head_commit_time(repo: &Git2Repository) -> Option<SystemTime> {
+        let commit = repo.head().ok()?.peel_to_commit().ok()?;
+        let secs = u64::try_from(commit.time().seconds()).ok()?;
+        Some(std::time::UNIX_EPOCH + Duration::from_secs(secs))
+    }
+
+    /// 変更されたファイルのうち最も新しい更新時刻を取得する
+    ///
+    /// 削除のみの場合などファイルから取得できなければindexの更新時刻を使う。
+    fn newest_change_time<'a>(
+        repo: &Git2Repository,
+        entries: impl Iterator<Item = git2::StatusEntry<'a>>,
+    ) -> Option<SystemTime> {
+        let workdir = repo.workdir()?;
+        entries
+            .filter_map(|entry| {
+                let path = workdir.join(entry.path()?);
+                std::fs::symlink_metadata(path)
+                    .and_then(|m| m.modified())
+                    .ok()
+            })
+            .max()
+            .or_else(|| {
+                std::fs::metadata(repo.path().join("index"))
+                    .and_then(|m| m.modified())
+                    .ok()
+            })
+    }
+
+    /// ローカルブランチから追跡されておらず、同名のローカルブランチもないリモートブランチを列挙する
+    fn collect_remote_only_branches(
+        repo: &Git2Repository,
+        local_branches: &[BranchStatus],
+    ) -> Vec<String> {
+        let Ok(branches) = repo.branches(Some(BranchType::Remote)) else {
+            return Vec::new();
+        };
+
+        let mut remote_only: Vec<String> = branches
+            .filter_map(Result::ok)
+            .filter_map(|(branch, _)| branch.name().ok().flatten().map(|n| n.to_string()))
+            .filter(|name| {
+                // origin/HEAD のようなシンボリック参照は除く
+                let Some((_, short_name)) = name.split_once('/') else {
+                    return false;
+                };
+                short_name != "HEAD"
+                    && !local_branches.iter().any(|local| {
+                        local.name == short_name || local.upstream.as_deref() == Some(name)
+                    })
+            })
+            .collect();
+        remote_only.sort();
+        remote_only
+    }
+
+    /// すべてのローカルブランチについて上流ブランチとのahead/behindを数える
+    fn collect_branch_statuses(repo: &Git2Repository) -> Vec<BranchStatus> {
+        let Ok(branches) = repo.branches(Some(BranchType::Local)) else {
+            return Vec::new();
+        };
+
+        let mut statuses: Vec<BranchStatus> = branches
+            .filter_map(Result::ok)
+            .filter_map(|(branch, _)| {
+                let name = branch.name().ok().flatten()?.to_string();
+                let local_oid = branch.get().target()?;
+
+                let upstream = branch.upstream().ok();
+                let upstream_name = upstream
+                    .as_ref()
+                    .and_then(|u| u.name().ok().flatten())
+                    .map(|n| n.to_string());
+                let (ahead, behind) = upstream
+                    .as_ref()
+                    .and_then(|u| u.get().target())
+                    .and_then(|upstream_oid| repo.graph_ahead_behind(local_oid, upstream_oid).ok())
+                    .unwrap_or((0, 0));
+
+                Some(BranchStatus {
+                    name,
+                    upstream: upstream_name,
+                    ahead,
+                    behind,
+                })
+            })
+            .collect();
+        statuses.sort_by(|a, b| a.name.cmp(&b.name));
+        statuses
+    }
+
     /// リモートブランチとの同期状態をチェック
     fn check_remote_sync(repo: &Git2Repository) -> PendectorResult<(bool, bool, Option<String>)> {
         // デフォルト値
@@ -174,45 +806,73 @@ impl GitStatus {
         timeout: Duration,
         show_progress: bool,
     ) -> Vec<Result<(), String>> {
+        let mode = if show_progress {
+            ProgressMode::Bar
+        } else {
+            ProgressMode::None
+        };
+        Self::perform_parallel_fetch_with_reporter(
+            repo_paths,
+            &FetchOptions::new(timeout),
+            &ProgressReporter::new(mode),
+        )
+    }
+
+    /// 進捗レポーターを指定した並列fetch実行
+    pub(crate) fn perform_parallel_fetch_with_reporter<P: AsRef<Path> + Sync>(
+        repo_paths: &[P],
+        options: &FetchOptions,
+        reporter: &ProgressReporter,
+    ) -> Vec<Result<(), String>> {
+        Self::perform_parallel_fetch_with_errors(repo_paths, options, reporter)
+            .into_iter()
+            .map(|result| result.map_err(|e| e.to_string()))
+            .collect()
+    }
+
+    /// 並列fetchを実行し、リポジトリごとに分類済みのエラーを返す
+    pub(crate) fn perform_parallel_fetch_with_errors<P: AsRef<Path> + Sync>(
+        repo_paths: &[P],
+        options: &FetchOptions,
+        reporter: &ProgressReporter,
+    ) -> Vec<PendectorResult<()>> {
         if repo_paths.is_empty() {
             return Vec::new();
         }
 
-        let progress_bar = if show_progress {
-            let pb = ProgressBar::new(repo_paths.len() as u64);
-            pb.set_style(
-                ProgressStyle::default_bar()
-                    .template(
-                        "Fetching repositories [{wide_bar:.cyan/blue}] {pos}/{len} ({elapsed})",
-                    )
-                    .unwrap()
-                    .progress_chars("##-"),
-            );
-            Some(pb)
-        } else {
-            None
-        };
-
-        let counter = Arc::new(AtomicUsize::new(0));
+        let progress = reporter.fetch_progress(repo_paths.len());
+
+        let results: Vec<PendectorResult<()>> = run_with_jobs(options.jobs, || {
+            repo_paths
+                .par_iter()
+                .map(|repo_path| {
+                    let repo_name = repo_name_of(repo_path.as_ref());
+                    progress.start(&repo_name);
+
+                    let on_slow = |elapsed: Duration| {
+                        progress.slow(&repo_name, elapsed);
+                        reporter.emit(&ProgressEvent::FetchSlow {
+                            path: repo_path.as_ref(),
+                            elapsed_secs: elapsed.as_secs(),
+                        });
+                    };
+                    let result = Self::perform_fetch_with_options(repo_path, options, &on_slow);
 
-        let results: Vec<Result<(), String>> = repo_paths
-            .par_iter()
-            .map(|repo_path| {
-                let result =
-                    Self::perform_fetch_with_timeout(repo_path, timeout).map_err(|e| e.to_string());
+                    let error = result.as_ref().err().map(|e| e.to_string());
+                    reporter.emit(&ProgressEvent::RepoFetched {
+                        path: repo_path.as_ref(),
+                        success: result.is_ok(),
+                        error: error.as_deref(),
+                    });
 
-                if let Some(ref pb) = progress_bar {
-                    let current = counter.fetch_add(1, Ordering::SeqCst) + 1;
-                    pb.set_position(current as u64);
-                }
+                    progress.complete(&repo_name, error.as_deref());
 
-                result
-            })
-            .collect();
+                    result
+                })
+                .collect()
+        });
 
-        if let Some(pb) = progress_bar {
-            pb.finish_with_message("Completed");
-        }
+        progress.finish();
 
         results
     }
@@ -222,103 +882,200 @@ impl GitStatus {
         repo_paths: &[P],
         show_progress: bool,
     ) -> Vec<Result<(), String>> {
-        if repo_paths.is_empty() {
-            return Vec::new();
-        }
-
-        let progress_bar = if show_progress {
-            let pb = ProgressBar::new(repo_paths.len() as u64);
-            pb.set_style(
-                ProgressStyle::default_bar()
-                    .template(
-                        "Fetching repositories [{wide_bar:.cyan/blue}] {pos}/{len} ({elapsed})",
-                    )
-                    .unwrap()
-                    .progress_chars("##-"),
-            );
-            Some(pb)
-        } else {
-            None
-        };
-
-        let counter = Arc::new(AtomicUsize::new(0));
-
-        let results: Vec<Result<(), String>> = repo_paths
-            .par_iter()
-            .map(|repo_path| {
-                let result = Self::perform_fetch(repo_path).map_err(|e| e.to_string());
-
-                if let Some(ref pb) = progress_bar {
-                    let current = counter.fetch_add(1, Ordering::SeqCst) + 1;
-                    pb.set_position(current as u64);
-                }
-
-                result
-            })
-            .collect();
-
-        if let Some(pb) = progress_bar {
-            pb.finish_with_message("Completed");
-        }
-
-        results
+        Self::perform_parallel_fetch_with_timeout_and_progress(
+            repo_paths,
+            Duration::from_secs(DEFAULT_FETCH_TIMEOUT_SECS),
+            show_progress,
+        )
     }
 
     /// git fetchを実行してリモートの最新状態を取得（タイムアウト付き）
     fn perform_fetch<P: AsRef<Path>>(repo_path: P) -> PendectorResult<()> {
-        Self::perform_fetch_with_timeout(repo_path, Duration::from_secs(5))
+        Self::perform_fetch_with_options(
+            repo_path,
+            &FetchOptions::new(Duration::from_secs(DEFAULT_FETCH_TIMEOUT_SECS)),
+            &|_| {},
+        )
     }
 
-    /// タイムアウト時間を指定してgit fetchを実行
-    fn perform_fetch_with_timeout<P: AsRef<Path>>(
+    /// fetch設定を指定してgit fetchを実行
+    ///
+    /// `slow_threshold` を超えるごとに `on_slow` を経過時間付きで呼び出す。
+    fn perform_fetch_with_options<P: AsRef<Path>>(
         repo_path: P,
-        timeout: Duration,
+        options: &FetchOptions,
+        on_slow: &dyn Fn(Duration),
     ) -> PendectorResult<()> {
         let repo_path = repo_path.as_ref();
         let repo_path_str = repo_path.to_string_lossy().to_string();
+        if is_interrupted() {
+            return Err(PendectorError::Interrupted);
+        }
+        if options.is_past_deadline() {
+            return Err(PendectorError::BudgetExceeded {
+                repo_path: repo_name_of(repo_path),
+            });
+        }
 
         // タイムアウト付きでgit fetch コマンドを実行
-        let child = Command::new("timeout")
-            .arg(format!("{}s", timeout.as_secs()))
-            .arg("git")
+        let mut command = Command::new("git");
+        command
+            .args(options.git_config_args())
             .args(["fetch", "--all", "--quiet"])
-            .env("GIT_TERMINAL_PROMPT", "0") // ターミナルプロンプトを無効化
-            .env("GIT_ASKPASS", "true") // 認証プロンプトを無効化（常にfalseを返す）
-            .env("SSH_ASKPASS", "true") // SSH認証プロンプトも無効化
             .current_dir(repo_path)
-            .spawn()
-            .map_err(|e| {
-                PendectorError::from_io_error(
-                    std::path::PathBuf::from(repo_path_str.clone()),
-                    format!("spawn git fetch: {e}"),
-                )
-            })?;
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+        for (name, value) in NON_INTERACTIVE_ENV {
+            if !options.passthrough_env.iter().any(|n| n == name) {
+                command.env(name, value);
+            }
+        }
+        command.envs(&options.env);
+        if let Some(git_dir) = detached_git_dir(repo_path) {
+            command
+                .env("GIT_DIR", git_dir)
+                .env("GIT_WORK_TREE", repo_path);
+        }
+
+        // タイムアウト時や中断時にsshなどの子孫プロセスもまとめて終了できるよう独立したプロセスグループで起動する。
+        // ただし端末でのプロンプトを許す場合は、/dev/ttyの読み取りでSIGTTINを受けて止まらないよう
+        // 前面のプロセスグループに残す（この場合、打ち切り時に子孫プロセスが残ることがある）
+        let own_group = cfg!(unix) && !options.allows_prompts();
+        #[cfg(unix)]
+        if own_group {
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+        }
 
-        let output = child.wait_with_output().map_err(|e| {
+        let mut child = command.spawn().map_err(|e| {
             PendectorError::from_io_error(
                 std::path::PathBuf::from(repo_path_str.clone()),
-                format!("wait for git fetch: {e}"),
+                format!("spawn git fetch: {e}"),
             )
         })?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+        // stderrはパイプが詰まらないよう別スレッドで読み切る
+        let stderr_reader = child.stderr.take().map(|mut pipe| {
+            thread::spawn(move || {
+                let mut buf = String::new();
+                let _ = pipe.read_to_string(&mut buf);
+                buf
+            })
+        });
+
+        let started = Instant::now();
+        let mut next_report = options.slow_threshold;
+        let outcome = loop {
+            let finished = child.try_wait().map_err(|e| {
+                PendectorError::from_io_error(
+                    std::path::PathBuf::from(repo_path_str.clone()),
+                    format!("wait for git fetch: {e}"),
+                )
+            })?;
+            if let Some(status) = finished {
+                break FetchOutcome::Exited(status);
+            }
+
+            // 独立したプロセスグループの子には端末のSIGINTが届かないので、中断の要求を受けてこちらで終了させる
+            if is_interrupted() {
+                kill_process_tree(&mut child, own_group);
+                break FetchOutcome::Interrupted;
+            }
+            if options.is_past_deadline() {
+                kill_process_tree(&mut child, own_group);
+                break FetchOutcome::OverBudget;
+            }
+            let elapsed = started.elapsed();
+            if elapsed >= options.timeout {
+                kill_process_tree(&mut child, own_group);
+                break FetchOutcome::TimedOut;
+            }
+            if elapsed >= next_report {
+                if options.skip_slow {
+                    kill_process_tree(&mut child, own_group);
+                    break FetchOutcome::Skipped;
+                }
+                on_slow(elapsed);
+                next_report += options.slow_threshold;
+            }
+
+            thread::sleep(FETCH_POLL_INTERVAL);
+        };
 
-            // fetchエラーを適切なPendectorErrorに変換
-            let mut error =
-                PendectorError::from_fetch_error(repo_path_str, &stderr, output.status.code());
+        // 強制終了した場合は残ったプロセスがパイプを保持している可能性があるので待たない
+        let stderr = match (&outcome, stderr_reader) {
+            (FetchOutcome::Exited(_), Some(handle)) => handle.join().unwrap_or_default(),
+            _ => String::new(),
+        };
 
-            // タイムアウトエラーの場合は実際のタイムアウト値を設定
-            if let PendectorError::TimeoutError { timeout_secs, .. } = &mut error {
-                *timeout_secs = timeout.as_secs();
+        let error = match outcome {
+            FetchOutcome::Interrupted => Some(PendectorError::Interrupted),
+            FetchOutcome::OverBudget => Some(PendectorError::BudgetExceeded {
+                repo_path: repo_name_of(repo_path),
+            }),
+            FetchOutcome::TimedOut => Some(PendectorError::TimeoutError {
+                repo_path: repo_name_of(repo_path),
+                timeout_secs: options.timeout.as_secs(),
+            }),
+            FetchOutcome::Skipped => Some(PendectorError::FetchSkipped {
+                repo_path: repo_name_of(repo_path),
+                elapsed_secs: started.elapsed().as_secs(),
+            }),
+            FetchOutcome::Exited(status) if !status.success() => {
+                // fetchエラーを適切なPendectorErrorに変換
+                Some(PendectorError::from_fetch_error(
+                    repo_path_str,
+                    &stderr,
+                    status.code(),
+                ))
             }
+            FetchOutcome::Exited(_) => None,
+        };
 
-            // fetchエラーは警告として扱い、処理を継続
-            eprintln!("Warning: {error}");
+        match error {
+            Some(error) => Err(error),
+            None => Ok(()),
         }
+    }
+}
+
+/// fetchプロセスの終わり方
+enum FetchOutcome {
+    /// プロセスが自分で終了した
+    Exited(ExitStatus),
+    /// タイムアウトで強制終了した
+    TimedOut,
+    /// 遅いfetchとして打ち切った
+    Skipped,
+    /// 中断の要求を受けて強制終了した
+    Interrupted,
+    /// スキャンの時間の上限を過ぎて強制終了した
+    OverBudget,
+}
 
-        Ok(())
+/// 子プロセスとその子孫（ssh, credential helperなど）を強制終了する
+///
+/// 子孫までまとめて終了できるのは `own_group`（独立したプロセスグループで起動した）場合のみ。
+fn kill_process_tree(child: &mut Child, own_group: bool) {
+    if own_group {
+        // process_group(0) で起動しているのでpidがそのままプロセスグループIDになる
+        #[cfg(unix)]
+        if let Ok(pgid) = libc::pid_t::try_from(child.id()) {
+            unsafe {
+                libc::kill(-pgid, libc::SIGKILL);
+            }
+        }
     }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// エラーメッセージ用にリポジトリのディレクトリ名を取得
+fn repo_name_of(repo_path: &Path) -> String {
+    repo_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| repo_path.display().to_string())
 }
 
 #[cfg(test)]
@@ -362,6 +1119,118 @@ mod tests {
         repo_path
     }
 
+    /// 空のリポジトリにコミットのないブランチ `main` を作り、originを設定する
+    fn create_unborn_repo(temp_dir: &TempDir, remote: &Path) -> std::path::PathBuf {
+        let repo_path = temp_dir.path().join("unborn");
+        for args in [
+            vec!["init", "-q", "-b", "main", "unborn"],
+            vec![
+                "-C",
+                "unborn",
+                "remote",
+                "add",
+                "origin",
+                remote.to_str().unwrap(),
+            ],
+        ] {
+            Command::new("git")
+                .args(args)
+                .current_dir(temp_dir.path())
+                .output()
+                .unwrap();
+        }
+        repo_path
+    }
+
+    #[test]
+    fn test_unborn_branch_with_empty_remote_needs_nothing() {
+        let temp_dir = TempDir::new().unwrap();
+        let remote = temp_dir.path().join("remote.git");
+        Git2Repository::init_bare(&remote).unwrap();
+        let repo_path = create_unborn_repo(&temp_dir, &remote);
+        Command::new("git")
+            .args(["fetch", "-q", "origin"])
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+
+        let status = GitStatus::get_repository_status(&repo_path).unwrap();
+        assert!(status.unborn);
+        assert_eq!(status.current_branch.as_deref(), Some("main"));
+        assert!(status.remote_branch.is_none());
+        assert!(!status.needs_pull);
+        assert!(!status.needs_push);
+    }
+
+    #[test]
+    fn test_unborn_branch_with_remote_commits() {
+        let temp_dir = TempDir::new().unwrap();
+        let remote = create_test_repo(&temp_dir);
+        fs::write(remote.join("README.md"), "# Test").unwrap();
+        for args in [
+            &["checkout", "-q", "-b", "main"][..],
+            &["add", "."],
+            &["commit", "-m", "Initial commit"],
+        ] {
+            Command::new("git")
+                .args(args)
+                .current_dir(&remote)
+                .output()
+                .unwrap();
+        }
+        let repo_path = create_unborn_repo(&temp_dir, &remote);
+        Command::new("git")
+            .args(["fetch", "-q", "origin"])
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+
+        let status = GitStatus::get_repository_status(&repo_path).unwrap();
+        assert!(status.unborn);
+        assert_eq!(status.remote_branch.as_deref(), Some("origin/main"));
+        assert!(status.needs_pull);
+        assert!(!status.needs_push);
+
+        let query = StatusQuery::new().with_unborn_remote(UnbornRemote::Ignore);
+        let status = GitStatus::get_repository_status_with_query(&repo_path, &query).unwrap();
+        assert!(!status.needs_pull);
+    }
+
+    #[test]
+    fn test_skip_remote_check_reports_only_working_tree_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let remote = temp_dir.path().join("remote.git");
+        Git2Repository::init_bare(&remote).unwrap();
+        let repo_path = create_unborn_repo(&temp_dir, &remote);
+        fs::write(repo_path.join("README.md"), "# Test").unwrap();
+        for args in [
+            &["config", "user.name", "Test User"][..],
+            &["config", "user.email", "test@example.com"],
+            &["add", "."],
+            &["commit", "-q", "-m", "Initial commit"],
+            &["push", "-q", "-u", "origin", "main"],
+            &["commit", "-q", "--allow-empty", "-m", "Unpushed"],
+        ] {
+            Command::new("git")
+                .args(args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+        }
+        fs::write(repo_path.join("new.txt"), "new").unwrap();
+
+        let status = GitStatus::get_repository_status(&repo_path).unwrap();
+        assert!(status.needs_push);
+        assert!(!status.branches.is_empty());
+
+        let query = StatusQuery::new().with_skip_remote_check(true);
+        let status = GitStatus::get_repository_status_with_query(&repo_path, &query).unwrap();
+        assert!(status.has_changes);
+        assert!(!status.needs_push);
+        assert!(status.remote_branch.is_none());
+        assert!(status.branches.is_empty());
+    }
+
     #[test]
     fn test_get_status_empty_repo() {
         let temp_dir = TempDir::new().unwrap();
@@ -372,11 +1241,210 @@ mod tests {
 
         let status = result.unwrap();
         assert!(!status.has_changes);
-        assert!(status.current_branch.is_none()); // No commits yet, so no branch
+        // コミットがなくてもHEADが指すブランチ名は分かる
+        assert!(status.unborn);
+        assert!(status.current_branch.is_some());
         assert!(status.changed_files.is_empty());
         assert!(!status.needs_pull);
         assert!(!status.needs_push);
         assert!(status.remote_branch.is_none());
+        assert!(status.dirty_since.is_none());
+    }
+
+    #[test]
+    fn test_remote_sync_detects_ahead_behind_and_diverged() {
+        use crate::test_util::{commit, fetch, push, RemoteFixture};
+
+        let fixture = RemoteFixture::new();
+        let local = fixture.clone_repo("local");
+        let other = fixture.clone_repo("other");
+
+        let status = GitStatus::get_repository_status(&local).unwrap();
+        assert_eq!(status.remote_branch.as_deref(), Some("origin/main"));
+        assert!(!status.needs_pull && !status.needs_push);
+
+        // ローカルだけ進んでいる
+        commit(&local, "local work");
+        let status = GitStatus::get_repository_status(&local).unwrap();
+        assert!(!status.needs_pull && status.needs_push);
+
+        // 別のクローンからpushされ、fetch後は分岐している
+        commit(&other, "other work");
+        push(&other);
+        fetch(&local);
+        let status = GitStatus::get_repository_status(&local).unwrap();
+        assert!(status.needs_pull && status.needs_push);
+
+        // 新しいクローンはリモートと一致し、別クローンの追加pushで遅れる
+        let fresh = fixture.clone_repo("fresh");
+        commit(&other, "more work");
+        push(&other);
+        fetch(&fresh);
+        let status = GitStatus::get_repository_status(&fresh).unwrap();
+        assert!(status.needs_pull && !status.needs_push);
+    }
+
+    #[test]
+    fn test_branch_matches_patterns() {
+        assert!(branch_matches("wip/*", "wip/login"));
+        assert!(branch_matches("wip/*", "wip/2024/login"));
+        assert!(branch_matches("backup-?", "backup-1"));
+        assert!(branch_matches("*-old", "feature-old"));
+        assert!(!branch_matches("wip/*", "main"));
+        assert!(!branch_matches("wip/*", "feature/wip/x"));
+        assert!(!branch_matches("backup-?", "backup-12"));
+    }
+
+    #[test]
+    fn test_ignored_branches_are_left_out_of_sync_reporting() {
+        use crate::test_util::{commit, RemoteFixture};
+
+        let fixture = RemoteFixture::new();
+        let local = fixture.clone_repo("local");
+        Command::new("git")
+            .args(["checkout", "-q", "-b", "wip/experiment"])
+            .current_dir(&local)
+            .output()
+            .unwrap();
+        commit(&local, "experiment");
+
+        let status = GitStatus::get_repository_status(&local).unwrap();
+        assert!(status.branches.iter().any(|b| b.name == "wip/experiment"));
+
+        let status =
+            GitStatus::get_repository_status_ignoring_branches(&local, &["wip/*".to_string()])
+                .unwrap();
+        assert!(!status.needs_push && !status.needs_pull);
+        let names: Vec<&str> = status.branches.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, ["main"]);
+    }
+
+    #[test]
+    fn test_compare_with_remote_counts_behind_upstream() {
+        let temp_dir = TempDir::new().unwrap();
+        use crate::test_util::{git, init_repo};
+
+        let upstream_path = temp_dir.path().join("upstream");
+        init_repo(&upstream_path);
+        git(
+            &upstream_path,
+            &["commit", "-q", "--allow-empty", "-m", "init"],
+        );
+
+        let fork_path = temp_dir.path().join("fork");
+        git(
+            temp_dir.path(),
+            &["clone", "-q", upstream_path.to_str().unwrap(), "fork"],
+        );
+        git(&fork_path, &["remote", "rename", "origin", "upstream"]);
+        for message in ["one", "two", "three"] {
+            git(
+                &upstream_path,
+                &["commit", "-q", "--allow-empty", "-m", message],
+            );
+        }
+        git(&fork_path, &["fetch", "-q", "upstream"]);
+
+        let default_branches: Vec<String> =
+            DEFAULT_BRANCH_NAMES.iter().map(|s| s.to_string()).collect();
+        let comparison = GitStatus::compare_with_remote(&fork_path, "upstream", &default_branches)
+            .unwrap()
+            .unwrap();
+        assert_eq!(comparison.remote_branch, "upstream/main");
+        assert_eq!(comparison.ahead, 0);
+        assert_eq!(comparison.behind, 3);
+
+        assert!(
+            GitStatus::compare_with_remote(&fork_path, "nonexistent", &default_branches)
+                .unwrap()
+                .is_none()
+        );
+
+        // HEADを公開しないリモートでは設定された名前だけを候補にする
+        git(&fork_path, &["remote", "set-head", "upstream", "-d"]);
+        assert!(
+            GitStatus::compare_with_remote(&fork_path, "upstream", &["trunk".to_string()])
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            GitStatus::compare_with_remote(&fork_path, "upstream", &default_branches)
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_get_status_respects_show_untracked_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = create_test_repo(&temp_dir);
+        fs::write(repo_path.join("untracked.txt"), "content").unwrap();
+
+        Command::new("git")
+            .args(["config", "status.showUntrackedFiles", "no"])
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+        let status = GitStatus::get_repository_status(&repo_path).unwrap();
+        assert!(!status.has_changes);
+        assert!(status.changed_files.is_empty());
+
+        Command::new("git")
+            .args(["config", "status.showUntrackedFiles", "normal"])
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+        let status = GitStatus::get_repository_status(&repo_path).unwrap();
+        assert!(status.has_changes);
+    }
+
+    #[test]
+    fn test_get_status_respects_core_excludesfile() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = create_test_repo(&temp_dir);
+        let excludes_file = temp_dir.path().join("global_ignore");
+        fs::write(&excludes_file, "*.swp\n").unwrap();
+        fs::write(repo_path.join("notes.swp"), "swap").unwrap();
+
+        Command::new("git")
+            .args([
+                "config",
+                "core.excludesfile",
+                excludes_file.to_str().unwrap(),
+            ])
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+        let status = GitStatus::get_repository_status(&repo_path).unwrap();
+        assert!(!status.has_changes);
+    }
+
+    #[test]
+    fn test_get_status_dirty_since_uses_newest_changed_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = create_test_repo(&temp_dir);
+
+        let old_file = repo_path.join("old.txt");
+        let new_file = repo_path.join("new.txt");
+        fs::write(&old_file, "old").unwrap();
+        fs::write(&new_file, "new").unwrap();
+        let old_time = SystemTime::now() - Duration::from_secs(10 * 86_400);
+        let new_time = SystemTime::now() - Duration::from_secs(2 * 86_400);
+        fs::File::options()
+            .write(true)
+            .open(&old_file)
+            .unwrap()
+            .set_modified(old_time)
+            .unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&new_file)
+            .unwrap()
+            .set_modified(new_time)
+            .unwrap();
+
+        let status = GitStatus::get_repository_status(&repo_path).unwrap();
+        assert_eq!(status.dirty_since, Some(new_time));
     }
 
     #[test]
@@ -477,6 +1545,84 @@ mod tests {
         assert!(status.remote_branch.is_none());
     }
 
+    #[test]
+    fn test_status_update_index_refreshes_stat_info() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = create_test_repo(&temp_dir);
+        let file = repo_path.join("README.md");
+        fs::write(&file, "# Test").unwrap();
+        for args in [&["add", "."][..], &["commit", "-m", "Initial commit"]] {
+            Command::new("git")
+                .args(args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+        }
+        // 内容は同じまま更新時刻だけを変え、indexの情報を古くする
+        let touched = SystemTime::now() + Duration::from_secs(3600);
+        fs::File::options()
+            .write(true)
+            .open(&file)
+            .unwrap()
+            .set_modified(touched)
+            .unwrap();
+        let index = repo_path.join(".git").join("index");
+        let before = fs::read(&index).unwrap();
+
+        let status = GitStatus::get_repository_status(&repo_path).unwrap();
+        assert!(!status.has_changes);
+        assert_eq!(fs::read(&index).unwrap(), before);
+
+        let query = StatusQuery::new().with_update_index(true);
+        let status = GitStatus::get_repository_status_with_query(&repo_path, &query).unwrap();
+        assert!(!status.has_changes);
+        assert_ne!(fs::read(&index).unwrap(), before);
+    }
+
+    #[test]
+    fn test_dirty_ignore_skips_matching_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = temp_dir.path();
+        Git2Repository::init(repo_path).unwrap();
+        fs::write(repo_path.join(".DS_Store"), "x").unwrap();
+        fs::write(repo_path.join("main.rs.orig"), "x").unwrap();
+
+        let dirty_ignore =
+            ExcludeFilter::from_patterns(&["*.orig".to_string(), ".DS_Store".to_string()]).unwrap();
+        let query = StatusQuery::new().with_dirty_ignore(dirty_ignore);
+        let status = GitStatus::get_repository_status_with_query(repo_path, &query).unwrap();
+        assert!(!status.has_changes);
+        assert!(status.changed_files.is_empty());
+
+        fs::write(repo_path.join("main.rs"), "fn main() {}").unwrap();
+        let status = GitStatus::get_repository_status_with_query(repo_path, &query).unwrap();
+        assert!(status.has_changes);
+        assert_eq!(status.changed_files.len(), 1);
+        assert!(status.changed_files[0].ends_with("main.rs"));
+    }
+
+    #[test]
+    fn test_status_file_limit_truncates_changed_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = temp_dir.path();
+        Git2Repository::init(repo_path).unwrap();
+        for i in 0..5 {
+            fs::write(repo_path.join(format!("dump{i}.csv")), "x").unwrap();
+        }
+
+        let query = StatusQuery::new().with_file_limit(Some(3));
+        let status = GitStatus::get_repository_status_with_query(repo_path, &query).unwrap();
+        assert!(status.has_changes);
+        assert!(status.changed_files_truncated);
+        assert_eq!(status.changed_files.len(), 3);
+
+        // 上限ちょうどなら打ち切らない
+        let query = StatusQuery::new().with_file_limit(Some(5));
+        let status = GitStatus::get_repository_status_with_query(repo_path, &query).unwrap();
+        assert!(!status.changed_files_truncated);
+        assert_eq!(status.changed_files.len(), 5);
+    }
+
     #[test]
     fn test_get_status_invalid_repo() {
         let temp_dir = TempDir::new().unwrap();
@@ -528,6 +1674,67 @@ mod tests {
         assert!(status.remote_branch.is_none());
     }
 
+    #[test]
+    fn test_get_status_lists_branches_with_ahead_behind() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = create_test_repo(&temp_dir);
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .args(args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+        };
+
+        fs::write(repo_path.join("README.md"), "# Test").unwrap();
+        git(&["add", "README.md"]);
+        git(&["commit", "-q", "-m", "Initial commit"]);
+        git(&["branch", "-M", "main"]);
+        let remote_path = temp_dir.path().join("remote.git");
+        git(&["clone", "-q", "--bare", ".", remote_path.to_str().unwrap()]);
+        git(&["remote", "add", "origin", remote_path.to_str().unwrap()]);
+        git(&["fetch", "-q", "origin"]);
+        git(&["branch", "-q", "-u", "origin/main", "main"]);
+        git(&["branch", "side"]);
+        git(&["push", "-q", "origin", "side:colleague", "side:side"]);
+        git(&["fetch", "-q", "origin"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "second"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "third"]);
+
+        let status = GitStatus::get_repository_status(&repo_path).unwrap();
+        assert_eq!(
+            status.branches,
+            vec![
+                BranchStatus {
+                    name: "main".to_string(),
+                    upstream: Some("origin/main".to_string()),
+                    ahead: 2,
+                    behind: 0,
+                },
+                BranchStatus {
+                    name: "side".to_string(),
+                    upstream: None,
+                    ahead: 0,
+                    behind: 0,
+                },
+            ]
+        );
+        // sideは同名のローカルブランチがあるので含めない
+        assert_eq!(
+            status.remote_only_branches,
+            vec!["origin/colleague".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_run_with_jobs_limits_parallelism() {
+        assert_eq!(run_with_jobs(Some(2), rayon::current_num_threads), 2);
+        assert_eq!(
+            run_with_jobs(Some(0), rayon::current_num_threads),
+            rayon::current_num_threads()
+        );
+    }
+
     #[test]
     fn test_perform_parallel_fetch() {
         let temp_dir = TempDir::new().unwrap();
@@ -591,4 +1798,184 @@ mod tests {
         assert!(!status.needs_push);
         assert!(status.remote_branch.is_none());
     }
+
+    #[test]
+    fn test_fetch_skip_slow_abandons_hanging_fetch() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = create_test_repo(&temp_dir);
+
+        // ext:: トランスポートでfetchが30秒間ハングするリモートを設定
+        Command::new("git")
+            .args(["config", "protocol.ext.allow", "always"])
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+        Command::new("git")
+            .args(["remote", "add", "origin", "ext::sleep 30"])
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+
+        let options = FetchOptions::new(Duration::from_secs(30))
+            .with_slow_threshold(Duration::from_millis(200))
+            .with_skip_slow(true);
+
+        let started = Instant::now();
+        let result = GitStatus::perform_fetch_with_options(&repo_path, &options, &|_| {});
+        assert!(matches!(result, Err(PendectorError::FetchSkipped { .. })));
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_fetch_reports_slow_fetch() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = create_test_repo(&temp_dir);
+
+        Command::new("git")
+            .args(["config", "protocol.ext.allow", "always"])
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+        Command::new("git")
+            .args(["remote", "add", "origin", "ext::sleep 1"])
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+
+        let options = FetchOptions::new(Duration::from_secs(30))
+            .with_slow_threshold(Duration::from_millis(300));
+
+        let reports = std::sync::Mutex::new(Vec::new());
+        let on_slow = |elapsed: Duration| reports.lock().unwrap().push(elapsed);
+        // sleepはgitのプロトコルを話さないので、終了後のfetchは失敗する
+        let result = GitStatus::perform_fetch_with_options(&repo_path, &options, &on_slow);
+        assert!(result.is_err());
+
+        let reports = reports.into_inner().unwrap();
+        assert!(!reports.is_empty());
+        assert!(reports[0] >= Duration::from_millis(300));
+    }
+
+    #[test]
+    fn test_fetch_timeout_kills_hanging_fetch() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = create_test_repo(&temp_dir);
+
+        Command::new("git")
+            .args(["config", "protocol.ext.allow", "always"])
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+        Command::new("git")
+            .args(["remote", "add", "origin", "ext::sleep 30"])
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+
+        let options = FetchOptions::new(Duration::from_millis(300));
+
+        let started = Instant::now();
+        let result = GitStatus::perform_fetch_with_options(&repo_path, &options, &|_| {});
+        let error = result.unwrap_err();
+        assert!(matches!(error, PendectorError::TimeoutError { .. }));
+        assert_eq!(
+            ErrorReport::from(&error).code,
+            crate::error::ErrorCode::Timeout
+        );
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_fetch_stops_at_deadline() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = create_test_repo(&temp_dir);
+
+        Command::new("git")
+            .args(["config", "protocol.ext.allow", "always"])
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+        Command::new("git")
+            .args(["remote", "add", "origin", "ext::sleep 30"])
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+
+        // タイムアウトより先に探索時間の上限が来たら、その時点で打ち切る
+        let started = Instant::now();
+        let options = FetchOptions::new(Duration::from_secs(60))
+            .with_deadline(Some(started + Duration::from_millis(300)));
+        let error =
+            GitStatus::perform_fetch_with_options(&repo_path, &options, &|_| {}).unwrap_err();
+        assert!(matches!(error, PendectorError::BudgetExceeded { .. }));
+        assert!(started.elapsed() < Duration::from_secs(10));
+
+        // 上限を過ぎていれば始めない
+        let options = FetchOptions::new(Duration::from_secs(60)).with_deadline(Some(started));
+        let error =
+            GitStatus::perform_fetch_with_options(&repo_path, &options, &|_| {}).unwrap_err();
+        assert!(matches!(error, PendectorError::BudgetExceeded { .. }));
+    }
+
+    #[test]
+    fn test_fetch_applies_configured_env() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = create_test_repo(&temp_dir);
+
+        // 自分自身をリモートにすれば、環境変数の設定がなければfetchは成功する
+        Command::new("git")
+            .args(["remote", "add", "origin", "."])
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+
+        let options = FetchOptions::new(Duration::from_secs(30));
+        assert!(GitStatus::perform_fetch_with_options(&repo_path, &options, &|_| {}).is_ok());
+
+        let missing_dir = temp_dir.path().join("missing");
+        let options = options.with_env(BTreeMap::from([(
+            "GIT_DIR".to_string(),
+            missing_dir.to_string_lossy().to_string(),
+        )]));
+        assert!(GitStatus::perform_fetch_with_options(&repo_path, &options, &|_| {}).is_err());
+    }
+
+    #[test]
+    fn test_fetch_allows_prompts_only_when_prompt_env_is_kept() {
+        let options = FetchOptions::new(Duration::from_secs(5));
+        assert!(!options.allows_prompts());
+        assert!(!options
+            .clone()
+            .with_passthrough_env(vec!["SSH_AUTH_SOCK".to_string()])
+            .allows_prompts());
+        assert!(options
+            .clone()
+            .with_passthrough_env(vec!["GIT_TERMINAL_PROMPT".to_string()])
+            .allows_prompts());
+        assert!(options
+            .with_env(BTreeMap::from([(
+                "GIT_ASKPASS".to_string(),
+                "/usr/libexec/askpass".to_string()
+            )]))
+            .allows_prompts());
+    }
+
+    #[test]
+    fn test_fetch_proxy_config_args() {
+        let options = FetchOptions::new(Duration::from_secs(5));
+        assert!(options.git_config_args().is_empty());
+
+        let options = options
+            .with_proxy(Some("http://proxy.example.com:8080".to_string()))
+            .with_ssh_proxy_command(Some("nc -X connect -x proxy:8080 %h %p".to_string()));
+        assert_eq!(
+            options.git_config_args(),
+            vec![
+                "-c",
+                "http.proxy=http://proxy.example.com:8080",
+                "-c",
+                "core.sshCommand=ssh -o 'ProxyCommand=nc -X connect -x proxy:8080 %h %p'",
+            ]
+        );
+    }
 }
@@ -0,0 +1,20 @@
+//! SIGINT/SIGTERMによる中断の要求
+//!
+//! シグナルハンドラはフラグを立てるだけで、走査や一括操作のループが [`is_interrupted`] を確かめて
+//! 後始末（fetchの子プロセスの終了、ロックの解放など）をしてから終了コード4で終わる。
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// 中断を要求する（すでに要求されていればtrueを返す）
+///
+/// シグナルハンドラから呼ぶので、アトミック変数の操作しか行わない。
+pub fn request_interrupt() -> bool {
+    INTERRUPTED.swap(true, Ordering::SeqCst)
+}
+
+/// 中断が要求されたか
+pub fn is_interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
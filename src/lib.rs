@@ -1,11 +1,35 @@
-pub mod cli;
+//! ローカルのGitリポジトリを探索し、未コミット・未プッシュの変更を検出するライブラリ
+//!
+//! クレートルートから再エクスポートされる型（[`RepoScanner`], [`ScanOptions`],
+//! [`Repository`], [`GitStatus`], [`RepoStatus`], [`PendectorError`]）と、CLIと同じ経路で
+//! スキャンを実行する [`run`]・[`RunOptions`]・[`ScanReport`] を公開APIとし、
+//! セマンティックバージョニングに従って互換性を維持する。
+
+pub(crate) mod cli;
 pub mod config;
 pub mod core;
 pub mod error;
 pub mod exclude;
 pub mod git;
+pub(crate) mod interrupt;
+pub(crate) mod lock;
 pub mod notify;
+pub(crate) mod ops;
 pub mod output;
+pub(crate) mod path_style;
+pub(crate) mod progress;
+pub mod run;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+pub(crate) mod time;
 
 pub use core::*;
-pub use error::{PendectorError, PendectorResult};
+pub use error::{ErrorCode, ErrorReport, PendectorError, PendectorResult};
+pub use git::{
+    BranchStatus, GitStatus, InProgressOperation, LockFile, RemoteComparison, RepoStatus,
+};
+pub use run::{run, RunError, RunOptions, ScanReport};
+
+/// `pendector` コマンドの入口（バイナリから呼ぶためのもので、公開APIには含めない）
+#[doc(hidden)]
+pub use cli::main as cli_main;
@@ -0,0 +1,87 @@
+use crate::error::{PendectorError, PendectorResult};
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// 多重起動を防ぐためのアドバイザリロック
+///
+/// ロックはこの値がドロップされるか、プロセスが終了した時点で解放される。
+#[derive(Debug)]
+pub struct RunLock {
+    _file: File,
+}
+
+impl RunLock {
+    /// デフォルトの場所（ランタイムディレクトリ）でロックを取得する
+    pub fn acquire() -> PendectorResult<Self> {
+        Self::acquire_at(default_lock_path())
+    }
+
+    /// 指定したパスでロックを取得する
+    pub fn acquire_at(path: PathBuf) -> PendectorResult<Self> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .map_err(|e| {
+                PendectorError::from_io_error(path.clone(), format!("open lock file: {e}"))
+            })?;
+
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                return Err(PendectorError::AlreadyRunning { lock_path: path });
+            }
+            Err(TryLockError::Error(e)) => {
+                return Err(PendectorError::from_io_error(
+                    path,
+                    format!("lock file: {e}"),
+                ));
+            }
+        }
+
+        // 調査用に保持しているプロセスのPIDを書き込む（失敗してもロック自体は有効）
+        let _ = file.set_len(0);
+        let _ = writeln!(file, "{}", std::process::id());
+
+        Ok(Self { _file: file })
+    }
+}
+
+/// ロックファイルのデフォルトパス
+pub fn default_lock_path() -> PathBuf {
+    dirs::runtime_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("pendector.lock")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_acquire_twice_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let lock_path = temp_dir.path().join("pendector.lock");
+
+        let _lock = RunLock::acquire_at(lock_path.clone()).unwrap();
+        let pid = std::fs::read_to_string(&lock_path).unwrap();
+        assert_eq!(pid.trim(), std::process::id().to_string());
+
+        let error = RunLock::acquire_at(lock_path.clone()).unwrap_err();
+        assert_eq!(error.code(), ErrorCode::AlreadyRunning);
+        assert!(error.to_string().contains("another pendector is running"));
+    }
+
+    #[test]
+    fn test_lock_released_on_drop() {
+        let temp_dir = TempDir::new().unwrap();
+        let lock_path = temp_dir.path().join("pendector.lock");
+
+        drop(RunLock::acquire_at(lock_path.clone()).unwrap());
+        assert!(RunLock::acquire_at(lock_path).is_ok());
+    }
+}
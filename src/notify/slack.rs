@@ -35,7 +35,7 @@ impl SlackNotifier {
 
         let changed_repos: Vec<&Repository> = repositories
             .iter()
-            .filter(|r| r.has_changes || r.needs_push || r.needs_pull)
+            .filter(|r| r.needs_attention())
             .collect();
 
         if changed_repos.is_empty() {
@@ -56,15 +56,16 @@ impl SlackNotifier {
         };
 
         for repo in display_repos {
-            let branch = repo.current_branch.as_deref().unwrap_or("unknown");
-            let file_count = repo.changed_files.len();
-            let files_label = if file_count == 1 {
+            let branch = repo.branch_label();
+            let files_label = if repo.changed_files.len() == 1 && !repo.changed_files_truncated {
                 "1 changed file".to_string()
             } else {
-                format!("{file_count} changed files")
+                format!("{} changed files", repo.changed_files_count())
             };
 
-            let remote_status = if repo.needs_push && repo.needs_pull {
+            let remote_status = if repo.status_error.is_some() {
+                " [status unavailable]"
+            } else if repo.needs_push && repo.needs_pull {
                 " [needs push and pull]"
             } else if repo.needs_push {
                 " [needs push]"
@@ -74,9 +75,13 @@ impl SlackNotifier {
                 ""
             };
 
+            // フォージのページがわかればリポジトリ名をリンクにする
+            let name = match repo.web_url {
+                Some(ref web_url) => format!("<{web_url}|{}>", repo.name),
+                None => repo.name.clone(),
+            };
             lines.push(format!(
-                "\u{2022} *{}* (`{branch}`) - {files_label}{remote_status}",
-                repo.name
+                "\u{2022} *{name}* (`{branch}`) - {files_label}{remote_status}"
             ));
         }
 
@@ -128,16 +133,13 @@ mod tests {
         needs_push: bool,
         needs_pull: bool,
     ) -> Repository {
-        Repository {
-            path: PathBuf::from(format!("/path/to/{name}")),
-            name: name.to_string(),
-            has_changes,
-            current_branch: Some(branch.to_string()),
-            changed_files: changed_files.into_iter().map(|s| s.to_string()).collect(),
-            needs_pull,
-            needs_push,
-            remote_branch: Some(format!("origin/{branch}")),
-        }
+        Repository::new(PathBuf::from(format!("/path/to/{name}")))
+            .with_git_info(
+                has_changes,
+                Some(branch.to_string()),
+                changed_files.into_iter().map(|s| s.to_string()).collect(),
+            )
+            .with_remote_info(needs_pull, needs_push, Some(format!("origin/{branch}")))
     }
 
     #[test]
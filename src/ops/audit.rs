@@ -0,0 +1,158 @@
+use crate::core::Storage;
+use crate::error::PendectorResult;
+use crate::time::format_utc;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// 操作の結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditResult {
+    /// 操作が成功した
+    Ok,
+    /// 操作が失敗した
+    Failed,
+    /// 操作を行わなかった（bisectの途中など）
+    Skipped,
+}
+
+/// 監査ログの1行（JSON Lines形式）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuditRecord {
+    /// 操作した時刻（UTCのRFC 3339形式）
+    pub timestamp: String,
+    /// 操作したリポジトリのパス
+    pub repo: PathBuf,
+    /// 操作の種類（`stash`、`wip`、`exec` など）
+    pub action: String,
+    pub result: AuditResult,
+    /// 結果の説明（エラーメッセージなど）
+    pub message: String,
+}
+
+impl AuditRecord {
+    pub fn new(
+        repo: &Path,
+        action: impl Into<String>,
+        result: AuditResult,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            timestamp: format_utc(SystemTime::now()),
+            repo: repo.to_path_buf(),
+            action: action.into(),
+            result,
+            message: message.into(),
+        }
+    }
+}
+
+/// 監査ログのファイル名
+pub const AUDIT_LOG_FILE: &str = "audit.jsonl";
+
+/// 監査ログの記録のスキーマのバージョン
+pub const AUDIT_SCHEMA_VERSION: u32 = 1;
+
+/// リポジトリに対して行った操作を追記していく監査ログ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditLog {
+    target: Option<(Storage, String)>,
+}
+
+impl AuditLog {
+    /// 保存領域の `audit.jsonl` に記録する
+    pub fn in_storage(storage: Storage) -> Self {
+        Self {
+            target: Some((storage, AUDIT_LOG_FILE.to_string())),
+        }
+    }
+
+    /// 指定したファイルに記録する
+    pub fn at(path: PathBuf) -> Self {
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let name = path.file_name().map_or_else(
+            || AUDIT_LOG_FILE.to_string(),
+            |n| n.to_string_lossy().into_owned(),
+        );
+        Self {
+            target: Some((Storage::new(dir), name)),
+        }
+    }
+
+    /// 何も記録しない
+    pub fn disabled() -> Self {
+        Self { target: None }
+    }
+
+    /// 記録先のファイル（無効ならNone）
+    #[cfg(test)]
+    pub fn path(&self) -> Option<PathBuf> {
+        self.target
+            .as_ref()
+            .map(|(storage, name)| storage.path(name))
+    }
+
+    /// 1件の記録を追記する
+    pub fn append(&self, record: &AuditRecord) -> PendectorResult<()> {
+        match &self.target {
+            Some((storage, name)) => storage.append(name, AUDIT_SCHEMA_VERSION, record),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_append_writes_json_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("nested").join("audit.jsonl");
+        let log = AuditLog::at(path.clone());
+
+        let repo = Path::new("/src/app");
+        log.append(&AuditRecord::new(
+            repo,
+            "stash",
+            AuditResult::Ok,
+            "Stashed app",
+        ))
+        .unwrap();
+        log.append(&AuditRecord::new(
+            repo,
+            "stash",
+            AuditResult::Failed,
+            "boom",
+        ))
+        .unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let records: Vec<serde_json::Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["version"], AUDIT_SCHEMA_VERSION);
+        assert_eq!(records[0]["repo"], "/src/app");
+        assert_eq!(records[0]["action"], "stash");
+        assert_eq!(records[0]["result"], "ok");
+        assert_eq!(records[1]["result"], "failed");
+        assert!(records[1]["timestamp"].as_str().unwrap().ends_with('Z'));
+    }
+
+    #[test]
+    fn test_disabled_log_writes_nothing() {
+        let log = AuditLog::disabled();
+        assert!(log.path().is_none());
+        log.append(&AuditRecord::new(
+            Path::new("/src/app"),
+            "exec",
+            AuditResult::Ok,
+            "",
+        ))
+        .unwrap();
+    }
+}
@@ -0,0 +1,207 @@
+use crate::error::{PendectorError, PendectorResult};
+use crate::git::open_repository;
+use crate::git::status::default_branch_candidates;
+use git2::{BranchType, Repository as GitRepository};
+use std::fmt;
+use std::path::Path;
+
+/// 削除してよいと判断したローカルブランチ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaleBranch {
+    pub name: String,
+    pub reason: StaleReason,
+}
+
+/// ブランチを不要と判断した理由
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StaleReason {
+    /// デフォルトブランチにマージ済み
+    Merged { into: String },
+    /// 上流ブランチがリモートから削除されている
+    UpstreamGone { upstream: String },
+}
+
+impl fmt::Display for StaleReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StaleReason::Merged { into } => write!(f, "merged into {into}"),
+            StaleReason::UpstreamGone { upstream } => write!(f, "upstream {upstream} is gone"),
+        }
+    }
+}
+
+/// デフォルトブランチにマージ済み、または上流が消えたローカルブランチを列挙する
+///
+/// 現在チェックアウトしているブランチとデフォルトブランチ自体は対象外。
+/// origin/HEADが取得できない場合は `default_branches` の名前を順に試す。
+pub fn find_stale_branches(
+    repo_path: &Path,
+    default_branches: &[String],
+) -> PendectorResult<Vec<StaleBranch>> {
+    let repo = open(repo_path)?;
+    let current = repo
+        .head()
+        .ok()
+        .filter(|head| head.is_branch())
+        .and_then(|head| head.shorthand().map(|s| s.to_string()));
+    let default = default_branch(&repo, default_branches);
+
+    let branches = repo
+        .branches(Some(BranchType::Local))
+        .map_err(|e| git_error(repo_path, e))?;
+
+    let mut stale = Vec::new();
+    for (branch, _) in branches.filter_map(Result::ok) {
+        let Some(name) = branch.name().ok().flatten().map(|n| n.to_string()) else {
+            continue;
+        };
+        if current.as_deref() == Some(name.as_str())
+            || default.as_ref().map(|(d, _)| d) == Some(&name)
+        {
+            continue;
+        }
+        let Some(tip) = branch.get().target() else {
+            continue;
+        };
+
+        if let Some((default_name, default_tip)) = &default {
+            let merged =
+                tip == *default_tip || repo.graph_descendant_of(*default_tip, tip).unwrap_or(false);
+            if merged {
+                stale.push(StaleBranch {
+                    name,
+                    reason: StaleReason::Merged {
+                        into: default_name.clone(),
+                    },
+                });
+                continue;
+            }
+        }
+
+        // 上流の設定はあるが参照が存在しない
+        let Some(refname) = branch.get().name() else {
+            continue;
+        };
+        if let Ok(upstream_ref) = repo.branch_upstream_name(refname) {
+            let upstream_ref = upstream_ref.as_str().unwrap_or_default().to_string();
+            if repo.find_reference(&upstream_ref).is_err() {
+                let upstream = upstream_ref
+                    .strip_prefix("refs/remotes/")
+                    .unwrap_or(&upstream_ref)
+                    .to_string();
+                stale.push(StaleBranch {
+                    name,
+                    reason: StaleReason::UpstreamGone { upstream },
+                });
+            }
+        }
+    }
+
+    stale.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(stale)
+}
+
+/// ローカルブランチを削除する
+pub fn delete_branch(repo_path: &Path, name: &str) -> PendectorResult<()> {
+    let repo = open(repo_path)?;
+    repo.find_branch(name, BranchType::Local)
+        .and_then(|mut branch| branch.delete())
+        .map_err(|e| git_error(repo_path, e))
+}
+
+/// デフォルトブランチ名とその先端コミット
+///
+/// `origin/HEAD` が指すブランチを優先し、なければ設定の `default_branches` の順に探す。
+fn default_branch(
+    repo: &GitRepository,
+    default_branches: &[String],
+) -> Option<(String, git2::Oid)> {
+    for name in default_branch_candidates(repo, "origin", default_branches) {
+        // ローカルブランチがなければリモート追跡ブランチと比較する
+        let tip = repo
+            .find_branch(&name, BranchType::Local)
+            .or_else(|_| repo.find_branch(&format!("origin/{name}"), BranchType::Remote))
+            .ok()
+            .and_then(|b| b.get().target());
+        if let Some(tip) = tip {
+            return Some((name, tip));
+        }
+    }
+    None
+}
+
+fn open(repo_path: &Path) -> PendectorResult<GitRepository> {
+    open_repository(repo_path).map_err(|e| git_error(repo_path, e))
+}
+
+fn git_error(repo_path: &Path, error: git2::Error) -> PendectorError {
+    PendectorError::from_git2_error(
+        repo_path.display().to_string(),
+        "branches".to_string(),
+        error,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{git, init_repo};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_find_and_delete_stale_branches() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("repo");
+        init_repo(&path);
+        git(&path, &["commit", "-q", "--allow-empty", "-m", "init"]);
+
+        // マージ済みのブランチ
+        git(&path, &["branch", "merged"]);
+        // 上流が消えたブランチ（未マージのコミットあり）
+        git(&path, &["checkout", "-q", "-b", "gone"]);
+        git(&path, &["commit", "-q", "--allow-empty", "-m", "gone work"]);
+        git(&path, &["config", "branch.gone.remote", "origin"]);
+        git(&path, &["config", "branch.gone.merge", "refs/heads/gone"]);
+        // 未マージで上流もないブランチは対象外
+        git(&path, &["checkout", "-q", "-b", "active", "main"]);
+        git(
+            &path,
+            &["commit", "-q", "--allow-empty", "-m", "active work"],
+        );
+        git(&path, &["checkout", "-q", "main"]);
+        git(
+            &path,
+            &["remote", "add", "origin", "/nonexistent/remote.git"],
+        );
+
+        let default_branches = vec!["main".to_string()];
+        let stale = find_stale_branches(&path, &default_branches).unwrap();
+        assert_eq!(
+            stale,
+            vec![
+                StaleBranch {
+                    name: "gone".to_string(),
+                    reason: StaleReason::UpstreamGone {
+                        upstream: "origin/gone".to_string()
+                    },
+                },
+                StaleBranch {
+                    name: "merged".to_string(),
+                    reason: StaleReason::Merged {
+                        into: "main".to_string()
+                    },
+                },
+            ]
+        );
+        assert_eq!(stale[0].reason.to_string(), "upstream origin/gone is gone");
+        assert_eq!(stale[1].reason.to_string(), "merged into main");
+
+        delete_branch(&path, "merged").unwrap();
+        let names: Vec<String> = find_stale_branches(&path, &default_branches)
+            .unwrap()
+            .into_iter()
+            .map(|b| b.name)
+            .collect();
+        assert_eq!(names, vec!["gone".to_string()]);
+    }
+}
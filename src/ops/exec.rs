@@ -0,0 +1,223 @@
+use crate::core::Repository;
+use crate::error::{PendectorError, PendectorResult};
+use crate::git::status::run_with_jobs;
+use crate::interrupt::is_interrupted;
+use crate::output::PrefixedLineWriter;
+use rayon::prelude::*;
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Command, ExitStatus, Stdio};
+
+/// 並列に実行したコマンドの出力の表示方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecOutput {
+    /// リポジトリごとに出力をまとめ、見出しを付けて一度に表示する
+    Buffered,
+    /// 出力を1行ずつ `[repo] line` の形で表示する
+    Stream,
+}
+
+/// 1つのリポジトリでのコマンドの実行結果
+#[derive(Debug)]
+pub struct ExecResult {
+    pub name: String,
+    pub status: PendectorResult<ExitStatus>,
+}
+
+impl ExecResult {
+    /// コマンドが起動でき、終了コード0で終わったか
+    pub fn success(&self) -> bool {
+        self.status.as_ref().is_ok_and(|status| status.success())
+    }
+}
+
+/// 各リポジトリでコマンドを並列に実行し、出力が混ざらないように表示する（`command` が空なら何もしない）
+pub fn exec_in_repositories(
+    repositories: &[Repository],
+    command: &[String],
+    output: ExecOutput,
+    jobs: Option<usize>,
+    stdout: &PrefixedLineWriter,
+    stderr: &PrefixedLineWriter,
+) -> Vec<ExecResult> {
+    if command.is_empty() {
+        return Vec::new();
+    }
+    run_with_jobs(jobs, || {
+        repositories
+            .par_iter()
+            .map(|repo| ExecResult {
+                name: repo.name.clone(),
+                status: match output {
+                    // 中断が要求されたらまだ始めていないリポジトリでは実行しない
+                    _ if is_interrupted() => Err(PendectorError::Interrupted),
+                    ExecOutput::Buffered => exec_buffered(repo, command, stdout, stderr),
+                    ExecOutput::Stream => exec_streaming(repo, command, stdout, stderr),
+                },
+            })
+            .collect()
+    })
+}
+
+/// リポジトリの作業ツリーでコマンドを実行するための設定（並列実行なので標準入力は渡さない）
+fn repo_command(repo: &Repository, command: &[String]) -> Command {
+    let mut cmd = Command::new(&command[0]);
+    cmd.args(&command[1..])
+        .current_dir(&repo.path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    cmd
+}
+
+fn spawn_error(repo: &Repository, command: &[String], error: std::io::Error) -> PendectorError {
+    PendectorError::from_io_error(
+        repo.path.clone(),
+        format!("failed to run '{}': {error}", command.join(" ")),
+    )
+}
+
+/// 出力をすべて受け取ってから、見出しと一緒にまとめて書き出す
+fn exec_buffered(
+    repo: &Repository,
+    command: &[String],
+    stdout: &PrefixedLineWriter,
+    stderr: &PrefixedLineWriter,
+) -> PendectorResult<ExitStatus> {
+    let captured = repo_command(repo, command)
+        .output()
+        .map_err(|e| spawn_error(repo, command, e))?;
+
+    // 見出しと出力を1つのまとまりにして、別のリポジトリの出力が割り込まないようにする
+    let mut block = format!("== {} ({}) ==\n", repo.name, repo.path.display()).into_bytes();
+    block.extend_from_slice(&captured.stdout);
+    stdout.write_block(block);
+    stderr.write_block(captured.stderr);
+    Ok(captured.status)
+}
+
+/// 出力を1行ずつリポジトリ名を付けて書き出す
+fn exec_streaming(
+    repo: &Repository,
+    command: &[String],
+    stdout: &PrefixedLineWriter,
+    stderr: &PrefixedLineWriter,
+) -> PendectorResult<ExitStatus> {
+    let mut child = repo_command(repo, command)
+        .spawn()
+        .map_err(|e| spawn_error(repo, command, e))?;
+    let child_stdout = child.stdout.take();
+    let child_stderr = child.stderr.take();
+
+    std::thread::scope(|scope| {
+        if let Some(pipe) = child_stderr {
+            scope.spawn(|| prefix_lines(&repo.name, pipe, stderr));
+        }
+        if let Some(pipe) = child_stdout {
+            prefix_lines(&repo.name, pipe, stdout);
+        }
+    });
+
+    child.wait().map_err(|e| spawn_error(repo, command, e))
+}
+
+/// 読み取った行ごとに `[name] ` を付けて書き出す
+fn prefix_lines(name: &str, pipe: impl Read, writer: &PrefixedLineWriter) {
+    for line in BufReader::new(pipe).split(b'\n').map_while(Result::ok) {
+        writer.write_line(name, &line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{self, Write};
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+    use tempfile::TempDir;
+
+    /// 書き込みスレッドの出力を受け取る共有バッファ
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    fn repositories(base: &Path, names: &[&str]) -> Vec<Repository> {
+        names
+            .iter()
+            .map(|name| {
+                let path = base.join(name);
+                std::fs::create_dir_all(&path).unwrap();
+                std::fs::write(path.join("file.txt"), format!("{name}\nsecond\n")).unwrap();
+                Repository::new(path)
+            })
+            .collect()
+    }
+
+    fn exec(
+        repos: &[Repository],
+        command: &[&str],
+        output: ExecOutput,
+    ) -> (String, String, Vec<ExecResult>) {
+        let command: Vec<String> = command.iter().map(|s| s.to_string()).collect();
+        let (out_buffer, err_buffer) = (SharedBuffer::default(), SharedBuffer::default());
+        let stdout = PrefixedLineWriter::new(out_buffer.clone());
+        let stderr = PrefixedLineWriter::new(err_buffer.clone());
+        let results = exec_in_repositories(repos, &command, output, Some(2), &stdout, &stderr);
+        stdout.finish().unwrap();
+        stderr.finish().unwrap();
+        (out_buffer.contents(), err_buffer.contents(), results)
+    }
+
+    #[test]
+    fn test_buffered_output_keeps_each_repository_together() {
+        let temp_dir = TempDir::new().unwrap();
+        let repos = repositories(temp_dir.path(), &["alpha", "beta"]);
+
+        let (stdout, _, results) = exec(&repos, &["cat", "file.txt"], ExecOutput::Buffered);
+        assert!(results.iter().all(ExecResult::success));
+        for name in ["alpha", "beta"] {
+            let header = format!("== {name} (");
+            let section = &stdout[stdout.find(&header).unwrap()..];
+            let body: Vec<&str> = section.lines().skip(1).take(2).collect();
+            assert_eq!(body, vec![name, "second"]);
+        }
+    }
+
+    #[test]
+    fn test_stream_output_prefixes_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let repos = repositories(temp_dir.path(), &["alpha", "beta"]);
+
+        let (stdout, _, _) = exec(&repos, &["cat", "file.txt"], ExecOutput::Stream);
+        let mut lines: Vec<&str> = stdout.lines().collect();
+        lines.sort();
+        assert_eq!(
+            lines,
+            vec![
+                "[alpha] alpha",
+                "[alpha] second",
+                "[beta] beta",
+                "[beta] second"
+            ]
+        );
+
+        let (_, stderr, results) = exec(&repos, &["cat", "missing.txt"], ExecOutput::Stream);
+        assert!(results.iter().all(|r| !r.success()));
+        assert!(stderr.lines().all(|line| line.starts_with('[')));
+    }
+}
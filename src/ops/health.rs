@@ -0,0 +1,77 @@
+use crate::error::{PendectorError, PendectorResult};
+use crate::git::{find_lock_files, LockFile};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// これより新しいロックファイルは実行中のgitコマンドのものとみなして削除しない
+pub const STALE_LOCK_MIN_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// `git maintenance run` を実行する（gcで緩いオブジェクトをpackにまとめ、参照をpacked-refsに移す）
+pub fn run_maintenance(repo_path: &Path) -> PendectorResult<()> {
+    let output = Command::new("git")
+        .args(["maintenance", "run"])
+        .current_dir(repo_path)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| {
+            PendectorError::from_io_error(
+                repo_path.to_path_buf(),
+                format!("failed to run 'git maintenance run': {e}"),
+            )
+        })?;
+    if output.status.success() {
+        return Ok(());
+    }
+    Err(PendectorError::GitOperationFailed {
+        repo_path: repo_path.display().to_string(),
+        operation: "maintenance run".to_string(),
+        source: String::from_utf8_lossy(&output.stderr)
+            .trim()
+            .to_string()
+            .into(),
+    })
+}
+
+/// 古いロックファイルを削除し、削除したものを返す
+pub fn remove_stale_lock_files(repo_path: &Path) -> PendectorResult<Vec<LockFile>> {
+    let mut removed = Vec::new();
+    for lock in find_lock_files(repo_path) {
+        if lock.age() < STALE_LOCK_MIN_AGE {
+            continue;
+        }
+        std::fs::remove_file(&lock.path).map_err(|e| PendectorError::FileSystemError {
+            path: lock.path.clone(),
+            message: e.to_string(),
+        })?;
+        removed.push(lock);
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use std::time::SystemTime;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_remove_stale_lock_files_keeps_fresh_locks() {
+        let temp_dir = TempDir::new().unwrap();
+        let git_dir = temp_dir.path().join(".git");
+        fs::create_dir(&git_dir).unwrap();
+
+        let stale = File::create(git_dir.join("index.lock")).unwrap();
+        stale
+            .set_modified(SystemTime::now() - Duration::from_secs(86_400))
+            .unwrap();
+        File::create(git_dir.join("HEAD.lock")).unwrap();
+
+        let removed = remove_stale_lock_files(temp_dir.path()).unwrap();
+        let names: Vec<_> = removed.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, vec!["index.lock"]);
+        assert!(!git_dir.join("index.lock").exists());
+        assert!(git_dir.join("HEAD.lock").exists());
+    }
+}
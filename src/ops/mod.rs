@@ -0,0 +1,17 @@
+pub mod audit;
+pub mod branches;
+pub mod exec;
+pub mod health;
+pub mod select;
+pub mod stash;
+pub mod sync_plan;
+pub mod wip;
+
+pub use audit::*;
+pub use branches::*;
+pub use exec::*;
+pub use health::*;
+pub use select::*;
+pub use stash::*;
+pub use sync_plan::*;
+pub use wip::*;
@@ -0,0 +1,95 @@
+use crate::core::Repository;
+use crate::error::{PendectorError, PendectorResult};
+use dialoguer::MultiSelect;
+use std::io::{self, IsTerminal};
+
+/// 一括操作の前に対象リポジトリを対話的に選択する（初期状態ではすべて選択済み）
+///
+/// 選択が取り消された場合（Escやqキー）はNoneを返す。何も選ばずに確定した場合とは区別する。
+pub fn select_repositories(
+    repositories: Vec<Repository>,
+    prompt: &str,
+) -> PendectorResult<Option<Vec<Repository>>> {
+    if repositories.is_empty() {
+        return Ok(Some(repositories));
+    }
+    if !io::stdin().is_terminal() || !io::stderr().is_terminal() {
+        return Err(PendectorError::Io(io::Error::other(
+            "interactive selection requires a terminal",
+        )));
+    }
+
+    let items: Vec<String> = repositories.iter().map(selection_label).collect();
+    let defaults = vec![true; items.len()];
+    let selected = MultiSelect::new()
+        .with_prompt(prompt)
+        .items(&items)
+        .defaults(&defaults)
+        .interact_opt()
+        .map_err(|dialoguer::Error::IO(e)| PendectorError::Io(e))?;
+
+    let Some(selected) = selected else {
+        return Ok(None);
+    };
+    Ok(Some(
+        repositories
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| selected.contains(index))
+            .map(|(_, repo)| repo)
+            .collect(),
+    ))
+}
+
+/// 選択肢に表示するリポジトリの要約
+fn selection_label(repo: &Repository) -> String {
+    let branch = repo.branch_label();
+    let mut state = Vec::new();
+    if repo.has_changes {
+        state.push(format!("{} changed", repo.changed_files_count()));
+    }
+    if repo.needs_push {
+        state.push("needs push".to_string());
+    }
+    if repo.needs_pull {
+        state.push("needs pull".to_string());
+    }
+
+    let path = repo.path.display();
+    if state.is_empty() {
+        format!("{} [{branch}] {path}", repo.name)
+    } else {
+        let state = state.join(", ");
+        format!("{} [{branch}] ({state}) {path}", repo.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_selection_label() {
+        let clean = Repository::new(PathBuf::from("/src/clean")).with_git_info(
+            false,
+            Some("main".to_string()),
+            vec![],
+        );
+        assert_eq!(selection_label(&clean), "clean [main] /src/clean");
+
+        let dirty = Repository::new(PathBuf::from("/src/dirty"))
+            .with_git_info(true, None, vec!["M a".to_string(), "?? b".to_string()])
+            .with_remote_info(false, true, Some("origin/main".to_string()));
+        assert_eq!(
+            selection_label(&dirty),
+            "dirty [unknown] (2 changed, needs push) /src/dirty"
+        );
+    }
+
+    #[test]
+    fn test_select_repositories_empty_does_not_prompt() {
+        let selected = select_repositories(Vec::new(), "Select").unwrap();
+        assert_eq!(selected.map(|repos| repos.len()), Some(0));
+    }
+}
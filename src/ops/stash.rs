@@ -0,0 +1,112 @@
+use crate::error::{PendectorError, PendectorResult};
+use crate::git::open_repository;
+use crate::time::format_utc;
+use git2::{Repository as GitRepository, Signature, StashApplyOptions, StashFlags};
+use std::path::Path;
+use std::time::SystemTime;
+
+/// pendectorが作成したstashを識別するためのメッセージ接頭辞
+pub const STASH_MESSAGE_PREFIX: &str = "pendector stash";
+
+/// 日時入りのstashメッセージを作成する
+pub fn stash_message(now: SystemTime) -> String {
+    format!("{STASH_MESSAGE_PREFIX} {}", format_utc(now))
+}
+
+/// 作業ツリーの変更（未追跡ファイルを含む）をstashする
+pub fn stash_changes(repo_path: &Path, message: &str) -> PendectorResult<()> {
+    let mut repo = open(repo_path)?;
+    let signature = repo
+        .signature()
+        .or_else(|_| Signature::now("pendector", "pendector@localhost"))
+        .map_err(|e| git_error(repo_path, e))?;
+
+    repo.stash_save(&signature, message, Some(StashFlags::INCLUDE_UNTRACKED))
+        .map_err(|e| git_error(repo_path, e))?;
+    Ok(())
+}
+
+/// pendectorが作成したstashがあるか
+pub fn has_pendector_stash(repo_path: &Path) -> PendectorResult<bool> {
+    let mut repo = open(repo_path)?;
+    Ok(find_pendector_stash(&mut repo)?.is_some())
+}
+
+/// pendectorが作成した最新のstashを取り出す（存在しなければfalse）
+pub fn pop_stash(repo_path: &Path) -> PendectorResult<bool> {
+    let mut repo = open(repo_path)?;
+    let Some(index) = find_pendector_stash(&mut repo)? else {
+        return Ok(false);
+    };
+
+    let mut options = StashApplyOptions::new();
+    options.reinstantiate_index();
+    repo.stash_pop(index, Some(&mut options))
+        .map_err(|e| git_error(repo_path, e))?;
+    Ok(true)
+}
+
+fn open(repo_path: &Path) -> PendectorResult<GitRepository> {
+    open_repository(repo_path).map_err(|e| git_error(repo_path, e))
+}
+
+fn find_pendector_stash(repo: &mut GitRepository) -> PendectorResult<Option<usize>> {
+    let mut found = None;
+    repo.stash_foreach(|index, message, _| {
+        // git2のメッセージは "On <branch>: <message>" の形式になる
+        if message.contains(STASH_MESSAGE_PREFIX) {
+            found = Some(index);
+            false
+        } else {
+            true
+        }
+    })?;
+    Ok(found)
+}
+
+fn git_error(repo_path: &Path, error: git2::Error) -> PendectorError {
+    PendectorError::from_git2_error(repo_path.display().to_string(), "stash".to_string(), error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{create_committed_repo, git};
+    use std::fs;
+
+    #[test]
+    fn test_stash_message_contains_date() {
+        let message = stash_message(std::time::UNIX_EPOCH);
+        assert_eq!(message, "pendector stash 1970-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn test_stash_and_pop_roundtrip() {
+        let temp_dir = create_committed_repo(&[("tracked.txt", "v1")]);
+        let path = temp_dir.path();
+        fs::write(path.join("tracked.txt"), "v2").unwrap();
+        fs::write(path.join("untracked.txt"), "new").unwrap();
+
+        assert!(!has_pendector_stash(path).unwrap());
+        stash_changes(path, &stash_message(SystemTime::now())).unwrap();
+        assert_eq!(fs::read_to_string(path.join("tracked.txt")).unwrap(), "v1");
+        assert!(!path.join("untracked.txt").exists());
+        assert!(has_pendector_stash(path).unwrap());
+
+        assert!(pop_stash(path).unwrap());
+        assert_eq!(fs::read_to_string(path.join("tracked.txt")).unwrap(), "v2");
+        assert!(path.join("untracked.txt").exists());
+        assert!(!pop_stash(path).unwrap());
+    }
+
+    #[test]
+    fn test_pop_ignores_foreign_stash() {
+        let temp_dir = create_committed_repo(&[("tracked.txt", "v1")]);
+        let path = temp_dir.path();
+        fs::write(path.join("tracked.txt"), "manual").unwrap();
+        git(path, &["stash", "-q", "-m", "my own stash"]);
+
+        assert!(!has_pendector_stash(path).unwrap());
+        assert!(!pop_stash(path).unwrap());
+    }
+}
@@ -0,0 +1,179 @@
+use crate::error::{PendectorError, PendectorResult};
+use crate::git::open_repository;
+use std::path::Path;
+
+/// リモートと同期するために必要な操作（簡単なものから順に並ぶ）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncPlan {
+    /// ローカルにしかないコミットをpushするだけでよい
+    PushOnly,
+    /// fast-forwardで取り込める
+    FastForward,
+    /// 分岐しているが、競合せずにrebaseできる
+    Rebase,
+    /// 分岐していて、取り込むと競合する
+    Conflict { paths: Vec<String> },
+}
+
+impl SyncPlan {
+    /// 簡単さの順位（小さいほど簡単）
+    pub fn rank(&self) -> u8 {
+        match self {
+            SyncPlan::PushOnly => 0,
+            SyncPlan::FastForward => 1,
+            SyncPlan::Rebase => 2,
+            SyncPlan::Conflict { .. } => 3,
+        }
+    }
+
+    /// 一覧の見出し
+    pub fn label(&self) -> &'static str {
+        match self {
+            SyncPlan::PushOnly => "Push only",
+            SyncPlan::FastForward => "Fast-forward",
+            SyncPlan::Rebase => "Clean rebase",
+            SyncPlan::Conflict { .. } => "Conflicts",
+        }
+    }
+}
+
+/// 現在のブランチを `remote_branch`（`origin/main` など）と同期する方法を調べる
+///
+/// 作業ツリーやindexには触れず、コミット同士をメモリ上でマージして競合の有無を判定する。
+/// 同期済みの場合はNoneを返す。
+pub fn plan_sync(repo_path: &Path, remote_branch: &str) -> PendectorResult<Option<SyncPlan>> {
+    let repo = open_repository(repo_path).map_err(|e| git_error(repo_path, e))?;
+    let remote_ref = repo
+        .find_reference(&format!("refs/remotes/{remote_branch}"))
+        .map_err(|e| git_error(repo_path, e))?;
+    let theirs = repo
+        .reference_to_annotated_commit(&remote_ref)
+        .map_err(|e| git_error(repo_path, e))?;
+    let (analysis, _) = repo
+        .merge_analysis(&[&theirs])
+        .map_err(|e| git_error(repo_path, e))?;
+
+    if analysis.is_fast_forward() || analysis.is_unborn() {
+        return Ok(Some(SyncPlan::FastForward));
+    }
+    let head = repo.head().map_err(|e| git_error(repo_path, e))?;
+    if analysis.is_up_to_date() {
+        // リモートのコミットはすべて取り込み済み
+        let ahead = head.target() != Some(theirs.id());
+        return Ok(ahead.then_some(SyncPlan::PushOnly));
+    }
+
+    let ours = head.peel_to_commit().map_err(|e| git_error(repo_path, e))?;
+    let theirs = repo
+        .find_commit(theirs.id())
+        .map_err(|e| git_error(repo_path, e))?;
+    let index = repo
+        .merge_commits(&ours, &theirs, None)
+        .map_err(|e| git_error(repo_path, e))?;
+    if !index.has_conflicts() {
+        return Ok(Some(SyncPlan::Rebase));
+    }
+    Ok(Some(SyncPlan::Conflict {
+        paths: conflicting_paths(&index),
+    }))
+}
+
+/// 競合したファイルのパス（重複を除いて名前順）
+fn conflicting_paths(index: &git2::Index) -> Vec<String> {
+    let mut paths: Vec<String> = index
+        .conflicts()
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .filter_map(|conflict| conflict.our.or(conflict.their).or(conflict.ancestor))
+        .map(|entry| String::from_utf8_lossy(&entry.path).into_owned())
+        .collect();
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+fn git_error(repo_path: &Path, error: git2::Error) -> PendectorError {
+    PendectorError::from_git2_error(
+        repo_path.display().to_string(),
+        "sync-plan".to_string(),
+        error,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{git, init_repo};
+    use tempfile::TempDir;
+
+    fn commit(path: &Path, file: &str, content: &str) {
+        std::fs::write(path.join(file), content).unwrap();
+        git(path, &["add", file]);
+        git(path, &["commit", "-q", "-m", file]);
+    }
+
+    /// originの `main` に `base.txt` だけがあるクローン
+    fn clone_repo(temp_dir: &TempDir) -> (std::path::PathBuf, std::path::PathBuf) {
+        let upstream = temp_dir.path().join("upstream");
+        init_repo(&upstream);
+        commit(&upstream, "base.txt", "base\n");
+
+        let clone = temp_dir.path().join("clone");
+        git(
+            temp_dir.path(),
+            &["clone", "-q", upstream.to_str().unwrap(), "clone"],
+        );
+        git(&clone, &["config", "user.name", "Test User"]);
+        git(&clone, &["config", "user.email", "test@example.com"]);
+        (upstream, clone)
+    }
+
+    #[test]
+    fn test_plan_sync_classifies_each_case() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, clone) = clone_repo(&temp_dir);
+        assert_eq!(plan_sync(&clone, "origin/main").unwrap(), None);
+
+        commit(&clone, "local.txt", "local\n");
+        assert_eq!(
+            plan_sync(&clone, "origin/main").unwrap(),
+            Some(SyncPlan::PushOnly)
+        );
+
+        git(&clone, &["reset", "-q", "--hard", "origin/main"]);
+        commit(&upstream, "remote.txt", "remote\n");
+        git(&clone, &["fetch", "-q"]);
+        assert_eq!(
+            plan_sync(&clone, "origin/main").unwrap(),
+            Some(SyncPlan::FastForward)
+        );
+
+        commit(&clone, "local.txt", "local\n");
+        assert_eq!(
+            plan_sync(&clone, "origin/main").unwrap(),
+            Some(SyncPlan::Rebase)
+        );
+    }
+
+    #[test]
+    fn test_plan_sync_reports_conflicting_paths_without_touching_the_work_tree() {
+        let temp_dir = TempDir::new().unwrap();
+        let (upstream, clone) = clone_repo(&temp_dir);
+        commit(&upstream, "base.txt", "remote\n");
+        git(&clone, &["fetch", "-q"]);
+        commit(&clone, "base.txt", "local\n");
+
+        assert_eq!(
+            plan_sync(&clone, "origin/main").unwrap(),
+            Some(SyncPlan::Conflict {
+                paths: vec!["base.txt".to_string()]
+            })
+        );
+        assert_eq!(
+            std::fs::read_to_string(clone.join("base.txt")).unwrap(),
+            "local\n"
+        );
+        assert!(!clone.join(".git").join("MERGE_HEAD").exists());
+    }
+}
@@ -0,0 +1,185 @@
+use crate::error::{PendectorError, PendectorResult};
+use crate::git::open_repository;
+use crate::time::format_utc_compact;
+use git2::{BranchType, IndexAddOption, Repository as GitRepository, ResetType, Signature};
+use std::path::Path;
+use std::time::SystemTime;
+
+/// WIPブランチ名の既定テンプレート
+pub const DEFAULT_WIP_BRANCH_TEMPLATE: &str = "wip/{branch}-{date}";
+
+/// WIPコミットに元のブランチを記録するトレーラー
+const BASE_BRANCH_TRAILER: &str = "Pendector-Base-Branch: ";
+
+/// テンプレートからWIPブランチ名を作成する
+pub fn wip_branch_name(template: &str, branch: &str, now: SystemTime) -> String {
+    template
+        .replace("{branch}", branch)
+        .replace("{date}", &format_utc_compact(now))
+}
+
+/// 未コミットの変更をすべてWIPブランチ上にコミットし、作成したブランチ名を返す
+///
+/// 作業ツリーはそのままで、HEADがWIPブランチに切り替わる。
+pub fn create_wip_commit(
+    repo_path: &Path,
+    template: &str,
+    now: SystemTime,
+) -> PendectorResult<String> {
+    let repo = open(repo_path)?;
+    let head = repo.head().map_err(|e| git_error(repo_path, e))?;
+    let Some(base) = head.shorthand().filter(|_| head.is_branch()) else {
+        return Err(PendectorError::GitOperationFailed {
+            repo_path: repo_path.display().to_string(),
+            operation: "wip".to_string(),
+            source: "HEAD is not on a branch".into(),
+        });
+    };
+    let base = base.to_string();
+    let head_commit = head.peel_to_commit().map_err(|e| git_error(repo_path, e))?;
+
+    let branch_name = wip_branch_name(template, &base, now);
+    repo.branch(&branch_name, &head_commit, false)
+        .map_err(|e| git_error(repo_path, e))?;
+
+    let mut index = repo.index().map_err(|e| git_error(repo_path, e))?;
+    index
+        .add_all(["*"], IndexAddOption::DEFAULT, None)
+        .and_then(|_| index.update_all(["*"], None))
+        .and_then(|_| index.write())
+        .map_err(|e| git_error(repo_path, e))?;
+    let tree_id = index.write_tree().map_err(|e| git_error(repo_path, e))?;
+    let tree = repo
+        .find_tree(tree_id)
+        .map_err(|e| git_error(repo_path, e))?;
+
+    let signature = repo
+        .signature()
+        .or_else(|_| Signature::now("pendector", "pendector@localhost"))
+        .map_err(|e| git_error(repo_path, e))?;
+    let message = format!("wip: uncommitted changes on {base}\n\n{BASE_BRANCH_TRAILER}{base}\n");
+    let branch_ref = format!("refs/heads/{branch_name}");
+    repo.commit(
+        Some(&branch_ref),
+        &signature,
+        &signature,
+        &message,
+        &tree,
+        &[&head_commit],
+    )
+    .map_err(|e| git_error(repo_path, e))?;
+    repo.set_head(&branch_ref)
+        .map_err(|e| git_error(repo_path, e))?;
+
+    Ok(branch_name)
+}
+
+/// HEADがpendectorの作成したWIPコミットか
+pub fn is_on_wip_commit(repo_path: &Path) -> PendectorResult<bool> {
+    let repo = open(repo_path)?;
+    Ok(wip_base_branch(&repo).is_some())
+}
+
+/// WIPコミットを取り消して元のブランチに戻り、戻したブランチ名を返す
+///
+/// 変更は未ステージの状態で作業ツリーに残り、WIPブランチは削除される。
+/// HEADがWIPコミットでなければ何もせず `None` を返す。
+pub fn undo_wip_commit(repo_path: &Path) -> PendectorResult<Option<String>> {
+    let repo = open(repo_path)?;
+    let Some(base) = wip_base_branch(&repo) else {
+        return Ok(None);
+    };
+    let head = repo.head().map_err(|e| git_error(repo_path, e))?;
+    let wip_branch = head.shorthand().unwrap_or_default().to_string();
+
+    let base_commit = repo
+        .find_branch(&base, BranchType::Local)
+        .and_then(|b| b.get().peel_to_commit())
+        .map_err(|e| git_error(repo_path, e))?;
+    repo.set_head(&format!("refs/heads/{base}"))
+        .and_then(|_| repo.reset(base_commit.as_object(), ResetType::Mixed, None))
+        .map_err(|e| git_error(repo_path, e))?;
+    repo.find_branch(&wip_branch, BranchType::Local)
+        .and_then(|mut b| b.delete())
+        .map_err(|e| git_error(repo_path, e))?;
+
+    Ok(Some(base))
+}
+
+/// HEADのWIPコミットに記録された元のブランチ名
+fn wip_base_branch(repo: &GitRepository) -> Option<String> {
+    let head = repo.head().ok()?;
+    if !head.is_branch() {
+        return None;
+    }
+    let commit = head.peel_to_commit().ok()?;
+    let message = commit.message()?;
+    if !message.starts_with("wip:") {
+        return None;
+    }
+    message
+        .lines()
+        .find_map(|line| line.strip_prefix(BASE_BRANCH_TRAILER))
+        .map(|base| base.trim().to_string())
+}
+
+fn open(repo_path: &Path) -> PendectorResult<GitRepository> {
+    open_repository(repo_path).map_err(|e| git_error(repo_path, e))
+}
+
+fn git_error(repo_path: &Path, error: git2::Error) -> PendectorError {
+    PendectorError::from_git2_error(repo_path.display().to_string(), "wip".to_string(), error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{create_committed_repo, git};
+    use std::fs;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn test_wip_branch_name() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_791_556_496);
+        assert_eq!(
+            wip_branch_name(DEFAULT_WIP_BRANCH_TEMPLATE, "main", now),
+            "wip/main-20261009T143456Z"
+        );
+        assert_eq!(
+            wip_branch_name("handoff/{branch}", "dev", now),
+            "handoff/dev"
+        );
+    }
+
+    #[test]
+    fn test_wip_and_undo_roundtrip() {
+        let temp_dir = create_committed_repo(&[("tracked.txt", "v1"), ("removed.txt", "gone")]);
+        let path = temp_dir.path();
+        fs::write(path.join("tracked.txt"), "v2").unwrap();
+        fs::write(path.join("untracked.txt"), "new").unwrap();
+        fs::remove_file(path.join("removed.txt")).unwrap();
+
+        let branch = create_wip_commit(path, "wip/{branch}", SystemTime::now()).unwrap();
+        assert_eq!(branch, "wip/main");
+        assert_eq!(git(path, &["branch", "--show-current"]), "wip/main");
+        assert_eq!(git(path, &["status", "--porcelain"]), "");
+        assert!(git(path, &["log", "-1", "--format=%s"]).starts_with("wip: "));
+        assert!(is_on_wip_commit(path).unwrap());
+
+        assert_eq!(undo_wip_commit(path).unwrap(), Some("main".to_string()));
+        assert_eq!(git(path, &["branch", "--show-current"]), "main");
+        assert_eq!(git(path, &["branch", "--list", "wip/*"]), "");
+        assert_eq!(fs::read_to_string(path.join("tracked.txt")).unwrap(), "v2");
+        let status = git(path, &["status", "--porcelain"]);
+        assert!(status.contains("M tracked.txt"));
+        assert!(status.contains("D removed.txt"));
+        assert!(status.contains("?? untracked.txt"));
+    }
+
+    #[test]
+    fn test_undo_without_wip_commit_is_noop() {
+        let temp_dir = create_committed_repo(&[("tracked.txt", "v1"), ("removed.txt", "gone")]);
+        assert!(!is_on_wip_commit(temp_dir.path()).unwrap());
+        assert_eq!(undo_wip_commit(temp_dir.path()).unwrap(), None);
+    }
+}
@@ -0,0 +1,166 @@
+use crate::core::{Attention, Repository};
+use crate::error::ErrorCode;
+use crate::time::format_utc;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// `--format json-v2` のスキーマのバージョン（互換性のない変更をしたら上げる）
+pub const JSON_ENVELOPE_VERSION: u32 = 1;
+
+/// `--format json-v2` の出力：リポジトリ一覧に生成日時・スキャンルート・集計・エラーを添える
+#[derive(Debug, Serialize)]
+pub struct JsonEnvelope<'a> {
+    pub version: u32,
+    pub generated_at: String,
+    pub scan_roots: &'a [String],
+    pub repositories: &'a [Repository],
+    pub summary: Summary,
+    pub errors: Vec<EnvelopeError<'a>>,
+    /// 結果は出たが注意が必要なこと（設定ファイルの読み込み失敗・飛ばしたパス・fetchの失敗）
+    pub warnings: Vec<EnvelopeWarning>,
+}
+
+/// リポジトリ一覧の集計
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Summary {
+    pub total: usize,
+    pub with_changes: usize,
+    pub needs_push: usize,
+    pub needs_pull: usize,
+    pub needs_attention: usize,
+    /// 対応が必要な理由ごとの件数
+    pub attention: BTreeMap<Attention, usize>,
+    pub errors: usize,
+}
+
+/// リポジトリごとの情報の取得（ステータス、fetch、リモートとの比較など）の失敗
+#[derive(Debug, Serialize)]
+pub struct EnvelopeError<'a> {
+    pub path: &'a Path,
+    /// 失敗した処理（`status`、`fetch`、`compare`、`artifacts`、`preview` のいずれか）
+    pub stage: &'static str,
+    pub code: ErrorCode,
+    pub message: &'a str,
+}
+
+/// 警告の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningKind {
+    /// 設定ファイルを読み込めず、デフォルト設定で実行した
+    ConfigFallback,
+    /// 存在しないスキャンルートや、探索中に読めなかったディレクトリを飛ばした
+    SkippedPath,
+    /// fetchに失敗し、リモートとの差分が古い可能性がある
+    FetchFailed,
+    /// 探索時間の上限を過ぎ、スキャンルートの一部を調べられなかった
+    BudgetExceeded,
+    /// `--recheck` で前回の結果を使えず、すべてのリポジトリを調べた
+    RecheckUnavailable,
+}
+
+/// `json-v2` 出力の警告
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EnvelopeWarning {
+    pub kind: WarningKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub message: String,
+    /// 標準エラー出力に表示する文言（表示する言語。`json-v2` には含めない）
+    #[serde(skip)]
+    pub display: String,
+}
+
+impl EnvelopeWarning {
+    pub fn new(kind: WarningKind, path: Option<String>, message: impl Into<String>) -> Self {
+        let message = message.into();
+        Self {
+            kind,
+            path,
+            display: message.clone(),
+            message,
+        }
+    }
+
+    /// 標準エラー出力に表示する文言を設定する（`message` は機械向けに英語のまま残す）
+    pub fn with_display(mut self, display: impl Into<String>) -> Self {
+        self.display = display.into();
+        self
+    }
+}
+
+impl<'a> JsonEnvelope<'a> {
+    pub fn new(
+        generated_at: SystemTime,
+        scan_roots: &'a [String],
+        repositories: &'a [Repository],
+    ) -> Self {
+        let errors: Vec<EnvelopeError<'a>> = repositories
+            .iter()
+            .flat_map(|repo| {
+                repo.errors().map(|(stage, error)| EnvelopeError {
+                    path: &repo.path,
+                    stage,
+                    code: error.code,
+                    message: &error.message,
+                })
+            })
+            .collect();
+        let warnings = repositories
+            .iter()
+            .filter_map(|repo| {
+                repo.fetch_error.as_ref().map(|error| {
+                    EnvelopeWarning::new(
+                        WarningKind::FetchFailed,
+                        Some(repo.path.display().to_string()),
+                        error.message.clone(),
+                    )
+                })
+            })
+            .collect();
+
+        Self {
+            version: JSON_ENVELOPE_VERSION,
+            generated_at: format_utc(generated_at),
+            scan_roots,
+            repositories,
+            summary: Summary::of(repositories),
+            errors,
+            warnings,
+        }
+    }
+
+    /// 実行全体の警告を、リポジトリごとの警告の前に加える
+    pub fn with_warnings(mut self, warnings: &[EnvelopeWarning]) -> Self {
+        self.warnings.splice(0..0, warnings.iter().cloned());
+        self
+    }
+}
+
+impl Summary {
+    /// リポジトリ一覧を集計する（エラーはステータス取得とfetchの失敗を別々に数える）
+    pub fn of(repositories: &[Repository]) -> Self {
+        let count = |f: fn(&Repository) -> bool| repositories.iter().filter(|r| f(r)).count();
+        Self {
+            total: repositories.len(),
+            with_changes: count(|r| r.has_changes),
+            needs_push: count(|r| r.needs_push),
+            needs_pull: count(|r| r.needs_pull),
+            needs_attention: count(|r| r.needs_attention()),
+            attention: Attention::value_variants()
+                .iter()
+                .map(|&attention| {
+                    let n = repositories
+                        .iter()
+                        .filter(|r| r.attention() == attention)
+                        .count();
+                    (attention, n)
+                })
+                .collect(),
+            errors: repositories.iter().map(|r| r.errors().count()).sum(),
+        }
+    }
+}
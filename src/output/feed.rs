@@ -0,0 +1,55 @@
+use crate::core::Repository;
+use crate::output::formatter::attention_reasons;
+use crate::output::Locale;
+use crate::time::format_utc;
+use serde::Serialize;
+
+/// 出力するJSON Feedの仕様のバージョン
+pub const JSON_FEED_VERSION: &str = "https://jsonfeed.org/version/1.1";
+
+/// `--format jsonfeed` の出力：対応が必要なリポジトリを1件ずつ項目にしたJSON Feed
+#[derive(Debug, Serialize)]
+pub struct JsonFeed {
+    pub version: &'static str,
+    pub title: &'static str,
+    pub items: Vec<FeedItem>,
+}
+
+/// JSON Feedの項目
+#[derive(Debug, Serialize)]
+pub struct FeedItem {
+    /// パスと分類から作る識別子（状態が変われば新しい項目として扱われる）
+    pub id: String,
+    pub title: String,
+    pub content_text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date_modified: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl JsonFeed {
+    pub fn new(repositories: &[Repository]) -> Self {
+        let items = repositories
+            .iter()
+            .filter(|repo| repo.needs_attention())
+            .map(|repo| {
+                let attention = repo.attention();
+                let branch = repo.branch_label();
+                let reasons = attention_reasons(repo, Locale::En).join(", ");
+                FeedItem {
+                    id: format!("{}#{attention}", repo.path.display()),
+                    title: format!("{} [{branch}]: {reasons}", repo.name),
+                    content_text: format!("{}\n{reasons}", repo.path.display()),
+                    date_modified: repo.dirty_since.or(repo.last_commit_at).map(format_utc),
+                    tags: vec![attention.to_string()],
+                }
+            })
+            .collect();
+
+        Self {
+            version: JSON_FEED_VERSION,
+            title: "pendector: repositories needing attention",
+            items,
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+
+/// `--format` に指定できる出力形式
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize, clap::ValueEnum,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    /// 人が読むためのテキスト（既定）
+    #[default]
+    Text,
+    /// リポジトリの配列
+    Json,
+    /// 生成日時・スキャンルート・集計・エラーを添えたJSON
+    JsonV2,
+    /// 対応が必要なリポジトリを1件ずつ並べたJSON Feed
+    Jsonfeed,
+}
+
+impl OutputFormat {
+    /// 形式の名前（`--format` や設定ファイルで指定する値）
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Json => "json",
+            OutputFormat::JsonV2 => "json-v2",
+            OutputFormat::Jsonfeed => "jsonfeed",
+        }
+    }
+
+    /// JSON形式（`json`・`json-v2`・`jsonfeed`）か
+    pub fn is_json(self) -> bool {
+        self != OutputFormat::Text
+    }
+}
+
+impl std::fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::ValueEnum;
+
+    #[test]
+    fn test_names_match_cli_and_config_values() {
+        for format in OutputFormat::value_variants() {
+            let cli_name = format.to_possible_value().unwrap().get_name().to_string();
+            assert_eq!(cli_name, format.as_str());
+            assert_eq!(
+                serde_json::to_string(format).unwrap(),
+                format!("\"{}\"", format.as_str())
+            );
+        }
+    }
+
+    #[test]
+    fn test_rejects_unknown_format() {
+        assert!(OutputFormat::from_str("josn", false).is_err());
+        assert_eq!(
+            OutputFormat::from_str("json-v2", false).unwrap(),
+            OutputFormat::JsonV2
+        );
+    }
+}
@@ -1,31 +1,416 @@
-use crate::core::Repository;
+use crate::core::{Attention, Repository, DEFAULT_STALE_DIRTY_AFTER};
+use crate::output::envelope::{EnvelopeWarning, JsonEnvelope};
+use crate::output::feed::JsonFeed;
+use crate::output::format::OutputFormat;
+use crate::output::hyperlink::{file_url, hyperlink, repository_url};
+use crate::output::icons::{IconStyle, Icons};
+use crate::output::messages::{Field, Locale, Message, SyncState};
+use crate::path_style::normalize_path;
+use crate::time::{format_age, format_utc};
 use colored::*;
+use console::measure_text_width;
 use serde_json;
+use std::time::{Duration, SystemTime};
 
 pub struct OutputFormatter {
     pub verbose: bool,
-    pub format: String,
+    pub format: OutputFormat,
+    pub show_remote_only_branches: bool,
+    pub dirty_warn_after: Duration,
+    pub width: Option<usize>,
+    pub icons: Icons,
+    /// `json-v2` 出力に含めるスキャンルート
+    pub scan_roots: Vec<String>,
+    /// `json-v2` 出力の生成日時（Noneなら整形時の現在時刻）
+    pub generated_at: Option<SystemTime>,
+    /// `json-v2` 出力に含める実行全体の警告
+    pub warnings: Vec<EnvelopeWarning>,
+    /// この時刻からみた各リポジトリの確認からの経過時間を表示する（watchモード用）
+    pub checked_ages_at: Option<SystemTime>,
+    /// テキスト出力のパスのシンボリックリンクを解決して表示するか
+    pub resolve_symlinks: bool,
+    /// テキスト出力のリポジトリ名とパスを端末のハイパーリンク（OSC 8）にするか
+    pub hyperlinks: bool,
+    /// テキスト出力の見出しや要約の言語
+    pub locale: Locale,
+    /// 記号やパスの省略を使わないスクリーンリーダー向けの表示か
+    pub plain: bool,
+}
+
+/// `git status --short` の状態コードを表示用の名前にする
+fn changed_file_label(code: &str) -> &'static str {
+    if code.contains('?') || code.contains('A') {
+        "new"
+    } else if code.contains('D') {
+        "deleted"
+    } else if code.contains('R') {
+        "renamed"
+    } else if code.contains('M') {
+        "modified"
+    } else {
+        "changed"
+    }
+}
+
+/// 変更されたパスを、まとめる先のディレクトリと表示する名前に分ける
+///
+/// 未追跡のディレクトリ（`sub/`）は末尾の `/` を付けたままディレクトリとして扱い、
+/// 名前の変更（`old -> new`）は変更後のパスのディレクトリにまとめる。
+fn split_changed_path(path: &str) -> (&str, String) {
+    if let Some((from, to)) = path.split_once(" -> ") {
+        let (dir, to_name) = to.rsplit_once('/').unwrap_or(("", to));
+        let (from_dir, from_name) = from.rsplit_once('/').unwrap_or(("", from));
+        let from = if from_dir == dir { from_name } else { from };
+        return (dir, format!("{from} -> {to_name}"));
+    }
+    match path.strip_suffix('/') {
+        Some(trimmed) => {
+            let (dir, name) = trimmed.rsplit_once('/').unwrap_or(("", trimmed));
+            (dir, format!("{name}/"))
+        }
+        None => {
+            let (dir, name) = path.rsplit_once('/').unwrap_or(("", path));
+            (dir, name.to_string())
+        }
+    }
+}
+
+/// 対応が必要な理由（レポートやフィードの1行に並べる）
+pub(crate) fn attention_reasons(repo: &Repository, locale: Locale) -> Vec<String> {
+    let mut reasons = Vec::new();
+    match repo.changed_files.len() {
+        0 => {}
+        1 if !repo.changed_files_truncated => reasons.push(Message::OneChangedFile.text(locale)),
+        _ => reasons.push(Message::ChangedFiles(&repo.changed_files_count()).text(locale)),
+    }
+    if repo.needs_push {
+        reasons.push(Message::NeedsPush.text(locale));
+    }
+    if repo.needs_pull {
+        reasons.push(Message::NeedsPull.text(locale));
+    }
+    if repo.status_error.is_some() {
+        reasons.push(Message::StatusUnavailable.text(locale));
+    }
+    if let Some(operation) = repo.operation {
+        reasons.push(Message::InProgress(&operation.to_string()).text(locale));
+    }
+    reasons
+}
+
+/// バイト数を2進接頭辞の単位で表す（例: `1.5 GiB`）
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next in &UNITS[1..] {
+        if size < 1024.0 {
+            break;
+        }
+        size /= 1024.0;
+        unit = next;
+    }
+    format!("{size:.1} {unit}")
+}
+
+/// 標準出力が端末の場合はその幅を返す
+pub fn terminal_width() -> Option<usize> {
+    console::Term::stdout()
+        .size_checked()
+        .map(|(_, columns)| columns as usize)
 }
 
 impl OutputFormatter {
-    pub fn new(verbose: bool, format: String) -> Self {
-        Self { verbose, format }
+    pub fn new(verbose: bool, format: OutputFormat) -> Self {
+        Self {
+            verbose,
+            format,
+            show_remote_only_branches: false,
+            dirty_warn_after: DEFAULT_STALE_DIRTY_AFTER,
+            width: None,
+            icons: IconStyle::default().icons(),
+            scan_roots: Vec::new(),
+            generated_at: None,
+            warnings: Vec::new(),
+            checked_ages_at: None,
+            resolve_symlinks: true,
+            hyperlinks: false,
+            locale: Locale::default(),
+            plain: false,
+        }
+    }
+
+    /// 各リポジトリを最後に確認してからの経過時間を `now` 時点で表示する
+    pub fn with_checked_ages_at(mut self, now: SystemTime) -> Self {
+        self.checked_ages_at = Some(now);
+        self
+    }
+
+    /// JSON形式（`json`・`json-v2`・`jsonfeed`）で出力するか
+    pub fn is_json(&self) -> bool {
+        self.format.is_json()
+    }
+
+    /// `json-v2` 出力に含めるスキャンルートを設定する
+    pub fn with_scan_roots(mut self, scan_roots: Vec<String>) -> Self {
+        self.scan_roots = scan_roots;
+        self
+    }
+
+    /// `json-v2` 出力の生成日時を固定する
+    pub fn with_generated_at(mut self, generated_at: SystemTime) -> Self {
+        self.generated_at = Some(generated_at);
+        self
+    }
+
+    /// `json-v2` 出力に含める実行全体の警告を設定する
+    pub fn with_warnings(mut self, warnings: Vec<EnvelopeWarning>) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
+    /// テキスト出力のパスのシンボリックリンクを解決するかを設定する
+    pub fn with_resolve_symlinks(mut self, resolve_symlinks: bool) -> Self {
+        self.resolve_symlinks = resolve_symlinks;
+        self
+    }
+
+    /// テキスト出力のリポジトリ名とパスをハイパーリンクにするかを設定する
+    pub fn with_hyperlinks(mut self, hyperlinks: bool) -> Self {
+        self.hyperlinks = hyperlinks;
+        self
+    }
+
+    /// テキスト出力の見出しや要約の言語を設定する
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        if self.plain {
+            self.icons = Icons::words(locale);
+        }
+        self
+    }
+
+    /// スクリーンリーダー向けの表示にする（記号を表示する言語の言葉にし、パスを省略しない）
+    pub fn with_plain(mut self, plain: bool) -> Self {
+        self.plain = plain;
+        if plain {
+            self.icons = Icons::words(self.locale);
+        }
+        self
+    }
+
+    /// 表示する言語でのメッセージ
+    fn message(&self, message: Message) -> String {
+        message.text(self.locale)
+    }
+
+    /// 表示する言語での詳細の項目名
+    fn field(&self, field: Field) -> String {
+        self.message(Message::Field(field))
+    }
+
+    /// テキスト出力で使う記号の種類
+    pub fn with_icons(mut self, style: IconStyle) -> Self {
+        self.icons = style.icons();
+        self
+    }
+
+    /// テキスト出力を収める幅（Noneなら制限しない）
+    pub fn with_width(mut self, width: Option<usize>) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// 既に `used` 桁使っている行にパスを収める
+    fn fit_path(&self, path: &str, used: usize) -> String {
+        match self.width.filter(|_| !self.plain) {
+            Some(width) => truncate_path_middle(&abbreviate_home(path), width.saturating_sub(used)),
+            None => path.to_string(),
+        }
+    }
+
+    /// リポジトリ名を、ハイパーリンクが有効ならフォージのページか作業ツリーへのリンクにする
+    fn link_name(&self, repo: &Repository, name: &str) -> String {
+        if self.hyperlinks {
+            hyperlink(&repository_url(repo), name)
+        } else {
+            name.to_string()
+        }
+    }
+
+    /// 表示するパスを、ハイパーリンクが有効なら作業ツリーへのリンクにする
+    fn link_path(&self, repo: &Repository, path: &str) -> String {
+        if self.hyperlinks {
+            hyperlink(&file_url(&repo.path), path)
+        } else {
+            path.to_string()
+        }
+    }
+
+    /// 字下げ付きの行を幅に合わせて折り返す
+    fn wrap(&self, indent: &str, text: &str) -> String {
+        match self.width {
+            Some(width) => wrap_with_indent(indent, text, width),
+            None => format!("{indent}{text}"),
+        }
+    }
+
+    /// 変更されたファイルをディレクトリごとにまとめ、状態を色分けして揃える
+    fn format_changed_files(&self, changed_files: &[String]) -> String {
+        let entries: Vec<(&str, &str)> = changed_files
+            .iter()
+            .map(|file| match (file.get(..2), file.get(3..)) {
+                (Some(code), Some(path)) => (code, path),
+                _ => ("", file.as_str()),
+            })
+            .collect();
+        let width = entries
+            .iter()
+            .map(|(code, _)| changed_file_label(code).len())
+            .max()
+            .unwrap_or(0);
+
+        // 直下のファイルを先に、続けてディレクトリ名の順に並べる（ディレクトリ内は元の順序のまま）
+        let mut groups: Vec<(&str, Vec<(&str, String)>)> = Vec::new();
+        for (code, path) in entries {
+            let (dir, name) = split_changed_path(path);
+            match groups.iter_mut().find(|(d, _)| *d == dir) {
+                Some((_, files)) => files.push((code, name)),
+                None => groups.push((dir, vec![(code, name)])),
+            }
+        }
+        groups.sort_by_key(|(dir, _)| (!dir.is_empty(), *dir));
+
+        let mut result = String::new();
+        for (dir, files) in groups {
+            let indent = if dir.is_empty() {
+                "    "
+            } else {
+                result.push_str(&format!("\n    {dir}/"));
+                "      "
+            };
+            for (code, name) in files {
+                let label = format!("{:<width$}", changed_file_label(code));
+                let line = self.wrap(&format!("{indent}{label}  "), &name);
+                // 折り返しの幅は色を付ける前の文字列で計算する
+                let colored_label = match changed_file_label(code) {
+                    "new" => label.green(),
+                    "deleted" => label.red(),
+                    "modified" => label.yellow(),
+                    _ => label.normal(),
+                };
+                result.push('\n');
+                result.push_str(&line.replacen(&label, &colored_label.to_string(), 1));
+            }
+        }
+        result
+    }
+
+    /// 未コミットの変更を古いとみなして強調する経過時間
+    pub fn with_dirty_warn_after(mut self, dirty_warn_after: Duration) -> Self {
+        self.dirty_warn_after = dirty_warn_after;
+        self
+    }
+
+    /// 変更が閾値より古ければその経過時間を返す
+    fn stale_dirty_age(&self, repo: &Repository) -> Option<Duration> {
+        let age = SystemTime::now().duration_since(repo.dirty_since?).ok()?;
+        (age >= self.dirty_warn_after).then_some(age)
+    }
+
+    /// verbose出力にローカルにないリモートブランチを含めるか
+    pub fn with_remote_only_branches(mut self, show: bool) -> Self {
+        self.show_remote_only_branches = show;
+        self
     }
 
     pub fn format_repositories(&self, repositories: &[Repository]) -> String {
-        match self.format.as_str() {
-            "json" => self.format_repositories_json(repositories),
-            _ => self.format_repositories_text(repositories),
+        match self.format {
+            OutputFormat::Json => self.format_repositories_json(repositories),
+            OutputFormat::JsonV2 => self.format_repositories_envelope(repositories),
+            OutputFormat::Jsonfeed => self.format_repositories_feed(repositories),
+            OutputFormat::Text => self.format_repositories_text(repositories),
         }
     }
 
+    /// cron向けの簡潔なレポート（対応が必要なリポジトリがなければ空文字列）
+    pub fn format_report(&self, repositories: &[Repository]) -> String {
+        let actionable: Vec<&Repository> = repositories
+            .iter()
+            .filter(|r| r.needs_attention())
+            .collect();
+        if actionable.is_empty() {
+            return String::new();
+        }
+
+        let mut output = format!(
+            "{}\n\n",
+            self.message(Message::ReportHeader(actionable.len()))
+        );
+
+        for repo in actionable {
+            let branch = repo.branch_label();
+            let reasons = attention_reasons(repo, self.locale).join(", ");
+            let path = repo.path.display();
+            output.push_str(&format!("{} [{branch}]: {reasons}\n  {path}\n", repo.name));
+        }
+
+        output
+    }
+
     fn format_repositories_json(&self, repositories: &[Repository]) -> String {
         serde_json::to_string_pretty(repositories).unwrap_or_else(|_| "{}".to_string())
     }
 
+    /// 複数の場所にクローンされているプロジェクトを、クローンごとの状態とともに表示する
+    pub fn format_duplicates(&self, groups: &[(&str, Vec<&Repository>)]) -> String {
+        if self.is_json() {
+            let groups: Vec<serde_json::Value> = groups
+                .iter()
+                .map(|(remote_id, clones)| {
+                    serde_json::json!({ "remote_id": remote_id, "repositories": clones })
+                })
+                .collect();
+            return serde_json::to_string_pretty(&groups).unwrap_or_else(|_| "[]".to_string());
+        }
+
+        if groups.is_empty() {
+            return self.message(Message::NoDuplicates);
+        }
+        let mut output = format!("{}\n", self.message(Message::FoundDuplicates(groups.len())));
+        for (remote_id, clones) in groups {
+            output.push_str(&format!(
+                "\n{} ({})\n",
+                remote_id.bold(),
+                self.message(Message::Clones(clones.len()))
+            ));
+            for repo in clones {
+                for line in self.format_repository(repo).lines() {
+                    output.push_str(&format!("  {line}\n"));
+                }
+            }
+        }
+        output
+    }
+
+    fn format_repositories_feed(&self, repositories: &[Repository]) -> String {
+        serde_json::to_string_pretty(&JsonFeed::new(repositories))
+            .unwrap_or_else(|_| "{}".to_string())
+    }
+
+    fn format_repositories_envelope(&self, repositories: &[Repository]) -> String {
+        let generated_at = self.generated_at.unwrap_or_else(SystemTime::now);
+        let envelope = JsonEnvelope::new(generated_at, &self.scan_roots, repositories)
+            .with_warnings(&self.warnings);
+        serde_json::to_string_pretty(&envelope).unwrap_or_else(|_| "{}".to_string())
+    }
+
     fn format_repositories_text(&self, repositories: &[Repository]) -> String {
         if repositories.is_empty() {
-            return "No repositories found.".to_string();
+            return self.message(Message::NoRepositories);
         }
 
         let mut output = String::new();
@@ -34,78 +419,419 @@ impl OutputFormatter {
         let total_count = repositories.len();
         let changed_count = repositories.iter().filter(|r| r.has_changes).count();
 
-        output.push_str(&format!("Found {total_count} repositories"));
-        if changed_count > 0 {
-            output.push_str(&format!(" ({changed_count} with changes)"));
-        }
-        output.push_str(":\n\n");
+        output.push_str(&self.message(Message::FoundRepositories {
+            total: total_count,
+            with_changes: changed_count,
+        }));
+        output.push_str("\n\n");
 
         for repo in repositories {
             output.push_str(&self.format_repository(repo));
             output.push('\n');
         }
 
+        // 片付けの目安として、無視された大きなディレクトリの合計を示す
+        let with_artifacts: Vec<&Repository> = repositories
+            .iter()
+            .filter(|r| r.artifacts_size() > 0)
+            .collect();
+        if !with_artifacts.is_empty() {
+            let total: u64 = with_artifacts.iter().map(|r| r.artifacts_size()).sum();
+            let message = Message::IgnoredArtifacts {
+                size: &format_size(total),
+                repositories: with_artifacts.len(),
+            };
+            output.push_str(&format!("\n{}\n", self.message(message)));
+        }
+
         output
     }
 
     fn format_repository(&self, repo: &Repository) -> String {
-        let name = if repo.has_changes {
-            repo.name.red().to_string()
-        } else {
-            repo.name.green().to_string()
+        let icons = &self.icons;
+        let name = match repo.attention() {
+            Attention::None => repo.name.green().to_string(),
+            Attention::Unsynced => repo.name.yellow().to_string(),
+            _ => repo.name.red().to_string(),
         };
+        let name = format!("{}{name}", icons.vcs);
 
-        let branch = repo.current_branch.as_deref().unwrap_or("unknown");
-        let files_count = repo.changed_files.len();
+        let branch = format!("{}{}", icons.branch, repo.branch_label());
+        let changed_files = self.message(Message::ChangedFiles(&repo.changed_files_count()));
         let path = repo
             .path
             .canonicalize()
-            .map(|p| p.display().to_string())
-            .unwrap_or_else(|_| repo.path.display().to_string());
+            .ok()
+            .filter(|_| self.resolve_symlinks)
+            .map(|p| normalize_path(&p).display().to_string())
+            .unwrap_or_else(|| repo.path.display().to_string());
 
         // リモート同期状態の表示
         let mut remote_status = String::new();
         if repo.needs_pull && repo.needs_push {
-            remote_status.push_str(" [↑↓]");
+            remote_status.push_str(&format!(" [{}]", icons.diverged));
         } else if repo.needs_pull {
-            remote_status.push_str(" [↓]");
+            remote_status.push_str(&format!(" [{}]", icons.pull));
         } else if repo.needs_push {
-            remote_status.push_str(" [↑]");
+            remote_status.push_str(&format!(" [{}]", icons.push));
+        }
+        // fetchせずに調べた同期状態は古い可能性がある
+        if repo.sync_stale && (repo.needs_pull || repo.needs_push) {
+            let flag = self.message(Message::Offline);
+            remote_status.push_str(&format!(" {}", flag.dimmed()));
+        }
+
+        // ステータスを取得できなかった場合の表示（変更なしに見えないようにする）
+        if let Some(ref status_error) = repo.status_error {
+            let marker = format!("{} status {}", icons.warning, status_error.code);
+            remote_status.push_str(&format!(" {}", marker.red().bold()));
+        }
+
+        // 途中で止まっている操作の表示
+        if let Some(operation) = repo.operation {
+            let operation = self.message(Message::InProgress(&operation.to_string()));
+            let marker = format!("{} {operation}", icons.warning);
+            remote_status.push_str(&format!(" {}", marker.red().bold()));
+        }
+
+        // ロックファイルが残っている場合の表示
+        if !repo.locks.is_empty() {
+            let marker = format!("{} {}", icons.warning, self.message(Message::Locked));
+            remote_status.push_str(&format!(" {}", marker.yellow()));
+        }
+
+        // fetchに失敗した場合の表示
+        if let Some(ref fetch_error) = repo.fetch_error {
+            let marker = format!("{} fetch {}", icons.warning, fetch_error.code);
+            remote_status.push_str(&format!(" {}", marker.yellow()));
+        }
+
+        // 比較したリモートから遅れている場合の表示
+        if let Some(ref comparison) = repo.remote_comparison {
+            if comparison.behind > 0 {
+                let behind = self.message(Message::BehindRemote {
+                    behind: comparison.behind,
+                    remote: &comparison.remote_branch,
+                });
+                remote_status.push_str(&format!(" {behind}"));
+            }
+        }
+
+        // 長く放置された変更の表示
+        if let Some(age) = self.stale_dirty_age(repo) {
+            let flag = self.message(Message::DirtyFor(&format_age(age)));
+            remote_status.push_str(&format!(" {}", flag.red().bold()));
+        }
+
+        // 表示している状態がどれだけ前のものか（watchモード）
+        if let (Some(now), Some(checked_at)) = (self.checked_ages_at, repo.checked_at) {
+            let age = now.duration_since(checked_at).unwrap_or_default();
+            let age = if age.as_secs() < 60 {
+                format!("{}s", age.as_secs())
+            } else {
+                format_age(age)
+            };
+            let flag = self.message(Message::CheckedAgo(&age));
+            remote_status.push_str(&format!(" {}", flag.dimmed()));
+        }
+
+        // ディスク使用量と無視された大きなディレクトリの表示
+        if let Some(disk_usage) = repo.disk_usage {
+            let flag = self.message(Message::OnDisk(&format_size(disk_usage)));
+            remote_status.push_str(&format!(" {flag}"));
+        }
+        if repo.artifacts_size() > 0 {
+            let flag = self.message(Message::ArtifactsSize(&format_size(repo.artifacts_size())));
+            remote_status.push_str(&format!(" {}", flag.yellow()));
         }
 
         if self.verbose {
             // Verbose mode shows additional details like specific changed files
+            let path_label = self.field(Field::Path);
+            let path = self.fit_path(&path, measure_text_width(&format!("  {path_label} ")));
             let mut result = format!(
-                "{name} [{branch}]{remote_status} ({files_count} changed files)\n  Path: {path}"
+                "{} [{branch}]{remote_status} ({changed_files})\n  {path_label} {}",
+                self.link_name(repo, &name),
+                self.link_path(repo, &path)
             );
 
             if let Some(ref remote_branch) = repo.remote_branch {
-                result.push_str(&format!("\n  Remote: {remote_branch}"));
+                result.push_str(&format!(
+                    "\n  {} {remote_branch}",
+                    self.field(Field::Remote)
+                ));
+            }
+
+            if let Some(ref web_url) = repo.web_url {
+                result.push_str(&format!("\n  {} {web_url}", self.field(Field::Web)));
+            }
+
+            if let Some(project_type) = repo.project_type {
+                result.push_str(&format!("\n  {} {project_type}", self.field(Field::Type)));
+            }
+
+            if let Some(ref git_dir) = repo.git_dir {
+                result.push_str(&format!(
+                    "\n  {} {}",
+                    self.field(Field::GitDir),
+                    git_dir.display()
+                ));
+            }
+
+            if let Some(ref status_error) = repo.status_error {
+                result.push_str(&format!(
+                    "\n  {} {}",
+                    self.field(Field::StatusError),
+                    status_error.message
+                ));
+            }
+
+            if let Some(operation) = repo.operation {
+                result.push_str(&format!(
+                    "\n  {} {operation}",
+                    self.field(Field::OperationInProgress)
+                ));
+            }
+
+            for lock in &repo.locks {
+                let locked = self.message(Message::RepositoryLocked(&lock.describe()));
+                result.push_str(&format!("\n  {locked}"));
+            }
+
+            if let Some(ref fetch_error) = repo.fetch_error {
+                result.push_str(&format!(
+                    "\n  {} {}",
+                    self.field(Field::FetchError),
+                    fetch_error.message
+                ));
+            }
+
+            if let Some(ref comparison_error) = repo.comparison_error {
+                result.push_str(&format!(
+                    "\n  {} {}",
+                    self.field(Field::ComparisonError),
+                    comparison_error.message
+                ));
             }
 
-            if repo.needs_pull || repo.needs_push {
-                result.push_str("\n  Sync status: ");
-                if repo.needs_pull && repo.needs_push {
-                    result.push_str("needs pull and push");
-                } else if repo.needs_pull {
-                    result.push_str("needs pull");
-                } else if repo.needs_push {
-                    result.push_str("needs push");
+            if let Some(ref comparison) = repo.remote_comparison {
+                let compared = self.message(Message::ComparedTo {
+                    remote: &comparison.remote_branch,
+                    ahead: comparison.ahead,
+                    behind: comparison.behind,
+                });
+                result.push_str(&format!("\n  {compared}"));
+            }
+
+            if let Some(dirty_since) = repo.dirty_since {
+                result.push_str(&format!(
+                    "\n  {} {}",
+                    self.field(Field::DirtySince),
+                    format_utc(dirty_since)
+                ));
+            }
+
+            if let Some(last_commit_at) = repo.last_commit_at {
+                result.push_str(&format!(
+                    "\n  {} {}",
+                    self.field(Field::LastCommit),
+                    format_utc(last_commit_at)
+                ));
+            }
+
+            if let Some(staleness) = repo.staleness {
+                result.push_str(&format!("\n  {} {staleness}", self.field(Field::Staleness)));
+            }
+
+            if let Some(disk_usage) = repo.disk_usage {
+                result.push_str(&format!(
+                    "\n  {} {}",
+                    self.field(Field::DiskUsage),
+                    format_size(disk_usage)
+                ));
+            }
+
+            if let Some(artifacts) = repo.artifacts.as_ref().filter(|a| !a.is_empty()) {
+                result.push_str(&format!("\n  {}", self.field(Field::IgnoredArtifacts)));
+                for artifact in artifacts {
+                    let size = format_size(artifact.size_bytes);
+                    result.push_str(&format!("\n    {} {size}", artifact.path));
+                }
+            }
+
+            if let Some(ref artifacts_error) = repo.artifacts_error {
+                result.push_str(&format!(
+                    "\n  {} {}",
+                    self.field(Field::ArtifactsError),
+                    artifacts_error.message
+                ));
+            }
+
+            if let Some(preview) = repo.diff_preview.as_ref().filter(|p| !p.lines.is_empty()) {
+                result.push_str(&format!("\n  {}", self.field(Field::DiffPreview)));
+                for line in &preview.lines {
+                    let line = if line.starts_with('+') {
+                        line.green().to_string()
+                    } else if line.starts_with('-') {
+                        line.red().to_string()
+                    } else {
+                        line.clone()
+                    };
+                    result.push_str(&format!("\n    {line}"));
+                }
+                if preview.truncated {
+                    result.push_str("\n    ...");
+                }
+            }
+
+            if let Some(ref diff_preview_error) = repo.diff_preview_error {
+                result.push_str(&format!(
+                    "\n  {} {}",
+                    self.field(Field::DiffPreviewError),
+                    diff_preview_error.message
+                ));
+            }
+
+            if repo.needs_pull || repo.needs_push || repo.sync_stale {
+                let state = match (repo.needs_pull, repo.needs_push) {
+                    (true, true) => SyncState::NeedsPullAndPush,
+                    (true, false) => SyncState::NeedsPull,
+                    (false, true) => SyncState::NeedsPush,
+                    (false, false) => SyncState::UpToDate,
+                };
+                result.push_str(&format!("\n  {}", self.message(Message::SyncStatus(state))));
+                if repo.sync_stale {
+                    result.push_str(&format!(" {}", self.message(Message::SyncMayBeStale)));
+                }
+            }
+
+            if !repo.branches.is_empty() {
+                result.push_str(&format!("\n  {}", self.field(Field::Branches)));
+                for branch in &repo.branches {
+                    let marker = if repo.current_branch.as_deref() == Some(branch.name.as_str()) {
+                        "*"
+                    } else {
+                        " "
+                    };
+                    let sync = branch.sync_summary();
+                    result.push_str(&format!("\n    {marker} {}: {sync}", branch.name));
+                }
+            }
+
+            if self.show_remote_only_branches && !repo.remote_only_branches.is_empty() {
+                result.push_str(&format!("\n  {}", self.field(Field::RemoteOnlyBranches)));
+                for branch in &repo.remote_only_branches {
+                    result.push_str(&format!("\n    {branch}"));
                 }
             }
 
             if !repo.changed_files.is_empty() {
-                result.push_str("\n  Changed files:");
-                for file in &repo.changed_files {
-                    result.push_str(&format!("\n    {file}"));
+                result.push_str(&format!("\n  {}", self.field(Field::ChangedFiles)));
+                result.push_str(&self.format_changed_files(&repo.changed_files));
+                if repo.changed_files_truncated {
+                    let truncated =
+                        self.message(Message::ChangedFilesTruncated(repo.changed_files.len()));
+                    result.push_str(&format!("\n    {truncated}"));
                 }
             }
             result
         } else {
             // Default mode shows essential information
-            format!("{name} [{branch}]{remote_status} ({files_count} changed files) - {path}")
+            let summary = format!(" [{branch}]{remote_status} ({changed_files}) - ");
+            // 幅はリンクの制御文字を含めずに測る
+            let path = self.fit_path(&path, measure_text_width(&format!("{name}{summary}")));
+            format!(
+                "{}{summary}{}",
+                self.link_name(repo, &name),
+                self.link_path(repo, &path)
+            )
+        }
+    }
+}
+
+/// ホームディレクトリ配下のパスを `~` で始まる形に短縮する
+fn abbreviate_home(path: &str) -> String {
+    match std::env::var("HOME") {
+        Ok(home) if !home.is_empty() && home != "/" => match path.strip_prefix(&home) {
+            Some(rest) if rest.is_empty() || rest.starts_with('/') => format!("~{rest}"),
+            _ => path.to_string(),
+        },
+        _ => path.to_string(),
+    }
+}
+
+/// パスが `max` 桁に収まらない場合に中間を省略する（例: `~/src/…/very-long-name`）
+fn truncate_path_middle(path: &str, max: usize) -> String {
+    if measure_text_width(path) <= max {
+        return path.to_string();
+    }
+
+    let components: Vec<&str> = path.split('/').collect();
+    if components.len() > 3 {
+        // 先頭2要素を残し、末尾は収まる限り多くの要素を残す
+        let head = components[..2].join("/");
+        let mut tail = components[components.len() - 1].to_string();
+        let fits = |tail: &str| measure_text_width(&format!("{head}/…/{tail}")) <= max;
+        if fits(&tail) {
+            for component in components[2..components.len() - 1].iter().rev() {
+                let longer = format!("{component}/{tail}");
+                if !fits(&longer) {
+                    break;
+                }
+                tail = longer;
+            }
+            return format!("{head}/…/{tail}");
+        }
+    }
+
+    // それでも収まらなければ末尾を優先して残す
+    let mut kept = Vec::new();
+    let mut kept_width = 0;
+    for c in path.chars().rev() {
+        let w = measure_text_width(c.encode_utf8(&mut [0; 4]));
+        if kept_width + w + 1 > max {
+            break;
+        }
+        kept_width += w;
+        kept.push(c);
+    }
+    let tail: String = kept.into_iter().rev().collect();
+    format!("…{tail}")
+}
+
+/// 字下げを保ったまま `width` 桁で折り返す（続きの行はさらに2桁字下げする）
+fn wrap_with_indent(indent: &str, text: &str, width: usize) -> String {
+    let continuation = format!("{indent}  ");
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let mut current_width = 0;
+    let mut limit = width.saturating_sub(indent.len()).max(1);
+
+    for c in text.chars() {
+        let w = measure_text_width(c.encode_utf8(&mut [0; 4]));
+        if current_width + w > limit && !current.is_empty() {
+            lines.push(std::mem::take(&mut current));
+            current_width = 0;
+            limit = width.saturating_sub(continuation.len()).max(1);
         }
+        current.push(c);
+        current_width += w;
     }
+    lines.push(current);
+
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let prefix = if i == 0 {
+                indent
+            } else {
+                continuation.as_str()
+            };
+            format!("{prefix}{line}")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 #[cfg(test)]
@@ -152,7 +878,7 @@ mod tests {
 
     #[test]
     fn test_format_repositories_empty() {
-        let formatter = OutputFormatter::new(false, "text".to_string());
+        let formatter = OutputFormatter::new(false, OutputFormat::Text);
         let repositories = Vec::new();
 
         let result = formatter.format_repositories(&repositories);
@@ -161,7 +887,7 @@ mod tests {
 
     #[test]
     fn test_format_repositories_single_clean() {
-        let formatter = OutputFormatter::new(false, "text".to_string());
+        let formatter = OutputFormatter::new(false, OutputFormat::Text);
         let repositories = vec![create_test_repository("clean_repo", false, Some("main"), 0)];
 
         let result = formatter.format_repositories(&repositories);
@@ -172,7 +898,7 @@ mod tests {
 
     #[test]
     fn test_format_repositories_single_with_changes() {
-        let formatter = OutputFormatter::new(false, "text".to_string());
+        let formatter = OutputFormatter::new(false, OutputFormat::Text);
         let repositories = vec![create_test_repository(
             "dirty_repo",
             true,
@@ -187,7 +913,7 @@ mod tests {
 
     #[test]
     fn test_format_repositories_multiple() {
-        let formatter = OutputFormatter::new(false, "text".to_string());
+        let formatter = OutputFormatter::new(false, OutputFormat::Text);
         let repositories = vec![
             create_test_repository("repo1", false, Some("main"), 0),
             create_test_repository("repo2", true, Some("feature"), 2),
@@ -205,7 +931,7 @@ mod tests {
 
     #[test]
     fn test_format_repositories_verbose_mode() {
-        let formatter = OutputFormatter::new(true, "text".to_string());
+        let formatter = OutputFormatter::new(true, OutputFormat::Text);
         let repositories = vec![
             create_test_repository("repo1", false, Some("main"), 0),
             create_test_repository("repo2", true, Some("feature/test"), 5),
@@ -227,7 +953,7 @@ mod tests {
 
     #[test]
     fn test_format_repository_default_mode() {
-        let formatter = OutputFormatter::new(false, "text".to_string());
+        let formatter = OutputFormatter::new(false, OutputFormat::Text);
         let repo = create_test_repository("test_repo", true, Some("main"), 3);
 
         let result = formatter.format_repository(&repo);
@@ -239,7 +965,7 @@ mod tests {
 
     #[test]
     fn test_format_repository_verbose_mode() {
-        let formatter = OutputFormatter::new(true, "text".to_string());
+        let formatter = OutputFormatter::new(true, OutputFormat::Text);
         let repo = create_test_repository("test_repo", true, Some("develop"), 7);
 
         let result = formatter.format_repository(&repo);
@@ -248,9 +974,315 @@ mod tests {
         assert!(result.contains("(7 changed files)"));
     }
 
+    #[test]
+    fn test_format_repository_verbose_lists_branches() {
+        use crate::git::BranchStatus;
+
+        let formatter = OutputFormatter::new(true, OutputFormat::Text);
+        let repo = create_test_repository("test_repo", false, Some("main"), 0).with_branches(
+            vec![
+                BranchStatus {
+                    name: "feature".to_string(),
+                    upstream: Some("origin/feature".to_string()),
+                    ahead: 3,
+                    behind: 1,
+                },
+                BranchStatus {
+                    name: "main".to_string(),
+                    upstream: Some("origin/main".to_string()),
+                    ahead: 0,
+                    behind: 0,
+                },
+                BranchStatus {
+                    name: "scratch".to_string(),
+                    upstream: None,
+                    ahead: 0,
+                    behind: 0,
+                },
+            ],
+            Vec::new(),
+        );
+
+        let result = formatter.format_repository(&repo);
+        assert!(result.contains("\n  Branches:"));
+        assert!(result.contains("\n      feature: ahead 3, behind 1 of origin/feature"));
+        assert!(result.contains("\n    * main: up to date with origin/main"));
+        assert!(result.contains("\n      scratch: no upstream"));
+
+        let simple = OutputFormatter::new(false, OutputFormat::Text);
+        assert!(!simple.format_repository(&repo).contains("Branches:"));
+    }
+
+    #[test]
+    fn test_format_repository_shows_remote_comparison() {
+        use crate::git::RemoteComparison;
+
+        let repo = create_test_repository("fork", false, Some("main"), 0).with_remote_comparison(
+            Some(RemoteComparison {
+                remote_branch: "upstream/main".to_string(),
+                ahead: 1,
+                behind: 42,
+            }),
+        );
+
+        let simple = OutputFormatter::new(false, OutputFormat::Text);
+        assert!(simple
+            .format_repository(&repo)
+            .contains("[main] (42 behind upstream/main)"));
+
+        let verbose = OutputFormatter::new(true, OutputFormat::Text);
+        assert!(verbose
+            .format_repository(&repo)
+            .contains("\n  Compared to upstream/main: ahead 1, behind 42"));
+    }
+
+    #[test]
+    fn test_format_repository_marks_lock_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let git_dir = temp_dir.path().join(".git");
+        std::fs::create_dir(&git_dir).unwrap();
+        std::fs::write(git_dir.join("index.lock"), "").unwrap();
+        let locks = crate::git::find_lock_files(temp_dir.path());
+
+        let repo = create_test_repository("locked", false, Some("main"), 0).with_locks(locks);
+
+        let simple = OutputFormatter::new(false, OutputFormat::Text);
+        assert!(simple.format_repository(&repo).contains("⚠ locked"));
+
+        let verbose = OutputFormatter::new(true, OutputFormat::Text);
+        assert!(verbose
+            .format_repository(&repo)
+            .contains("\n  Repository locked (index.lock present, 0 minutes old)"));
+    }
+
+    #[test]
+    fn test_format_repository_marks_operation_in_progress() {
+        use crate::git::InProgressOperation;
+
+        colored::control::set_override(false);
+        let repo = create_test_repository("bisecting", false, Some("main"), 0)
+            .with_operation(Some(InProgressOperation::Bisect));
+
+        let simple = OutputFormatter::new(false, OutputFormat::Text);
+        assert!(simple
+            .format_repository(&repo)
+            .contains("⚠ bisect in progress"));
+
+        let verbose = OutputFormatter::new(true, OutputFormat::Text);
+        assert!(verbose
+            .format_repository(&repo)
+            .contains("\n  Operation in progress: bisect"));
+
+        let report = simple.format_report(&[repo]);
+        assert!(report.contains("bisecting [main]: bisect in progress"));
+    }
+
+    #[test]
+    fn test_format_repository_marks_status_error() {
+        use crate::error::{ErrorCode, ErrorReport};
+
+        let repo =
+            create_test_repository("broken", false, None, 0).with_status_error(Some(ErrorReport {
+                code: ErrorCode::GitOperation,
+                message: "failed to read index".to_string(),
+            }));
+
+        let simple = OutputFormatter::new(false, OutputFormat::Text);
+        assert!(simple
+            .format_repository(&repo)
+            .contains("⚠ status git_operation"));
+
+        let verbose = OutputFormatter::new(true, OutputFormat::Text);
+        assert!(verbose
+            .format_repository(&repo)
+            .contains("\n  Status error: failed to read index"));
+
+        let report = simple.format_report(&[repo]);
+        assert!(report.contains("broken [unknown]: status unavailable"));
+    }
+
+    #[test]
+    fn test_plain_output_spells_out_states() {
+        colored::control::set_override(false);
+        let repo = create_test_repository("app", true, Some("main"), 3).with_remote_info(
+            false,
+            true,
+            Some("origin/main".to_string()),
+        );
+        let long_path = format!("/{}/app", "very-long-directory".repeat(5));
+        let repo = Repository {
+            path: long_path.clone().into(),
+            ..repo
+        };
+
+        let formatter = OutputFormatter::new(false, OutputFormat::Text)
+            .with_width(Some(40))
+            .with_plain(true);
+        let line = formatter.format_repository(&repo);
+        assert!(line.contains("[needs push]"), "{line}");
+        assert!(line.contains("(3 changed files)"));
+        assert!(line.ends_with(&long_path));
+        assert!(line.is_ascii());
+    }
+
+    #[test]
+    fn test_format_repository_marks_fetch_error() {
+        use crate::error::{ErrorCode, ErrorReport};
+
+        let repo = create_test_repository("offline", false, Some("main"), 0).with_fetch_error(
+            Some(ErrorReport {
+                code: ErrorCode::Timeout,
+                message: "Operation timed out after 5s for 'offline'".to_string(),
+            }),
+        );
+
+        let simple = OutputFormatter::new(false, OutputFormat::Text);
+        assert!(simple.format_repository(&repo).contains("⚠ fetch timeout"));
+
+        let verbose = OutputFormatter::new(true, OutputFormat::Text);
+        assert!(verbose
+            .format_repository(&repo)
+            .contains("\n  Fetch error: Operation timed out after 5s for 'offline'"));
+
+        let json = OutputFormatter::new(false, OutputFormat::Json).format_repositories(&[repo]);
+        assert!(json.contains("\"code\": \"timeout\""));
+    }
+
+    #[test]
+    fn test_format_repository_icon_styles() {
+        let repo = create_test_repository_with_remote(
+            "icon_repo",
+            false,
+            Some("main"),
+            0,
+            true,
+            true,
+            Some("origin/main"),
+        );
+
+        let unicode = OutputFormatter::new(false, OutputFormat::Text);
+        assert!(unicode.format_repository(&repo).contains("[main] [↑↓]"));
+
+        let ascii = OutputFormatter::new(false, OutputFormat::Text).with_icons(IconStyle::Ascii);
+        assert!(ascii.format_repository(&repo).contains("[main] [^v]"));
+
+        let nerdfont =
+            OutputFormatter::new(false, OutputFormat::Text).with_icons(IconStyle::Nerdfont);
+        let result = nerdfont.format_repository(&repo);
+        assert!(result.starts_with("\u{e702} "));
+        assert!(result.contains("[\u{e0a0} main] [\u{f0ee}\u{f0ed}]"));
+    }
+
+    #[test]
+    fn test_truncate_path_middle() {
+        assert_eq!(truncate_path_middle("~/src/short", 20), "~/src/short");
+        assert_eq!(
+            truncate_path_middle("~/src/github.com/someone/very-long-name", 30),
+            "~/src/…/someone/very-long-name"
+        );
+        assert_eq!(
+            truncate_path_middle("~/src/github.com/someone/very-long-name", 22),
+            "~/src/…/very-long-name"
+        );
+        assert_eq!(
+            truncate_path_middle("/opt/a/b/an-extremely-long-repository-name", 12),
+            "…sitory-name"
+        );
+    }
+
+    #[test]
+    fn test_wrap_with_indent() {
+        assert_eq!(
+            wrap_with_indent("    ", "?? short.txt", 40),
+            "    ?? short.txt"
+        );
+        assert_eq!(
+            wrap_with_indent("    ", "?? abcdefghijklmnop", 12),
+            "    ?? abcde\n      fghijk\n      lmnop"
+        );
+    }
+
+    #[test]
+    fn test_format_repository_fits_width() {
+        let formatter = OutputFormatter::new(false, OutputFormat::Text).with_width(Some(110));
+        let repo = Repository::new(PathBuf::from(
+            "/nonexistent/workspace/github.com/someone/a-repository-with-a-long-name",
+        ))
+        .with_git_info(false, Some("main".to_string()), vec![]);
+
+        let result = formatter.format_repository(&repo);
+        assert!(measure_text_width(&result) <= 110, "{result}");
+        assert!(
+            result.ends_with("/nonexistent/…/someone/a-repository-with-a-long-name"),
+            "{result}"
+        );
+
+        let unlimited = OutputFormatter::new(false, OutputFormat::Text);
+        assert!(unlimited
+            .format_repository(&repo)
+            .ends_with("/nonexistent/workspace/github.com/someone/a-repository-with-a-long-name"));
+    }
+
+    #[test]
+    fn test_format_repository_hyperlinks() {
+        let repo = Repository::new(PathBuf::from("/nonexistent/src/api"))
+            .with_git_info(false, Some("main".to_string()), vec![])
+            .with_web_url(Some("https://github.com/acme/api".to_string()));
+
+        let plain = OutputFormatter::new(false, OutputFormat::Text).format_repository(&repo);
+        assert!(!plain.contains("\x1b]8;;"));
+
+        let linked = OutputFormatter::new(false, OutputFormat::Text)
+            .with_hyperlinks(true)
+            .format_repository(&repo);
+        assert!(linked.contains("\x1b]8;;https://github.com/acme/api\x1b\\"));
+        assert!(linked.ends_with(
+            "\x1b]8;;file:///nonexistent/src/api\x1b\\/nonexistent/src/api\x1b]8;;\x1b\\"
+        ));
+    }
+
+    #[test]
+    fn test_format_repository_flags_old_changes() {
+        let formatter = OutputFormatter::new(false, OutputFormat::Text)
+            .with_dirty_warn_after(Duration::from_secs(7 * 86_400));
+        let fresh = create_test_repository("fresh", true, Some("main"), 1)
+            .with_dirty_since(Some(SystemTime::now() - Duration::from_secs(86_400)));
+        let old = create_test_repository("old", true, Some("main"), 1)
+            .with_dirty_since(Some(SystemTime::now() - Duration::from_secs(12 * 86_400)));
+
+        assert!(!formatter.format_repository(&fresh).contains("[dirty"));
+        assert!(formatter.format_repository(&old).contains("[dirty 12d]"));
+
+        let verbose = OutputFormatter::new(true, OutputFormat::Text);
+        assert!(verbose
+            .format_repository(&old)
+            .contains("\n  Dirty since: "));
+    }
+
+    #[test]
+    fn test_format_repository_remote_only_branches_opt_in() {
+        let repo = create_test_repository("test_repo", false, Some("main"), 0)
+            .with_branches(Vec::new(), vec!["origin/colleague".to_string()]);
+
+        let formatter = OutputFormatter::new(true, OutputFormat::Text);
+        assert!(!formatter
+            .format_repository(&repo)
+            .contains("origin/colleague"));
+
+        let formatter = formatter.with_remote_only_branches(true);
+        let result = formatter.format_repository(&repo);
+        assert!(
+            result.contains("\n  Remote branches without local checkout:\n    origin/colleague")
+        );
+
+        let simple =
+            OutputFormatter::new(false, OutputFormat::Text).with_remote_only_branches(true);
+        assert!(!simple.format_repository(&repo).contains("origin/colleague"));
+    }
+
     #[test]
     fn test_format_repository_verbose_no_branch() {
-        let formatter = OutputFormatter::new(true, "text".to_string());
+        let formatter = OutputFormatter::new(true, OutputFormat::Text);
         let repo = create_test_repository("test_repo", false, None, 0);
 
         let result = formatter.format_repository(&repo);
@@ -261,8 +1293,8 @@ mod tests {
 
     #[test]
     fn test_formatter_verbose_flag() {
-        let verbose_formatter = OutputFormatter::new(true, "text".to_string());
-        let simple_formatter = OutputFormatter::new(false, "text".to_string());
+        let verbose_formatter = OutputFormatter::new(true, OutputFormat::Text);
+        let simple_formatter = OutputFormatter::new(false, OutputFormat::Text);
 
         assert!(verbose_formatter.verbose);
         assert!(!simple_formatter.verbose);
@@ -270,7 +1302,7 @@ mod tests {
 
     #[test]
     fn test_format_repositories_json() {
-        let formatter = OutputFormatter::new(false, "json".to_string());
+        let formatter = OutputFormatter::new(false, OutputFormat::Json);
         let repositories = vec![
             create_test_repository("repo1", false, Some("main"), 0),
             create_test_repository("repo2", true, Some("feature"), 2),
@@ -288,9 +1320,32 @@ mod tests {
         assert!(result.contains("feature"));
     }
 
+    #[test]
+    fn test_format_repository_marks_offline_sync_status() {
+        let repo = create_test_repository_with_remote(
+            "pull_repo",
+            false,
+            Some("main"),
+            0,
+            true,
+            false,
+            Some("origin/main"),
+        )
+        .with_sync_stale(true);
+
+        let result = OutputFormatter::new(false, OutputFormat::Text).format_repository(&repo);
+        assert!(result.contains("[↓] (offline)"));
+
+        let result = OutputFormatter::new(true, OutputFormat::Text).format_repository(&repo);
+        assert!(result.contains("Sync status: needs pull (not fetched, may be stale)"));
+
+        let result = OutputFormatter::new(false, OutputFormat::Json).format_repositories(&[repo]);
+        assert!(result.contains("\"sync_stale\": true"));
+    }
+
     #[test]
     fn test_format_repositories_json_empty() {
-        let formatter = OutputFormatter::new(false, "json".to_string());
+        let formatter = OutputFormatter::new(false, OutputFormat::Json);
         let repositories = Vec::new();
 
         let result = formatter.format_repositories(&repositories);
@@ -299,7 +1354,7 @@ mod tests {
 
     #[test]
     fn test_format_repository_with_remote_sync() {
-        let formatter = OutputFormatter::new(true, "text".to_string());
+        let formatter = OutputFormatter::new(true, OutputFormat::Text);
 
         // Test repository that needs push
         let repo_needs_push = create_test_repository_with_remote(
@@ -350,9 +1405,149 @@ mod tests {
         assert!(result.contains("needs pull and push"));
     }
 
+    #[test]
+    fn test_format_report_empty_when_nothing_actionable() {
+        let formatter = OutputFormatter::new(false, OutputFormat::Text);
+        let repositories = vec![
+            create_test_repository("clean1", false, Some("main"), 0),
+            create_test_repository("clean2", false, Some("main"), 0),
+        ];
+
+        assert_eq!(formatter.format_report(&repositories), "");
+        assert_eq!(formatter.format_report(&[]), "");
+    }
+
+    #[test]
+    fn test_format_report_lists_actionable_repositories() {
+        let formatter = OutputFormatter::new(false, OutputFormat::Text);
+        let repositories = vec![
+            create_test_repository("clean", false, Some("main"), 0),
+            create_test_repository("dirty", true, Some("main"), 1),
+            create_test_repository_with_remote(
+                "ahead",
+                false,
+                Some("develop"),
+                0,
+                false,
+                true,
+                Some("origin/develop"),
+            ),
+        ];
+
+        let result = formatter.format_report(&repositories);
+        assert!(result.starts_with("pendector: 2 repositories need attention\n"));
+        assert!(result.contains("dirty [main]: 1 changed file\n  /test/dirty\n"));
+        assert!(result.contains("ahead [develop]: needs push\n  /test/ahead\n"));
+        assert!(!result.contains("clean"));
+    }
+
+    #[test]
+    fn test_format_changed_files_groups_by_directory() {
+        let formatter = OutputFormatter::new(true, OutputFormat::Text);
+        let files = vec![
+            " M src/main.rs".to_string(),
+            " D README.md".to_string(),
+            "?? src/new.rs".to_string(),
+            "A  docs/guide.md".to_string(),
+        ];
+
+        let result = formatter.format_changed_files(&files);
+        // 色の有無は他のテストの設定に左右されるので、取り除いてから比べる
+        assert_eq!(
+            console::strip_ansi_codes(&result),
+            "\n    deleted   README.md\n    docs/\n      new       guide.md\n    src/\n      modified  main.rs\n      new       new.rs"
+        );
+    }
+
+    #[test]
+    fn test_format_changed_files_untracked_directory() {
+        let formatter = OutputFormatter::new(true, OutputFormat::Text);
+        let files = vec!["?? sub/".to_string(), "?? src/gen/".to_string()];
+
+        let result = formatter.format_changed_files(&files);
+        assert_eq!(
+            console::strip_ansi_codes(&result),
+            "\n    new  sub/\n    src/\n      new  gen/"
+        );
+    }
+
+    #[test]
+    fn test_format_changed_files_rename() {
+        let formatter = OutputFormatter::new(true, OutputFormat::Text);
+        let files = vec![
+            "R  src/a.rs -> src/b.rs".to_string(),
+            "R  old/c.rs -> src/c.rs".to_string(),
+        ];
+
+        let result = formatter.format_changed_files(&files);
+        assert_eq!(
+            console::strip_ansi_codes(&result),
+            "\n    src/\n      renamed  a.rs -> b.rs\n      renamed  old/c.rs -> c.rs"
+        );
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    }
+
+    #[test]
+    fn test_format_repositories_shows_disk_usage_and_artifacts() {
+        colored::control::set_override(false);
+        let formatter = OutputFormatter::new(true, OutputFormat::Text);
+        let repo = create_test_repository("cleanup", false, Some("main"), 0)
+            .with_disk_usage(Some(5 * 1024 * 1024))
+            .with_artifacts(Some(vec![crate::git::Artifact {
+                path: "target/".to_string(),
+                size_bytes: 4 * 1024 * 1024,
+            }]));
+
+        let result = formatter.format_repositories(&[repo]);
+        assert!(result.contains("(5.0 MiB on disk)"));
+        assert!(result.contains("(artifacts 4.0 MiB)"));
+        assert!(result.contains("  Ignored artifacts:\n    target/ 4.0 MiB"));
+        assert!(result.contains("Ignored artifacts: 4.0 MiB across 1 repositories"));
+    }
+
+    #[test]
+    fn test_format_repository_shows_diff_preview() {
+        colored::control::set_override(false);
+        let formatter = OutputFormatter::new(true, OutputFormat::Text);
+        let repo = create_test_repository("draft", true, Some("main"), 1).with_diff_preview(Some(
+            crate::git::DiffPreview {
+                lines: vec![
+                    "@@ -1 +1 @@".to_string(),
+                    "-old".to_string(),
+                    "+new".to_string(),
+                ],
+                truncated: true,
+            },
+        ));
+
+        let result = formatter.format_repositories(&[repo]);
+        assert!(result.contains("  Diff preview:\n    @@ -1 +1 @@\n    -old\n    +new\n    ..."));
+    }
+
+    #[test]
+    fn test_format_repository_shows_checked_age() {
+        colored::control::set_override(false);
+        let now = SystemTime::now();
+        let repo = create_test_repository("watched", false, Some("main"), 0)
+            .with_checked_at(Some(now - Duration::from_secs(42)));
+
+        let formatter = OutputFormatter::new(false, OutputFormat::Text);
+        assert!(!formatter.format_repository(&repo).contains("checked"));
+        let formatter = formatter.with_checked_ages_at(now);
+        assert!(formatter
+            .format_repository(&repo)
+            .contains("(checked 42s ago)"));
+    }
+
     #[test]
     fn test_format_repository_no_remote() {
-        let formatter = OutputFormatter::new(false, "text".to_string());
+        let formatter = OutputFormatter::new(false, OutputFormat::Text);
         let repo = create_test_repository("local_repo", false, Some("main"), 0);
 
         let result = formatter.format_repository(&repo);
@@ -0,0 +1,58 @@
+use crate::core::Repository;
+use crate::path_style::canonicalize_path;
+use std::path::Path;
+
+/// OSC 8 のハイパーリンクで `text` を囲む（対応する端末ではクリックで `url` を開ける）
+pub fn hyperlink(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\")
+}
+
+/// ローカルパスの `file://` URL（URLに使えない文字はパーセントエンコードする）
+pub fn file_url(path: &Path) -> String {
+    let path = canonicalize_path(path);
+    let mut url = String::from("file://");
+    for byte in path.to_string_lossy().replace('\\', "/").bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                url.push(byte as char)
+            }
+            _ => url.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    if !url.starts_with("file:///") {
+        // Windowsのドライブレター（`C:/...`）の前にも `/` を置く
+        url.insert(7, '/');
+    }
+    url
+}
+
+/// リポジトリ名のリンク先（フォージのページがわかればそこ、なければ作業ツリー）
+pub fn repository_url(repo: &Repository) -> String {
+    match repo.web_url {
+        Some(ref web_url) => web_url.clone(),
+        None => file_url(&repo.path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_hyperlink_and_urls() {
+        assert_eq!(
+            hyperlink("https://example.com", "repo"),
+            "\x1b]8;;https://example.com\x1b\\repo\x1b]8;;\x1b\\"
+        );
+        assert_eq!(
+            file_url(Path::new("/nonexistent/my repo#1")),
+            "file:///nonexistent/my%20repo%231"
+        );
+
+        let repo = Repository::new(PathBuf::from("/nonexistent/api"));
+        assert_eq!(repository_url(&repo), "file:///nonexistent/api");
+        let repo = repo.with_web_url(Some("https://github.com/acme/api".to_string()));
+        assert_eq!(repository_url(&repo), "https://github.com/acme/api");
+    }
+}
@@ -0,0 +1,123 @@
+use crate::output::Locale;
+use serde::{Deserialize, Serialize};
+
+/// テキスト出力で使う記号の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum IconStyle {
+    /// Unicodeの矢印（既定）
+    #[default]
+    Unicode,
+    /// ASCII文字のみ
+    Ascii,
+    /// Nerd Fontのグリフ（対応フォントが必要）
+    Nerdfont,
+}
+
+/// 表示に使う記号のセット
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Icons {
+    /// バージョン管理システムの種類（Gitリポジトリ）
+    pub vcs: &'static str,
+    /// ブランチ名の前に付ける記号
+    pub branch: &'static str,
+    /// push が必要
+    pub push: &'static str,
+    /// pull が必要
+    pub pull: &'static str,
+    /// push と pull の両方が必要
+    pub diverged: &'static str,
+    /// 警告（fetchの失敗など）
+    pub warning: &'static str,
+}
+
+impl Icons {
+    /// 記号の代わりに指定した言語の言葉で表す（`--plain`、スクリーンリーダー向け）
+    pub fn words(locale: Locale) -> Self {
+        match locale {
+            Locale::En => Icons {
+                vcs: "",
+                branch: "",
+                push: "needs push",
+                pull: "needs pull",
+                diverged: "needs pull and push",
+                warning: "warning:",
+            },
+            Locale::Ja => Icons {
+                vcs: "",
+                branch: "",
+                push: "pushが必要",
+                pull: "pullが必要",
+                diverged: "pullとpushが必要",
+                warning: "警告:",
+            },
+        }
+    }
+}
+
+impl IconStyle {
+    pub fn icons(self) -> Icons {
+        match self {
+            IconStyle::Unicode => Icons {
+                vcs: "",
+                branch: "",
+                push: "↑",
+                pull: "↓",
+                diverged: "↑↓",
+                warning: "⚠",
+            },
+            IconStyle::Ascii => Icons {
+                vcs: "",
+                branch: "",
+                push: "^",
+                pull: "v",
+                diverged: "^v",
+                warning: "!",
+            },
+            IconStyle::Nerdfont => Icons {
+                vcs: "\u{e702} ",
+                branch: "\u{e0a0} ",
+                push: "\u{f0ee}",
+                pull: "\u{f0ed}",
+                diverged: "\u{f0ee}\u{f0ed}",
+                warning: "\u{f071}",
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ascii_icons_are_ascii() {
+        let icons = IconStyle::Ascii.icons();
+        for icon in [
+            icons.vcs,
+            icons.branch,
+            icons.push,
+            icons.pull,
+            icons.diverged,
+            icons.warning,
+        ] {
+            assert!(icon.is_ascii());
+        }
+    }
+
+    #[test]
+    fn test_words_follow_locale() {
+        assert_eq!(Icons::words(Locale::En).push, "needs push");
+        assert_eq!(Icons::words(Locale::Ja).diverged, "pullとpushが必要");
+    }
+
+    #[test]
+    fn test_icon_style_from_config_value() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            icons: IconStyle,
+        }
+        let wrapper: Wrapper = toml::from_str("icons = \"nerdfont\"").unwrap();
+        assert_eq!(wrapper.icons, IconStyle::Nerdfont);
+    }
+}
@@ -0,0 +1,725 @@
+use clap::ValueEnum;
+
+/// 出力メッセージの言語
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Locale {
+    /// 英語
+    #[default]
+    En,
+    /// 日本語
+    Ja,
+}
+
+impl Locale {
+    /// 環境変数（`LC_ALL`、`LC_MESSAGES`、`LANG` の順）から言語を決める（`ja_JP.UTF-8` などなら日本語）
+    pub fn from_env() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .map_or_else(Self::default, |value| Self::from_locale_name(&value))
+    }
+
+    /// ロケール名（`ja_JP.UTF-8`、`en_US` など）から言語を決める（対応していなければ英語）
+    pub fn from_locale_name(name: &str) -> Self {
+        if name.starts_with("ja") {
+            Locale::Ja
+        } else {
+            Locale::En
+        }
+    }
+}
+
+/// リポジトリの同期状態（`Sync status:` の表示）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncState {
+    NeedsPullAndPush,
+    NeedsPull,
+    NeedsPush,
+    UpToDate,
+}
+
+/// `-v` の一覧でリポジトリの詳細に付ける項目名
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Path,
+    Remote,
+    Web,
+    Type,
+    GitDir,
+    StatusError,
+    OperationInProgress,
+    FetchError,
+    ComparisonError,
+    DirtySince,
+    LastCommit,
+    Staleness,
+    DiskUsage,
+    IgnoredArtifacts,
+    ArtifactsError,
+    DiffPreview,
+    DiffPreviewError,
+    Branches,
+    RemoteOnlyBranches,
+    ChangedFiles,
+}
+
+/// 複数のリポジトリに順に適用する操作（対象の選択と、対象がない場合の表示に使う）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BulkAction {
+    Stash,
+    StashPop,
+    Wip,
+    WipUndo,
+    Maintenance,
+    FixLocks,
+    Exec,
+}
+
+/// スキャンルートとして使えない理由
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RootProblem {
+    /// 存在しない
+    Missing,
+    /// ディレクトリではない
+    NotDirectory,
+}
+
+/// ユーザー向けに表示するメッセージ（言語ごとの文言はここにまとめる）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message<'a> {
+    /// リポジトリが1つも見つからなかった
+    NoRepositories,
+    /// 一覧の見出し
+    FoundRepositories { total: usize, with_changes: usize },
+    /// 変更ファイル数（`1000+` のように上限を示す場合もある）
+    ChangedFiles(&'a str),
+    /// 無視された大きなディレクトリの合計
+    IgnoredArtifacts { size: &'a str, repositories: usize },
+    /// `--report` の見出し
+    ReportHeader(usize),
+    /// 複数の場所にクローンされたプロジェクトがない
+    NoDuplicates,
+    /// `--duplicates` の見出し
+    FoundDuplicates(usize),
+    /// プロジェクトのクローンの数
+    Clones(usize),
+    /// 同期状態の見出し
+    SyncStatus(SyncState),
+    /// fetchせずに調べたため同期状態が古い可能性がある
+    SyncMayBeStale,
+    /// エラーメッセージの接頭辞
+    ErrorPrefix,
+    /// 警告メッセージの接頭辞
+    WarningPrefix,
+    /// 設定ファイルを読み込めずにデフォルトの設定で続ける
+    UsingDefaultConfig,
+    /// 読み込めなかった設定ファイルのエラーと、デフォルトの設定で続けること（`json-v2` の警告）
+    ConfigFallback(&'a str),
+    /// `--notify-slack` にWebhookのURLが指定されていない
+    SlackWebhookMissing,
+    /// スキャンルートごとに異なる出力形式が設定されている
+    FormatMismatch { formats: &'a str, using: &'a str },
+    /// 存在しないスキャンルートを飛ばす
+    SkippedMissingPath(&'a str),
+    /// 固定したリポジトリがGitリポジトリではないので飛ばす
+    SkippedPinnedRepository(&'a str),
+    /// gitディレクトリが離れたリポジトリを使えないので飛ばす
+    SkippedDetachedRepository {
+        git_dir: &'a str,
+        work_tree: &'a str,
+    },
+    /// `--recheck` で使う前回の結果を読み込めなかった
+    LastScanUnreadable(&'a str),
+    /// `--recheck` で使う前回の結果がない
+    NoPreviousScan,
+    /// `--recheck` のための結果を保存できなかった
+    LastScanNotSaved(&'a str),
+    /// 探索時間の上限を過ぎてスキャンルートの一部を調べられなかった
+    TimeBudgetExceeded {
+        root: &'a str,
+        budget_secs: u64,
+        repositories: usize,
+        not_descended: usize,
+        not_checked: usize,
+        fetches_abandoned: usize,
+    },
+    /// 権限がなく探索できなかったディレクトリの件数（`-v` で一覧を表示できる）
+    PermissionDenied { root: &'a str, count: usize },
+    /// 権限がなく探索できなかったディレクトリ
+    PermissionDeniedPath(&'a str),
+    /// 探索中に読めなかったパス
+    WalkFailed(&'a str),
+    /// スキャンルートとして使えない
+    InvalidRoot { path: &'a str, problem: RootProblem },
+    /// `-v` の詳細の項目名（`Path:` など）
+    Field(Field),
+    /// fetchせずに調べた同期状態の印
+    Offline,
+    /// 途中で止まっている操作（`rebase` など）
+    InProgress(&'a str),
+    /// ロックファイルが残っている印
+    Locked,
+    /// 残っているロックファイルの説明
+    RepositoryLocked(&'a str),
+    /// 比較したリモートから遅れている
+    BehindRemote { behind: usize, remote: &'a str },
+    /// 比較したリモートとの差
+    ComparedTo {
+        remote: &'a str,
+        ahead: usize,
+        behind: usize,
+    },
+    /// 未コミットの変更が放置されている期間
+    DirtyFor(&'a str),
+    /// 表示している状態を調べてからの時間（watchモード）
+    CheckedAgo(&'a str),
+    /// ディスク使用量
+    OnDisk(&'a str),
+    /// 無視された大きなディレクトリの合計
+    ArtifactsSize(&'a str),
+    /// 変更ファイルの一覧を打ち切った
+    ChangedFilesTruncated(usize),
+    /// 対応が必要な理由：変更ファイルが1件
+    OneChangedFile,
+    /// 対応が必要な理由：pushが必要
+    NeedsPush,
+    /// 対応が必要な理由：pullが必要
+    NeedsPull,
+    /// 対応が必要な理由：状態を取得できなかった
+    StatusUnavailable,
+    /// fetchの集計（`-v`）
+    FetchedRepositories {
+        fetched: usize,
+        attempted: usize,
+        failed: usize,
+    },
+    /// watchモードで設定ファイルを読み直して変わった設定
+    ConfigReloaded(&'a str),
+    /// watchモードで設定ファイルを読み直せなかった
+    ConfigReloadFailed(&'a str),
+    /// 一括操作の対象を選ぶ質問
+    SelectRepositories(BulkAction),
+    /// 一括操作の対象がない
+    NothingToDo(BulkAction),
+    /// 一括操作の対象の選択を取り消した
+    SelectionCancelled,
+    /// 操作の途中にあるリポジトリを一括操作から外した
+    SkippedInOperation {
+        name: &'a str,
+        operation: &'a str,
+        path: &'a str,
+    },
+    /// 変更をstashした
+    Stashed(&'a str),
+    /// stashした変更を戻した
+    Restored(&'a str),
+    /// WIPコミットを取り消して元のブランチに戻した
+    RestoredTo { name: &'a str, base: &'a str },
+    /// 変更をWIPブランチにコミットした
+    CommittedTo { name: &'a str, branch: &'a str },
+    /// `git maintenance run` を実行した
+    RanMaintenance(&'a str),
+    /// 新しいロックファイルは使用中の可能性があるので残した
+    KeptRecentLocks(&'a str),
+    /// 古いロックファイルを削除した
+    RemovedLocks { locks: &'a str, name: &'a str },
+    /// 不要なブランチを削除する予定（`--dry-run`）
+    WouldDeleteBranch { name: &'a str, reason: &'a str },
+    /// 不要なブランチを削除した
+    DeletedBranch { name: &'a str, reason: &'a str },
+    /// 参照・packの統計（`health -v`）
+    RepositoryStats {
+        refs: usize,
+        loose_refs: usize,
+        packs: usize,
+        pack_size: &'a str,
+        loose_objects: usize,
+    },
+    /// gcやmaintenanceで整理したほうがよい理由
+    MaintenanceAdvised(&'a str),
+    /// ロックファイルの残っているリポジトリがない
+    NoLockFiles,
+    /// 同期待ちのリポジトリがない
+    AllInSync,
+    /// 同期の計画で未コミットの変更があることの印
+    UncommittedChanges,
+    /// `exec` のコマンドが失敗したリポジトリの数
+    CommandFailed { failed: usize, total: usize },
+}
+
+impl Message<'_> {
+    /// 指定した言語の文言
+    pub fn text(&self, locale: Locale) -> String {
+        match locale {
+            Locale::En => self.english(),
+            Locale::Ja => self.japanese(),
+        }
+    }
+
+    fn english(&self) -> String {
+        match *self {
+            Message::NoRepositories => "No repositories found.".to_string(),
+            Message::FoundRepositories {
+                total,
+                with_changes: 0,
+            } => format!("Found {total} repositories:"),
+            Message::FoundRepositories {
+                total,
+                with_changes,
+            } => format!("Found {total} repositories ({with_changes} with changes):"),
+            Message::ChangedFiles(count) => format!("{count} changed files"),
+            Message::IgnoredArtifacts { size, repositories } => {
+                format!("Ignored artifacts: {size} across {repositories} repositories")
+            }
+            Message::ReportHeader(1) => "pendector: 1 repository needs attention".to_string(),
+            Message::ReportHeader(count) => {
+                format!("pendector: {count} repositories need attention")
+            }
+            Message::NoDuplicates => "No projects cloned in multiple locations.".to_string(),
+            Message::FoundDuplicates(count) => {
+                format!("Found {count} projects cloned in multiple locations:")
+            }
+            Message::Clones(count) => format!("{count} clones"),
+            Message::SyncStatus(state) => {
+                let state = match state {
+                    SyncState::NeedsPullAndPush => "needs pull and push",
+                    SyncState::NeedsPull => "needs pull",
+                    SyncState::NeedsPush => "needs push",
+                    SyncState::UpToDate => "up to date",
+                };
+                format!("Sync status: {state}")
+            }
+            Message::SyncMayBeStale => "(not fetched, may be stale)".to_string(),
+            Message::ErrorPrefix => "Error".to_string(),
+            Message::WarningPrefix => "Warning".to_string(),
+            Message::UsingDefaultConfig => "Using default configuration".to_string(),
+            Message::ConfigFallback(error) => format!("{error}; using default configuration"),
+            Message::SlackWebhookMissing => "--notify-slack requires webhook URL (use --slack-webhook-url or config file [slack] section)".to_string(),
+            Message::FormatMismatch { formats, using } => format!(
+                "scan roots configure different output formats ({formats}); using '{using}' for all"
+            ),
+            Message::SkippedMissingPath(path) => {
+                format!("Path '{path}' does not exist, skipping")
+            }
+            Message::SkippedPinnedRepository(path) => {
+                format!("pinned repository '{path}' is not a git repository, skipping")
+            }
+            Message::SkippedDetachedRepository { git_dir, work_tree } => format!(
+                "detached repository '{git_dir}' (work tree '{work_tree}') is not usable, skipping"
+            ),
+            Message::LastScanUnreadable(error) => {
+                format!("failed to read the last scan: {error}")
+            }
+            Message::NoPreviousScan => {
+                "no previous scan to recheck, scanning every repository".to_string()
+            }
+            Message::LastScanNotSaved(error) => {
+                format!("failed to save the scan for --recheck: {error}")
+            }
+            Message::PermissionDenied { root, count: 1 } => format!(
+                "skipped 1 directory under '{root}' (permission denied); use -v to list them"
+            ),
+            Message::PermissionDenied { root, count } => format!(
+                "skipped {count} directories under '{root}' (permission denied); use -v to list them"
+            ),
+            Message::PermissionDeniedPath(path) => {
+                format!("skipped '{path}' (permission denied)")
+            }
+            Message::WalkFailed(error) => format!("failed to access path during scan: {error}"),
+            Message::InvalidRoot {
+                path,
+                problem: RootProblem::Missing,
+            } => format!("Path '{path}' does not exist"),
+            Message::InvalidRoot {
+                path,
+                problem: RootProblem::NotDirectory,
+            } => format!("Path '{path}' is not a directory"),
+            Message::Field(field) => match field {
+                Field::Path => "Path:",
+                Field::Remote => "Remote:",
+                Field::Web => "Web:",
+                Field::Type => "Type:",
+                Field::GitDir => "Git dir:",
+                Field::StatusError => "Status error:",
+                Field::OperationInProgress => "Operation in progress:",
+                Field::FetchError => "Fetch error:",
+                Field::ComparisonError => "Comparison error:",
+                Field::DirtySince => "Dirty since:",
+                Field::LastCommit => "Last commit:",
+                Field::Staleness => "Staleness:",
+                Field::DiskUsage => "Disk usage:",
+                Field::IgnoredArtifacts => "Ignored artifacts:",
+                Field::ArtifactsError => "Artifacts error:",
+                Field::DiffPreview => "Diff preview:",
+                Field::DiffPreviewError => "Diff preview error:",
+                Field::Branches => "Branches:",
+                Field::RemoteOnlyBranches => "Remote branches without local checkout:",
+                Field::ChangedFiles => "Changed files:",
+            }
+            .to_string(),
+            Message::Offline => "(offline)".to_string(),
+            Message::InProgress(operation) => format!("{operation} in progress"),
+            Message::Locked => "locked".to_string(),
+            Message::RepositoryLocked(lock) => format!("Repository locked ({lock})"),
+            Message::BehindRemote { behind, remote } => format!("({behind} behind {remote})"),
+            Message::ComparedTo {
+                remote,
+                ahead,
+                behind,
+            } => format!("Compared to {remote}: ahead {ahead}, behind {behind}"),
+            Message::DirtyFor(age) => format!("[dirty {age}]"),
+            Message::CheckedAgo(age) => format!("(checked {age} ago)"),
+            Message::OnDisk(size) => format!("({size} on disk)"),
+            Message::ArtifactsSize(size) => format!("(artifacts {size})"),
+            Message::ChangedFilesTruncated(count) => {
+                format!("... more than {count} changed files, list truncated")
+            }
+            Message::OneChangedFile => "1 changed file".to_string(),
+            Message::NeedsPush => "needs push".to_string(),
+            Message::NeedsPull => "needs pull".to_string(),
+            Message::StatusUnavailable => "status unavailable".to_string(),
+            Message::FetchedRepositories {
+                fetched,
+                attempted,
+                failed,
+            } => format!("Fetched {fetched} of {attempted} repositories ({failed} failed)"),
+            Message::ConfigReloaded(change) => format!("Config reloaded: {change}"),
+            Message::ConfigReloadFailed(error) => {
+                format!("failed to reload config, keeping previous settings: {error}")
+            }
+            Message::SelectRepositories(action) => match action {
+                BulkAction::Stash => "Select repositories to stash",
+                BulkAction::StashPop => "Select repositories to restore",
+                BulkAction::Wip => "Select repositories to commit as wip",
+                BulkAction::WipUndo => "Select repositories to undo",
+                BulkAction::Maintenance => "Select repositories to run git maintenance in",
+                BulkAction::FixLocks => "Select repositories to remove lock files from",
+                BulkAction::Exec => "Select repositories to run the command in",
+            }
+            .to_string(),
+            Message::NothingToDo(action) => match action {
+                BulkAction::Stash => "No repositories with changes to stash",
+                BulkAction::StashPop => "No pendector stashes to restore",
+                BulkAction::Wip => "No repositories with changes to commit",
+                BulkAction::WipUndo => "No wip commits to undo",
+                BulkAction::Maintenance => "No repositories need maintenance",
+                BulkAction::FixLocks => "No lock files found",
+                BulkAction::Exec => "No repositories to run the command in",
+            }
+            .to_string(),
+            Message::SelectionCancelled => "Selection cancelled, nothing was changed".to_string(),
+            Message::SkippedInOperation {
+                name,
+                operation,
+                path,
+            } => format!("Skipped {name}: {operation} in progress ({path})"),
+            Message::Stashed(name) => format!("Stashed {name}"),
+            Message::Restored(name) => format!("Restored {name}"),
+            Message::RestoredTo { name, base } => format!("Restored {name} to {base}"),
+            Message::CommittedTo { name, branch } => format!("Committed {name} to {branch}"),
+            Message::RanMaintenance(name) => format!("Ran git maintenance in {name}"),
+            Message::KeptRecentLocks(name) => {
+                format!("Kept recent lock files in {name} (a git command may still be running)")
+            }
+            Message::RemovedLocks { locks, name } => format!("Removed {locks} from {name}"),
+            Message::WouldDeleteBranch { name, reason } => format!("Would delete {name} ({reason})"),
+            Message::DeletedBranch { name, reason } => format!("Deleted {name} ({reason})"),
+            Message::RepositoryStats {
+                refs,
+                loose_refs,
+                packs,
+                pack_size,
+                loose_objects,
+            } => format!(
+                "refs: {refs} ({loose_refs} loose), packs: {packs} ({pack_size}), loose objects: {loose_objects}"
+            ),
+            Message::MaintenanceAdvised(reason) => {
+                format!("would benefit from git gc / git maintenance run: {reason}")
+            }
+            Message::NoLockFiles => "No lock files found".to_string(),
+            Message::AllInSync => "All repositories are in sync".to_string(),
+            Message::UncommittedChanges => "[uncommitted changes]".to_string(),
+            Message::CommandFailed { failed, total } => {
+                format!("Command failed in {failed} of {total} repositories")
+            }
+            Message::TimeBudgetExceeded {
+                root,
+                budget_secs,
+                repositories,
+                not_descended,
+                not_checked,
+                fetches_abandoned,
+            } => format!(
+                "time budget of {budget_secs}s exceeded for '{root}': checked {repositories} repositories; {not_descended} directories not descended, {not_checked} repositories not checked, {fetches_abandoned} fetches abandoned"
+            ),
+        }
+    }
+
+    fn japanese(&self) -> String {
+        match *self {
+            Message::NoRepositories => "リポジトリが見つかりませんでした。".to_string(),
+            Message::FoundRepositories {
+                total,
+                with_changes: 0,
+            } => format!("{total}件のリポジトリ:"),
+            Message::FoundRepositories {
+                total,
+                with_changes,
+            } => format!("{total}件のリポジトリ（変更あり {with_changes}件）:"),
+            Message::ChangedFiles(count) => format!("変更ファイル {count}件"),
+            Message::IgnoredArtifacts { size, repositories } => {
+                format!("無視された成果物: {repositories}件のリポジトリで合計 {size}")
+            }
+            Message::ReportHeader(count) => {
+                format!("pendector: 対応が必要なリポジトリが{count}件あります")
+            }
+            Message::NoDuplicates => {
+                "複数の場所にクローンされたプロジェクトはありません。".to_string()
+            }
+            Message::FoundDuplicates(count) => {
+                format!("複数の場所にクローンされたプロジェクトが{count}件あります:")
+            }
+            Message::Clones(count) => format!("クローン {count}件"),
+            Message::SyncStatus(state) => {
+                let state = match state {
+                    SyncState::NeedsPullAndPush => "pullとpushが必要",
+                    SyncState::NeedsPull => "pullが必要",
+                    SyncState::NeedsPush => "pushが必要",
+                    SyncState::UpToDate => "最新",
+                };
+                format!("同期状態: {state}")
+            }
+            Message::SyncMayBeStale => "（fetchしていないため古い可能性あり）".to_string(),
+            Message::ErrorPrefix => "エラー".to_string(),
+            Message::WarningPrefix => "警告".to_string(),
+            Message::UsingDefaultConfig => "デフォルトの設定を使います".to_string(),
+            Message::ConfigFallback(error) => format!("{error}（デフォルトの設定を使います）"),
+            Message::SlackWebhookMissing => "--notify-slack にはWebhookのURLが必要です（--slack-webhook-url か設定ファイルの [slack] セクションで指定してください）".to_string(),
+            Message::FormatMismatch { formats, using } => format!(
+                "スキャンルートごとに異なる出力形式が設定されています（{formats}）。すべて '{using}' で出力します"
+            ),
+            Message::SkippedMissingPath(path) => {
+                format!("パス '{path}' が存在しないため飛ばします")
+            }
+            Message::SkippedPinnedRepository(path) => {
+                format!("固定したリポジトリ '{path}' はGitリポジトリではないため飛ばします")
+            }
+            Message::SkippedDetachedRepository { git_dir, work_tree } => format!(
+                "gitディレクトリ '{git_dir}'（作業ツリー '{work_tree}'）のリポジトリは使えないため飛ばします"
+            ),
+            Message::LastScanUnreadable(error) => {
+                format!("前回の結果を読み込めませんでした: {error}")
+            }
+            Message::NoPreviousScan => {
+                "調べ直す前回の結果がないため、すべてのリポジトリを調べます".to_string()
+            }
+            Message::LastScanNotSaved(error) => {
+                format!("--recheck のための結果を保存できませんでした: {error}")
+            }
+            Message::PermissionDenied { root, count } => format!(
+                "'{root}' の配下で権限のない{count}件のディレクトリを飛ばしました（-v で一覧を表示します）"
+            ),
+            Message::PermissionDeniedPath(path) => {
+                format!("権限がないため '{path}' を飛ばしました")
+            }
+            Message::WalkFailed(error) => format!("探索中にパスを読めませんでした: {error}"),
+            Message::InvalidRoot {
+                path,
+                problem: RootProblem::Missing,
+            } => format!("パス '{path}' が存在しません"),
+            Message::InvalidRoot {
+                path,
+                problem: RootProblem::NotDirectory,
+            } => format!("パス '{path}' はディレクトリではありません"),
+            Message::Field(field) => match field {
+                Field::Path => "パス:",
+                Field::Remote => "リモート:",
+                Field::Web => "Web:",
+                Field::Type => "種類:",
+                Field::GitDir => "gitディレクトリ:",
+                Field::StatusError => "状態の取得エラー:",
+                Field::OperationInProgress => "途中の操作:",
+                Field::FetchError => "fetchのエラー:",
+                Field::ComparisonError => "リモートとの比較のエラー:",
+                Field::DirtySince => "変更の開始:",
+                Field::LastCommit => "最後のコミット:",
+                Field::Staleness => "放置の度合い:",
+                Field::DiskUsage => "ディスク使用量:",
+                Field::IgnoredArtifacts => "無視された成果物:",
+                Field::ArtifactsError => "成果物の集計エラー:",
+                Field::DiffPreview => "差分のプレビュー:",
+                Field::DiffPreviewError => "差分のプレビューのエラー:",
+                Field::Branches => "ブランチ:",
+                Field::RemoteOnlyBranches => "ローカルにないリモートブランチ:",
+                Field::ChangedFiles => "変更ファイル:",
+            }
+            .to_string(),
+            Message::Offline => "(オフライン)".to_string(),
+            Message::InProgress(operation) => format!("{operation}の途中"),
+            Message::Locked => "ロック中".to_string(),
+            Message::RepositoryLocked(lock) => {
+                format!("リポジトリがロックされています（{lock}）")
+            }
+            Message::BehindRemote { behind, remote } => format!("({remote} より{behind}件遅れ)"),
+            Message::ComparedTo {
+                remote,
+                ahead,
+                behind,
+            } => format!("{remote} との差: {ahead}件進み、{behind}件遅れ"),
+            Message::DirtyFor(age) => format!("[変更を{age}放置]"),
+            Message::CheckedAgo(age) => format!("({age}前に確認)"),
+            Message::OnDisk(size) => format!("(ディスク使用量 {size})"),
+            Message::ArtifactsSize(size) => format!("(成果物 {size})"),
+            Message::ChangedFilesTruncated(count) => {
+                format!("... 変更ファイルが{count}件を超えたため一覧を打ち切りました")
+            }
+            Message::OneChangedFile => "1件の変更ファイル".to_string(),
+            Message::NeedsPush => "pushが必要".to_string(),
+            Message::NeedsPull => "pullが必要".to_string(),
+            Message::StatusUnavailable => "状態を取得できません".to_string(),
+            Message::FetchedRepositories {
+                fetched,
+                attempted,
+                failed,
+            } => format!("{attempted}件中{fetched}件のリポジトリをfetchしました（失敗 {failed}件）"),
+            Message::ConfigReloaded(change) => format!("設定を読み直しました: {change}"),
+            Message::ConfigReloadFailed(error) => {
+                format!("設定を読み直せなかったため、前の設定を使い続けます: {error}")
+            }
+            Message::SelectRepositories(action) => match action {
+                BulkAction::Stash => "stashするリポジトリを選んでください",
+                BulkAction::StashPop => "元に戻すリポジトリを選んでください",
+                BulkAction::Wip => "wipとしてコミットするリポジトリを選んでください",
+                BulkAction::WipUndo => "取り消すリポジトリを選んでください",
+                BulkAction::Maintenance => "git maintenance を実行するリポジトリを選んでください",
+                BulkAction::FixLocks => "ロックファイルを削除するリポジトリを選んでください",
+                BulkAction::Exec => "コマンドを実行するリポジトリを選んでください",
+            }
+            .to_string(),
+            Message::NothingToDo(action) => match action {
+                BulkAction::Stash => "stashする変更のあるリポジトリはありません",
+                BulkAction::StashPop => "元に戻すpendectorのstashはありません",
+                BulkAction::Wip => "コミットする変更のあるリポジトリはありません",
+                BulkAction::WipUndo => "取り消すwipコミットはありません",
+                BulkAction::Maintenance => "maintenanceが必要なリポジトリはありません",
+                BulkAction::FixLocks => "ロックファイルは見つかりませんでした",
+                BulkAction::Exec => "コマンドを実行するリポジトリはありません",
+            }
+            .to_string(),
+            Message::SelectionCancelled => {
+                "選択を取り消したため、何も変更していません".to_string()
+            }
+            Message::SkippedInOperation {
+                name,
+                operation,
+                path,
+            } => format!("{name} は{operation}の途中のため飛ばしました（{path}）"),
+            Message::Stashed(name) => format!("{name} の変更をstashしました"),
+            Message::Restored(name) => format!("{name} のstashを戻しました"),
+            Message::RestoredTo { name, base } => format!("{name} を {base} に戻しました"),
+            Message::CommittedTo { name, branch } => {
+                format!("{name} の変更を {branch} にコミットしました")
+            }
+            Message::RanMaintenance(name) => format!("{name} で git maintenance を実行しました"),
+            Message::KeptRecentLocks(name) => format!(
+                "{name} の新しいロックファイルは残しました（gitのコマンドが実行中の可能性があります）"
+            ),
+            Message::RemovedLocks { locks, name } => format!("{name} から {locks} を削除しました"),
+            Message::WouldDeleteBranch { name, reason } => {
+                format!("{name} を削除します（{reason}）")
+            }
+            Message::DeletedBranch { name, reason } => format!("{name} を削除しました（{reason}）"),
+            Message::RepositoryStats {
+                refs,
+                loose_refs,
+                packs,
+                pack_size,
+                loose_objects,
+            } => format!(
+                "参照: {refs}件（loose {loose_refs}件）、pack: {packs}件（{pack_size}）、looseオブジェクト: {loose_objects}件"
+            ),
+            Message::MaintenanceAdvised(reason) => {
+                format!("git gc / git maintenance run で整理できます: {reason}")
+            }
+            Message::NoLockFiles => "ロックファイルは見つかりませんでした".to_string(),
+            Message::AllInSync => "すべてのリポジトリが同期済みです".to_string(),
+            Message::UncommittedChanges => "[未コミットの変更あり]".to_string(),
+            Message::CommandFailed { failed, total } => {
+                format!("{total}件中{failed}件のリポジトリでコマンドが失敗しました")
+            }
+            Message::TimeBudgetExceeded {
+                root,
+                budget_secs,
+                repositories,
+                not_descended,
+                not_checked,
+                fetches_abandoned,
+            } => format!(
+                "'{root}' の探索時間の上限 {budget_secs}秒を過ぎました: {repositories}件のリポジトリを調べ、{not_descended}件のディレクトリを探索せず、{not_checked}件のリポジトリを調べず、{fetches_abandoned}件のfetchを打ち切りました"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_from_locale_name() {
+        assert_eq!(Locale::from_locale_name("ja_JP.UTF-8"), Locale::Ja);
+        assert_eq!(Locale::from_locale_name("ja"), Locale::Ja);
+        assert_eq!(Locale::from_locale_name("en_US.UTF-8"), Locale::En);
+        assert_eq!(Locale::from_locale_name("C"), Locale::En);
+    }
+
+    #[test]
+    fn test_messages_in_each_locale() {
+        let header = Message::FoundRepositories {
+            total: 3,
+            with_changes: 1,
+        };
+        assert_eq!(
+            header.text(Locale::En),
+            "Found 3 repositories (1 with changes):"
+        );
+        assert_eq!(header.text(Locale::Ja), "3件のリポジトリ（変更あり 1件）:");
+        assert_eq!(
+            Message::ReportHeader(1).text(Locale::En),
+            "pendector: 1 repository needs attention"
+        );
+        assert_eq!(
+            Message::SyncStatus(SyncState::NeedsPush).text(Locale::Ja),
+            "同期状態: pushが必要"
+        );
+        assert_eq!(
+            Message::SkippedMissingPath("~/src").text(Locale::En),
+            "Path '~/src' does not exist, skipping"
+        );
+        assert_eq!(
+            Message::SkippedMissingPath("~/src").text(Locale::Ja),
+            "パス '~/src' が存在しないため飛ばします"
+        );
+    }
+
+    #[test]
+    fn test_permission_denied_counts_directories() {
+        assert_eq!(
+            Message::PermissionDenied {
+                root: "~/src",
+                count: 1
+            }
+            .text(Locale::En),
+            "skipped 1 directory under '~/src' (permission denied); use -v to list them"
+        );
+        assert_eq!(
+            Message::PermissionDenied {
+                root: "~/src",
+                count: 3
+            }
+            .text(Locale::En),
+            "skipped 3 directories under '~/src' (permission denied); use -v to list them"
+        );
+    }
+}
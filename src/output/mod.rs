@@ -1,3 +1,16 @@
+pub mod envelope;
+pub mod feed;
+pub mod format;
 pub mod formatter;
+pub mod hyperlink;
+pub mod icons;
+pub mod messages;
+pub mod stream;
 
+pub use envelope::*;
+pub use feed::*;
+pub use format::*;
 pub use formatter::*;
+pub use icons::*;
+pub use messages::*;
+pub use stream::*;
@@ -0,0 +1,150 @@
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+
+/// 複数のスレッドから渡された出力を、1つの書き込みスレッドが受け取った順に書き出す
+///
+/// まとまり（1リポジトリ分の行や、`[repo] ` を付けた1行）は1回の `write_all` で書くので、
+/// rayonのワーカーから同時に渡されても行の途中で混ざることはない。
+/// プロセスを終了する前に [`PrefixedLineWriter::finish`] で書き終わるのを待つこと。
+pub struct PrefixedLineWriter {
+    sender: Sender<Vec<u8>>,
+    handle: JoinHandle<io::Result<()>>,
+}
+
+impl PrefixedLineWriter {
+    pub fn new<W: Write + Send + 'static>(mut writer: W) -> Self {
+        let (sender, receiver) = mpsc::channel::<Vec<u8>>();
+        let handle = thread::spawn(move || {
+            for block in receiver {
+                writer.write_all(&block)?;
+                writer.flush()?;
+            }
+            Ok(())
+        });
+        Self { sender, handle }
+    }
+
+    /// 標準出力へ書き出す
+    pub fn stdout() -> Self {
+        Self::new(io::stdout())
+    }
+
+    /// 標準エラー出力へ書き出す
+    pub fn stderr() -> Self {
+        Self::new(io::stderr())
+    }
+
+    /// まとまりを書き出す（空でなく末尾に改行がなければ付ける）
+    pub fn write_block(&self, block: impl Into<Vec<u8>>) {
+        let mut block = block.into();
+        if block.is_empty() {
+            return;
+        }
+        if !block.ends_with(b"\n") {
+            block.push(b'\n');
+        }
+        // 書き込みスレッドが失敗して終了している場合のエラーは finish で返す
+        let _ = self.sender.send(block);
+    }
+
+    /// 1行を `[prefix] ` を付けて書き出す
+    pub fn write_line(&self, prefix: &str, line: &[u8]) {
+        let mut block = format!("[{prefix}] ").into_bytes();
+        block.extend_from_slice(line);
+        self.write_block(block);
+    }
+
+    /// 渡されたものをすべて書き終えるまで待つ
+    pub fn finish(self) -> io::Result<()> {
+        drop(self.sender);
+        self.handle
+            .join()
+            .unwrap_or_else(|_| Err(io::Error::other("output writer thread panicked")))
+    }
+}
+
+/// 標準出力に書けなくなった（読み手がパイプを閉じたなど）
+static STDOUT_CLOSED: AtomicBool = AtomicBool::new(false);
+
+/// 標準出力へ書く
+///
+/// `print!` と違い、`pendector | head` のように読み手が先に終了してもパニックせず、
+/// 以降の出力を捨てる。終了コードは呼び出し側が計算したとおりに返せる。
+pub fn write_stdout(text: &str) {
+    if STDOUT_CLOSED.load(Ordering::Relaxed) {
+        return;
+    }
+    let mut stdout = io::stdout().lock();
+    if stdout
+        .write_all(text.as_bytes())
+        .and_then(|()| stdout.flush())
+        .is_err()
+    {
+        STDOUT_CLOSED.store(true, Ordering::Relaxed);
+    }
+}
+
+/// 1行を他のスレッドの出力と混ざらないように標準エラー出力へ書く
+pub fn write_stderr_line(line: &str) {
+    let mut stderr = io::stderr().lock();
+    let _ = stderr.write_all(format!("{line}\n").as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rayon::prelude::*;
+    use std::sync::{Arc, Mutex};
+
+    /// 書き込み単位を分けずに記録する共有バッファ
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            // 1バイトずつ書いても、まとまりの途中に他の出力が入らないことを確かめる
+            let Some(&byte) = buf.first() else {
+                return Ok(0);
+            };
+            self.0.lock().unwrap().push(byte);
+            thread::yield_now();
+            Ok(1)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_line_writer_keeps_blocks_intact() {
+        let buffer = SharedBuffer::default();
+        let writer = PrefixedLineWriter::new(buffer.clone());
+        (0..64).into_par_iter().for_each(|i| {
+            writer.write_block(format!("repo-{i} [main]\n  Path: /src/repo-{i}"));
+        });
+        writer.finish().unwrap();
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 128);
+        for pair in lines.chunks(2) {
+            let name = pair[0].strip_suffix(" [main]").unwrap();
+            assert_eq!(pair[1], format!("  Path: /src/{name}"));
+        }
+    }
+
+    #[test]
+    fn test_line_writer_prefixes_lines() {
+        let buffer = SharedBuffer::default();
+        let writer = PrefixedLineWriter::new(buffer.clone());
+        writer.write_line("alpha", b"first");
+        writer.write_line("beta", b"");
+        writer.finish().unwrap();
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(output, "[alpha] first\n[beta] \n");
+    }
+}
@@ -0,0 +1,135 @@
+//! パスの書き方の違い（Windowsのドライブレター・区切り文字 `\`・UNCや `\\?\` の接頭辞）を揃える
+//!
+//! 設定ファイルのパス・スキャンルート・除外パターンの絶対パスは比較の前にここで揃え、
+//! 同じ場所を指すパスが同じ文字列になるようにする。Windows形式のパスは区切り文字を `/` にする
+//! （Windowsのパス処理は `/` も区切り文字として扱う）。Unix形式のパスは `\` がファイル名に使える文字なので変えない。
+
+use std::path::{Path, PathBuf};
+
+/// `canonicalize` がWindowsで付ける接頭辞（長いパスを扱うための形式）
+const VERBATIM_PREFIXES: [&str; 2] = [r"\\?\", "//?/"];
+
+/// Windows形式のパス（`C:\`・`C:/`・`C:`、`\\server\share`、`\\?\` で始まる）か
+pub fn is_windows_style(path: &str) -> bool {
+    has_drive_letter(path)
+        || path.starts_with(r"\\")
+        || VERBATIM_PREFIXES
+            .iter()
+            .any(|prefix| path.starts_with(prefix))
+}
+
+/// 絶対パスか（実行中のOSに関わらず、Windows形式の絶対パスも絶対パスとみなす）
+pub fn is_absolute_path(path: &str) -> bool {
+    // `C:` だけではドライブのカレントディレクトリを指す相対パス
+    Path::new(path).is_absolute() || (is_windows_style(path) && path.len() > 2)
+}
+
+/// パスの書き方を揃える
+///
+/// `\\?\C:\src\` は `C:/src`、`\\?\UNC\server\share` と `\\server\share` は `//server/share` になる。
+/// ドライブレターは大文字にし、重なった区切り文字と末尾の区切り文字は除く（`C:/` のようなドライブのルートは残す）。
+pub fn normalize_path_str(path: &str) -> String {
+    if !is_windows_style(path) {
+        return path.to_string();
+    }
+    let rest = VERBATIM_PREFIXES
+        .iter()
+        .find_map(|prefix| path.strip_prefix(prefix))
+        .unwrap_or(path);
+    let (unc, rest) = match rest
+        .strip_prefix(r"UNC\")
+        .or_else(|| rest.strip_prefix("UNC/"))
+        .or_else(|| rest.strip_prefix(r"\\"))
+    {
+        Some(share) => (true, share),
+        None => (false, rest),
+    };
+    let rest = rest.replace('\\', "/");
+    let parts: Vec<&str> = rest
+        .split('/')
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect();
+    if unc {
+        return format!("//{}", parts.join("/"));
+    }
+    match parts.split_first() {
+        Some((drive, tail)) if has_drive_letter(drive) => {
+            let drive = drive.to_ascii_uppercase();
+            if tail.is_empty() && rest.len() == 2 {
+                // `C:` はドライブのカレントディレクトリを指すのでルートにはしない
+                drive
+            } else {
+                format!("{drive}/{}", tail.join("/"))
+            }
+        }
+        _ => format!("/{}", parts.join("/")),
+    }
+}
+
+/// [`normalize_path_str`] の `Path` 版（UTF-8でないパスはそのまま返す）
+pub fn normalize_path(path: &Path) -> PathBuf {
+    match path.to_str() {
+        Some(path) => PathBuf::from(normalize_path_str(path)),
+        None => path.to_path_buf(),
+    }
+}
+
+/// 正規化したパス（`\\?\` を除いた書き方に揃える）。正規化できなければ書き方だけ揃えたパス
+pub fn canonicalize_path(path: &Path) -> PathBuf {
+    path.canonicalize()
+        .map(|canonical| normalize_path(&canonical))
+        .unwrap_or_else(|_| normalize_path(path))
+}
+
+/// `C:` で始まり、その後が終わりか区切り文字か
+fn has_drive_letter(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    bytes.len() >= 2
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && bytes.get(2).is_none_or(|&b| b == b'/' || b == b'\\')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_drive_letters_and_separators() {
+        assert_eq!(normalize_path_str(r"c:\Users\me\src\"), "C:/Users/me/src");
+        assert_eq!(normalize_path_str(r"C:/Users//me\.\src"), "C:/Users/me/src");
+        assert_eq!(normalize_path_str(r"d:\"), "D:/");
+        assert_eq!(normalize_path_str("d:"), "D:");
+    }
+
+    #[test]
+    fn test_normalize_verbatim_and_unc_prefixes() {
+        assert_eq!(normalize_path_str(r"\\?\C:\src\app"), "C:/src/app");
+        assert_eq!(
+            normalize_path_str(r"\\?\UNC\server\share\src"),
+            "//server/share/src"
+        );
+        assert_eq!(
+            normalize_path_str(r"\\server\share\src\"),
+            "//server/share/src"
+        );
+    }
+
+    #[test]
+    fn test_unix_paths_are_unchanged() {
+        assert_eq!(normalize_path_str("/home/me/src/"), "/home/me/src/");
+        assert_eq!(normalize_path_str(r"/tmp/odd\name"), r"/tmp/odd\name");
+        assert_eq!(normalize_path_str("C:foo"), "C:foo");
+        assert!(!is_windows_style("relative/path"));
+    }
+
+    #[test]
+    fn test_is_absolute_path() {
+        assert!(is_absolute_path("/src"));
+        assert!(is_absolute_path(r"C:\src"));
+        assert!(is_absolute_path("c:/"));
+        assert!(is_absolute_path(r"\\server\share"));
+        assert!(!is_absolute_path("C:"));
+        assert!(!is_absolute_path(r"build\out"));
+    }
+}
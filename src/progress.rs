@@ -0,0 +1,381 @@
+use crate::output::write_stderr_line;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+use std::io::IsTerminal;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// stderrが端末でないときに進捗を1行で書き出す間隔のデフォルト（秒）
+pub const DEFAULT_PROGRESS_INTERVAL_SECS: u64 = 10;
+
+/// 進捗の表示方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ProgressMode {
+    /// stderrにプログレスバーを表示する（stderrが端末でなければ一定間隔で進捗を1行ずつ書く）
+    #[default]
+    Bar,
+    /// stderrに改行区切りのJSONイベントを出力する
+    Json,
+    /// 進捗を表示しない
+    None,
+}
+
+/// `--progress json` で出力される進捗イベント
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ProgressEvent<'a> {
+    /// スキャンルート配下の探索を開始した
+    DiscoveryStarted { root: &'a Path },
+    /// スキャンルート配下の探索が完了した
+    DiscoveryFinished { root: &'a Path, repositories: usize },
+    /// 1リポジトリのfetchが完了した
+    RepoFetched {
+        path: &'a Path,
+        success: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<&'a str>,
+    },
+    /// fetchが閾値を超えても終わっていない
+    FetchSlow { path: &'a Path, elapsed_secs: u64 },
+    /// 1リポジトリのステータス取得が完了した
+    RepoStatused { path: &'a Path, has_changes: bool },
+    /// すべての処理が完了した
+    Done { repositories: usize },
+}
+
+/// 進捗イベントを表示方式に応じて出力する
+#[derive(Debug, Clone, Copy)]
+pub struct ProgressReporter {
+    mode: ProgressMode,
+    /// stderrが端末でないときに進捗の行を書く間隔
+    plain_interval: Duration,
+}
+
+impl Default for ProgressReporter {
+    fn default() -> Self {
+        Self::new(ProgressMode::default())
+    }
+}
+
+impl ProgressReporter {
+    pub fn new(mode: ProgressMode) -> Self {
+        Self {
+            mode,
+            plain_interval: Duration::from_secs(DEFAULT_PROGRESS_INTERVAL_SECS),
+        }
+    }
+
+    /// stderrが端末でないときに進捗の行を書く間隔を設定する
+    pub fn with_plain_interval(mut self, interval: Duration) -> Self {
+        self.plain_interval = interval;
+        self
+    }
+
+    /// イベントを1行のJSONとしてstderrに出力する（JSONモード以外では何もしない）
+    pub fn emit(&self, event: &ProgressEvent<'_>) {
+        if self.mode != ProgressMode::Json {
+            return;
+        }
+        // ワーカースレッドから同時に呼ばれても行が混ざらないよう、1行ずつまとめて書く
+        if let Ok(line) = serde_json::to_string(event) {
+            write_stderr_line(&line);
+        }
+    }
+
+    /// fetch用の進捗表示を作成する（バーモード以外ではバーを描画しない）
+    pub fn fetch_progress(&self, len: usize) -> FetchProgress {
+        self.fetch_progress_on(len, std::io::stderr().is_terminal())
+    }
+
+    /// stderrが端末かどうかを指定してfetch用の進捗表示を作成する
+    ///
+    /// 端末でなければ（cronやCI）制御文字を含むバーの代わりに、進捗を一定間隔でテキストの行として書く。
+    fn fetch_progress_on(&self, len: usize, terminal: bool) -> FetchProgress {
+        let bar_mode = self.mode == ProgressMode::Bar;
+        let plain = (bar_mode && !terminal).then(|| PlainProgress::new(len, self.plain_interval));
+        let bar = (bar_mode && terminal).then(|| {
+            let pb = ProgressBar::new(len as u64);
+            pb.set_style(
+                ProgressStyle::default_bar()
+                    .template(
+                        "Fetching repositories [{wide_bar:.cyan/blue}] {pos}/{len}{prefix} ({elapsed}) {msg}",
+                    )
+                    .unwrap()
+                    .progress_chars("##-"),
+            );
+            // 1件が長時間止まっていても経過時間が更新されるようにする
+            pb.enable_steady_tick(Duration::from_millis(250));
+            pb
+        });
+
+        FetchProgress {
+            bar,
+            plain,
+            in_flight: Mutex::new(Vec::new()),
+            failures: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+/// 並列fetchの進捗（実行中のリポジトリ名をバーのメッセージに、失敗数をバーの件数の横に表示する）
+pub struct FetchProgress {
+    bar: Option<ProgressBar>,
+    /// バーの代わりに書く進捗の行（stderrが端末でないとき）
+    plain: Option<PlainProgress>,
+    in_flight: Mutex<Vec<String>>,
+    /// fetchに失敗したリポジトリ名とエラー（完了順）
+    failures: Mutex<Vec<(String, String)>>,
+}
+
+impl FetchProgress {
+    /// バーが描画されているか
+    #[cfg(test)]
+    pub fn is_visible(&self) -> bool {
+        self.bar.is_some()
+    }
+
+    /// リポジトリのfetch開始を記録する
+    pub fn start(&self, name: &str) {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        in_flight.push(name.to_string());
+        self.update_message(&in_flight);
+    }
+
+    /// リポジトリのfetch完了を記録する（失敗した場合はエラーを渡す）
+    pub fn complete(&self, name: &str, error: Option<&str>) {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if let Some(index) = in_flight.iter().position(|n| n == name) {
+            in_flight.remove(index);
+        }
+        self.update_message(&in_flight);
+
+        if let Some(error) = error {
+            let mut failures = self.failures.lock().unwrap();
+            failures.push((name.to_string(), error.to_string()));
+            if let Some(ref pb) = self.bar {
+                pb.set_prefix(format!(" ({} failed)", failures.len()));
+            }
+        }
+        if let Some(ref pb) = self.bar {
+            pb.inc(1);
+        }
+        if let Some(ref plain) = self.plain {
+            let failed = self.failures.lock().unwrap().len();
+            plain.complete(&in_flight, failed);
+        }
+    }
+
+    /// 終わらないfetchをバーの上に報告する
+    pub fn slow(&self, name: &str, elapsed: Duration) {
+        if let Some(ref pb) = self.bar {
+            let secs = elapsed.as_secs();
+            pb.println(format!("still fetching {name} ({secs}s)…"));
+        }
+        if self.plain.is_some() {
+            let secs = elapsed.as_secs();
+            write_stderr_line(&format!("still fetching {name} ({secs}s)..."));
+        }
+    }
+
+    /// 進捗表示を終了し、バーや進捗の行を表示していた場合は失敗したリポジトリを一覧にする
+    pub fn finish(self) {
+        let failures = self.failures.into_inner().unwrap();
+        if let Some(pb) = self.bar {
+            pb.finish_with_message("Completed");
+        } else if let Some(plain) = self.plain {
+            write_stderr_line(&plain.finished_line(failures.len()));
+        } else {
+            return;
+        }
+        if let Some(summary) = describe_failures(&failures) {
+            eprintln!("{summary}");
+        }
+    }
+
+    fn update_message(&self, in_flight: &[String]) {
+        if let Some(ref pb) = self.bar {
+            pb.set_message(describe_in_flight(in_flight));
+        }
+    }
+}
+
+/// 端末でないstderrに書く進捗の行（前回から間隔が空いたときだけ書く）
+struct PlainProgress {
+    len: usize,
+    interval: Duration,
+    started: Instant,
+    /// 完了した件数と、最後に行を書いた時刻
+    state: Mutex<(usize, Instant)>,
+}
+
+impl PlainProgress {
+    fn new(len: usize, interval: Duration) -> Self {
+        let now = Instant::now();
+        Self {
+            len,
+            interval,
+            started: now,
+            state: Mutex::new((0, now)),
+        }
+    }
+
+    /// 1件の完了を記録し、前回から間隔が空いていれば進捗の行を書く
+    fn complete(&self, in_flight: &[String], failed: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.0 += 1;
+        if state.1.elapsed() < self.interval {
+            return;
+        }
+        state.1 = Instant::now();
+        write_stderr_line(&self.progress_line(state.0, failed, in_flight));
+    }
+
+    fn progress_line(&self, done: usize, failed: usize, in_flight: &[String]) -> String {
+        let mut line = format!(
+            "Fetching repositories: {done}/{} ({failed} failed, {}s elapsed)",
+            self.len,
+            self.started.elapsed().as_secs()
+        );
+        if !in_flight.is_empty() {
+            line.push_str(&format!(", in progress: {}", describe_in_flight(in_flight)));
+        }
+        line
+    }
+
+    fn finished_line(&self, failed: usize) -> String {
+        format!(
+            "Fetching repositories: completed {} ({failed} failed) in {}s",
+            self.len,
+            self.started.elapsed().as_secs()
+        )
+    }
+}
+
+/// fetchに失敗したリポジトリの一覧（失敗がなければNone）
+fn describe_failures(failures: &[(String, String)]) -> Option<String> {
+    if failures.is_empty() {
+        return None;
+    }
+    let mut lines = vec![format!("Failed to fetch {} repositories:", failures.len())];
+    lines.extend(
+        failures
+            .iter()
+            .map(|(name, error)| format!("  {name}: {error}")),
+    );
+    Some(lines.join("\n"))
+}
+
+/// 実行中のリポジトリ名を短く要約する
+fn describe_in_flight(names: &[String]) -> String {
+    const MAX_NAMES: usize = 3;
+
+    if names.len() <= MAX_NAMES {
+        names.join(", ")
+    } else {
+        let shown = names[..MAX_NAMES].join(", ");
+        let rest = names.len() - MAX_NAMES;
+        format!("{shown} +{rest} more")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_serialization() {
+        let event = ProgressEvent::RepoFetched {
+            path: Path::new("/src/repo"),
+            success: false,
+            error: Some("timed out"),
+        };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"event":"repo_fetched","path":"/src/repo","success":false,"error":"timed out"}"#
+        );
+
+        let event = ProgressEvent::Done { repositories: 3 };
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"event":"done","repositories":3}"#
+        );
+    }
+
+    #[test]
+    fn test_fetch_bar_only_in_bar_mode() {
+        assert!(ProgressReporter::new(ProgressMode::Bar)
+            .fetch_progress_on(1, true)
+            .is_visible());
+        assert!(!ProgressReporter::new(ProgressMode::Json)
+            .fetch_progress_on(1, true)
+            .is_visible());
+        assert!(!ProgressReporter::new(ProgressMode::None)
+            .fetch_progress_on(1, true)
+            .is_visible());
+    }
+
+    #[test]
+    fn test_fetch_progress_without_terminal_writes_plain_lines() {
+        let reporter = ProgressReporter::new(ProgressMode::Bar);
+        let progress = reporter.fetch_progress_on(2, false);
+        assert!(!progress.is_visible());
+        assert!(progress.plain.is_some());
+        assert!(reporter.fetch_progress_on(2, true).plain.is_none());
+        assert!(ProgressReporter::new(ProgressMode::None)
+            .fetch_progress_on(2, false)
+            .plain
+            .is_none());
+
+        let plain = PlainProgress::new(5, Duration::from_secs(10));
+        let line = plain.progress_line(2, 1, &["repo3".to_string()]);
+        assert!(line.starts_with("Fetching repositories: 2/5 (1 failed, "));
+        assert!(line.ends_with(", in progress: repo3"));
+        assert!(!line.contains('\x1b'));
+    }
+
+    #[test]
+    fn test_describe_in_flight() {
+        let names: Vec<String> = ["a", "b", "c", "d", "e"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        assert_eq!(describe_in_flight(&[]), "");
+        assert_eq!(describe_in_flight(&names[..2]), "a, b");
+        assert_eq!(describe_in_flight(&names[..3]), "a, b, c");
+        assert_eq!(describe_in_flight(&names), "a, b, c +2 more");
+    }
+
+    #[test]
+    fn test_fetch_progress_tracks_in_flight() {
+        let progress = ProgressReporter::new(ProgressMode::None).fetch_progress(2);
+        progress.start("repo1");
+        progress.start("repo2");
+        progress.complete("repo1", None);
+        assert_eq!(
+            *progress.in_flight.lock().unwrap(),
+            vec!["repo2".to_string()]
+        );
+        progress.complete("repo2", Some("timed out"));
+        assert!(progress.in_flight.lock().unwrap().is_empty());
+        assert_eq!(
+            *progress.failures.lock().unwrap(),
+            vec![("repo2".to_string(), "timed out".to_string())]
+        );
+        progress.finish();
+    }
+
+    #[test]
+    fn test_describe_failures() {
+        assert_eq!(describe_failures(&[]), None);
+        let failures = vec![
+            ("repo1".to_string(), "timed out".to_string()),
+            ("repo2".to_string(), "auth failed".to_string()),
+        ];
+        assert_eq!(
+            describe_failures(&failures).unwrap(),
+            "Failed to fetch 2 repositories:\n  repo1: timed out\n  repo2: auth failed"
+        );
+    }
+}
@@ -0,0 +1,1483 @@
+//! CLIとライブラリで共通の実行経路：スキャンルートの決定・走査・表示対象の選択・出力形式の解決
+//!
+//! [`run`] は [`RunOptions`]（設定ファイルとそれに重ねる指定）から決まるすべてのスキャンルートを走査し、[`ScanReport`] を返す。
+//!
+//! 警告は [`ScanReport::warnings`] で返し、表示は呼び出し側に任せる。
+//! 進捗と診断（`-v` の集計や `-vv` の絞り込みの理由）は標準エラー出力に書き、標準出力には何も書かない。
+
+use crate::cli::{resolve_options, Args, EffectiveOptions};
+use crate::config::{expand_tilde, Config, PathConfigResolved};
+use crate::core::{
+    log_filter_decision, most_actionable, partition_duplicates, sort_by_canonical_path,
+    sort_by_staleness, Attention, BudgetExceeded, FetchSummary, FilterReason, LastScan,
+    PendingRepository, ProjectType, RepoScanner, Repository, ScanOptions, Storage, WalkErrors,
+    WalkFailure,
+};
+use crate::error::{ErrorReport, PendectorError, PendectorResult};
+use crate::exclude::ExcludeFilter;
+use crate::git::layout::register_detached_layout;
+use crate::git::status::DEFAULT_FETCH_SLOW_THRESHOLD_SECS;
+use crate::interrupt::is_interrupted;
+use crate::lock::RunLock;
+use crate::output::{
+    terminal_width, EnvelopeWarning, IconStyle, Locale, Message, OutputFormat, OutputFormatter,
+    RootProblem, Summary, WarningKind,
+};
+use crate::path_style::{canonicalize_path, normalize_path};
+use crate::progress::ProgressMode;
+use crate::time;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+pub use crate::cli::{ExitCode, GroupBy, Overrides, SortKey};
+
+/// 実行の失敗（CLIでは [`RunError::exit_code`] の終了コードで終了する）
+#[derive(Debug, Error)]
+pub enum RunError {
+    /// CLI引数の誤り
+    #[error("{0}")]
+    Usage(String),
+    /// スキャンルートが存在しない・ディレクトリでない
+    #[error("{}", invalid_root(.path, *.problem, Locale::En))]
+    InvalidRoot { path: String, problem: RootProblem },
+    /// スキャンルートの走査に失敗
+    #[error("failed to scan path '{path}': {source}")]
+    Scan {
+        path: String,
+        source: PendectorError,
+    },
+    /// ロックの取得などスキャンルートに依らない失敗
+    #[error(transparent)]
+    Pendector(#[from] PendectorError),
+}
+
+impl RunError {
+    /// CLIで使う終了コード
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            RunError::Usage(_)
+            | RunError::InvalidRoot { .. }
+            | RunError::Scan {
+                source: PendectorError::ConfigError { .. },
+                ..
+            } => ExitCode::Usage,
+            RunError::Pendector(PendectorError::Interrupted) => ExitCode::Interrupted,
+            _ => ExitCode::ScanError,
+        }
+    }
+
+    /// 指定した言語でのエラーメッセージ
+    pub fn text(&self, locale: Locale) -> String {
+        match self {
+            RunError::InvalidRoot { path, problem } => invalid_root(path, *problem, locale),
+            _ => self.to_string(),
+        }
+    }
+}
+
+/// スキャンルートとして使えないことのメッセージ
+fn invalid_root(path: &str, problem: RootProblem, locale: Locale) -> String {
+    Message::InvalidRoot { path, problem }.text(locale)
+}
+
+/// 実行の設定：設定ファイルと、それに重ねる指定・スキャンルート
+///
+/// ライブラリからは [`RunOptions::new`] と `with_*` で組み立てる（CLIは引数と設定ファイルから組み立てる）。
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct RunOptions {
+    pub config: Config,
+    /// 走査するスキャンルートと、それぞれに明示された深さ
+    pub roots: Vec<(String, Option<usize>)>,
+    /// 設定ファイルの値に重ねる指定
+    pub overrides: Overrides,
+    /// 状態表示として実行するか（falseならサブコマンド向けにfetchや表示の絞り込みを行わない）
+    pub show_status: bool,
+    /// 表示するリポジトリの上限
+    pub limit: Option<usize>,
+    /// 最も対応を急ぐものから表示する件数
+    pub top: Option<usize>,
+    /// 前回対応が必要だったリポジトリだけを調べ直す
+    pub recheck: bool,
+    /// 表示する対応の種類（空ならすべて）
+    pub attention: Vec<Attention>,
+    /// 表示するプロジェクトの種類（空ならすべて）
+    pub project_types: Vec<ProjectType>,
+    /// 表示の並び順
+    pub sort: SortKey,
+    /// 表示に残した・除いた理由を標準エラー出力に書くか
+    pub explain_filtering: bool,
+    /// 警告と表示の言語
+    pub locale: Locale,
+    /// 存在しないスキャンルートを飛ばすか（設定ファイルの `skip_missing_paths` でも有効になる）
+    pub skip_missing_paths: bool,
+    /// 遅いfetchとみなす閾値
+    pub fetch_slow_threshold: Duration,
+    /// 遅いfetchを打ち切るか
+    pub fetch_skip_slow: bool,
+    /// 進捗の表示方法
+    pub progress: ProgressMode,
+    /// 進捗を1行で書き出す間隔（Noneなら設定ファイルの値）
+    pub progress_interval: Option<Duration>,
+    /// デフォルトブランチと比較するリモート
+    pub compare_remote: Option<String>,
+    /// リモートとの同期状態を調べるか（falseなら設定ファイルによらず調べない）
+    pub remote_check: bool,
+    /// 古い変更とみなす日数（Noneなら設定ファイルの値）
+    pub dirty_warn_days: Option<u64>,
+    /// ディスク使用量を計測するか
+    pub disk_usage: bool,
+    /// 無視された大きなディレクトリを調べるか
+    pub artifacts: bool,
+    /// 変更のあるリポジトリで表示する差分の行数
+    pub preview_lines: Option<usize>,
+    /// gitの定期fetchに任せるリポジトリはfetchしないか
+    pub trust_maintenance: bool,
+    /// fetchする実行同士が重ならないようロックを取るか
+    pub lock: bool,
+    /// 探索中に読めなかったディレクトリの扱い
+    pub walk_errors: WalkErrors,
+    /// リモートにだけあるブランチを表示するか
+    pub remote_only_branches: bool,
+    /// テキスト出力の幅（Noneなら端末の幅）
+    pub width: Option<usize>,
+    /// テキスト出力の記号（Noneなら設定ファイルの値）
+    pub icons: Option<IconStyle>,
+    /// テキスト出力のパスのシンボリックリンクを解決するか
+    pub resolve_symlinks: bool,
+    /// パスを端末のハイパーリンクにするか
+    pub hyperlinks: bool,
+    /// 色や記号を使わずに表示するか
+    pub plain: bool,
+    /// テキスト出力の分け方
+    pub group_by: Option<GroupBy>,
+    /// 前回の結果（`--recheck` で使う）を保存する場所（Noneなら保存しない）
+    pub storage: Option<Storage>,
+}
+
+impl RunOptions {
+    /// 設定ファイルのスキャンルートを走査する既定の設定
+    pub fn new(config: Config) -> Self {
+        let roots = config
+            .get_default_paths()
+            .iter()
+            .map(|path| (path.clone(), None))
+            .collect();
+        Self {
+            config,
+            roots,
+            overrides: Overrides::new(),
+            show_status: true,
+            limit: None,
+            top: None,
+            recheck: false,
+            attention: Vec::new(),
+            project_types: Vec::new(),
+            sort: SortKey::default(),
+            explain_filtering: false,
+            locale: Locale::default(),
+            skip_missing_paths: false,
+            fetch_slow_threshold: Duration::from_secs(DEFAULT_FETCH_SLOW_THRESHOLD_SECS),
+            fetch_skip_slow: false,
+            progress: ProgressMode::default(),
+            progress_interval: None,
+            compare_remote: None,
+            remote_check: true,
+            dirty_warn_days: None,
+            disk_usage: false,
+            artifacts: false,
+            preview_lines: None,
+            trust_maintenance: false,
+            lock: true,
+            walk_errors: WalkErrors::default(),
+            remote_only_branches: false,
+            width: None,
+            icons: None,
+            resolve_symlinks: true,
+            hyperlinks: false,
+            plain: false,
+            group_by: None,
+            storage: None,
+        }
+    }
+
+    /// CLI引数と設定ファイルから決める
+    pub(crate) fn from_args(args: &Args, config: Config) -> Result<Self, RunError> {
+        let cli_roots = args.scan_roots().map_err(RunError::Usage)?;
+        let roots = resolve_paths_to_scan(args, &config, &cli_roots);
+        Ok(Self::new(config)
+            .with_roots(roots)
+            .with_overrides(Overrides::from_args(args))
+            .with_show_status(args.command.is_none())
+            .with_limit(args.limit)
+            .with_top(args.top)
+            .with_recheck(args.recheck)
+            .with_attention(args.attention.clone())
+            .with_project_types(args.project_type.clone())
+            .with_sort(args.sort)
+            .with_explain_filtering(args.explain_filtering())
+            .with_locale(args.locale())
+            .with_skip_missing_paths(args.skip_missing_paths)
+            .with_fetch_slow_threshold(Duration::from_secs(args.fetch_slow_threshold))
+            .with_fetch_skip_slow(args.fetch_skip_slow)
+            .with_progress(args.progress_mode())
+            .with_progress_interval(args.progress_interval.map(Duration::from_secs))
+            .with_compare_remote(args.compare_remote.clone())
+            .with_remote_check(!args.no_remote_check)
+            .with_dirty_warn_days(args.dirty_warn_days)
+            .with_disk_usage(args.du)
+            .with_artifacts(args.artifacts)
+            .with_preview_lines(args.preview)
+            .with_trust_maintenance(args.trust_maintenance)
+            .with_lock(!args.no_lock)
+            .with_walk_errors(walk_errors(args))
+            .with_remote_only_branches(args.remote_branches)
+            .with_width(args.width)
+            .with_icons(args.icons)
+            .with_resolve_symlinks(!args.no_resolve_symlinks)
+            .with_hyperlinks(args.hyperlinks)
+            .with_plain(args.plain)
+            .with_group_by(args.group_by))
+    }
+
+    /// 走査するスキャンルートと、それぞれに明示した深さを設定する
+    pub fn with_roots(mut self, roots: Vec<(String, Option<usize>)>) -> Self {
+        self.roots = roots;
+        self
+    }
+
+    /// 設定ファイルの値に重ねる指定を設定する
+    pub fn with_overrides(mut self, overrides: Overrides) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
+    /// 状態表示として実行するかを設定する
+    pub fn with_show_status(mut self, show_status: bool) -> Self {
+        self.show_status = show_status;
+        self
+    }
+
+    /// 表示するリポジトリの上限を設定する
+    pub fn with_limit(mut self, limit: Option<usize>) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// 最も対応を急ぐものから表示する件数を設定する
+    pub fn with_top(mut self, top: Option<usize>) -> Self {
+        self.top = top;
+        self
+    }
+
+    /// 前回対応が必要だったリポジトリだけを調べ直すかを設定する
+    pub fn with_recheck(mut self, recheck: bool) -> Self {
+        self.recheck = recheck;
+        self
+    }
+
+    /// 表示する対応の種類を設定する
+    pub fn with_attention(mut self, attention: Vec<Attention>) -> Self {
+        self.attention = attention;
+        self
+    }
+
+    /// 表示するプロジェクトの種類を設定する
+    pub fn with_project_types(mut self, project_types: Vec<ProjectType>) -> Self {
+        self.project_types = project_types;
+        self
+    }
+
+    /// 表示の並び順を設定する
+    pub fn with_sort(mut self, sort: SortKey) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// 表示に残した・除いた理由を書くかを設定する
+    pub fn with_explain_filtering(mut self, explain_filtering: bool) -> Self {
+        self.explain_filtering = explain_filtering;
+        self
+    }
+
+    /// 警告と表示の言語を設定する
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// 存在しないスキャンルートを飛ばすかを設定する
+    pub fn with_skip_missing_paths(mut self, skip_missing_paths: bool) -> Self {
+        self.skip_missing_paths = skip_missing_paths;
+        self
+    }
+
+    /// 遅いfetchとみなす閾値を設定する
+    pub fn with_fetch_slow_threshold(mut self, fetch_slow_threshold: Duration) -> Self {
+        self.fetch_slow_threshold = fetch_slow_threshold;
+        self
+    }
+
+    /// 遅いfetchを打ち切るかを設定する
+    pub fn with_fetch_skip_slow(mut self, fetch_skip_slow: bool) -> Self {
+        self.fetch_skip_slow = fetch_skip_slow;
+        self
+    }
+
+    /// 進捗の表示方法を設定する
+    pub fn with_progress(mut self, progress: ProgressMode) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// 進捗を1行で書き出す間隔を設定する
+    pub fn with_progress_interval(mut self, progress_interval: Option<Duration>) -> Self {
+        self.progress_interval = progress_interval;
+        self
+    }
+
+    /// デフォルトブランチと比較するリモートを設定する
+    pub fn with_compare_remote(mut self, compare_remote: Option<String>) -> Self {
+        self.compare_remote = compare_remote;
+        self
+    }
+
+    /// リモートとの同期状態を調べるかを設定する
+    pub fn with_remote_check(mut self, remote_check: bool) -> Self {
+        self.remote_check = remote_check;
+        self
+    }
+
+    /// 古い変更とみなす日数を設定する
+    pub fn with_dirty_warn_days(mut self, dirty_warn_days: Option<u64>) -> Self {
+        self.dirty_warn_days = dirty_warn_days;
+        self
+    }
+
+    /// ディスク使用量を計測するかを設定する
+    pub fn with_disk_usage(mut self, disk_usage: bool) -> Self {
+        self.disk_usage = disk_usage;
+        self
+    }
+
+    /// 無視された大きなディレクトリを調べるかを設定する
+    pub fn with_artifacts(mut self, artifacts: bool) -> Self {
+        self.artifacts = artifacts;
+        self
+    }
+
+    /// 表示する差分の行数を設定する
+    pub fn with_preview_lines(mut self, preview_lines: Option<usize>) -> Self {
+        self.preview_lines = preview_lines;
+        self
+    }
+
+    /// gitの定期fetchに任せるリポジトリをfetchしないかを設定する
+    pub fn with_trust_maintenance(mut self, trust_maintenance: bool) -> Self {
+        self.trust_maintenance = trust_maintenance;
+        self
+    }
+
+    /// fetchの前にロックを取るかを設定する
+    pub fn with_lock(mut self, lock: bool) -> Self {
+        self.lock = lock;
+        self
+    }
+
+    /// 探索中に読めなかったディレクトリの扱いを設定する
+    pub fn with_walk_errors(mut self, walk_errors: WalkErrors) -> Self {
+        self.walk_errors = walk_errors;
+        self
+    }
+
+    /// リモートにだけあるブランチを表示するかを設定する
+    pub fn with_remote_only_branches(mut self, remote_only_branches: bool) -> Self {
+        self.remote_only_branches = remote_only_branches;
+        self
+    }
+
+    /// テキスト出力の幅を設定する
+    pub fn with_width(mut self, width: Option<usize>) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// テキスト出力の記号を設定する
+    pub fn with_icons(mut self, icons: Option<IconStyle>) -> Self {
+        self.icons = icons;
+        self
+    }
+
+    /// テキスト出力のパスのシンボリックリンクを解決するかを設定する
+    pub fn with_resolve_symlinks(mut self, resolve_symlinks: bool) -> Self {
+        self.resolve_symlinks = resolve_symlinks;
+        self
+    }
+
+    /// パスを端末のハイパーリンクにするかを設定する
+    pub fn with_hyperlinks(mut self, hyperlinks: bool) -> Self {
+        self.hyperlinks = hyperlinks;
+        self
+    }
+
+    /// 色や記号を使わずに表示するかを設定する
+    pub fn with_plain(mut self, plain: bool) -> Self {
+        self.plain = plain;
+        self
+    }
+
+    /// テキスト出力の分け方を設定する
+    pub fn with_group_by(mut self, group_by: Option<GroupBy>) -> Self {
+        self.group_by = group_by;
+        self
+    }
+
+    /// 前回の結果を保存する場所を指定する
+    pub fn with_storage(mut self, storage: Option<Storage>) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    /// `--recheck` で使う前回の結果（保存されていなければ警告に加えてNone）
+    fn last_scan(&self, warnings: &mut Vec<EnvelopeWarning>) -> Option<LastScan> {
+        let loaded = match &self.storage {
+            Some(storage) => LastScan::load(storage).unwrap_or_else(|e| {
+                warnings.push(self.warning(
+                    WarningKind::RecheckUnavailable,
+                    None,
+                    Message::LastScanUnreadable(&e.to_string()),
+                ));
+                None
+            }),
+            None => None,
+        };
+        if loaded.is_none() {
+            warnings.push(self.warning(
+                WarningKind::RecheckUnavailable,
+                None,
+                Message::NoPreviousScan,
+            ));
+        }
+        loaded
+    }
+
+    /// 前回対応が必要だったリポジトリと、前回走査していないスキャンルートだけを調べ直す（走査中の警告も返す）
+    pub fn recheck(
+        &self,
+        last: &LastScan,
+    ) -> Result<(Vec<Repository>, Vec<EnvelopeWarning>), RunError> {
+        recheck_pending(self, last)
+    }
+
+    /// 今回の結果から `--recheck` のための記録を作る（`previous` は調べ直した前回の結果で、そのスキャンルートも引き継ぐ）
+    fn next_last_scan(&self, previous: Option<&LastScan>, repositories: &[Repository]) -> LastScan {
+        let mut roots = previous.map(|last| last.roots.clone()).unwrap_or_default();
+        for (path, _) in &self.roots {
+            if !roots.contains(path) {
+                roots.push(path.clone());
+            }
+        }
+        LastScan::new(roots, repositories)
+    }
+
+    /// `--recheck` のための記録を保存する（保存する場所がなければ何もしない）
+    pub fn save_last_scan(&self, last_scan: &LastScan) -> PendectorResult<()> {
+        match &self.storage {
+            Some(storage) => last_scan.save(storage),
+            None => Ok(()),
+        }
+    }
+
+    /// すべてのスキャンルートを走査し、走査中の警告と一緒に返す（`for_display` なら状態表示のためにfetch・絞り込みを行う）
+    pub fn scan(
+        &self,
+        for_display: bool,
+        limit: Option<usize>,
+    ) -> Result<(Vec<Repository>, Vec<EnvelopeWarning>), RunError> {
+        scan_repositories(self, &self.roots, for_display, limit)
+    }
+
+    /// 表示対象の並べ替えと件数制限
+    pub fn select_for_display(&self, repositories: Vec<Repository>) -> Vec<Repository> {
+        select_for_display(self, repositories)
+    }
+
+    /// データの最も古いリポジトリから `batch` 件を調べ直し、調べた件数を返す
+    pub fn recheck_oldest(
+        &self,
+        known: &mut Vec<Repository>,
+        batch: usize,
+    ) -> Result<usize, RunError> {
+        recheck_oldest(self, known, batch)
+    }
+
+    /// 出力形式・verboseを解決した出力フォーマッタ
+    pub fn formatter(&self) -> OutputFormatter {
+        build_formatter(self)
+    }
+
+    /// スキャンルートごとに設定された出力形式（重複を除く）
+    pub fn configured_formats(&self) -> Vec<OutputFormat> {
+        configured_values(&self.config, &self.roots, |c| c.format)
+    }
+
+    /// 状態表示の本文を組み立てる
+    pub fn render(&self, formatter: OutputFormatter, repositories: &[Repository]) -> String {
+        render_status(self, formatter, repositories)
+    }
+
+    /// スキャンルートに適用する設定
+    fn resolve(&self, path: &str) -> EffectiveOptions {
+        resolve_options(&self.overrides, &self.config, path)
+    }
+
+    /// 走査中の警告（`json-v2` には英語、標準エラー出力には表示する言語の文言を使う）
+    fn warning(
+        &self,
+        kind: WarningKind,
+        path: Option<String>,
+        message: Message,
+    ) -> EnvelopeWarning {
+        EnvelopeWarning::new(kind, path, message.text(Locale::En))
+            .with_display(message.text(self.locale))
+    }
+}
+
+/// リポジトリごとの情報の取得（ステータス、fetch、リモートとの比較など）の失敗
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepositoryError {
+    pub path: PathBuf,
+    /// 失敗した処理（`status`、`fetch`、`compare`、`artifacts`、`preview` のいずれか）
+    pub stage: &'static str,
+    pub error: ErrorReport,
+}
+
+/// 1回の実行結果
+#[derive(Debug, Clone)]
+pub struct ScanReport {
+    /// 表示対象のリポジトリ（サブコマンドでは見つかったすべてのリポジトリ）
+    pub repositories: Vec<Repository>,
+    pub errors: Vec<RepositoryError>,
+    pub summary: Summary,
+    /// 表示対象を絞り込む前のすべてのリポジトリのfetchの結果
+    pub fetch: FetchSummary,
+    pub duration: Duration,
+    pub roots: Vec<String>,
+    /// 状態表示で `--recheck` のために保存する記録（保存は呼び出し側が [`RunOptions::save_last_scan`] で行う）
+    pub last_scan: Option<LastScan>,
+    /// 実行中の警告（飛ばしたスキャンルートや、探索時間の上限を過ぎたスキャンルートなど）
+    pub warnings: Vec<EnvelopeWarning>,
+}
+
+impl ScanReport {
+    fn new(
+        repositories: Vec<Repository>,
+        fetch: FetchSummary,
+        roots: Vec<String>,
+        duration: Duration,
+        last_scan: Option<LastScan>,
+        warnings: Vec<EnvelopeWarning>,
+    ) -> Self {
+        let errors = repositories
+            .iter()
+            .flat_map(|repo| {
+                repo.errors().map(|(stage, error)| RepositoryError {
+                    path: repo.path.clone(),
+                    stage,
+                    error: error.clone(),
+                })
+            })
+            .collect();
+        Self {
+            summary: Summary::of(&repositories),
+            fetch,
+            repositories,
+            errors,
+            duration,
+            roots,
+            last_scan,
+            warnings,
+        }
+    }
+}
+
+/// スキャンルートを走査し、状態表示なら設定に従って表示対象を選ぶ
+///
+/// サブコマンド向けの実行（`show_status` がfalse）ではfetchや表示の絞り込みを行わない。
+/// 警告は標準エラー出力に書かず [`ScanReport::warnings`] で返す。
+pub fn run(options: &RunOptions) -> Result<ScanReport, RunError> {
+    let started = Instant::now();
+    // 表示するリポジトリを絞り込む場合は、絞り込み後に件数を制限するため探索は打ち切らない
+    let show = options.show_status;
+    let discovery_limit = options
+        .limit
+        .filter(|_| show && !any_display_filter(options));
+    let mut warnings = Vec::new();
+    let previous = if show && options.recheck {
+        options.last_scan(&mut warnings)
+    } else {
+        None
+    };
+    let (mut repositories, mut scan_warnings) = match &previous {
+        Some(last) => options.recheck(last)?,
+        None => options.scan(show, discovery_limit)?,
+    };
+    warnings.append(&mut scan_warnings);
+    let fetch = FetchSummary::of(&repositories);
+    let mut last_scan = None;
+    if show {
+        last_scan = Some(options.next_last_scan(previous.as_ref(), &repositories));
+        repositories = options.select_for_display(repositories);
+    }
+    Ok(ScanReport::new(
+        repositories,
+        fetch,
+        options.roots.iter().map(|(path, _)| path.clone()).collect(),
+        started.elapsed(),
+        last_scan,
+        warnings,
+    ))
+}
+
+/// スキャン対象パスの決定：CLI引数とフラグに基づく
+fn resolve_paths_to_scan(
+    args: &Args,
+    config: &Config,
+    cli_roots: &[(String, Option<usize>)],
+) -> Vec<(String, Option<usize>)> {
+    let config_roots = || {
+        config
+            .get_default_paths()
+            .iter()
+            .map(|path| (path.clone(), None))
+    };
+    if args.target_paths().is_empty() {
+        // パスが指定されていない場合は設定ファイルのデフォルトパスを使用
+        config_roots().collect()
+    } else if args.add_path {
+        // --add-pathフラグがある場合は設定ファイルのパスに追加
+        config_roots().chain(cli_roots.iter().cloned()).collect()
+    } else {
+        // 通常は指定されたパスのみを使用（設定ファイルのパスは上書き）
+        cli_roots.to_vec()
+    }
+}
+
+/// 探索時間の上限を過ぎたスキャンルートの警告
+fn budget_message<'a>(root: &'a str, exceeded: &BudgetExceeded) -> Message<'a> {
+    Message::TimeBudgetExceeded {
+        root,
+        budget_secs: exceeded.budget.as_secs(),
+        repositories: exceeded.repositories,
+        not_descended: exceeded.directories_not_descended,
+        not_checked: exceeded.repositories_not_checked,
+        fetches_abandoned: exceeded.fetches_abandoned,
+    }
+}
+
+/// 探索中に読めなかったパスの警告（権限のないディレクトリは `-v` のときだけ1件ずつ挙げる）
+fn walk_warnings(
+    run: &RunOptions,
+    root: &str,
+    denied: &[PathBuf],
+    failures: &[WalkFailure],
+) -> Vec<EnvelopeWarning> {
+    let mut warnings = Vec::new();
+    if run.walk_errors == WalkErrors::Details {
+        for path in denied {
+            let path = path.display().to_string();
+            warnings.push(run.warning(
+                WarningKind::SkippedPath,
+                Some(path.clone()),
+                Message::PermissionDeniedPath(&path),
+            ));
+        }
+    } else if !denied.is_empty() {
+        warnings.push(run.warning(
+            WarningKind::SkippedPath,
+            Some(root.to_string()),
+            Message::PermissionDenied {
+                root,
+                count: denied.len(),
+            },
+        ));
+    }
+    for failure in failures {
+        warnings.push(run.warning(
+            WarningKind::SkippedPath,
+            failure.path.as_ref().map(|path| path.display().to_string()),
+            Message::WalkFailed(&failure.message),
+        ));
+    }
+    warnings
+}
+
+/// スキャンルートと設定ファイルで固定したリポジトリに共通の走査オプション
+///
+/// `dirty_ignore` のパターンはここで一度だけコンパイルし、誤りがあれば設定エラーにする。
+fn common_scan_options(
+    run: &RunOptions,
+    options: &EffectiveOptions,
+    fetch: bool,
+) -> PendectorResult<ScanOptions> {
+    let config = &run.config;
+    let path_config = &options.path_config;
+    let dirty_ignore = ExcludeFilter::from_patterns(&path_config.dirty_ignore).map_err(|e| {
+        PendectorError::ConfigError {
+            path: PathBuf::new(),
+            message: format!("Invalid dirty_ignore pattern: {e}"),
+        }
+    })?;
+    let dirty_warn_days = run
+        .dirty_warn_days
+        .unwrap_or(config.defaults.dirty_warn_days);
+
+    Ok(ScanOptions::new()
+        .with_fetch(fetch)
+        .with_offline(run.overrides.offline)
+        .with_explain_filtering(run.explain_filtering)
+        .with_fetch_timeout(Duration::from_secs(options.fetch_timeout))
+        .with_fetch_slow_threshold(run.fetch_slow_threshold)
+        .with_fetch_skip_slow(run.fetch_skip_slow)
+        .with_progress(run.progress)
+        .with_progress_interval(
+            run.progress_interval
+                .unwrap_or(Duration::from_secs(config.defaults.progress_interval)),
+        )
+        .with_compare_remote(run.compare_remote.clone())
+        .with_default_branches(config.defaults.default_branches.clone())
+        .with_ignore_branches(config.defaults.ignore_branches.clone())
+        .with_status_file_limit(config.defaults.status_file_limit)
+        .with_status_update_index(config.defaults.status_update_index)
+        .with_unborn_remote(config.defaults.unborn_remote)
+        .with_skip_remote_check(!run.remote_check || !config.defaults.remote_check)
+        .with_web_url_templates(config.defaults.web_url_templates.clone())
+        .with_stale_dirty_after(time::days(dirty_warn_days))
+        .with_jobs(path_config.jobs)
+        .with_fetch_jobs(path_config.fetch_jobs)
+        .with_time_budget(path_config.time_budget.map(Duration::from_secs))
+        .with_disk_usage(run.disk_usage)
+        .with_artifacts(run.artifacts)
+        .with_preview_lines(run.preview_lines)
+        .with_trust_maintenance(run.trust_maintenance)
+        .with_priority(path_config.priority)
+        .with_name_style(path_config.name_style)
+        .with_dirty_ignore(dirty_ignore)
+        .with_fetch_env(path_config.fetch_env.clone())
+        .with_fetch_env_passthrough(path_config.fetch_env_passthrough.clone())
+        .with_fetch_proxy(
+            path_config.proxy.clone(),
+            path_config.ssh_proxy_command.clone(),
+        ))
+}
+
+/// 探索中に読めなかったディレクトリの扱い（--strict > --skip-permission-errors > -v）
+fn walk_errors(args: &Args) -> WalkErrors {
+    if args.strict {
+        WalkErrors::Strict
+    } else if args.skip_permission_errors {
+        WalkErrors::SkipPermissionDenied
+    } else if args.is_verbose() {
+        WalkErrors::Details
+    } else {
+        WalkErrors::Summary
+    }
+}
+
+/// スキャンルートごとの表示の絞り込み（CLI引数 > パス固有設定 > デフォルト設定）
+fn retain_for_display(
+    run: &RunOptions,
+    options: &EffectiveOptions,
+    repositories: &mut Vec<Repository>,
+) {
+    repositories.retain(|repo| match display_filter_reason(run, options, repo) {
+        Some(reason) => {
+            if run.explain_filtering {
+                log_filter_decision(reason, &repo.path, None);
+            }
+            false
+        }
+        None => true,
+    });
+}
+
+/// 表示から除く理由（残す場合はNone）
+fn display_filter_reason(
+    run: &RunOptions,
+    options: &EffectiveOptions,
+    repo: &Repository,
+) -> Option<FilterReason> {
+    if options.changes_only {
+        // 状態を取得できなかったリポジトリは変更の有無が分からないので残す
+        if !(repo.has_changes || repo.attention() == Attention::Error) {
+            return Some(FilterReason::ChangesOnly);
+        }
+    } else if options.path_config.hide_clean && !repo.needs_attention() {
+        // 変更がなくてもpush・pull待ちや操作の途中のものは残す
+        return Some(FilterReason::HideClean);
+    }
+    if !run.attention.is_empty() && !run.attention.contains(&repo.attention()) {
+        return Some(FilterReason::Attention);
+    }
+    if !run.project_types.is_empty()
+        && !repo
+            .project_type
+            .is_some_and(|project_type| run.project_types.contains(&project_type))
+    {
+        return Some(FilterReason::ProjectType);
+    }
+    None
+}
+
+/// fetchを行う実行同士が重ならないよう、最初のfetchの前にロックを取得してスキャン終了まで保持する
+fn acquire_run_lock(run: &RunOptions, run_lock: &mut Option<RunLock>) -> Result<(), RunError> {
+    if run.lock && run_lock.is_none() {
+        *run_lock = Some(RunLock::acquire()?);
+    }
+    Ok(())
+}
+
+/// 走査の単位：ディレクトリを探索するスキャンルートか、設定ファイルで固定したリポジトリ
+enum ScanTarget<'a> {
+    Root(&'a str, Option<usize>),
+    Pinned(String, PathBuf),
+}
+
+/// すべてのスキャンルートを走査し、重複を除いたリポジトリ一覧と走査中の警告を返す
+///
+/// 設定ファイルの `[repos]` で固定したリポジトリは探索せずに常に含める。
+/// 状態表示のための走査（`for_display`）ではfetchを行い、変更のあるものだけを表示するルートは走査時に絞り込む。
+fn scan_repositories(
+    run: &RunOptions,
+    paths_to_scan: &[(String, Option<usize>)],
+    for_display: bool,
+    limit: Option<usize>,
+) -> Result<(Vec<Repository>, Vec<EnvelopeWarning>), RunError> {
+    let config = &run.config;
+    let scanner = RepoScanner::new();
+    let mut all_repositories = Vec::new();
+    let mut warnings = Vec::new();
+    let mut run_lock: Option<RunLock> = None;
+
+    let mut targets: Vec<ScanTarget> = paths_to_scan
+        .iter()
+        .map(|(path, depth_override)| ScanTarget::Root(path, *depth_override))
+        .collect();
+
+    // 設定ファイルで固定したリポジトリ（スキャンルートの外にあってもよい）
+    for path in config.pinned_repos() {
+        let repo_path = PathBuf::from(expand_tilde(&path));
+        if !repo_path.join(".git").exists() {
+            warnings.push(run.warning(
+                WarningKind::SkippedPath,
+                Some(path.clone()),
+                Message::SkippedPinnedRepository(&path),
+            ));
+            continue;
+        }
+        targets.push(ScanTarget::Pinned(path, repo_path));
+    }
+    // gitディレクトリが離れているものは、作業ツリーのパスで開けるよう登録してから調べる
+    for detached in config.detached_repos() {
+        let git_dir = PathBuf::from(expand_tilde(&detached.git_dir));
+        let work_tree = PathBuf::from(expand_tilde(&detached.work_tree));
+        if !git_dir.join("HEAD").exists() || !work_tree.is_dir() {
+            warnings.push(run.warning(
+                WarningKind::SkippedPath,
+                Some(detached.work_tree.clone()),
+                Message::SkippedDetachedRepository {
+                    git_dir: &detached.git_dir,
+                    work_tree: &detached.work_tree,
+                },
+            ));
+            continue;
+        }
+        register_detached_layout(&work_tree, &git_dir);
+        targets.push(ScanTarget::Pinned(detached.work_tree, work_tree));
+    }
+
+    // 優先度の高いものから探索・fetchする（件数制限に達しても先に残る）
+    targets.sort_by_cached_key(|target| match target {
+        ScanTarget::Root(path, _) => run.resolve(path).path_config.priority,
+        ScanTarget::Pinned(path, _) => config.get_path_config(path).priority,
+    });
+
+    for target in targets {
+        // 中断が要求されたら残りは探索せず、fetchのロックを解放して終わる
+        if is_interrupted() {
+            return Err(PendectorError::Interrupted.into());
+        }
+        // 上限に達していれば残りは探索しない
+        let remaining = limit.map(|limit| limit.saturating_sub(all_repositories.len()));
+        if remaining == Some(0) {
+            break;
+        }
+
+        match target {
+            ScanTarget::Root(path, depth_override) => {
+                // チルダ展開してからパスの存在確認
+                let expanded_path = expand_tilde(path);
+                let path_buf = std::path::Path::new(expanded_path.as_str());
+                if !path_buf.exists() {
+                    if run.skip_missing_paths || config.defaults.skip_missing_paths {
+                        warnings.push(run.warning(
+                            WarningKind::SkippedPath,
+                            Some(path.to_string()),
+                            Message::SkippedMissingPath(path),
+                        ));
+                        continue;
+                    }
+                    return Err(RunError::InvalidRoot {
+                        path: path.to_string(),
+                        problem: RootProblem::Missing,
+                    });
+                }
+                if !path_buf.is_dir() {
+                    return Err(RunError::InvalidRoot {
+                        path: path.to_string(),
+                        problem: RootProblem::NotDirectory,
+                    });
+                }
+
+                let options = run.resolve(path);
+                let fetch = for_display && options.fetch;
+                if fetch {
+                    acquire_run_lock(run, &mut run_lock)?;
+                }
+
+                // ルートごとに指定した深さが最も優先される
+                let scan_options = common_scan_options(run, &options, fetch)
+                    .map_err(|source| RunError::Scan {
+                        path: path.to_string(),
+                        source,
+                    })?
+                    .with_max_depth(depth_override.unwrap_or(options.max_depth))
+                    .with_follow_symlinks(options.follow_symlinks)
+                    .with_exclude_patterns(options.exclude_patterns.clone())
+                    .with_exclude_repos(options.path_config.exclude_repos.clone())
+                    .with_limit(remaining)
+                    .with_walk_errors(run.walk_errors)
+                    .with_scan_stats(options.verbose);
+
+                match scanner.scan_root(expanded_path.as_str(), &scan_options) {
+                    Ok(scan) => {
+                        let mut repositories = scan.repositories;
+                        warnings.extend(walk_warnings(
+                            run,
+                            path,
+                            &scan.permission_denied,
+                            &scan.walk_failures,
+                        ));
+                        if let Some(exceeded) = scan.budget_exceeded {
+                            warnings.push(run.warning(
+                                WarningKind::BudgetExceeded,
+                                Some(path.to_string()),
+                                budget_message(path, &exceeded),
+                            ));
+                        }
+                        if for_display {
+                            retain_for_display(run, &options, &mut repositories);
+                        }
+                        all_repositories.append(&mut repositories);
+                    }
+                    Err(e @ PendectorError::GitRepositoryNotFound(_)) => {
+                        // Git repository not found は続行
+                        warnings.push(EnvelopeWarning::new(
+                            WarningKind::SkippedPath,
+                            Some(path.to_string()),
+                            e.to_string(),
+                        ));
+                    }
+                    Err(e @ PendectorError::FileSystemError { .. }) => return Err(e.into()),
+                    Err(e) => {
+                        return Err(RunError::Scan {
+                            path: path.to_string(),
+                            source: e,
+                        })
+                    }
+                }
+            }
+            ScanTarget::Pinned(path, repo_path) => {
+                // 固定したリポジトリはCLI引数でパスを指定してもパス固有設定を使う
+                let options = EffectiveOptions::from_path_config(
+                    &run.overrides,
+                    config.get_path_config(&path),
+                );
+                let fetch = for_display && options.fetch;
+                if fetch {
+                    acquire_run_lock(run, &mut run_lock)?;
+                }
+                let scan_options =
+                    common_scan_options(run, &options, fetch).map_err(|source| RunError::Scan {
+                        path: path.clone(),
+                        source,
+                    })?;
+                let mut repositories: Vec<Repository> = scanner
+                    .scan_repositories(&[repo_path], &scan_options)
+                    .into_iter()
+                    .map(|repo| repo.with_scan_root(Some(path.clone())))
+                    .collect();
+                if for_display {
+                    retain_for_display(run, &options, &mut repositories);
+                }
+                all_repositories.append(&mut repositories);
+            }
+        }
+    }
+    if is_interrupted() {
+        return Err(PendectorError::Interrupted.into());
+    }
+
+    // 複数のスキャンルートが同じリポジトリを指している場合は重複を除き、実行ごとに同じ順序で出力する
+    let (mut repositories, duplicates) = partition_duplicates(all_repositories);
+    assign_scan_roots(paths_to_scan, &mut repositories);
+    sort_by_canonical_path(&mut repositories);
+    if for_display && run.explain_filtering {
+        for repo in &duplicates {
+            log_filter_decision(
+                FilterReason::Duplicate,
+                &repo.path,
+                repo.scan_root.as_deref(),
+            );
+        }
+        for repo in &repositories {
+            log_filter_decision(FilterReason::Included, &repo.path, None);
+        }
+    }
+    Ok((repositories, warnings))
+}
+
+/// いずれかのスキャンルートで表示するリポジトリを絞り込むか（絞り込み後に件数を制限するため探索は打ち切らない）
+fn any_display_filter(run: &RunOptions) -> bool {
+    !run.attention.is_empty()
+        || !run.project_types.is_empty()
+        || run
+            .roots
+            .iter()
+            .map(|(path, _)| run.resolve(path))
+            .any(|options| options.changes_only || options.path_config.hide_clean)
+}
+
+/// 表示対象のソート・件数制限（変更の有無による絞り込みは走査時に済んでいる）
+fn select_for_display(run: &RunOptions, all_repositories: Vec<Repository>) -> Vec<Repository> {
+    // ソートの実施：フィルタ後・出力前（正規化パス順からの安定ソート）
+    let mut sorted_repos = all_repositories;
+    match run.sort {
+        SortKey::Path => {
+            sorted_repos.sort_by(|a, b| a.path.cmp(&b.path));
+        }
+        SortKey::Staleness => {
+            sorted_repos.sort_by(|a, b| a.path.cmp(&b.path));
+            sort_by_staleness(&mut sorted_repos);
+        }
+    }
+    // 優先度の高いものを常に先に表示する（同じ優先度の中では上の並び順を保つ）
+    sorted_repos.sort_by_key(|repo| repo.priority);
+    if let Some(limit) = run.limit {
+        sorted_repos.truncate(limit);
+    }
+    if let Some(top) = run.top {
+        sorted_repos = most_actionable(sorted_repos, top);
+    }
+    sorted_repos
+}
+
+/// 設定から出力フォーマッタを組み立てる
+fn build_formatter(run: &RunOptions) -> OutputFormatter {
+    let config = &run.config;
+    // 出力フォーマットとverboseモードは最初のスキャンルートの設定で決める
+    let (format, verbose) = match run.roots.first() {
+        Some((path, _)) => {
+            let options =
+                EffectiveOptions::from_path_config(&run.overrides, config.get_path_config(path));
+            (options.format, options.verbose)
+        }
+        None => (
+            run.overrides.format.unwrap_or_default(),
+            run.overrides.verbose,
+        ),
+    };
+
+    let dirty_warn_days = run
+        .dirty_warn_days
+        .unwrap_or(config.defaults.dirty_warn_days);
+    OutputFormatter::new(verbose, format)
+        .with_remote_only_branches(run.remote_only_branches)
+        .with_dirty_warn_after(time::days(dirty_warn_days))
+        .with_width(run.width.or_else(terminal_width))
+        .with_icons(run.icons.unwrap_or(config.defaults.icons))
+        .with_resolve_symlinks(run.resolve_symlinks)
+        .with_hyperlinks(run.hyperlinks && !run.plain)
+        .with_plain(run.plain)
+        .with_locale(run.locale)
+        .with_scan_roots(
+            run.roots
+                .iter()
+                .map(|(path, _)| expand_tilde(path))
+                .collect(),
+        )
+}
+
+/// スキャンルートごとの設定値（重複を除き、ルートの順序を保つ）
+fn configured_values<T: PartialEq>(
+    config: &Config,
+    paths_to_scan: &[(String, Option<usize>)],
+    value: impl Fn(&PathConfigResolved) -> T,
+) -> Vec<T> {
+    let mut values = Vec::new();
+    for (path, _) in paths_to_scan {
+        let v = value(&config.get_path_config(path));
+        if !values.contains(&v) {
+            values.push(v);
+        }
+    }
+    values
+}
+
+/// 各リポジトリに、それを含むスキャンルートを記録する（入れ子のルートでは最も内側のルート）
+///
+/// どのルートにも含まれないもの（設定ファイルで固定したリポジトリ）は記録済みの値を残す。
+fn assign_scan_roots(paths_to_scan: &[(String, Option<usize>)], repositories: &mut [Repository]) {
+    let roots: Vec<Vec<PathBuf>> = paths_to_scan
+        .iter()
+        .map(|(path, _)| {
+            let expanded = PathBuf::from(expand_tilde(path));
+            let canonical = canonicalize_path(&expanded);
+            vec![normalize_path(&expanded), canonical]
+        })
+        .collect();
+
+    for repo in repositories {
+        let repo_path = normalize_path(&repo.path);
+        let index = roots
+            .iter()
+            .enumerate()
+            .filter_map(|(i, prefixes)| {
+                prefixes
+                    .iter()
+                    .filter(|prefix| repo_path.starts_with(prefix))
+                    .map(|prefix| prefix.components().count())
+                    .max()
+                    .map(|depth| (i, depth))
+            })
+            .max_by_key(|&(i, depth)| (depth, std::cmp::Reverse(i)))
+            .map(|(i, _)| i);
+        if let Some(index) = index {
+            repo.scan_root = Some(paths_to_scan[index].0.clone());
+        }
+    }
+}
+
+/// リポジトリを記録したスキャンルートごとに分ける（ルートの順、記録のないものは最初のルートに含める）
+fn group_by_root(
+    paths_to_scan: &[(String, Option<usize>)],
+    repositories: &[Repository],
+) -> Vec<(String, Vec<Repository>)> {
+    let mut sections: Vec<(String, Vec<Repository>)> = paths_to_scan
+        .iter()
+        .map(|(path, _)| (path.clone(), Vec::new()))
+        .collect();
+    for repo in repositories {
+        let root = repo
+            .scan_root
+            .clone()
+            .or_else(|| paths_to_scan.first().map(|(path, _)| path.clone()))
+            .unwrap_or_default();
+        match sections.iter_mut().find(|(section, _)| *section == root) {
+            Some((_, repos)) => repos.push(repo.clone()),
+            None => sections.push((root, vec![repo.clone()])),
+        }
+    }
+    sections.retain(|(_, repos)| !repos.is_empty());
+    sections
+}
+
+/// 状態表示の本文を組み立てる
+///
+/// `--group-by root` 指定時や、スキャンルートごとにverbose設定が異なる場合は、ルートごとのセクションに分ける。
+/// verbose設定が異なる場合は、それぞれのセクションをそのルートの設定で表示する。
+fn render_status(
+    run: &RunOptions,
+    mut formatter: OutputFormatter,
+    repositories: &[Repository],
+) -> String {
+    let (config, paths_to_scan) = (&run.config, run.roots.as_slice());
+    let per_root_verbose =
+        !run.overrides.verbose && configured_values(config, paths_to_scan, |c| c.verbose).len() > 1;
+    let grouped = run.group_by == Some(GroupBy::Root);
+    if formatter.is_json() || !(grouped || per_root_verbose) {
+        return formatter.format_repositories(repositories);
+    }
+
+    let mut sections = Vec::new();
+    for (root, repos) in group_by_root(paths_to_scan, repositories) {
+        if per_root_verbose {
+            formatter.verbose = config.get_path_config(&root).verbose;
+        }
+        sections.push(format!(
+            "== {root} ==\n{}",
+            formatter.format_repositories(&repos)
+        ));
+    }
+    if sections.is_empty() {
+        return formatter.format_repositories(repositories);
+    }
+    sections.join("\n\n")
+}
+
+/// データの最も古いリポジトリから `batch` 件を調べ直し、調べた件数を返す
+///
+/// 調べ直して表示対象から外れたもの（変更がなくなったものなど）は一覧から除く。
+fn recheck_oldest(
+    run: &RunOptions,
+    known: &mut Vec<Repository>,
+    batch: usize,
+) -> Result<usize, RunError> {
+    let scanner = RepoScanner::new();
+    let mut run_lock: Option<RunLock> = None;
+
+    known.sort_by_key(|repo| repo.checked_at);
+    let targets: Vec<Repository> = known.drain(..batch.min(known.len())).collect();
+    for repo in &targets {
+        if is_interrupted() {
+            return Err(PendectorError::Interrupted.into());
+        }
+        known.append(&mut restatus(
+            run,
+            &scanner,
+            &mut run_lock,
+            &repo.path,
+            &repo.scan_root,
+        )?);
+    }
+    sort_by_canonical_path(known);
+    Ok(targets.len())
+}
+
+/// 1つのリポジトリの状態を取得し直す（表示対象から外れたものは含めない）
+fn restatus(
+    run: &RunOptions,
+    scanner: &RepoScanner,
+    run_lock: &mut Option<RunLock>,
+    repo_path: &Path,
+    scan_root: &Option<String>,
+) -> Result<Vec<Repository>, RunError> {
+    let options = run.resolve(&repo_path.to_string_lossy());
+    if options.fetch {
+        acquire_run_lock(run, run_lock)?;
+    }
+    let scan_options =
+        common_scan_options(run, &options, options.fetch).map_err(|source| RunError::Scan {
+            path: repo_path.display().to_string(),
+            source,
+        })?;
+    let mut repositories: Vec<Repository> = scanner
+        .scan_repositories(&[repo_path], &scan_options)
+        .into_iter()
+        .map(|rechecked| rechecked.with_scan_root(scan_root.clone()))
+        .collect();
+    retain_for_display(run, &options, &mut repositories);
+    Ok(repositories)
+}
+
+/// 前回対応が必要だったリポジトリを調べ直し、前回走査していないスキャンルートは通常どおり走査する
+///
+/// 前回から消えたリポジトリは警告せずに除く。
+fn recheck_pending(
+    run: &RunOptions,
+    last: &LastScan,
+) -> Result<(Vec<Repository>, Vec<EnvelopeWarning>), RunError> {
+    let new_roots: Vec<(String, Option<usize>)> = run
+        .roots
+        .iter()
+        .filter(|(path, _)| !last.roots.contains(path))
+        .cloned()
+        .collect();
+    let (mut all_repositories, warnings) = if new_roots.is_empty() {
+        (Vec::new(), Vec::new())
+    } else {
+        scan_repositories(run, &new_roots, true, None)?
+    };
+
+    let scanner = RepoScanner::new();
+    let mut run_lock: Option<RunLock> = None;
+    for PendingRepository { path, scan_root } in &last.pending {
+        if is_interrupted() {
+            return Err(PendectorError::Interrupted.into());
+        }
+        if path.is_dir() {
+            all_repositories.append(&mut restatus(
+                run,
+                &scanner,
+                &mut run_lock,
+                path,
+                scan_root,
+            )?);
+        }
+    }
+
+    let (mut repositories, _) = partition_duplicates(all_repositories);
+    sort_by_canonical_path(&mut repositories);
+    Ok((repositories, warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// CLI引数を使わずに、指定したスキャンルートだけを走査する設定
+    fn options_for(roots: &[&str]) -> RunOptions {
+        RunOptions::new(Config::default())
+            .with_roots(roots.iter().map(|root| (root.to_string(), None)).collect())
+    }
+
+    #[test]
+    fn test_run_reports_repositories_and_summary() {
+        let temp_dir = TempDir::new().unwrap();
+        git2::Repository::init(temp_dir.path().join("a_repo")).unwrap();
+        let dirty = temp_dir.path().join("b_repo");
+        git2::Repository::init(&dirty).unwrap();
+        fs::write(dirty.join("new.txt"), "new").unwrap();
+
+        let root = temp_dir.path().to_str().unwrap();
+        let report = run(&options_for(&[root])).unwrap();
+
+        assert_eq!(report.roots, vec![root.to_string()]);
+        assert_eq!(report.repositories.len(), 2);
+        assert_eq!(report.summary.total, 2);
+        assert_eq!(report.summary.with_changes, 1);
+        assert!(report.errors.is_empty());
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_run_options_from_args_match_the_builder() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().to_str().unwrap();
+        let args = Args::try_parse_with_sources_from([
+            "pendector",
+            "--no-config",
+            "--limit",
+            "3",
+            "--changes-only",
+            root,
+        ])
+        .unwrap();
+        let options = RunOptions::from_args(&args, Config::default()).unwrap();
+
+        assert_eq!(options.roots, vec![(root.to_string(), None)]);
+        assert_eq!(options.limit, Some(3));
+        assert!(options.overrides.changes_only);
+        // CLI引数でスキャンルートを置き換えたのでパス固有設定は使わない
+        assert!(!options.overrides.path_configs);
+        assert!(options.show_status);
+    }
+
+    #[test]
+    fn test_run_leaves_saving_the_last_scan_to_the_caller() {
+        let temp_dir = TempDir::new().unwrap();
+        let dirty = temp_dir.path().join("repo");
+        git2::Repository::init(&dirty).unwrap();
+        fs::write(dirty.join("new.txt"), "new").unwrap();
+        let data_dir = TempDir::new().unwrap();
+        let storage = Storage::new(data_dir.path().to_path_buf());
+
+        let options =
+            options_for(&[temp_dir.path().to_str().unwrap()]).with_storage(Some(storage.clone()));
+        let report = run(&options).unwrap();
+
+        assert_eq!(LastScan::load(&storage).unwrap(), None);
+        let last_scan = report.last_scan.unwrap();
+        assert_eq!(last_scan.pending.len(), 1);
+        options.save_last_scan(&last_scan).unwrap();
+        assert_eq!(LastScan::load(&storage).unwrap(), Some(last_scan));
+    }
+
+    #[test]
+    fn test_run_returns_warnings_instead_of_printing() {
+        let temp_dir = TempDir::new().unwrap();
+        let missing = temp_dir.path().join("missing");
+        let missing = missing.to_str().unwrap();
+
+        // 前回の結果がなければ警告してすべて調べる
+        let options = options_for(&[missing])
+            .with_skip_missing_paths(true)
+            .with_recheck(true)
+            .with_locale(Locale::Ja);
+        let report = run(&options).unwrap();
+
+        let kinds: Vec<WarningKind> = report.warnings.iter().map(|w| w.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![WarningKind::RecheckUnavailable, WarningKind::SkippedPath]
+        );
+        let skipped = &report.warnings[1];
+        assert_eq!(skipped.path.as_deref(), Some(missing));
+        assert_eq!(
+            skipped.message,
+            Message::SkippedMissingPath(missing).text(Locale::En)
+        );
+        assert_eq!(
+            skipped.display,
+            Message::SkippedMissingPath(missing).text(Locale::Ja)
+        );
+    }
+
+    #[test]
+    fn test_run_returns_unreadable_paths_as_warnings() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().to_str().unwrap();
+        git2::Repository::init(temp_dir.path().join("repo")).unwrap();
+        // たどると自身に戻るシンボリックリンクは探索時のエラーになる
+        std::os::unix::fs::symlink(temp_dir.path(), temp_dir.path().join("loop")).unwrap();
+
+        let options = options_for(&[root])
+            .with_overrides(Overrides::new().with_follow_symlinks(true))
+            .with_progress(ProgressMode::None);
+        let report = run(&options).unwrap();
+
+        assert_eq!(report.repositories.len(), 1);
+        assert_eq!(report.warnings.len(), 1);
+        let warning = &report.warnings[0];
+        assert_eq!(warning.kind, WarningKind::SkippedPath);
+        let loop_path = temp_dir.path().join("loop");
+        assert_eq!(warning.path.as_deref(), loop_path.to_str());
+        assert!(warning
+            .message
+            .starts_with("failed to access path during scan: "));
+    }
+
+    #[test]
+    fn test_run_rejects_missing_root_as_usage_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let missing = temp_dir.path().join("missing");
+
+        let error = run(&options_for(&[missing.to_str().unwrap()])).unwrap_err();
+
+        assert!(matches!(error, RunError::InvalidRoot { .. }));
+        assert_eq!(error.exit_code(), ExitCode::Usage);
+        assert!(error.to_string().ends_with("does not exist"));
+    }
+
+    #[test]
+    fn test_run_records_innermost_scan_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let outer = temp_dir.path().join("src");
+        let inner = outer.join("work");
+        git2::Repository::init(outer.join("tool")).unwrap();
+        git2::Repository::init(inner.join("app")).unwrap();
+
+        let (outer, inner) = (outer.to_str().unwrap(), inner.to_str().unwrap());
+        let report = run(&options_for(&[outer, inner])).unwrap();
+
+        let roots: Vec<(&str, Option<&str>)> = report
+            .repositories
+            .iter()
+            .map(|repo| (repo.name.as_str(), repo.scan_root.as_deref()))
+            .collect();
+        assert_eq!(roots, vec![("tool", Some(outer)), ("app", Some(inner))]);
+    }
+}
@@ -0,0 +1,160 @@
+//! テスト用のリポジトリを作るユーティリティ（`test-util` フィーチャーで公開）
+//!
+//! 一時ディレクトリにbareリポジトリを作り、ローカルの「リモート」として
+//! クローン同士でpush/fetchできるようにする。コミット済みの単独のリポジトリも作れる。
+//! 失敗時はpanicする。
+
+use git2::{IndexAddOption, PushOptions, Repository, RepositoryInitOptions, Signature};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tempfile::TempDir;
+
+/// リモートとクローンで使うブランチ名
+pub const FIXTURE_BRANCH: &str = "main";
+
+/// ローカルのbareリポジトリをoriginとするテスト用のリモート
+pub struct RemoteFixture {
+    dir: TempDir,
+    remote_path: PathBuf,
+}
+
+impl RemoteFixture {
+    /// 初期コミットを1つ持つbareリポジトリを作成する
+    pub fn new() -> Self {
+        let dir = TempDir::new().expect("create temp dir");
+        let remote_path = dir.path().join("remote.git");
+        let mut options = RepositoryInitOptions::new();
+        options
+            .bare(true)
+            .initial_head(&format!("refs/heads/{FIXTURE_BRANCH}"));
+        Repository::init_opts(&remote_path, &options).expect("init bare remote");
+
+        let fixture = Self { dir, remote_path };
+        // 空のリモートはクローンできないので、作業用のクローンから初期コミットをpushする
+        let seed = fixture.dir.path().join(".seed");
+        let mut options = RepositoryInitOptions::new();
+        options.initial_head(&format!("refs/heads/{FIXTURE_BRANCH}"));
+        let repo = Repository::init_opts(&seed, &options).expect("init seed repository");
+        repo.remote("origin", fixture.remote_url().as_str())
+            .expect("add origin");
+        commit(&seed, "initial commit");
+        push(&seed);
+        fs::remove_dir_all(&seed).expect("remove seed repository");
+        fixture
+    }
+
+    /// bareリポジトリのパス
+    pub fn remote_path(&self) -> &Path {
+        &self.remote_path
+    }
+
+    /// クローンを置くディレクトリ（スキャン対象のルートとして使える）
+    pub fn root(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// リモートを `name` ディレクトリにクローンし、そのパスを返す
+    pub fn clone_repo(&self, name: &str) -> PathBuf {
+        let path = self.dir.path().join(name);
+        Repository::clone(&self.remote_url(), &path).expect("clone remote");
+        path
+    }
+
+    fn remote_url(&self) -> String {
+        self.remote_path.to_string_lossy().to_string()
+    }
+}
+
+impl Default for RemoteFixture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 作業ツリーにファイルを1つ追加してコミットする
+pub fn commit(repo_path: &Path, message: &str) {
+    let repo = Repository::open(repo_path).expect("open repository");
+    let file_name = format!(
+        "{}.txt",
+        message.replace(|c: char| !c.is_alphanumeric(), "-")
+    );
+    fs::write(repo_path.join(&file_name), message).expect("write file");
+
+    let mut index = repo.index().expect("open index");
+    index
+        .add_all(["*"], IndexAddOption::DEFAULT, None)
+        .expect("stage files");
+    index.write().expect("write index");
+    let tree = repo
+        .find_tree(index.write_tree().expect("write tree"))
+        .expect("find tree");
+    let signature = Signature::now("Test User", "test@example.com").expect("signature");
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    let parents: Vec<_> = parent.iter().collect();
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &parents,
+    )
+    .expect("commit");
+}
+
+/// 現在のブランチをoriginにpushし、リモート追跡ブランチも更新する
+pub fn push(repo_path: &Path) {
+    let repo = Repository::open(repo_path).expect("open repository");
+    let mut remote = repo.find_remote("origin").expect("find origin");
+    let refspec = format!("refs/heads/{FIXTURE_BRANCH}:refs/heads/{FIXTURE_BRANCH}");
+    remote
+        .push(&[refspec.as_str()], Some(&mut PushOptions::new()))
+        .expect("push");
+    fetch(repo_path);
+}
+
+/// originからfetchしてリモート追跡ブランチを更新する
+pub fn fetch(repo_path: &Path) {
+    let repo = Repository::open(repo_path).expect("open repository");
+    let mut remote = repo.find_remote("origin").expect("find origin");
+    // 空のrefspecを渡すと設定済みのrefspec（refs/remotes/origin/*）が使われる
+    remote.fetch(&[] as &[&str], None, None).expect("fetch");
+}
+
+/// `git` コマンドをリポジトリで実行し、標準出力を前後の空白を除いて返す
+pub fn git(repo_path: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(repo_path)
+        .output()
+        .expect("run git");
+    assert!(
+        output.status.success(),
+        "git {} failed: {}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+/// `main` ブランチとコミットするユーザーを設定した空のリポジトリを作る
+pub fn init_repo(repo_path: &Path) {
+    fs::create_dir_all(repo_path).expect("create repository directory");
+    git(repo_path, &["init", "-q", "-b", FIXTURE_BRANCH]);
+    git(repo_path, &["config", "user.name", "Test User"]);
+    git(repo_path, &["config", "user.email", "test@example.com"]);
+}
+
+/// `files`（ファイル名と内容）を最初のコミットに含むリポジトリを一時ディレクトリに作る
+pub fn create_committed_repo(files: &[(&str, &str)]) -> TempDir {
+    let temp_dir = TempDir::new().expect("create temp dir");
+    let path = temp_dir.path();
+    init_repo(path);
+    for (name, content) in files {
+        fs::write(path.join(name), content).expect("write file");
+    }
+    git(path, &["add", "."]);
+    git(path, &["commit", "-q", "-m", "init"]);
+    temp_dir
+}
@@ -0,0 +1,125 @@
+use serde::Serializer;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// 時刻をUTCのRFC 3339形式（秒精度）で表す
+pub fn format_utc(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let rem = secs % 86_400;
+    let (hour, minute, second) = (rem / 3600, rem % 3600 / 60, rem % 60);
+    format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}Z")
+}
+
+/// 時刻をUTCの区切り文字なし形式（ブランチ名などに使える `20261016T123456Z`）で表す
+pub fn format_utc_compact(time: SystemTime) -> String {
+    format_utc(time).replace(['-', ':'], "")
+}
+
+/// 日数を期間にする（大きすぎる日数は表せる最大の期間に丸める）
+pub fn days(days: u64) -> Duration {
+    Duration::from_secs(days.saturating_mul(86_400))
+}
+
+/// 経過時間を最も大きい単位で短く表す（例: `12d`, `5h`, `3m`）
+pub fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    if secs >= 86_400 {
+        format!("{}d", secs / 86_400)
+    } else if secs >= 3600 {
+        format!("{}h", secs / 3600)
+    } else {
+        format!("{}m", secs / 60)
+    }
+}
+
+/// 経過時間を最も大きい単位で英語の文章向けに表す（例: `3 days`, `1 hour`）
+pub fn format_age_long(age: Duration) -> String {
+    let secs = age.as_secs();
+    let (count, unit) = if secs >= 86_400 {
+        (secs / 86_400, "day")
+    } else if secs >= 3600 {
+        (secs / 3600, "hour")
+    } else {
+        (secs / 60, "minute")
+    };
+    if count == 1 {
+        format!("1 {unit}")
+    } else {
+        format!("{count} {unit}s")
+    }
+}
+
+/// `Option<SystemTime>` をRFC 3339形式の文字列（またはnull）としてシリアライズする
+pub fn serialize_optional_utc<S: Serializer>(
+    time: &Option<SystemTime>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match time {
+        Some(time) => serializer.serialize_str(&format_utc(*time)),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// 1970-01-01からの日数をグレゴリオ暦の年月日に変換する
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_format_utc() {
+        assert_eq!(format_utc(UNIX_EPOCH), "1970-01-01T00:00:00Z");
+        assert_eq!(
+            format_utc(UNIX_EPOCH + Duration::from_secs(951_782_400)),
+            "2000-02-29T00:00:00Z"
+        );
+        assert_eq!(
+            format_utc(UNIX_EPOCH + Duration::from_secs(1_791_556_496)),
+            "2026-10-09T14:34:56Z"
+        );
+        assert_eq!(
+            format_utc_compact(UNIX_EPOCH + Duration::from_secs(1_791_556_496)),
+            "20261009T143456Z"
+        );
+    }
+
+    #[test]
+    fn test_days_saturates() {
+        assert_eq!(days(2), Duration::from_secs(2 * 86_400));
+        assert_eq!(days(u64::MAX), Duration::from_secs(u64::MAX));
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(Duration::from_secs(59)), "0m");
+        assert_eq!(format_age(Duration::from_secs(3 * 60)), "3m");
+        assert_eq!(format_age(Duration::from_secs(5 * 3600 + 59)), "5h");
+        assert_eq!(format_age(Duration::from_secs(12 * 86_400 + 3600)), "12d");
+    }
+
+    #[test]
+    fn test_format_age_long() {
+        assert_eq!(format_age_long(Duration::from_secs(30)), "0 minutes");
+        assert_eq!(format_age_long(Duration::from_secs(3600)), "1 hour");
+        assert_eq!(
+            format_age_long(Duration::from_secs(3 * 86_400 + 5)),
+            "3 days"
+        );
+    }
+}
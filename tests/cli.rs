@@ -1,17 +1,53 @@
 use assert_cmd::Command;
 use predicates::prelude::*;
 use std::fs;
+use std::path::Path;
 use tempfile::TempDir;
 
+/// テスト用のデータディレクトリ（監査ログや前回のスキャン結果を利用者の環境に残さない）
+fn test_data_home() -> std::path::PathBuf {
+    Path::new(env!("CARGO_TARGET_TMPDIR")).join("xdg-data")
+}
+
+/// データディレクトリをテスト用に差し替えたpendectorのコマンド
+fn pendector() -> Command {
+    let mut cmd = Command::cargo_bin("pendector").unwrap();
+    cmd.env("XDG_DATA_HOME", test_data_home());
+    cmd
+}
+
+/// 標準入出力を直接扱うテスト向けの `pendector()`
+fn pendector_process() -> std::process::Command {
+    let mut cmd = std::process::Command::new(assert_cmd::cargo::cargo_bin("pendector"));
+    cmd.env("XDG_DATA_HOME", test_data_home());
+    cmd
+}
+
+/// gitコマンドで本物のリポジトリを作成する
+fn init_git_repo(repo_path: &Path) {
+    fs::create_dir_all(repo_path).unwrap();
+    for args in [
+        vec!["init", "-q"],
+        vec!["config", "user.name", "Test User"],
+        vec!["config", "user.email", "test@example.com"],
+    ] {
+        std::process::Command::new("git")
+            .args(&args)
+            .current_dir(repo_path)
+            .output()
+            .unwrap();
+    }
+}
+
 #[test]
 fn runs_with_help() {
-    let mut cmd = Command::cargo_bin("pendector").unwrap();
+    let mut cmd = pendector();
     cmd.arg("--help").assert().success();
 }
 
 #[test]
 fn scans_current_directory() {
-    let mut cmd = Command::cargo_bin("pendector").unwrap();
+    let mut cmd = pendector();
     cmd.arg("--no-config").assert().success();
 }
 
@@ -25,7 +61,7 @@ fn scans_specific_directory() {
     fs::create_dir_all(&repo_path).unwrap();
     fs::create_dir_all(repo_path.join(".git")).unwrap();
 
-    let mut cmd = Command::cargo_bin("pendector").unwrap();
+    let mut cmd = pendector();
     cmd.arg("--no-config")
         .arg(base_path.to_str().unwrap())
         .assert()
@@ -35,13 +71,13 @@ fn scans_specific_directory() {
 
 #[test]
 fn verbose_flag_works() {
-    let mut cmd = Command::cargo_bin("pendector").unwrap();
+    let mut cmd = pendector();
     cmd.arg("--no-config").arg("--verbose").assert().success();
 }
 
 #[test]
 fn changes_only_flag_works() {
-    let mut cmd = Command::cargo_bin("pendector").unwrap();
+    let mut cmd = pendector();
     cmd.arg("--no-config")
         .arg("--changes-only")
         .assert()
@@ -50,7 +86,7 @@ fn changes_only_flag_works() {
 
 #[test]
 fn max_depth_flag_works() {
-    let mut cmd = Command::cargo_bin("pendector").unwrap();
+    let mut cmd = pendector();
     cmd.arg("--no-config")
         .arg("--max-depth")
         .arg("3")
@@ -72,7 +108,7 @@ fn multiple_paths_work() {
     fs::create_dir_all(&repo2_path).unwrap();
     fs::create_dir_all(repo2_path.join(".git")).unwrap();
 
-    let mut cmd = Command::cargo_bin("pendector").unwrap();
+    let mut cmd = pendector();
     cmd.arg("--no-config")
         .arg(temp_dir1.path().to_str().unwrap())
         .arg(temp_dir2.path().to_str().unwrap())
@@ -84,7 +120,7 @@ fn multiple_paths_work() {
 
 #[test]
 fn json_format_works() {
-    let mut cmd = Command::cargo_bin("pendector").unwrap();
+    let mut cmd = pendector();
     cmd.arg("--no-config")
         .arg("--format")
         .arg("json")
@@ -104,7 +140,7 @@ fn json_format_contains_valid_structure() {
     fs::create_dir_all(&repo_path).unwrap();
     fs::create_dir_all(repo_path.join(".git")).unwrap();
 
-    let mut cmd = Command::cargo_bin("pendector").unwrap();
+    let mut cmd = pendector();
     cmd.arg("--no-config")
         .arg(base_path.to_str().unwrap())
         .arg("--format")
@@ -119,8 +155,13 @@ fn json_format_contains_valid_structure() {
 
 #[test]
 fn fetch_flag_works() {
-    let mut cmd = Command::cargo_bin("pendector").unwrap();
-    cmd.arg("--no-config").arg("--fetch").assert().success();
+    let mut cmd = pendector();
+    // 並列に走る他のテストとロックを取り合わないようにする
+    cmd.arg("--no-config")
+        .arg("--fetch")
+        .arg("--no-lock")
+        .assert()
+        .success();
 }
 
 #[test]
@@ -133,10 +174,11 @@ fn fetch_flag_with_specific_directory() {
     fs::create_dir_all(&repo_path).unwrap();
     fs::create_dir_all(repo_path.join(".git")).unwrap();
 
-    let mut cmd = Command::cargo_bin("pendector").unwrap();
+    let mut cmd = pendector();
     cmd.arg("--no-config")
         .arg(base_path.to_str().unwrap())
         .arg("--fetch")
+        .arg("--no-lock")
         .assert()
         .success()
         .stdout(predicate::str::contains("test_repo"));
@@ -144,9 +186,10 @@ fn fetch_flag_with_specific_directory() {
 
 #[test]
 fn fetch_timeout_flag_works() {
-    let mut cmd = Command::cargo_bin("pendector").unwrap();
+    let mut cmd = pendector();
     cmd.arg("--no-config")
         .arg("--fetch")
+        .arg("--no-lock")
         .arg("--fetch-timeout")
         .arg("10")
         .assert()
@@ -163,10 +206,11 @@ fn fetch_timeout_with_specific_directory() {
     fs::create_dir_all(&repo_path).unwrap();
     fs::create_dir_all(repo_path.join(".git")).unwrap();
 
-    let mut cmd = Command::cargo_bin("pendector").unwrap();
+    let mut cmd = pendector();
     cmd.arg("--no-config")
         .arg(base_path.to_str().unwrap())
         .arg("--fetch")
+        .arg("--no-lock")
         .arg("--fetch-timeout")
         .arg("15")
         .assert()
@@ -176,7 +220,7 @@ fn fetch_timeout_with_specific_directory() {
 
 #[test]
 fn sort_flag_works() {
-    let mut cmd = Command::cargo_bin("pendector").unwrap();
+    let mut cmd = pendector();
     cmd.arg("--no-config")
         .arg("--sort")
         .arg("path")
@@ -184,6 +228,15 @@ fn sort_flag_works() {
         .success();
 }
 
+#[test]
+fn sort_rejects_unknown_key() {
+    let mut cmd = pendector();
+    cmd.args(["--no-config", "--sort", "stalenes"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("invalid value 'stalenes'"));
+}
+
 #[test]
 fn sort_output_is_alphabetical() {
     let temp_dir = TempDir::new().unwrap();
@@ -196,7 +249,7 @@ fn sort_output_is_alphabetical() {
         fs::create_dir_all(repo_path.join(".git")).unwrap();
     }
 
-    let mut cmd = Command::cargo_bin("pendector").unwrap();
+    let mut cmd = pendector();
     let output = cmd
         .arg("--no-config")
         .arg(base_path.to_str().unwrap())
@@ -233,7 +286,7 @@ fn sort_output_json_is_alphabetical() {
         fs::create_dir_all(repo_path.join(".git")).unwrap();
     }
 
-    let mut cmd = Command::cargo_bin("pendector").unwrap();
+    let mut cmd = pendector();
     let output = cmd
         .arg("--no-config")
         .arg(base_path.to_str().unwrap())
@@ -262,10 +315,1951 @@ fn sort_output_json_is_alphabetical() {
 
 #[test]
 fn notify_slack_without_webhook_url_fails() {
-    let mut cmd = Command::cargo_bin("pendector").unwrap();
+    let mut cmd = pendector();
     cmd.arg("--no-config")
         .arg("--notify-slack")
         .assert()
-        .failure()
+        .code(2)
         .stderr(predicates::str::contains("webhook URL"));
 }
+
+#[test]
+fn invalid_dirty_ignore_pattern_is_a_single_config_error() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().join("repos");
+    for name in ["one", "two"] {
+        init_git_repo(&root.join(name));
+    }
+    let config_path = temp_dir.path().join("config.toml");
+    fs::write(&config_path, "[defaults]\ndirty_ignore = [\"a{b\"]\n").unwrap();
+
+    let output = pendector()
+        .arg("--config")
+        .arg(&config_path)
+        .arg(&root)
+        .assert()
+        .code(2)
+        .get_output()
+        .stderr
+        .clone();
+    let stderr = String::from_utf8(output).unwrap();
+    assert_eq!(
+        stderr.matches("Invalid dirty_ignore pattern").count(),
+        1,
+        "{stderr}"
+    );
+}
+
+#[test]
+fn progress_json_emits_events_on_stderr() {
+    let temp_dir = TempDir::new().unwrap();
+    let base_path = temp_dir.path();
+
+    let repo_path = base_path.join("test_repo");
+    fs::create_dir_all(repo_path.join(".git")).unwrap();
+
+    let mut cmd = pendector();
+    let output = cmd
+        .arg("--no-config")
+        .arg(base_path.to_str().unwrap())
+        .arg("--progress")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("test_repo"))
+        .get_output()
+        .stderr
+        .clone();
+
+    let stderr = String::from_utf8(output).unwrap();
+    let events: Vec<serde_json::Value> = stderr
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let names: Vec<&str> = events
+        .iter()
+        .map(|e| e["event"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "discovery_started",
+            "discovery_finished",
+            "repo_statused",
+            "done"
+        ]
+    );
+}
+
+#[test]
+fn per_path_max_depth_suffix() {
+    let temp_dir = TempDir::new().unwrap();
+    let base_path = temp_dir.path();
+
+    // depth 4 で見つかるリポジトリ
+    let deep_repo = base_path.join("level1").join("level2").join("deep_repo");
+    fs::create_dir_all(deep_repo.join(".git")).unwrap();
+
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .arg(format!("{}:2", base_path.to_str().unwrap()))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("deep_repo").not());
+
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .arg(format!("{}:4", base_path.to_str().unwrap()))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("deep_repo"));
+}
+
+#[test]
+fn repeated_max_depth_must_match_paths() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .args(["-d", "1", "-d", "2"])
+        .arg(temp_dir.path().to_str().unwrap())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--max-depth was given 2 times"));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn fetch_refuses_to_overlap_with_running_instance() {
+    let runtime_dir = TempDir::new().unwrap();
+    let scan_dir = TempDir::new().unwrap();
+
+    // 実行中のpendectorの代わりにテストがロックを保持する
+    let lock_file = fs::File::create(runtime_dir.path().join("pendector.lock")).unwrap();
+    lock_file.lock().unwrap();
+
+    let mut cmd = pendector();
+    cmd.env("XDG_RUNTIME_DIR", runtime_dir.path())
+        .arg("--no-config")
+        .arg("--fetch")
+        .arg(scan_dir.path().to_str().unwrap())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("another pendector is running"));
+
+    let mut cmd = pendector();
+    cmd.env("XDG_RUNTIME_DIR", runtime_dir.path())
+        .arg("--no-config")
+        .arg("--fetch")
+        .arg("--no-lock")
+        .arg(scan_dir.path().to_str().unwrap())
+        .assert()
+        .success();
+}
+
+#[test]
+fn offline_skips_fetch_enabled_in_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = temp_dir.path().join("repos").join("repo");
+    init_git_repo(&repo);
+    std::process::Command::new("git")
+        .args(["remote", "add", "origin"])
+        .arg(temp_dir.path().join("missing.git"))
+        .current_dir(&repo)
+        .output()
+        .unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "[defaults]\npaths = [{:?}]\nfetch = true\nformat = \"json\"\n",
+            repo.parent().unwrap().to_str().unwrap()
+        ),
+    )
+    .unwrap();
+
+    let fetch_error = |extra: &[&str]| {
+        let output = pendector()
+            .env("XDG_RUNTIME_DIR", temp_dir.path())
+            .arg("--config")
+            .arg(&config_path)
+            .args(extra)
+            .output()
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json[0]["fetch_error"].clone()
+    };
+    assert!(!fetch_error(&[]).is_null());
+    assert!(fetch_error(&["--offline"]).is_null());
+
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .arg("--offline")
+        .arg("--fetch")
+        .assert()
+        .failure();
+}
+
+#[test]
+fn exec_runs_command_in_each_repository() {
+    let temp_dir = TempDir::new().unwrap();
+    init_git_repo(&temp_dir.path().join("alpha"));
+    init_git_repo(&temp_dir.path().join("beta"));
+
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .arg("exec")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("--stream")
+        .arg("--")
+        .args(["git", "rev-parse", "--is-inside-work-tree"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[alpha] true"))
+        .stdout(predicate::str::contains("[beta] true"));
+
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .arg("exec")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("--")
+        .args(["git", "rev-parse", "--verify", "HEAD"])
+        .assert()
+        .code(3)
+        .stdout(predicate::str::contains("== alpha ("))
+        .stderr(predicate::str::contains(
+            "Command failed in 2 of 2 repositories",
+        ));
+}
+
+#[cfg(unix)]
+#[test]
+fn closed_stdout_pipe_keeps_the_exit_code() {
+    use std::io::Read;
+    use std::process::Stdio;
+
+    // パイプのバッファより多く出力するよう、リポジトリに見えるディレクトリを大量に作る
+    let temp_dir = TempDir::new().unwrap();
+    for i in 0..2000 {
+        fs::create_dir_all(temp_dir.path().join(format!("repo_{i:04}/.git"))).unwrap();
+    }
+
+    // `pendector | head` と同じく、読み手が先に終了してもエラーにせず、本来の終了コードを返す
+    // （状態を取得できないリポジトリは対応が必要なので --check では1になる）
+    for (check, expected) in [(false, 0), (true, 1)] {
+        let mut child = pendector_process()
+            .arg("--no-config")
+            .arg("--verbose")
+            .args(check.then_some("--check"))
+            .arg(temp_dir.path())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+        let mut head = [0u8; 16];
+        child.stdout.take().unwrap().read_exact(&mut head).unwrap();
+
+        let status = child.wait().unwrap();
+        assert_eq!(status.code(), Some(expected));
+    }
+}
+
+#[test]
+fn interrupt_stops_hanging_fetch_and_its_children() {
+    use std::process::Stdio;
+    use std::time::{Duration, Instant};
+
+    // fetchの間ハングし、自分のPIDを書き残すリモート
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path().join("hanging_repo");
+    init_git_repo(&repo_path);
+    let pid_file = temp_dir.path().join("remote.pid");
+    let script = temp_dir.path().join("hang.sh");
+    fs::write(
+        &script,
+        format!(
+            "#!/bin/sh\necho $$ > {}\nexec sleep 30\n",
+            pid_file.display()
+        ),
+    )
+    .unwrap();
+    std::process::Command::new("chmod")
+        .args(["+x", script.to_str().unwrap()])
+        .status()
+        .unwrap();
+    for args in [
+        vec!["config", "protocol.ext.allow", "always"],
+        vec![
+            "remote",
+            "add",
+            "origin",
+            &format!("ext::{}", script.display()),
+        ],
+    ] {
+        std::process::Command::new("git")
+            .args(&args)
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+    }
+
+    let mut child = pendector_process()
+        .env("XDG_RUNTIME_DIR", temp_dir.path())
+        .arg("--no-config")
+        .arg("--fetch")
+        .arg("--fetch-timeout")
+        .arg("60")
+        .arg(temp_dir.path())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let started = Instant::now();
+    while !pid_file.exists() && started.elapsed() < Duration::from_secs(10) {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    let remote_pid = fs::read_to_string(&pid_file).unwrap().trim().to_string();
+    std::process::Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .unwrap();
+
+    let status = child.wait().unwrap();
+    assert_eq!(status.code(), Some(4));
+    assert!(started.elapsed() < Duration::from_secs(20));
+
+    // fetchの子孫プロセスも残っていない（ゾンビは終了済みとみなす）
+    let alive = || {
+        fs::read_to_string(format!("/proc/{remote_pid}/stat")).is_ok_and(|stat| {
+            !stat
+                .rsplit(')')
+                .next()
+                .unwrap_or("")
+                .trim_start()
+                .starts_with('Z')
+        })
+    };
+    let waited = Instant::now();
+    while alive() && waited.elapsed() < Duration::from_secs(5) {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+    assert!(!alive());
+}
+
+#[test]
+fn explain_excludes_names_pattern_and_source() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().join("src");
+    fs::create_dir_all(root.join("app/node_modules/dep")).unwrap();
+    fs::create_dir_all(root.join("vendor/lib")).unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "[defaults]\npaths = [{root:?}]\nexclude_patterns = [\"node_modules\"]\n\n[[path_configs]]\npath = {root:?}\nexclude_patterns = [\"vendor\"]\n",
+            root = root.to_str().unwrap()
+        ),
+    )
+    .unwrap();
+
+    let explain = |path: &Path, extra: &[&str]| {
+        let mut cmd = pendector();
+        cmd.arg("--config")
+            .arg(&config_path)
+            .args(extra)
+            .arg("explain-excludes")
+            .arg(path)
+            .assert()
+    };
+    explain(&root.join("app/node_modules/dep"), &[])
+        .success()
+        .stdout(predicate::str::contains(
+            "Excluded by 'node_modules' from defaults",
+        ));
+    explain(&root.join("vendor/lib"), &[])
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "Excluded by 'vendor' from path config '{}'",
+            root.to_str().unwrap()
+        )));
+    explain(&root.join("vendor/lib"), &["--exclude", "!vendor"])
+        .success()
+        .stdout(predicate::str::contains(
+            "Kept: '!vendor' from --exclude re-includes it",
+        ));
+    explain(&root.join("app"), &[])
+        .success()
+        .stdout(predicate::str::contains("Kept: no exclude pattern matches"));
+    explain(temp_dir.path(), &[]).code(2);
+}
+
+#[test]
+fn double_verbose_logs_filter_reasons() {
+    let temp_dir = TempDir::new().unwrap();
+    init_git_repo(&temp_dir.path().join("clean"));
+    init_git_repo(&temp_dir.path().join("node_modules/dep"));
+    let dirty = temp_dir.path().join("dirty");
+    init_git_repo(&dirty);
+    fs::write(dirty.join("new.txt"), "x").unwrap();
+
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .arg("-vv")
+        .arg("--changes-only")
+        .arg("--exclude")
+        .arg("node_modules")
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "filter: dropped reason=exclude_pattern",
+        ))
+        .stderr(predicate::str::contains("detail=\"node_modules\""))
+        .stderr(
+            predicate::str::is_match("filter: dropped reason=changes_only path=\".*clean\"")
+                .unwrap(),
+        )
+        .stderr(predicate::str::is_match("filter: kept reason=included path=\".*dirty\"").unwrap());
+
+    // -v だけでは理由を出力しない
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .arg("-v")
+        .arg("--changes-only")
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("filter:").not());
+}
+
+#[test]
+fn name_style_relative_shows_path_under_scan_root() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().join("src");
+    init_git_repo(&root.join("acme/api"));
+    init_git_repo(&root.join("other/api"));
+    let config_path = temp_dir.path().join("config.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "[defaults]\npaths = [{root:?}]\nformat = \"json\"\n\n[[path_configs]]\npath = {root:?}\nname_style = \"relative\"\n",
+            root = root.to_str().unwrap()
+        ),
+    )
+    .unwrap();
+
+    let output = pendector()
+        .arg("--config")
+        .arg(&config_path)
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let names: Vec<&str> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|repo| repo["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["acme/api", "other/api"]);
+}
+
+#[test]
+fn diagnostics_never_reach_stdout() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = temp_dir.path().join("repos").join("repo");
+    init_git_repo(&repo);
+    fs::write(repo.join("new.txt"), "x").unwrap();
+    // 存在しないリモートへのfetchで失敗と進捗を発生させる
+    std::process::Command::new("git")
+        .args(["remote", "add", "origin"])
+        .arg(temp_dir.path().join("missing.git"))
+        .current_dir(&repo)
+        .output()
+        .unwrap();
+    let missing = temp_dir.path().join("missing");
+
+    let run = |format: &str| {
+        pendector()
+            .env("XDG_RUNTIME_DIR", temp_dir.path())
+            .arg("--no-config")
+            .args([
+                "-vv",
+                "--fetch",
+                "--skip-missing-paths",
+                "--progress",
+                "json",
+            ])
+            .args(["--format", format])
+            .arg(temp_dir.path().join("repos"))
+            .arg(&missing)
+            .output()
+            .unwrap()
+    };
+
+    // JSON出力はstdout全体がそのままパースできる
+    let output = run("json");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("does not exist, skipping"));
+    assert!(stderr.contains("filter: kept reason=included"));
+    assert!(stderr.contains("\"event\":"));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 1);
+
+    // テキスト出力でも診断はstderrだけに出る
+    let output = run("text");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("repo"));
+    for diagnostic in ["Warning", "filter:", "\"event\"", "Scanned '"] {
+        assert!(
+            !stdout.contains(diagnostic),
+            "{diagnostic} leaked to stdout"
+        );
+    }
+}
+
+#[test]
+fn json_v2_lists_warnings() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo = temp_dir.path().join("repos").join("repo");
+    init_git_repo(&repo);
+    std::process::Command::new("git")
+        .args(["remote", "add", "origin"])
+        .arg(temp_dir.path().join("missing.git"))
+        .current_dir(&repo)
+        .output()
+        .unwrap();
+    let config = temp_dir.path().join("config.toml");
+    fs::write(&config, "not = [valid").unwrap();
+    let missing = temp_dir.path().join("missing");
+
+    let output = pendector()
+        .env("XDG_RUNTIME_DIR", temp_dir.path())
+        .arg("--config")
+        .arg(&config)
+        .args(["--fetch", "--skip-missing-paths", "--format", "json-v2"])
+        .arg(temp_dir.path().join("repos"))
+        .arg(&missing)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let warnings = json["warnings"].as_array().unwrap();
+    let kinds: Vec<&str> = warnings
+        .iter()
+        .map(|w| w["kind"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        kinds,
+        vec!["config_fallback", "skipped_path", "fetch_failed"]
+    );
+    assert_eq!(warnings[1]["path"], missing.display().to_string());
+    assert!(warnings[2]["path"].as_str().unwrap().ends_with("repo"));
+}
+
+#[cfg(unix)]
+#[test]
+fn json_v2_warns_about_unreadable_directories() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = TempDir::new().unwrap();
+    let repos = temp_dir.path().join("repos");
+    init_git_repo(&repos.join("repo"));
+    let locked = repos.join("locked");
+    fs::create_dir(&locked).unwrap();
+    fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+    // 権限を無視できる実行環境（rootなど）ではディレクトリを読めてしまうので確かめられない
+    if fs::read_dir(&locked).is_ok() {
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+        return;
+    }
+
+    let run = |extra: &[&str]| {
+        let output = pendector()
+            .arg("--no-config")
+            .args(["--format", "json-v2"])
+            .args(extra)
+            .arg(&repos)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        // 探索中に直接書かれず、警告として1度だけ表示される
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.matches("(permission denied)").count() <= 1);
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["repositories"].as_array().unwrap().len(), 1);
+        json["warnings"].as_array().unwrap().clone()
+    };
+
+    let warnings = run(&[]);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0]["kind"], "skipped_path");
+    assert_eq!(warnings[0]["path"], repos.display().to_string());
+
+    // -v では読めなかったディレクトリを1件ずつ挙げる
+    let warnings = run(&["--verbose"]);
+    let denied: Vec<&serde_json::Value> = warnings
+        .iter()
+        .filter(|w| w["kind"] == "skipped_path")
+        .collect();
+    assert_eq!(denied.len(), 1);
+    assert_eq!(denied[0]["path"], locked.display().to_string());
+
+    assert!(run(&["--skip-permission-errors"]).is_empty());
+
+    fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+#[test]
+fn json_v2_warns_when_the_time_budget_is_exceeded() {
+    let temp_dir = TempDir::new().unwrap();
+    let repos = temp_dir.path().join("repos");
+    init_git_repo(&repos.join("repo"));
+    let config = temp_dir.path().join("config.toml");
+    fs::write(&config, "[defaults]\ntime_budget = 0\n").unwrap();
+
+    let output = pendector()
+        .arg("--config")
+        .arg(&config)
+        .args(["--format", "json-v2"])
+        .arg(&repos)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let warnings = json["warnings"].as_array().unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0]["kind"], "budget_exceeded");
+    assert_eq!(warnings[0]["path"], repos.display().to_string());
+    assert!(warnings[0]["message"]
+        .as_str()
+        .unwrap()
+        .contains("1 directories not descended"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("time budget of 0s exceeded"));
+}
+
+#[test]
+fn later_config_files_override_earlier_ones() {
+    let temp_dir = TempDir::new().unwrap();
+    init_git_repo(&temp_dir.path().join("repos").join("repo"));
+    let base = temp_dir.path().join("base.toml");
+    let local = temp_dir.path().join("local.toml");
+    fs::write(
+        &base,
+        format!(
+            "[defaults]\nformat = \"text\"\npaths = [{:?}]\n",
+            temp_dir.path().join("repos").display().to_string()
+        ),
+    )
+    .unwrap();
+    fs::write(&local, "[defaults]\nformat = \"json\"\n").unwrap();
+
+    let output = pendector()
+        .arg("--config")
+        .arg(&base)
+        .arg("--config")
+        .arg(&local)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    // 形式は後のファイル、スキャン対象は前のファイルの設定が使われる
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json[0]["name"], "repo");
+}
+
+#[test]
+fn version_verbose_shows_build_info() {
+    pendector()
+        .arg("--version")
+        .assert()
+        .success()
+        .stdout(format!("pendector {}\n", env!("CARGO_PKG_VERSION")));
+
+    pendector()
+        .args(["--version", "--verbose"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("commit: "))
+        .stdout(predicate::str::contains("built: "))
+        .stdout(predicate::str::contains("features: "));
+}
+
+#[test]
+fn config_can_be_read_from_stdin() {
+    let temp_dir = TempDir::new().unwrap();
+    init_git_repo(&temp_dir.path().join("repos").join("repo"));
+    let config = format!(
+        "[defaults]\nformat = \"json\"\npaths = [{:?}]\n",
+        temp_dir.path().join("repos").display().to_string()
+    );
+
+    let output = pendector()
+        .args(["--config", "-"])
+        .write_stdin(config)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json[0]["name"], "repo");
+}
+
+#[test]
+fn top_shows_most_actionable_repositories() {
+    let temp_dir = TempDir::new().unwrap();
+    for name in ["clean", "dirty", "notes"] {
+        init_git_repo(&temp_dir.path().join(name));
+    }
+    fs::write(temp_dir.path().join("dirty").join("new.txt"), "x").unwrap();
+    fs::write(temp_dir.path().join("notes").join("new.txt"), "x").unwrap();
+
+    let output = pendector()
+        .args(["--no-config", "--format", "json", "--top", "5"])
+        .arg(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let names: Vec<&str> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["dirty", "notes"]);
+}
+
+#[test]
+fn stash_appends_audit_records() {
+    let temp_dir = TempDir::new().unwrap();
+    let data_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path().join("dirty_repo");
+    init_git_repo(&repo_path);
+    fs::write(repo_path.join("file.txt"), "v1").unwrap();
+    for args in [vec!["add", "."], vec!["commit", "-q", "-m", "init"]] {
+        std::process::Command::new("git")
+            .args(&args)
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+    }
+    fs::write(repo_path.join("file.txt"), "v2").unwrap();
+
+    let mut cmd = pendector();
+    cmd.env("XDG_DATA_HOME", data_dir.path())
+        .arg("--no-config")
+        .arg("stash")
+        .arg(temp_dir.path().to_str().unwrap())
+        .assert()
+        .success();
+
+    let log = fs::read_to_string(data_dir.path().join("pendector").join("audit.jsonl")).unwrap();
+    let record: serde_json::Value = serde_json::from_str(log.lines().next().unwrap()).unwrap();
+    assert_eq!(record["action"], "stash");
+    assert_eq!(record["result"], "ok");
+    assert!(record["repo"].as_str().unwrap().ends_with("dirty_repo"));
+}
+
+#[test]
+fn fetch_outcomes_are_reported_per_repository() {
+    let temp_dir = TempDir::new().unwrap();
+    let remote = temp_dir.path().join("remote.git");
+    std::process::Command::new("git")
+        .args(["init", "-q", "--bare"])
+        .arg(&remote)
+        .output()
+        .unwrap();
+    let repos = temp_dir.path().join("repos");
+    for (name, url) in [
+        ("good", remote.clone()),
+        ("broken", temp_dir.path().join("missing.git")),
+    ] {
+        let repo = repos.join(name);
+        init_git_repo(&repo);
+        std::process::Command::new("git")
+            .args(["remote", "add", "origin"])
+            .arg(url)
+            .current_dir(&repo)
+            .output()
+            .unwrap();
+    }
+
+    let output = pendector()
+        .env("XDG_RUNTIME_DIR", temp_dir.path())
+        .args(["--no-config", "--fetch", "--verbose", "--format", "json"])
+        .arg(&repos)
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let outcome = |name: &str| {
+        json.as_array()
+            .unwrap()
+            .iter()
+            .find(|repo| repo["name"] == name)
+            .unwrap()["fetch_outcome"]
+            .clone()
+    };
+    assert_eq!(outcome("good"), "fetched");
+    assert_eq!(outcome("broken"), "failed");
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("Fetched 1 of 2 repositories (1 failed)")
+    );
+}
+
+#[test]
+fn lang_selects_the_message_language() {
+    let temp_dir = TempDir::new().unwrap();
+    init_git_repo(&temp_dir.path().join("repo"));
+    fs::write(temp_dir.path().join("repo").join("new.txt"), "x").unwrap();
+
+    let mut cmd = pendector();
+    cmd.env("LANG", "en_US.UTF-8")
+        .args(["--no-config", "--lang", "ja"])
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1件のリポジトリ（変更あり 1件）:"))
+        .stdout(predicate::str::contains("変更ファイル 1件"));
+
+    let mut cmd = pendector();
+    cmd.env("LANG", "ja_JP.UTF-8")
+        .env_remove("LC_ALL")
+        .env_remove("LC_MESSAGES")
+        .args(["--no-config"])
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1件のリポジトリ"));
+
+    // 警告も選んだ言語で表示する
+    let missing = temp_dir.path().join("missing");
+    let mut cmd = pendector();
+    cmd.args(["--no-config", "--lang", "ja", "--skip-missing-paths"])
+        .arg(temp_dir.path())
+        .arg(&missing)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(format!(
+            "警告: パス '{}' が存在しないため飛ばします",
+            missing.display()
+        )));
+
+    // 詳細の項目名やエラー、一括操作の結果も選んだ言語で表示する
+    let mut cmd = pendector();
+    cmd.args(["--no-config", "--lang", "ja", "--verbose"])
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\n  パス: "))
+        .stdout(predicate::str::contains("\n  変更ファイル:"));
+
+    let mut cmd = pendector();
+    cmd.args(["--no-config", "--lang", "ja"])
+        .arg(&missing)
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(format!(
+            "エラー: パス '{}' が存在しません",
+            missing.display()
+        )));
+
+    let mut cmd = pendector();
+    cmd.args(["--no-config", "--lang", "ja", "stash", "--pop"])
+        .arg(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "元に戻すpendectorのstashはありません",
+        ));
+}
+
+#[test]
+fn type_filters_by_detected_project_type() {
+    let temp_dir = TempDir::new().unwrap();
+    for (name, marker) in [("crate", "Cargo.toml"), ("web", "package.json")] {
+        let repo = temp_dir.path().join(name);
+        init_git_repo(&repo);
+        fs::write(repo.join(marker), "").unwrap();
+    }
+
+    let output = pendector()
+        .args(["--no-config", "--type", "rust", "--format", "json"])
+        .arg(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let repos = json.as_array().unwrap();
+    assert_eq!(repos.len(), 1);
+    assert_eq!(repos[0]["name"], "crate");
+    assert_eq!(repos[0]["project_type"], "rust");
+}
+
+#[test]
+fn recheck_only_reports_repositories_that_needed_attention() {
+    let temp_dir = TempDir::new().unwrap();
+    let data_dir = TempDir::new().unwrap();
+    let dirty = temp_dir.path().join("dirty_repo");
+    let clean = temp_dir.path().join("clean_repo");
+    init_git_repo(&dirty);
+    init_git_repo(&clean);
+    fs::write(dirty.join("new.txt"), "new").unwrap();
+
+    let run = |extra: &[&str]| {
+        let output = pendector()
+            .env("XDG_DATA_HOME", data_dir.path())
+            .args(["--no-config", "--format", "json"])
+            .args(extra)
+            .arg(temp_dir.path().to_str().unwrap())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json.as_array()
+            .unwrap()
+            .iter()
+            .map(|repo| {
+                let name = repo["name"].as_str().unwrap().to_string();
+                (name, repo["has_changes"].as_bool().unwrap())
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(run(&[]).len(), 2);
+    // 前回きれいだったリポジトリの変更は調べ直さないので表示されない
+    fs::remove_file(dirty.join("new.txt")).unwrap();
+    fs::write(clean.join("new.txt"), "new").unwrap();
+    assert_eq!(run(&["--recheck"]), vec![("dirty_repo".to_string(), false)]);
+    // 片付いたリポジトリは次の --recheck の対象から外れる
+    assert!(run(&["--recheck"]).is_empty());
+}
+
+#[test]
+fn sync_plan_groups_repositories_by_what_syncing_takes() {
+    let temp_dir = TempDir::new().unwrap();
+    let upstream = temp_dir.path().join("upstream");
+    init_git_repo(&upstream);
+    let git = |dir: &Path, args: &[&str]| {
+        std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+    };
+    git(&upstream, &["commit", "-q", "--allow-empty", "-m", "init"]);
+    let repos = temp_dir.path().join("repos");
+    fs::create_dir(&repos).unwrap();
+    for name in ["behind", "synced"] {
+        git(&repos, &["clone", "-q", upstream.to_str().unwrap(), name]);
+    }
+    git(&upstream, &["commit", "-q", "--allow-empty", "-m", "more"]);
+    git(&repos.join("behind"), &["fetch", "-q"]);
+
+    pendector()
+        .arg("--no-config")
+        .arg("sync-plan")
+        .arg(repos.to_str().unwrap())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Fast-forward (1):\n  behind ("))
+        .stdout(predicate::str::contains("synced").not());
+}
+
+#[test]
+fn health_verbose_reports_packing_and_fix_runs_maintenance() {
+    let temp_dir = TempDir::new().unwrap();
+    let data_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path().join("loose_repo");
+    init_git_repo(&repo_path);
+    for args in [
+        &["commit", "-q", "--allow-empty", "-m", "init"][..],
+        &["config", "gc.auto", "1"],
+    ] {
+        std::process::Command::new("git")
+            .args(args)
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+    }
+    let health = |extra: &[&str]| {
+        let mut cmd = pendector();
+        cmd.env("XDG_DATA_HOME", data_dir.path())
+            .args(["--no-config", "health"])
+            .args(extra)
+            .arg(temp_dir.path().to_str().unwrap())
+            .assert()
+            .success()
+    };
+
+    health(&["--verbose"])
+        .stdout(predicate::str::contains(
+            "refs: 1 (1 loose), packs: 0 (0 B)",
+        ))
+        .stdout(predicate::str::contains(
+            "would benefit from git gc / git maintenance run: 2 loose objects (gc.auto is 1)",
+        ));
+    health(&["--fix"]).stdout(predicate::str::contains(
+        "Ran git maintenance in loose_repo",
+    ));
+    health(&["--verbose"])
+        .stdout(predicate::str::contains("packs: 1"))
+        .stdout(predicate::str::contains("would benefit").not());
+}
+
+#[test]
+fn help_lists_exit_codes() {
+    let mut cmd = pendector();
+    cmd.arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Exit codes:"))
+        .stdout(predicate::str::contains("4  Interrupted"));
+}
+
+#[test]
+fn missing_path_is_usage_error() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .arg(temp_dir.path().join("missing").to_str().unwrap())
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("does not exist"));
+}
+
+#[test]
+fn skip_missing_paths_scans_remaining_roots() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().join("src");
+    init_git_repo(&root.join("repo"));
+
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .arg("--skip-missing-paths")
+        .arg(temp_dir.path().join("missing").to_str().unwrap())
+        .arg(root.to_str().unwrap())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("repo"))
+        .stderr(predicate::str::contains("does not exist, skipping"));
+}
+
+#[test]
+fn check_exits_with_findings() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path().join("dirty_repo");
+    init_git_repo(&repo_path);
+
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .arg("--check")
+        .arg(temp_dir.path().to_str().unwrap())
+        .assert()
+        .code(0);
+
+    fs::write(repo_path.join("new_file.txt"), "content").unwrap();
+
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .arg("--check")
+        .arg(temp_dir.path().to_str().unwrap())
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("dirty_repo"));
+
+    // --checkなしでは変更があっても成功
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .arg(temp_dir.path().to_str().unwrap())
+        .assert()
+        .code(0);
+}
+
+#[test]
+fn report_mode_is_silent_when_clean() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path().join("some_repo");
+    init_git_repo(&repo_path);
+
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .arg("--report")
+        .arg(temp_dir.path().to_str().unwrap())
+        .assert()
+        .code(0)
+        .stdout(predicate::str::is_empty());
+
+    fs::write(repo_path.join("new_file.txt"), "content").unwrap();
+
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .arg("--report")
+        .arg(temp_dir.path().to_str().unwrap())
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains(
+            "pendector: 1 repository needs attention",
+        ))
+        .stdout(predicate::str::contains("some_repo"));
+}
+
+#[test]
+fn du_and_artifacts_report_sizes_in_json() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path().join("build_repo");
+    init_git_repo(&repo_path);
+    fs::write(repo_path.join(".gitignore"), "target/\n").unwrap();
+    fs::create_dir_all(repo_path.join("target")).unwrap();
+    fs::write(
+        repo_path.join("target").join("app"),
+        vec![0u8; 2 * 1024 * 1024],
+    )
+    .unwrap();
+
+    let mut cmd = pendector();
+    let output = cmd
+        .arg("--no-config")
+        .arg("--format")
+        .arg("json")
+        .arg("--du")
+        .arg("--artifacts")
+        .arg(temp_dir.path().to_str().unwrap())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let repo = &json[0];
+    assert!(repo["disk_usage"].as_u64().unwrap() >= 2 * 1024 * 1024);
+    assert_eq!(repo["artifacts"][0]["path"], "target/");
+    assert_eq!(repo["artifacts"][0]["size_bytes"], 2 * 1024 * 1024);
+}
+
+#[test]
+fn capabilities_prints_version_and_formats_as_json() {
+    let mut cmd = pendector();
+    let output = cmd
+        .arg("--format")
+        .arg("json")
+        .arg("capabilities")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(
+        json["output_formats"],
+        serde_json::json!(["text", "json", "json-v2", "jsonfeed"])
+    );
+    assert!(json["icon_styles"]
+        .as_array()
+        .unwrap()
+        .contains(&serde_json::json!("nerdfont")));
+}
+
+#[test]
+fn per_path_verbose_renders_each_root_as_a_section() {
+    let temp_dir = TempDir::new().unwrap();
+    let work = temp_dir.path().join("work");
+    let oss = temp_dir.path().join("oss");
+    init_git_repo(&work.join("work_repo"));
+    init_git_repo(&oss.join("oss_repo"));
+    let config_path = temp_dir.path().join("config.toml");
+    let content = format!(
+        "[defaults]\npaths = [{work:?}, {oss:?}]\n\n[[path_configs]]\npath = {work:?}\nverbose = true\nformat = \"json\"\n",
+        work = work.to_str().unwrap(),
+        oss = oss.to_str().unwrap(),
+    );
+    fs::write(&config_path, &content).unwrap();
+
+    let output = pendector()
+        .arg("--config")
+        .arg(&config_path)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("scan roots configure different output formats (json, text)"));
+
+    // 出力形式は最初のルートの設定に揃える（JSONではverboseは関係しない）
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 2);
+
+    // 形式が揃っていれば、ルートごとのverbose設定でセクションに分けて表示する
+    fs::write(&config_path, content.replace("format = \"json\"\n", "")).unwrap();
+    let output = pendector()
+        .arg("--config")
+        .arg(&config_path)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let work_section = format!("== {} ==", work.to_str().unwrap());
+    let oss_section = format!("== {} ==", oss.to_str().unwrap());
+    let (work_part, oss_part) = stdout.split_once(&oss_section).unwrap();
+    assert!(work_part.contains(&work_section));
+    assert!(work_part.contains("work_repo") && work_part.contains("  Path: "));
+    assert!(oss_part.contains("oss_repo") && !oss_part.contains("  Path: "));
+}
+
+#[test]
+fn changes_only_applies_per_scan_root() {
+    let temp_dir = TempDir::new().unwrap();
+    let work = temp_dir.path().join("work");
+    let oss = temp_dir.path().join("oss");
+    for repo in [
+        work.join("work_clean"),
+        work.join("work_dirty"),
+        oss.join("oss_clean"),
+    ] {
+        init_git_repo(&repo);
+    }
+    fs::write(work.join("work_dirty").join("new_file.txt"), "content").unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    let content = format!(
+        "[defaults]\npaths = [{work:?}, {oss:?}]\n\n[[path_configs]]\npath = {work:?}\nchanges_only = true\n",
+        work = work.to_str().unwrap(),
+        oss = oss.to_str().unwrap(),
+    );
+    fs::write(&config_path, content).unwrap();
+
+    let output = pendector()
+        .arg("--config")
+        .arg(&config_path)
+        .arg("--format")
+        .arg("json")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let mut names: Vec<_> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["name"].as_str().unwrap().to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["oss_clean", "work_dirty"]);
+}
+
+#[test]
+fn duplicates_lists_projects_cloned_in_multiple_locations() {
+    let temp_dir = TempDir::new().unwrap();
+    for (name, url) in [
+        ("foo", "git@github.com:me/foo.git"),
+        ("foo-copy", "https://github.com/me/foo"),
+        ("bar", "https://github.com/me/bar.git"),
+    ] {
+        let repo_path = temp_dir.path().join(name);
+        init_git_repo(&repo_path);
+        std::process::Command::new("git")
+            .args(["remote", "add", "origin", url])
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+    }
+    fs::write(temp_dir.path().join("foo-copy").join("new_file.txt"), "x").unwrap();
+
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .arg("--duplicates")
+        .arg(temp_dir.path().to_str().unwrap())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Found 1 projects cloned in multiple locations:",
+        ))
+        .stdout(predicate::str::contains("github.com/me/foo (2 clones)"))
+        .stdout(predicate::str::contains("foo-copy"))
+        .stdout(predicate::str::contains("github.com/me/bar").not());
+}
+
+#[test]
+fn sort_by_staleness_puts_long_dirty_repositories_first() {
+    let temp_dir = TempDir::new().unwrap();
+    for name in ["a_clean", "b_dirty"] {
+        let repo_path = temp_dir.path().join(name);
+        init_git_repo(&repo_path);
+        fs::write(repo_path.join("file.txt"), "v1").unwrap();
+        for args in [vec!["add", "."], vec!["commit", "-q", "-m", "init"]] {
+            std::process::Command::new("git")
+                .args(&args)
+                .current_dir(&repo_path)
+                .output()
+                .unwrap();
+        }
+    }
+    fs::write(temp_dir.path().join("b_dirty").join("file.txt"), "v2").unwrap();
+
+    let output = pendector()
+        .arg("--no-config")
+        .arg("--format")
+        .arg("json")
+        .arg("--sort")
+        .arg("staleness")
+        .arg("--dirty-warn-days")
+        .arg("0")
+        .arg(temp_dir.path().to_str().unwrap())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json[0]["name"], "b_dirty");
+    assert_eq!(json[0]["staleness"], "stale-dirty");
+    assert_eq!(json[1]["staleness"], "active");
+    assert!(json[1]["last_commit_at"].as_str().unwrap().ends_with('Z'));
+}
+
+#[test]
+fn huge_dirty_warn_days_does_not_overflow() {
+    let temp_dir = TempDir::new().unwrap();
+    init_git_repo(&temp_dir.path().join("repo"));
+
+    pendector()
+        .arg("--no-config")
+        .arg("--dirty-warn-days")
+        .arg(u64::MAX.to_string())
+        .arg(temp_dir.path().to_str().unwrap())
+        .assert()
+        .success();
+}
+
+#[test]
+fn pinned_repos_are_included_outside_scan_roots() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().join("root");
+    init_git_repo(&root.join("scanned"));
+    let pinned = temp_dir.path().join("elsewhere").join("dotfiles");
+    init_git_repo(&pinned);
+    let missing = temp_dir.path().join("missing");
+    let config_path = temp_dir.path().join("config.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "[defaults]\npaths = [{:?}]\n\n[repos]\npaths = [{:?}, {:?}]\n",
+            root.to_str().unwrap(),
+            pinned.to_str().unwrap(),
+            missing.to_str().unwrap(),
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = pendector();
+    cmd.arg("--config")
+        .arg(&config_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("scanned"))
+        .stdout(predicate::str::contains("dotfiles"))
+        .stderr(predicate::str::contains(
+            "is not a git repository, skipping",
+        ));
+}
+
+#[test]
+fn detached_repos_are_statused_with_their_work_tree() {
+    let temp_dir = TempDir::new().unwrap();
+    let git_dir = temp_dir.path().join("dotfiles.git");
+    let work_tree = temp_dir.path().join("home");
+    fs::create_dir(&work_tree).unwrap();
+    std::process::Command::new("git")
+        .args(["init", "-q", "--bare"])
+        .arg(&git_dir)
+        .output()
+        .unwrap();
+    fs::write(work_tree.join(".bashrc"), "alias ll='ls -l'\n").unwrap();
+    let config_path = temp_dir.path().join("config.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "[defaults]\npaths = []\n\n[[repos.detached]]\ngit_dir = {:?}\nwork_tree = {:?}\n",
+            git_dir.to_str().unwrap(),
+            work_tree.to_str().unwrap(),
+        ),
+    )
+    .unwrap();
+
+    let output = pendector()
+        .arg("--config")
+        .arg(&config_path)
+        .arg("--format")
+        .arg("json")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json[0]["name"], "home");
+    assert_eq!(json[0]["git_dir"], git_dir.to_str().unwrap());
+    assert_eq!(json[0]["has_changes"], true);
+    assert_eq!(json[0]["changed_files"][0], "?? .bashrc");
+}
+
+#[test]
+fn hide_clean_keeps_unsynced_repositories_from_config_paths() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().join("root");
+    let clean = root.join("clean_repo");
+    init_git_repo(&clean);
+    let git = |dir: &Path, args: &[&str]| {
+        std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+    };
+    git(&clean, &["commit", "-q", "--allow-empty", "-m", "init"]);
+    // クローンでコミットすると、変更はないがpush待ちの状態になる
+    git(&root, &["clone", "-q", "clean_repo", "ahead_repo"]);
+    let ahead = root.join("ahead_repo");
+    git(&ahead, &["config", "user.name", "Test User"]);
+    git(&ahead, &["config", "user.email", "test@example.com"]);
+    git(
+        &ahead,
+        &["commit", "-q", "--allow-empty", "-m", "local work"],
+    );
+
+    let root = root.to_str().unwrap().to_string();
+    let config_path = temp_dir.path().join("config.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "[defaults]\npaths = [{root:?}]\n\n[[path_configs]]\npath = {root:?}\nhide_clean = true\n"
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = pendector();
+    cmd.arg("--config")
+        .arg(&config_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ahead_repo"))
+        .stdout(predicate::str::contains("clean_repo").not());
+
+    // CLI引数で指定したパスでは隠さない
+    let mut cmd = pendector();
+    cmd.arg("--config")
+        .arg(&config_path)
+        .arg(&root)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("clean_repo"));
+}
+
+#[test]
+fn limit_caps_reported_repositories() {
+    let temp_dir = TempDir::new().unwrap();
+    for name in ["a_repo", "b_repo", "c_repo"] {
+        init_git_repo(&temp_dir.path().join(name));
+    }
+    fs::write(
+        temp_dir.path().join("c_repo").join("new_file.txt"),
+        "content",
+    )
+    .unwrap();
+
+    let mut cmd = pendector();
+    let output = cmd
+        .arg("--no-config")
+        .arg("--format")
+        .arg("json")
+        .arg("--limit")
+        .arg("2")
+        .arg(temp_dir.path().to_str().unwrap())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let names: Vec<_> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["name"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(names, vec!["a_repo", "b_repo"]);
+
+    // --changes-only では絞り込み後の件数を制限する
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .arg("--changes-only")
+        .arg("--limit")
+        .arg("1")
+        .arg(temp_dir.path().to_str().unwrap())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("c_repo"));
+}
+
+#[test]
+fn health_reports_and_fixes_stale_lock_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path().join("locked_repo");
+    init_git_repo(&repo_path);
+    let lock_path = repo_path.join(".git").join("index.lock");
+    let lock = fs::File::create(&lock_path).unwrap();
+    lock.set_modified(std::time::SystemTime::now() - std::time::Duration::from_secs(3 * 86_400))
+        .unwrap();
+
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .arg("health")
+        .arg(temp_dir.path().to_str().unwrap())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Repository locked (index.lock present, 3 days old)",
+        ));
+
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .arg("health")
+        .arg("--fix-locks")
+        .arg(temp_dir.path().to_str().unwrap())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Removed index.lock from locked_repo",
+        ));
+    assert!(!lock_path.exists());
+}
+
+#[test]
+fn watch_reloads_config_when_it_changes() {
+    use std::io::Read;
+    use std::process::Stdio;
+    use std::time::Duration;
+
+    let temp_dir = TempDir::new().unwrap();
+    let first = temp_dir.path().join("first");
+    let second = temp_dir.path().join("second");
+    init_git_repo(&first.join("repo_a"));
+    init_git_repo(&second.join("repo_b"));
+    let config_path = temp_dir.path().join("config.toml");
+    let write_config = |root: &Path| {
+        let content = format!("[defaults]\npaths = [{:?}]\n", root.to_str().unwrap());
+        fs::write(&config_path, content).unwrap();
+    };
+    write_config(&first);
+
+    let mut child = pendector_process()
+        .arg("--config")
+        .arg(&config_path)
+        .arg("--progress")
+        .arg("none")
+        .arg("watch")
+        .arg("--interval")
+        .arg("30")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    std::thread::sleep(Duration::from_secs(2));
+    write_config(&second);
+    std::thread::sleep(Duration::from_secs(3));
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    let mut stdout = String::new();
+    child
+        .stdout
+        .take()
+        .unwrap()
+        .read_to_string(&mut stdout)
+        .unwrap();
+    let mut stderr = String::new();
+    child
+        .stderr
+        .take()
+        .unwrap()
+        .read_to_string(&mut stderr)
+        .unwrap();
+
+    assert!(stdout.contains("repo_a"), "stdout: {stdout}");
+    assert!(stdout.contains("repo_b"), "stdout: {stdout}");
+    assert!(
+        stderr.contains("Config reloaded: paths:"),
+        "stderr: {stderr}"
+    );
+}
+
+#[test]
+fn stash_and_pop_across_repositories() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path().join("dirty_repo");
+    init_git_repo(&repo_path);
+    fs::write(repo_path.join("file.txt"), "v1").unwrap();
+    for args in [vec!["add", "."], vec!["commit", "-q", "-m", "init"]] {
+        std::process::Command::new("git")
+            .args(&args)
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+    }
+    fs::write(repo_path.join("file.txt"), "v2").unwrap();
+
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .arg("stash")
+        .arg(temp_dir.path().to_str().unwrap())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Stashed dirty_repo"));
+    assert_eq!(
+        fs::read_to_string(repo_path.join("file.txt")).unwrap(),
+        "v1"
+    );
+
+    let list = std::process::Command::new("git")
+        .args(["stash", "list"])
+        .current_dir(&repo_path)
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&list.stdout).contains("pendector stash "));
+
+    let mut cmd = pendector();
+    cmd.arg("stash")
+        .arg("--no-config")
+        .arg("--pop")
+        .arg(temp_dir.path().to_str().unwrap())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Restored dirty_repo"));
+    assert_eq!(
+        fs::read_to_string(repo_path.join("file.txt")).unwrap(),
+        "v2"
+    );
+}
+
+#[test]
+fn stash_skips_repositories_in_the_middle_of_bisect() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path().join("bisect_repo");
+    init_git_repo(&repo_path);
+    fs::write(
+        repo_path.join(".git").join("BISECT_LOG"),
+        "git bisect start\n",
+    )
+    .unwrap();
+    fs::write(repo_path.join("file.txt"), "change").unwrap();
+
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .arg("--report")
+        .arg(temp_dir.path().to_str().unwrap())
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("bisect in progress"));
+
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .arg("stash")
+        .arg(temp_dir.path().to_str().unwrap())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "No repositories with changes to stash",
+        ))
+        .stderr(predicate::str::contains(
+            "Skipped bisect_repo: bisect in progress",
+        ));
+    assert!(repo_path.join("file.txt").exists());
+}
+
+#[test]
+fn exec_skips_repositories_in_the_middle_of_bisect() {
+    let temp_dir = TempDir::new().unwrap();
+    init_git_repo(&temp_dir.path().join("alpha"));
+    let bisect_repo = temp_dir.path().join("bisect_repo");
+    init_git_repo(&bisect_repo);
+    fs::write(
+        bisect_repo.join(".git").join("BISECT_LOG"),
+        "git bisect start\n",
+    )
+    .unwrap();
+
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .arg("exec")
+        .arg(temp_dir.path().to_str().unwrap())
+        .arg("--stream")
+        .arg("--")
+        .args(["git", "rev-parse", "--is-inside-work-tree"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[alpha] true"))
+        .stdout(predicate::str::contains("[bisect_repo]").not())
+        .stderr(predicate::str::contains(
+            "Skipped bisect_repo: bisect in progress",
+        ));
+}
+
+#[test]
+fn wip_commits_changes_and_undo_restores_them() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path().join("laptop_repo");
+    init_git_repo(&repo_path);
+    fs::write(repo_path.join("file.txt"), "v1").unwrap();
+    for args in [
+        vec!["checkout", "-q", "-b", "main"],
+        vec!["add", "."],
+        vec!["commit", "-q", "-m", "init"],
+    ] {
+        std::process::Command::new("git")
+            .args(&args)
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+    }
+    fs::write(repo_path.join("file.txt"), "v2").unwrap();
+
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .arg("wip")
+        .arg("--branch-template")
+        .arg("handoff/{branch}")
+        .arg(temp_dir.path().to_str().unwrap())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Committed laptop_repo to handoff/main",
+        ));
+
+    let current_branch = |path: &Path| {
+        let output = std::process::Command::new("git")
+            .args(["branch", "--show-current"])
+            .current_dir(path)
+            .output()
+            .unwrap();
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    };
+    assert_eq!(current_branch(&repo_path), "handoff/main");
+
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .arg("wip")
+        .arg("--undo")
+        .arg(temp_dir.path().to_str().unwrap())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Restored laptop_repo to main"));
+    assert_eq!(current_branch(&repo_path), "main");
+    assert_eq!(
+        fs::read_to_string(repo_path.join("file.txt")).unwrap(),
+        "v2"
+    );
+}
+
+#[test]
+fn branches_stale_delete_honours_dry_run() {
+    let temp_dir = TempDir::new().unwrap();
+    let repo_path = temp_dir.path().join("branchy_repo");
+    init_git_repo(&repo_path);
+    for args in [
+        vec!["checkout", "-q", "-b", "main"],
+        vec!["commit", "-q", "--allow-empty", "-m", "init"],
+        vec!["branch", "finished-feature"],
+    ] {
+        std::process::Command::new("git")
+            .args(&args)
+            .current_dir(&repo_path)
+            .output()
+            .unwrap();
+    }
+    let branch_exists = || {
+        std::process::Command::new("git")
+            .args(["rev-parse", "--verify", "-q", "refs/heads/finished-feature"])
+            .current_dir(&repo_path)
+            .status()
+            .unwrap()
+            .success()
+    };
+
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .args(["branches", "--stale", "--delete", "--dry-run"])
+        .arg(temp_dir.path().to_str().unwrap())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Would delete finished-feature (merged into main)",
+        ));
+    assert!(branch_exists());
+
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .args(["branches", "--stale", "--delete"])
+        .arg(temp_dir.path().to_str().unwrap())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Deleted finished-feature (merged into main)",
+        ));
+    assert!(!branch_exists());
+}
+
+#[test]
+fn attention_filter_selects_classes() {
+    let temp_dir = TempDir::new().unwrap();
+    init_git_repo(&temp_dir.path().join("clean_repo"));
+    let dirty = temp_dir.path().join("dirty_repo");
+    init_git_repo(&dirty);
+    fs::write(dirty.join("a.txt"), "a").unwrap();
+
+    let output = pendector()
+        .arg("--no-config")
+        .arg("--format")
+        .arg("json")
+        .arg("--attention")
+        .arg("dirty,error")
+        .arg(temp_dir.path().to_str().unwrap())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let names: Vec<_> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["name"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(names, vec!["dirty_repo"]);
+
+    let mut cmd = pendector();
+    cmd.arg("--no-config")
+        .arg("--attention")
+        .arg("none")
+        .arg(temp_dir.path().to_str().unwrap())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("clean_repo"))
+        .stdout(predicate::str::contains("dirty_repo").not());
+}
+
+#[test]
+fn high_priority_repositories_are_listed_first() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().join("root");
+    init_git_repo(&root.join("a_repo"));
+    init_git_repo(&root.join("b_repo"));
+    let dotfiles = temp_dir.path().join("z_dotfiles");
+    init_git_repo(&dotfiles);
+    let config_path = temp_dir.path().join("config.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "[defaults]\npaths = [{root:?}]\n\n[repos]\npaths = [{dotfiles:?}]\n\n[[path_configs]]\npath = {dotfiles:?}\npriority = \"high\"\n",
+            root = root.to_str().unwrap(),
+            dotfiles = dotfiles.to_str().unwrap(),
+        ),
+    )
+    .unwrap();
+
+    let output = pendector()
+        .arg("--config")
+        .arg(&config_path)
+        .arg("--format")
+        .arg("json")
+        .arg("--limit")
+        .arg("2")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let names: Vec<_> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["name"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(names, vec!["z_dotfiles", "a_repo"]);
+    assert_eq!(json[0]["priority"], "high");
+    assert!(json[1].get("priority").is_none());
+}
+
+#[cfg(unix)]
+#[test]
+fn no_resolve_symlinks_keeps_discovered_path() {
+    let temp_dir = TempDir::new().unwrap();
+    let target = temp_dir.path().join("sprint-42");
+    init_git_repo(&target.join("repo"));
+    let link = temp_dir.path().join("current");
+    std::os::unix::fs::symlink(&target, &link).unwrap();
+    let link_repo = link.join("repo").display().to_string();
+
+    let run = |extra: &[&str]| {
+        let output = pendector()
+            .arg(&link)
+            .args(["--width", "500"])
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    assert!(!run(&[]).contains(&link_repo));
+    assert!(run(&["--no-resolve-symlinks"]).contains(&link_repo));
+}
+
+#[test]
+fn explicit_default_format_overrides_path_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().join("work");
+    init_git_repo(&root.join("repo"));
+    let config_path = temp_dir.path().join("config.toml");
+    fs::write(
+        &config_path,
+        format!(
+            "[defaults]\npaths = [{root:?}]\n\n[[path_configs]]\npath = {root:?}\nformat = \"json\"\n",
+            root = root.to_str().unwrap(),
+        ),
+    )
+    .unwrap();
+
+    let run = |extra: &[&str]| {
+        let output = pendector()
+            .arg("--config")
+            .arg(&config_path)
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    assert!(run(&[]).trim_start().starts_with('['));
+    assert!(!run(&["--format", "text"]).trim_start().starts_with('['));
+}
+
+#[test]
+fn group_by_root_splits_output_per_scan_root() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    let work = temp_dir.path().join("work");
+    init_git_repo(&src.join("tool"));
+    init_git_repo(&work.join("app"));
+
+    let output = pendector()
+        .arg("--no-config")
+        .args(["--group-by", "root"])
+        .arg(&src)
+        .arg(&work)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let src_header = stdout.find(&format!("== {} ==", src.display())).unwrap();
+    let work_header = stdout.find(&format!("== {} ==", work.display())).unwrap();
+    assert!(src_header < stdout.find("tool").unwrap());
+    assert!(work_header < stdout.find("app").unwrap());
+    assert!(stdout.find("tool").unwrap() < work_header);
+
+    let output = pendector()
+        .arg("--no-config")
+        .args(["--format", "json"])
+        .arg(&src)
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json[0]["scan_root"], src.to_str().unwrap());
+}
@@ -0,0 +1,91 @@
+use insta::assert_snapshot;
+use pendector::core::Repository;
+use pendector::output::{IconStyle, OutputFormat, OutputFormatter};
+use pendector::{ErrorCode, ErrorReport};
+use std::path::PathBuf;
+use std::time::{Duration, UNIX_EPOCH};
+
+/// 実行環境や現在時刻に依存しない固定のリポジトリ一覧
+fn fixtures() -> Vec<Repository> {
+    let dirty_since = UNIX_EPOCH + Duration::from_secs(1_760_000_000);
+    vec![
+        Repository::new(PathBuf::from("/srv/src/clean-repo"))
+            .with_git_info(false, Some("main".to_string()), Vec::new())
+            .with_remote_info(false, false, Some("origin/main".to_string())),
+        Repository::new(PathBuf::from("/srv/src/dirty-repo"))
+            .with_git_info(
+                true,
+                Some("feature/login".to_string()),
+                vec![
+                    " M src/main.rs".to_string(),
+                    "A  src/login.rs".to_string(),
+                    "?? notes.txt".to_string(),
+                ],
+            )
+            .with_remote_info(false, true, Some("origin/feature/login".to_string()))
+            .with_dirty_since(Some(dirty_since)),
+        Repository::new(PathBuf::from("/srv/src/diverged-repo"))
+            .with_git_info(false, Some("main".to_string()), Vec::new())
+            .with_remote_info(true, true, Some("origin/main".to_string())),
+        Repository::new(PathBuf::from("/srv/src/broken-repo")).with_status_error(Some(
+            ErrorReport {
+                code: ErrorCode::GitOperation,
+                message: "failed to read index".to_string(),
+            },
+        )),
+        Repository::new(PathBuf::from("/srv/src/offline-repo"))
+            .with_git_info(false, Some("main".to_string()), Vec::new())
+            .with_remote_info(false, false, Some("origin/main".to_string()))
+            .with_fetch_error(Some(ErrorReport {
+                code: ErrorCode::Timeout,
+                message: "Operation timed out after 5s for '/srv/src/offline-repo'".to_string(),
+            })),
+    ]
+}
+
+fn formatter(verbose: bool, format: OutputFormat) -> OutputFormatter {
+    colored::control::set_override(false);
+    // 「放置された変更」の表示は現在時刻に依存するので無効にする
+    OutputFormatter::new(verbose, format)
+        .with_width(Some(100))
+        .with_dirty_warn_after(Duration::from_secs(u64::MAX))
+}
+
+#[test]
+fn text_output() {
+    assert_snapshot!(formatter(false, OutputFormat::Text).format_repositories(&fixtures()));
+}
+
+#[test]
+fn text_output_with_ascii_icons() {
+    let formatter = formatter(false, OutputFormat::Text).with_icons(IconStyle::Ascii);
+    assert_snapshot!(formatter.format_repositories(&fixtures()));
+}
+
+#[test]
+fn verbose_output() {
+    assert_snapshot!(formatter(true, OutputFormat::Text).format_repositories(&fixtures()));
+}
+
+#[test]
+fn json_output() {
+    assert_snapshot!(formatter(false, OutputFormat::Json).format_repositories(&fixtures()));
+}
+
+#[test]
+fn json_v2_output() {
+    let formatter = formatter(false, OutputFormat::JsonV2)
+        .with_scan_roots(vec!["/srv/src".to_string()])
+        .with_generated_at(UNIX_EPOCH + Duration::from_secs(1_760_100_000));
+    assert_snapshot!(formatter.format_repositories(&fixtures()));
+}
+
+#[test]
+fn jsonfeed_output() {
+    assert_snapshot!(formatter(false, OutputFormat::Jsonfeed).format_repositories(&fixtures()));
+}
+
+#[test]
+fn report_output() {
+    assert_snapshot!(formatter(false, OutputFormat::Text).format_report(&fixtures()));
+}